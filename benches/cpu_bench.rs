@@ -2,7 +2,7 @@
 // Performance benchmarks for CPU instruction execution
 
 use criterion::{criterion_group, criterion_main, Criterion};
-use nes_rs::{Bus, Cpu};
+use nes_rs::{Bus, Cartridge, Cpu, Emulator, EmulatorConfig};
 use std::hint::black_box;
 
 /// Benchmark CPU instruction execution
@@ -161,49 +161,43 @@ fn bench_instruction_sequence(c: &mut Criterion) {
     group.finish();
 }
 
+/// Helper function to create an emulator running NOPs from an NROM cartridge
+fn create_nop_emulator() -> Emulator {
+    let mut cart = Cartridge::new();
+    cart.prg_rom = vec![0xEA; 32 * 1024]; // NOP
+    cart.chr_rom = vec![0; 8 * 1024];
+
+    // Reset vector at $8000
+    cart.prg_rom[0x7FFC] = 0x00;
+    cart.prg_rom[0x7FFD] = 0x80;
+
+    let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    emulator.insert_cartridge(cart).unwrap();
+    emulator
+}
+
 /// Benchmark CPU execution over multiple frames
-/// Simulates realistic emulator workload
+/// Simulates realistic emulator workload: the scheduler clocks the PPU and APU
+/// on every CPU cycle
 fn bench_frame_execution(c: &mut Criterion) {
     let mut group = c.benchmark_group("frame_execution");
     group.sample_size(20); // Reduce sample size for longer benchmarks
 
     group.bench_function("1000_cycles", |b| {
-        let mut cpu = Cpu::new();
-        let mut bus = Bus::new();
-
-        // Fill memory with NOPs
-        for i in 0..=0xFFFF {
-            bus.write(i, 0xEA); // NOP
-        }
-
-        cpu.reset(&mut bus);
+        let mut emulator = create_nop_emulator();
 
         b.iter(|| {
-            let start_cycles = cpu.cycles;
-            while cpu.cycles - start_cycles < 1000 {
-                cpu.step(black_box(&mut bus));
-            }
+            black_box(emulator.run_cycles(1000));
         });
     });
 
     group.bench_function("29780_cycles_one_frame", |b| {
-        let mut cpu = Cpu::new();
-        let mut bus = Bus::new();
-
-        // Fill memory with NOPs
-        for i in 0..=0xFFFF {
-            bus.write(i, 0xEA); // NOP
-        }
-
-        cpu.reset(&mut bus);
+        let mut emulator = create_nop_emulator();
 
         b.iter(|| {
             // NES CPU runs at ~1.789773 MHz
             // At 60 FPS: ~29,780 cycles per frame
-            let start_cycles = cpu.cycles;
-            while cpu.cycles - start_cycles < 29780 {
-                cpu.step(black_box(&mut bus));
-            }
+            black_box(emulator.run_frame());
         });
     });
 
@@ -60,8 +60,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let noise = apu.noise_output();
         let dmc = apu.dmc_output();
 
-        // Process audio sample
-        audio_system.process_apu_sample(pulse1, pulse2, triangle, noise, dmc);
+        // Process audio sample (no cartridge, so no expansion audio)
+        audio_system.process_apu_sample(pulse1, pulse2, triangle, noise, dmc, 0.0);
 
         // Print progress every second
         if cycle % 1_789_773 == 0 {
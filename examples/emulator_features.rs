@@ -1,6 +1,7 @@
 // Example: Emulator Features
 //
 // This example demonstrates all the quality-of-life features added to the emulator:
+// - Running frames through the emulation scheduler
 // - Save states (quick save/load and multiple slots)
 // - Screenshots
 // - Speed control (fast forward, slow motion, pause)
@@ -42,6 +43,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!();
     }
 
+    // Demonstrate running the emulator
+    println!("Demonstration: Running Frames");
+    println!("-----------------------------");
+    let mut total_cycles = 0;
+    let mut total_samples = 0;
+    for _ in 0..60 {
+        let result = emulator.run_frame();
+        total_cycles += result.cpu_cycles;
+        total_samples += result.audio_samples;
+    }
+    println!("✓ Ran 60 frames");
+    println!("  - CPU cycles: {}", total_cycles);
+    println!("  - Audio samples: {}", total_samples);
+    println!();
+
     // Demonstrate reset functionality
     println!("Demonstration: Reset");
     println!("--------------------");
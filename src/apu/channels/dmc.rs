@@ -1,10 +1,13 @@
 //! DMC (Delta Modulation Channel) implementation for sample playback
 
 use crate::apu::components::Timer;
-use crate::apu::constants::DMC_RATE_TABLE;
+use crate::apu::constants::{DMC_RATE_TABLE, DMC_RATE_TABLE_PAL};
+use crate::region::Region;
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
 
 /// DMC channel for sample playback
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DmcChannel {
     /// Enabled flag (from $4015)
     pub(crate) enabled: bool,
@@ -50,6 +53,10 @@ pub struct DmcChannel {
 
     /// IRQ pending flag
     pub(crate) irq_flag: bool,
+
+    /// Console region, which selects the rate table
+    #[serde(skip)]
+    pub(crate) region: Region,
 }
 
 impl Default for DmcChannel {
@@ -77,6 +84,7 @@ impl DmcChannel {
             output_level: 0,
             silence_flag: true,
             irq_flag: false,
+            region: Region::Ntsc,
         }
     }
 
@@ -95,7 +103,11 @@ impl DmcChannel {
 
         // Set rate from rate table
         let rate_index = (data & 0x0F) as usize;
-        self.timer.set_period_direct(DMC_RATE_TABLE[rate_index]);
+        let rate = match self.region {
+            Region::Ntsc | Region::Dendy => DMC_RATE_TABLE[rate_index],
+            Region::Pal => DMC_RATE_TABLE_PAL[rate_index],
+        };
+        self.timer.set_period_direct(rate);
     }
 
     /// Write to register 1 ($4011 - direct load)
@@ -237,6 +249,45 @@ impl DmcChannel {
     pub fn output(&self) -> u8 {
         self.output_level
     }
+
+    /// Append this channel's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bool(self.enabled);
+        writer.write_bool(self.irq_enabled);
+        writer.write_bool(self.loop_flag);
+        self.timer.save_state(writer);
+        writer.write_u16(self.sample_address);
+        writer.write_u16(self.sample_length);
+        writer.write_u16(self.current_address);
+        writer.write_u16(self.bytes_remaining);
+        writer.write_u8(self.sample_buffer);
+        writer.write_bool(self.sample_buffer_empty);
+        writer.write_u8(self.shift_register);
+        writer.write_u8(self.bits_remaining);
+        writer.write_u8(self.output_level);
+        writer.write_bool(self.silence_flag);
+        writer.write_bool(self.irq_flag);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.enabled = reader.read_bool()?;
+        self.irq_enabled = reader.read_bool()?;
+        self.loop_flag = reader.read_bool()?;
+        self.timer.load_state(reader)?;
+        self.sample_address = reader.read_u16()?;
+        self.sample_length = reader.read_u16()?;
+        self.current_address = reader.read_u16()?;
+        self.bytes_remaining = reader.read_u16()?;
+        self.sample_buffer = reader.read_u8()?;
+        self.sample_buffer_empty = reader.read_bool()?;
+        self.shift_register = reader.read_u8()?;
+        self.bits_remaining = reader.read_u8()?;
+        self.output_level = reader.read_u8()?;
+        self.silence_flag = reader.read_bool()?;
+        self.irq_flag = reader.read_bool()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -578,4 +629,19 @@ mod tests {
             assert_eq!(dmc.timer.period, DMC_RATE_TABLE[rate_index as usize]);
         }
     }
+
+    #[test]
+    fn test_dmc_rate_table_by_region() {
+        let mut dmc = DmcChannel::new();
+        for (region, table) in [
+            (Region::Pal, DMC_RATE_TABLE_PAL),
+            (Region::Dendy, DMC_RATE_TABLE),
+        ] {
+            dmc.region = region;
+            for rate_index in 0..16 {
+                dmc.write_register_0(rate_index);
+                assert_eq!(dmc.timer.period, table[rate_index as usize]);
+            }
+        }
+    }
 }
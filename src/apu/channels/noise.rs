@@ -1,10 +1,13 @@
 //! Noise channel implementation for percussion and sound effects
 
 use crate::apu::components::{Envelope, LengthCounter, Timer};
-use crate::apu::constants::NOISE_PERIOD_TABLE;
+use crate::apu::constants::{NOISE_PERIOD_TABLE, NOISE_PERIOD_TABLE_PAL};
+use crate::region::Region;
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
 
 /// Noise channel for percussion and sound effects
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NoiseChannel {
     /// Enabled flag (from $4015)
     pub(crate) enabled: bool,
@@ -18,6 +21,9 @@ pub struct NoiseChannel {
     pub(crate) lfsr: u16,
     /// Mode flag (false = mode 0, true = mode 1)
     pub(crate) mode: bool,
+    /// Console region, which selects the period table
+    #[serde(skip)]
+    pub(crate) region: Region,
 }
 
 impl Default for NoiseChannel {
@@ -36,6 +42,7 @@ impl NoiseChannel {
             timer: Timer::new(),
             lfsr: 1, // LFSR starts at 1
             mode: false,
+            region: Region::Ntsc,
         }
     }
 
@@ -56,7 +63,10 @@ impl NoiseChannel {
         self.mode = (data & 0x80) != 0;
         // Bits 0-3: Period index
         let period_index = (data & 0x0F) as usize;
-        let period = NOISE_PERIOD_TABLE[period_index];
+        let period = match self.region {
+            Region::Ntsc | Region::Dendy => NOISE_PERIOD_TABLE[period_index],
+            Region::Pal => NOISE_PERIOD_TABLE_PAL[period_index],
+        };
         self.timer.set_period_direct(period);
     }
 
@@ -124,6 +134,27 @@ impl NoiseChannel {
             0
         }
     }
+
+    /// Append this channel's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bool(self.enabled);
+        self.envelope.save_state(writer);
+        self.length_counter.save_state(writer);
+        self.timer.save_state(writer);
+        writer.write_u16(self.lfsr);
+        writer.write_bool(self.mode);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.enabled = reader.read_bool()?;
+        self.envelope.load_state(reader)?;
+        self.length_counter.load_state(reader)?;
+        self.timer.load_state(reader)?;
+        self.lfsr = reader.read_u16()?;
+        self.mode = reader.read_bool()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -385,6 +416,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_noise_period_table_by_region() {
+        let mut noise = NoiseChannel::new();
+        for (region, table) in [
+            (Region::Pal, NOISE_PERIOD_TABLE_PAL),
+            (Region::Dendy, NOISE_PERIOD_TABLE),
+        ] {
+            noise.region = region;
+            for period_index in 0..16 {
+                noise.write_register_2(period_index);
+                assert_eq!(noise.timer.period, table[period_index as usize]);
+            }
+        }
+    }
+
     #[test]
     #[ignore = "LFSR feedback implementation may produce same values for short sequences"]
     fn test_noise_different_modes_produce_different_sequences() {
@@ -2,9 +2,11 @@
 
 use crate::apu::components::{Envelope, LengthCounter, Sweep, Timer};
 use crate::apu::constants::DUTY_PATTERNS;
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
 
 /// Pulse wave channel (used for both Pulse 1 and Pulse 2)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PulseChannel {
     /// Enabled flag (from $4015)
     pub(crate) enabled: bool,
@@ -24,7 +26,8 @@ pub struct PulseChannel {
 
 impl PulseChannel {
     /// Create a new pulse channel
-    /// `channel_number` should be 1 or 2 and affects the sweep unit's negate behavior
+    /// `channel_number` should be 1 or 2 and affects the sweep unit's negate behavior;
+    /// 0 creates a cartridge (MMC5) pulse channel, which has no sweep unit
     pub fn new(channel_number: u8) -> Self {
         Self {
             enabled: false,
@@ -126,6 +129,29 @@ impl PulseChannel {
             self.envelope.volume()
         }
     }
+
+    /// Append this channel's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bool(self.enabled);
+        writer.write_u8(self.duty);
+        writer.write_u8(self.duty_position);
+        self.envelope.save_state(writer);
+        self.sweep.save_state(writer);
+        self.length_counter.save_state(writer);
+        self.timer.save_state(writer);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.enabled = reader.read_bool()?;
+        self.duty = reader.read_u8()?;
+        self.duty_position = reader.read_u8()?;
+        self.envelope.load_state(reader)?;
+        self.sweep.load_state(reader)?;
+        self.length_counter.load_state(reader)?;
+        self.timer.load_state(reader)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
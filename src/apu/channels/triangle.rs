@@ -2,9 +2,11 @@
 
 use crate::apu::components::{LengthCounter, LinearCounter, Timer};
 use crate::apu::constants::TRIANGLE_SEQUENCE;
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
 
 /// Triangle wave channel for bass and melody sounds
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TriangleChannel {
     /// Enabled flag (from $4015)
     pub(crate) enabled: bool,
@@ -121,6 +123,25 @@ impl TriangleChannel {
         // Return current position in triangle sequence
         TRIANGLE_SEQUENCE[self.sequence_position as usize]
     }
+
+    /// Append this channel's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bool(self.enabled);
+        self.linear_counter.save_state(writer);
+        self.length_counter.save_state(writer);
+        self.timer.save_state(writer);
+        writer.write_u8(self.sequence_position);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.enabled = reader.read_bool()?;
+        self.linear_counter.load_state(reader)?;
+        self.length_counter.load_state(reader)?;
+        self.timer.load_state(reader)?;
+        self.sequence_position = reader.read_u8()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,10 @@
 //! Envelope generator for controlling volume over time
 
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
+
 /// Envelope generator for controlling volume over time
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Envelope {
     /// Start flag - set when length counter is loaded
     pub(crate) start: bool,
@@ -67,6 +70,27 @@ impl Envelope {
     pub fn restart(&mut self) {
         self.start = true;
     }
+
+    /// Append this component's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bool(self.start);
+        writer.write_u8(self.divider);
+        writer.write_u8(self.decay_level);
+        writer.write_u8(self.period);
+        writer.write_bool(self.loop_flag);
+        writer.write_bool(self.constant_volume);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.start = reader.read_bool()?;
+        self.divider = reader.read_u8()?;
+        self.decay_level = reader.read_u8()?;
+        self.period = reader.read_u8()?;
+        self.loop_flag = reader.read_bool()?;
+        self.constant_volume = reader.read_bool()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
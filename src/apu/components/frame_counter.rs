@@ -8,9 +8,13 @@
 //! - 5-step mode: No IRQs and runs at approximately 192 Hz
 
 use crate::apu::constants::{
-    FRAME_COUNTER_4_STEP_CYCLES, FRAME_COUNTER_4_STEP_PERIOD, FRAME_COUNTER_5_STEP_CYCLES,
-    FRAME_COUNTER_5_STEP_PERIOD,
+    FRAME_COUNTER_4_STEP_CYCLES, FRAME_COUNTER_4_STEP_CYCLES_PAL, FRAME_COUNTER_4_STEP_PERIOD,
+    FRAME_COUNTER_4_STEP_PERIOD_PAL, FRAME_COUNTER_5_STEP_CYCLES, FRAME_COUNTER_5_STEP_CYCLES_PAL,
+    FRAME_COUNTER_5_STEP_PERIOD, FRAME_COUNTER_5_STEP_PERIOD_PAL,
 };
+use crate::region::Region;
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
 
 /// Events that the frame counter can generate
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,7 +28,7 @@ pub enum FrameEvent {
 }
 
 /// Frame counter sequencer mode
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FrameMode {
     /// 4-step mode (default) - approximately 240 Hz
     FourStep,
@@ -33,7 +37,7 @@ pub enum FrameMode {
 }
 
 /// Frame counter for clocking APU components
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FrameCounter {
     /// Current mode (4-step or 5-step)
     mode: FrameMode,
@@ -49,6 +53,9 @@ pub struct FrameCounter {
     reset_pending: bool,
     /// Delay counter for $4017 write effects (takes 3-4 CPU cycles)
     write_delay: u8,
+    /// Console region, which selects the step timing
+    #[serde(skip)]
+    region: Region,
 }
 
 impl FrameCounter {
@@ -62,9 +69,21 @@ impl FrameCounter {
             irq_pending: false,
             reset_pending: false,
             write_delay: 0,
+            region: Region::Ntsc,
         }
     }
 
+    /// Set the console region
+    ///
+    /// PAL steps are about 11% longer in CPU cycles; the Dendy uses NTSC timing.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - The console region to emulate
+    pub fn set_region(&mut self, region: Region) {
+        self.region = region;
+    }
+
     /// Reset the frame counter to its initial state
     pub fn reset(&mut self) {
         self.mode = FrameMode::FourStep;
@@ -146,8 +165,18 @@ impl FrameCounter {
 
     /// Clock the 4-step sequencer
     fn clock_4_step(&mut self, events: &mut Vec<FrameEvent>) {
+        let (steps, period) = match self.region {
+            Region::Ntsc | Region::Dendy => {
+                (FRAME_COUNTER_4_STEP_CYCLES, FRAME_COUNTER_4_STEP_PERIOD)
+            }
+            Region::Pal => (
+                FRAME_COUNTER_4_STEP_CYCLES_PAL,
+                FRAME_COUNTER_4_STEP_PERIOD_PAL,
+            ),
+        };
+
         // Check if we've hit a frame step
-        if self.step < 4 && self.cycle == FRAME_COUNTER_4_STEP_CYCLES[self.step] {
+        if self.step < 4 && self.cycle == steps[self.step] {
             match self.step {
                 0 => {
                     // Step 1: Quarter frame
@@ -176,8 +205,8 @@ impl FrameCounter {
         }
 
         // Reset at end of frame
-        if self.cycle >= FRAME_COUNTER_4_STEP_PERIOD {
-            // The IRQ flag is also set at cycle 29830 in 4-step mode
+        if self.cycle >= period {
+            // The IRQ flag is also set at cycle 29830 (33253 on PAL) in 4-step mode
             if !self.irq_inhibit {
                 self.irq_pending = true;
                 events.push(FrameEvent::SetIrq);
@@ -189,8 +218,18 @@ impl FrameCounter {
 
     /// Clock the 5-step sequencer
     fn clock_5_step(&mut self, events: &mut Vec<FrameEvent>) {
+        let (steps, period) = match self.region {
+            Region::Ntsc | Region::Dendy => {
+                (FRAME_COUNTER_5_STEP_CYCLES, FRAME_COUNTER_5_STEP_PERIOD)
+            }
+            Region::Pal => (
+                FRAME_COUNTER_5_STEP_CYCLES_PAL,
+                FRAME_COUNTER_5_STEP_PERIOD_PAL,
+            ),
+        };
+
         // Check if we've hit a frame step
-        if self.step < 5 && self.cycle == FRAME_COUNTER_5_STEP_CYCLES[self.step] {
+        if self.step < 5 && self.cycle == steps[self.step] {
             match self.step {
                 0 => {
                     // Step 1: Quarter frame
@@ -217,7 +256,7 @@ impl FrameCounter {
         }
 
         // Reset at end of frame (no IRQ in 5-step mode)
-        if self.cycle >= FRAME_COUNTER_5_STEP_PERIOD {
+        if self.cycle >= period {
             self.cycle = 0;
             self.step = 0;
         }
@@ -252,6 +291,41 @@ impl FrameCounter {
     pub fn irq_inhibited(&self) -> bool {
         self.irq_inhibit
     }
+
+    /// Append the frame counter's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u8(match self.mode {
+            FrameMode::FourStep => 0,
+            FrameMode::FiveStep => 1,
+        });
+        writer.write_u32(self.cycle);
+        writer.write_u8(self.step as u8);
+        writer.write_bool(self.irq_inhibit);
+        writer.write_bool(self.irq_pending);
+        writer.write_bool(self.reset_pending);
+        writer.write_u8(self.write_delay);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.mode = match reader.read_u8()? {
+            0 => FrameMode::FourStep,
+            1 => FrameMode::FiveStep,
+            other => {
+                return Err(StateError::Invalid(format!(
+                    "invalid frame counter mode {}",
+                    other
+                )))
+            }
+        };
+        self.cycle = reader.read_u32()?;
+        self.step = reader.read_u8()? as usize;
+        self.irq_inhibit = reader.read_bool()?;
+        self.irq_pending = reader.read_bool()?;
+        self.reset_pending = reader.read_bool()?;
+        self.write_delay = reader.read_u8()?;
+        Ok(())
+    }
 }
 
 impl Default for FrameCounter {
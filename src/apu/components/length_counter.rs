@@ -1,9 +1,11 @@
 //! Length counter for controlling note duration
 
 use crate::apu::constants::LENGTH_COUNTER_TABLE;
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
 
 /// Length counter for controlling note duration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LengthCounter {
     /// Counter value
     pub(crate) counter: u8,
@@ -40,6 +42,19 @@ impl LengthCounter {
     pub fn set_halt(&mut self, halt: bool) {
         self.halt = halt;
     }
+
+    /// Append this component's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u8(self.counter);
+        writer.write_bool(self.halt);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.counter = reader.read_u8()?;
+        self.halt = reader.read_bool()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,12 @@
 //! Linear counter for the triangle channel
 
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
+
 /// Linear counter for the triangle channel
 /// The linear counter gates the length counter and provides an additional
 /// mechanism for controlling note duration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LinearCounter {
     /// Counter value
     pub(crate) counter: u8,
@@ -54,6 +57,23 @@ impl LinearCounter {
     pub fn set_reload_flag(&mut self) {
         self.reload_flag = true;
     }
+
+    /// Append this component's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u8(self.counter);
+        writer.write_u8(self.reload_value);
+        writer.write_bool(self.control_flag);
+        writer.write_bool(self.reload_flag);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.counter = reader.read_u8()?;
+        self.reload_value = reader.read_u8()?;
+        self.control_flag = reader.read_bool()?;
+        self.reload_flag = reader.read_bool()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
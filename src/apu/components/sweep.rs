@@ -1,7 +1,10 @@
 //! Sweep unit for pitch bending
 
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
+
 /// Sweep unit for pitch bending
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sweep {
     /// Enabled flag
     enabled: bool,
@@ -16,6 +19,7 @@ pub struct Sweep {
     /// Reload flag
     reload: bool,
     /// Channel number (1 or 2) - affects negate calculation
+    /// (0 for cartridge pulse channels, which have no sweep unit)
     pub(crate) channel: u8,
 }
 
@@ -49,6 +53,11 @@ impl Sweep {
 
     /// Check if the sweep unit is muting the channel
     pub fn is_muting(&self, current_period: u16) -> bool {
+        // Channels without a sweep unit are never muted
+        if self.channel == 0 {
+            return false;
+        }
+
         // Mute if current period < 8 or target period > 0x7FF
         current_period < 8 || self.calculate_target_period(current_period) > 0x7FF
     }
@@ -81,6 +90,29 @@ impl Sweep {
         self.shift = data & 0x07;
         self.reload = true;
     }
+
+    /// Append this component's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bool(self.enabled);
+        writer.write_u8(self.divider);
+        writer.write_u8(self.period);
+        writer.write_bool(self.negate);
+        writer.write_u8(self.shift);
+        writer.write_bool(self.reload);
+        writer.write_u8(self.channel);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.enabled = reader.read_bool()?;
+        self.divider = reader.read_u8()?;
+        self.period = reader.read_u8()?;
+        self.negate = reader.read_bool()?;
+        self.shift = reader.read_u8()?;
+        self.reload = reader.read_bool()?;
+        self.channel = reader.read_u8()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -182,6 +214,14 @@ mod tests {
         assert!(!sweep.is_muting(100));
     }
 
+    #[test]
+    fn test_sweep_without_unit_never_mutes() {
+        let sweep = Sweep::new(0);
+
+        assert!(!sweep.is_muting(0));
+        assert!(!sweep.is_muting(0x7FF));
+    }
+
     #[test]
     fn test_sweep_clock_disabled() {
         let mut sweep = Sweep::new(1);
@@ -1,7 +1,10 @@
 //! Timer for controlling the frequency of waveforms
 
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
+
 /// Timer for controlling the frequency of the pulse wave
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Timer {
     /// Period (11-bit value)
     pub(crate) period: u16,
@@ -38,6 +41,19 @@ impl Timer {
     pub fn set_period_direct(&mut self, period: u16) {
         self.period = period;
     }
+
+    /// Append this component's state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u16(self.period);
+        writer.write_u16(self.counter);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.period = reader.read_u16()?;
+        self.counter = reader.read_u16()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -59,3 +59,31 @@ pub const FRAME_COUNTER_4_STEP_PERIOD: u32 = 29830;
 
 /// Total cycles for one frame in 5-step mode
 pub const FRAME_COUNTER_5_STEP_PERIOD: u32 = 37282;
+
+// ============================================================================
+// PAL Timing
+// ============================================================================
+// The PAL 2A07 runs from a slower clock, so its period tables and frame
+// counter steps are shorter in CPU cycles. The Dendy uses the NTSC values.
+
+/// Noise channel period lookup table (PAL)
+pub const NOISE_PERIOD_TABLE_PAL: [u16; 16] = [
+    4, 8, 14, 30, 60, 88, 118, 148, 188, 236, 354, 472, 708, 944, 1890, 3778,
+];
+
+/// DMC rate table (PAL)
+pub const DMC_RATE_TABLE_PAL: [u16; 16] = [
+    398, 354, 316, 298, 276, 236, 210, 198, 176, 148, 132, 118, 98, 78, 66, 50,
+];
+
+/// 4-step mode sequence (PAL)
+pub const FRAME_COUNTER_4_STEP_CYCLES_PAL: [u32; 4] = [8313, 16627, 24939, 33252];
+
+/// 5-step mode sequence (PAL)
+pub const FRAME_COUNTER_5_STEP_CYCLES_PAL: [u32; 5] = [8313, 16627, 24939, 33252, 41565];
+
+/// Total cycles for one frame in 4-step mode (PAL)
+pub const FRAME_COUNTER_4_STEP_PERIOD_PAL: u32 = 33253;
+
+/// Total cycles for one frame in 5-step mode (PAL)
+pub const FRAME_COUNTER_5_STEP_PERIOD_PAL: u32 = 41566;
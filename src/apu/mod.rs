@@ -58,6 +58,9 @@
 // | $4017   | Frame counter (W)                     |
 
 use crate::bus::MemoryMappedDevice;
+use crate::region::Region;
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
 
 // Module declarations
 mod channels;
@@ -74,6 +77,8 @@ pub use components::{FrameCounter, FrameEvent, FrameMode};
 /// APU structure representing the Audio Processing Unit state
 ///
 /// Phase 7 implementation with full pulse, triangle, noise, and DMC channel support.
+/// The whole APU is serializable so save states capture every channel exactly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Apu {
     // ========================================
     // Pulse Channels (Phase 7 - Implemented)
@@ -116,6 +121,12 @@ pub struct Apu {
     /// Read: Status of each channel (length counter > 0)
     /// Write: Enable/disable channels
     status_control: u8,
+
+    /// Console region, which selects the noise, DMC and frame counter timing
+    ///
+    /// Not part of save states: it belongs to the console, not the game.
+    #[serde(skip)]
+    region: Region,
 }
 
 impl Apu {
@@ -154,6 +165,8 @@ impl Apu {
 
             // Control
             status_control: 0x00,
+
+            region: Region::Ntsc,
         }
     }
 
@@ -161,8 +174,43 @@ impl Apu {
     ///
     /// Resets all registers to their default values.
     /// This simulates a power cycle or reset signal.
+    /// The region is kept.
     pub fn reset(&mut self) {
+        let region = self.region;
         *self = Self::new();
+        self.set_region(region);
+    }
+
+    /// Set the console region
+    ///
+    /// PAL consoles use their own noise period, DMC rate and frame counter
+    /// tables; the Dendy uses the NTSC ones. Periods already loaded into the
+    /// channels take effect on the next register write.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - The console region to emulate
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::apu::Apu;
+    /// use nes_rs::Region;
+    ///
+    /// let mut apu = Apu::new();
+    /// apu.set_region(Region::Pal);
+    /// assert_eq!(apu.region(), Region::Pal);
+    /// ```
+    pub fn set_region(&mut self, region: Region) {
+        self.region = region;
+        self.noise.region = region;
+        self.dmc.region = region;
+        self.frame_counter.set_region(region);
+    }
+
+    /// Get the console region
+    pub fn region(&self) -> Region {
+        self.region
     }
 
     /// Clock the APU timer (called every CPU cycle)
@@ -250,6 +298,20 @@ impl Apu {
     ///
     /// Mixed audio sample as f32 in range [0.0, ~1.0]
     pub fn output(&self) -> f32 {
+        self.output_with_expansion(0.0)
+    }
+
+    /// Get the mixed output sample, including cartridge expansion audio
+    ///
+    /// # Arguments
+    ///
+    /// * `expansion` - Expansion audio level from `Mapper::audio_output`,
+    ///   already scaled relative to the APU channels
+    ///
+    /// # Returns
+    ///
+    /// Mixed audio sample as f32
+    pub fn output_with_expansion(&self, expansion: f32) -> f32 {
         let pulse1_out = self.pulse1.output();
         let pulse2_out = self.pulse2.output();
         let triangle_out = self.triangle.output();
@@ -257,7 +319,14 @@ impl Apu {
         let dmc_out = self.dmc.output();
 
         // Use non-linear mixing formula
-        self.mix_channels(pulse1_out, pulse2_out, triangle_out, noise_out, dmc_out)
+        self.mix_channels(
+            pulse1_out,
+            pulse2_out,
+            triangle_out,
+            noise_out,
+            dmc_out,
+            expansion,
+        )
     }
 
     /// Mix all APU channels using the non-linear formula
@@ -269,19 +338,28 @@ impl Apu {
     /// * `triangle` - Triangle channel output (0-15)
     /// * `noise` - Noise channel output (0-15)
     /// * `dmc` - DMC channel output (0-127)
+    /// * `expansion` - Cartridge expansion audio level
     ///
     /// # Returns
     ///
-    /// Mixed audio sample as f32 in range [0.0, ~1.0]
-    fn mix_channels(&self, pulse1: u8, pulse2: u8, triangle: u8, noise: u8, dmc: u8) -> f32 {
+    /// Mixed audio sample as f32 in range [0.0, ~1.0] plus the expansion level
+    fn mix_channels(
+        &self,
+        pulse1: u8,
+        pulse2: u8,
+        triangle: u8,
+        noise: u8,
+        dmc: u8,
+        expansion: f32,
+    ) -> f32 {
         // Mix pulse channels using non-linear formula
         let pulse_out = self.mix_pulse(pulse1, pulse2);
 
         // Mix triangle, noise, and DMC using non-linear formula
         let tnd_out = self.mix_tnd(triangle, noise, dmc);
 
-        // Combine
-        pulse_out + tnd_out
+        // Combine; expansion audio is already linear on the output scale
+        pulse_out + tnd_out + expansion
     }
 
     /// Mix pulse channels using the NES non-linear formula
@@ -560,6 +638,45 @@ impl Apu {
             _ => {}
         }
     }
+
+    // ========================================
+    // Save States
+    // ========================================
+
+    /// Append the complete APU state to a save state
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - State writer to append to
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        self.pulse1.save_state(writer);
+        self.pulse2.save_state(writer);
+        self.triangle.save_state(writer);
+        self.noise.save_state(writer);
+        self.dmc.save_state(writer);
+        self.frame_counter.save_state(writer);
+        writer.write_u8(self.status_control);
+    }
+
+    /// Restore APU state written by `save_state`
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - State reader positioned at the APU state
+    ///
+    /// # Returns
+    ///
+    /// An error if the state is truncated or invalid
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.pulse1.load_state(reader)?;
+        self.pulse2.load_state(reader)?;
+        self.triangle.load_state(reader)?;
+        self.noise.load_state(reader)?;
+        self.dmc.load_state(reader)?;
+        self.frame_counter.load_state(reader)?;
+        self.status_control = reader.read_u8()?;
+        Ok(())
+    }
 }
 
 impl MemoryMappedDevice for Apu {
@@ -2,6 +2,7 @@
 
 use crate::apu::Apu;
 use crate::bus::MemoryMappedDevice;
+use crate::region::Region;
 
 #[test]
 fn test_frame_counter_default_mode() {
@@ -257,3 +258,30 @@ fn test_both_modes_clock_envelopes() {
     // (We can't easily test the exact envelope state without exposing internals,
     // but we verify the modes work without crashing)
 }
+
+#[test]
+fn test_frame_counter_pal_timing() {
+    let mut apu = Apu::new();
+    apu.set_region(Region::Pal);
+
+    // The NTSC step 4 (29829 cycles) is not a step on PAL
+    for _ in 0..29829 {
+        apu.clock();
+    }
+    assert!(!apu.frame_irq_pending());
+
+    // PAL step 4 is at 33252 cycles
+    for _ in 29829..33252 {
+        apu.clock();
+    }
+    assert!(apu.frame_irq_pending());
+}
+
+#[test]
+fn test_reset_keeps_region() {
+    let mut apu = Apu::new();
+    apu.set_region(Region::Pal);
+    apu.reset();
+    assert_eq!(apu.region(), Region::Pal);
+    assert_eq!(apu.noise.region, Region::Pal);
+}
@@ -222,6 +222,20 @@ fn test_both_pulse_channels_work() {
     assert!(mixed <= 1.0);
 }
 
+#[test]
+fn test_expansion_audio_adds_to_mix() {
+    let mut apu = Apu::new();
+
+    apu.write(0x4015, 0x01);
+    apu.write(0x4000, 0x3F); // Constant volume = 15
+    apu.write(0x4003, 0x08);
+
+    // Expansion audio is already scaled and adds linearly
+    let mixed = apu.output();
+    assert_eq!(apu.output_with_expansion(0.0), mixed);
+    assert!((apu.output_with_expansion(0.2) - mixed - 0.2).abs() < 1e-6);
+}
+
 #[test]
 fn test_sweep_units_differ_for_pulse_1_and_2() {
     // Pulse 1 uses one's complement for negate
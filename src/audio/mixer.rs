@@ -11,12 +11,15 @@
 /// ```text
 /// pulse_out = 95.88 / (8128 / (pulse1 + pulse2) + 100)
 /// tnd_out = 159.79 / (1 / (triangle/8227 + noise/12241 + dmc/22638) + 100)
-/// output = pulse_out + tnd_out
+/// output = pulse_out + tnd_out + expansion
 /// ```
 ///
 /// Where pulse1, pulse2, triangle, noise, and dmc are the raw output
 /// values from each channel (0-15 for pulse, 0-15 for triangle,
-/// 0-15 for noise, 0-127 for DMC).
+/// 0-15 for noise, 0-127 for DMC). Cartridges with their own sound chip
+/// (VRC6, MMC5, ...) feed an analog expansion level through the console's
+/// audio path; the mapper scales it to this output range, so it is added
+/// after the non-linear mixing.
 pub struct Mixer {
     /// Volume control (0.0 = mute, 1.0 = full volume)
     volume: f32,
@@ -62,11 +65,22 @@ impl Mixer {
     /// * `triangle` - Triangle channel output (0-15)
     /// * `noise` - Noise channel output (0-15)
     /// * `dmc` - DMC channel output (0-127)
+    /// * `expansion` - Cartridge expansion audio level from
+    ///   `Mapper::audio_output` (0.0 without expansion audio)
     ///
     /// # Returns
     ///
-    /// Mixed audio sample as f32 in range [0.0, 1.0]
-    pub fn mix(&self, pulse1: u8, pulse2: u8, triangle: u8, noise: u8, dmc: u8) -> f32 {
+    /// Mixed audio sample as f32 in range [0.0, 1.0], or [-1.0, 1.0] when
+    /// bipolar expansion audio (such as VRC7 FM) swings below zero
+    pub fn mix(
+        &self,
+        pulse1: u8,
+        pulse2: u8,
+        triangle: u8,
+        noise: u8,
+        dmc: u8,
+        expansion: f32,
+    ) -> f32 {
         // Mix pulse channels using non-linear formula
         let pulse_out = self.mix_pulse(pulse1, pulse2);
 
@@ -76,11 +90,11 @@ impl Mixer {
         // Combine and apply volume.
         // NES formulas yield ~[0.0, 1.0], and we use this range directly.
         // This ensures silence (0.0) maps to 0.0, avoiding DC offset and pops.
-        let mixed = pulse_out + tnd_out;
+        let mixed = pulse_out + tnd_out + expansion;
         let output = mixed * self.volume;
 
-        // Clamp to valid range [0.0, 1.0]
-        output.clamp(0.0, 1.0)
+        // The APU alone stays within [0.0, 1.0]; FM expansion audio is bipolar
+        output.clamp(-1.0, 1.0)
     }
 
     /// Mix pulse channels using the NES non-linear formula
@@ -144,11 +158,13 @@ impl Mixer {
     /// * `triangle` - Triangle channel output (0-15)
     /// * `noise` - Noise channel output (0-15)
     /// * `dmc` - DMC channel output (0-127)
+    /// * `expansion` - Cartridge expansion audio level
     /// * `pulse1_vol` - Pulse 1 volume multiplier (0.0-1.0)
     /// * `pulse2_vol` - Pulse 2 volume multiplier (0.0-1.0)
     /// * `triangle_vol` - Triangle volume multiplier (0.0-1.0)
     /// * `noise_vol` - Noise volume multiplier (0.0-1.0)
     /// * `dmc_vol` - DMC volume multiplier (0.0-1.0)
+    /// * `expansion_vol` - Expansion audio volume multiplier (0.0-1.0)
     ///
     /// # Returns
     ///
@@ -162,11 +178,13 @@ impl Mixer {
         triangle: u8,
         noise: u8,
         dmc: u8,
+        expansion: f32,
         pulse1_vol: f32,
         pulse2_vol: f32,
         triangle_vol: f32,
         noise_vol: f32,
         dmc_vol: f32,
+        expansion_vol: f32,
     ) -> f32 {
         // Apply individual channel volumes
         let p1 = (pulse1 as f32 * pulse1_vol.clamp(0.0, 1.0)) as u8;
@@ -174,8 +192,9 @@ impl Mixer {
         let tri = (triangle as f32 * triangle_vol.clamp(0.0, 1.0)) as u8;
         let noi = (noise as f32 * noise_vol.clamp(0.0, 1.0)) as u8;
         let d = (dmc as f32 * dmc_vol.clamp(0.0, 1.0)) as u8;
+        let exp = expansion * expansion_vol.clamp(0.0, 1.0);
 
-        self.mix(p1, p2, tri, noi, d)
+        self.mix(p1, p2, tri, noi, d, exp)
     }
 }
 
@@ -210,7 +229,7 @@ mod tests {
     #[test]
     fn test_mix_silence() {
         let mixer = Mixer::new();
-        let output = mixer.mix(0, 0, 0, 0, 0);
+        let output = mixer.mix(0, 0, 0, 0, 0, 0.0);
         // Silence (all channels at 0) should map to 0.0 to avoid DC offset
         assert_eq!(output, 0.0);
     }
@@ -218,7 +237,7 @@ mod tests {
     #[test]
     fn test_mix_pulse_only() {
         let mixer = Mixer::new();
-        let output = mixer.mix(15, 15, 0, 0, 0);
+        let output = mixer.mix(15, 15, 0, 0, 0, 0.0);
         // Output should be non-zero and positive
         assert!(output > 0.0);
         assert!(output <= 1.0);
@@ -227,7 +246,7 @@ mod tests {
     #[test]
     fn test_mix_all_channels() {
         let mixer = Mixer::new();
-        let output = mixer.mix(15, 15, 15, 15, 127);
+        let output = mixer.mix(15, 15, 15, 15, 127, 0.0);
         // Output should be in valid range [0.0, 1.0]
         assert!(output >= 0.0);
         assert!(output <= 1.0);
@@ -239,15 +258,35 @@ mod tests {
         mixer.set_volume(0.5);
         assert_eq!(mixer.volume(), 0.5);
 
-        let output_half = mixer.mix(15, 15, 15, 15, 127);
+        let output_half = mixer.mix(15, 15, 15, 15, 127, 0.0);
 
         mixer.set_volume(1.0);
-        let output_full = mixer.mix(15, 15, 15, 15, 127);
+        let output_full = mixer.mix(15, 15, 15, 15, 127, 0.0);
 
         // Half volume should produce smaller output
         assert!(output_half.abs() < output_full.abs());
     }
 
+    #[test]
+    fn test_mix_expansion_audio() {
+        let mixer = Mixer::new();
+
+        // Expansion audio is added linearly on top of the APU mix
+        let apu_only = mixer.mix(8, 0, 8, 0, 0, 0.0);
+        let with_expansion = mixer.mix(8, 0, 8, 0, 0, 0.1);
+        assert!((with_expansion - apu_only - 0.1).abs() < 1e-6);
+        assert_eq!(mixer.mix(0, 0, 0, 0, 0, 0.25), 0.25);
+
+        // The master volume applies to the expansion audio too
+        let mixer = Mixer::with_volume(0.5);
+        assert_eq!(mixer.mix(0, 0, 0, 0, 0, 0.25), 0.125);
+
+        // Per-channel volume can mute the expansion audio alone
+        let muted =
+            mixer.mix_with_channel_volumes(8, 0, 0, 0, 0, 0.2, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0);
+        assert_eq!(muted, mixer.mix(8, 0, 0, 0, 0, 0.0));
+    }
+
     #[test]
     fn test_mix_pulse_formula() {
         let mixer = Mixer::new();
@@ -27,8 +27,11 @@
 // let noise = apu.noise_output();
 // let dmc = apu.dmc_output();
 //
+// // Cartridge sound chips contribute through Mapper::audio_output
+// let expansion = 0.0;
+//
 // // Process audio
-// audio_system.process_apu_sample(pulse1, pulse2, triangle, noise, dmc);
+// audio_system.process_apu_sample(pulse1, pulse2, triangle, noise, dmc, expansion);
 // ```
 
 pub mod mixer;
@@ -39,6 +42,7 @@ pub use mixer::Mixer;
 pub use output::{AudioConfig, AudioOutput, AudioOutputBuilder};
 pub use resampler::{sample_rates, AudioBuffer, Resampler};
 
+use crate::region::Region;
 use std::sync::{Arc, Mutex};
 
 /// Complete audio system for NES emulation
@@ -96,6 +100,20 @@ impl AudioSystem {
         Self::new(AudioConfig::new())
     }
 
+    /// Set the console region
+    ///
+    /// The APU produces one sample per CPU cycle, so the resampler's input
+    /// rate follows the region's CPU clock. Audio systems start out NTSC.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - The console region being emulated
+    pub fn set_region(&mut self, region: Region) {
+        let mut resampler = self.resampler.lock().unwrap();
+        let output_rate = resampler.output_rate();
+        *resampler = Resampler::for_region(region, output_rate);
+    }
+
     /// Process one APU sample (call this every APU clock)
     ///
     /// # Arguments
@@ -105,9 +123,20 @@ impl AudioSystem {
     /// * `triangle` - Triangle channel output (0-15)
     /// * `noise` - Noise channel output (0-15)
     /// * `dmc` - DMC channel output (0-127)
-    pub fn process_apu_sample(&mut self, pulse1: u8, pulse2: u8, triangle: u8, noise: u8, dmc: u8) {
+    /// * `expansion` - Cartridge expansion audio level (0.0 without expansion audio)
+    pub fn process_apu_sample(
+        &mut self,
+        pulse1: u8,
+        pulse2: u8,
+        triangle: u8,
+        noise: u8,
+        dmc: u8,
+        expansion: f32,
+    ) {
         // Mix the channels
-        let mixed_sample = self.mixer.mix(pulse1, pulse2, triangle, noise, dmc);
+        let mixed_sample = self
+            .mixer
+            .mix(pulse1, pulse2, triangle, noise, dmc, expansion);
 
         // Add to resampler
         let mut resampler = self.resampler.lock().unwrap();
@@ -3,6 +3,10 @@
 // The NES APU generates samples at the CPU clock rate (approximately 1.789773 MHz).
 // Modern audio hardware expects samples at standard rates like 44.1 kHz or 48 kHz.
 // This module handles the conversion using simple linear interpolation.
+// PAL and Dendy consoles run the CPU at a different clock, so their input rate
+// comes from the region (see `Resampler::for_region`).
+
+use crate::region::Region;
 
 /// Sample rate constants
 pub mod sample_rates {
@@ -69,6 +73,26 @@ impl Resampler {
         Self::new(sample_rates::NES_CPU_CLOCK, sample_rates::AUDIO_48_KHZ)
     }
 
+    /// Create a resampler for a console region
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - Console region, which sets the input rate (the CPU clock)
+    /// * `output_rate` - Output sample rate (44.1 kHz or 48 kHz)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::audio::{sample_rates, Resampler};
+    /// use nes_rs::Region;
+    ///
+    /// let resampler = Resampler::for_region(Region::Pal, sample_rates::AUDIO_48_KHZ);
+    /// assert_eq!(resampler.input_rate(), Region::Pal.cpu_clock_hz());
+    /// ```
+    pub fn for_region(region: Region, output_rate: f64) -> Self {
+        Self::new(region.cpu_clock_hz(), output_rate)
+    }
+
     /// Add an input sample from the APU
     ///
     /// Call this method every APU clock cycle with the current mixed output.
@@ -244,6 +268,12 @@ mod tests {
 
         let resampler = Resampler::new_48_khz();
         assert_eq!(resampler.output_rate(), sample_rates::AUDIO_48_KHZ);
+
+        let resampler = Resampler::for_region(Region::Ntsc, sample_rates::AUDIO_48_KHZ);
+        assert_eq!(resampler.input_rate(), sample_rates::NES_CPU_CLOCK);
+
+        let resampler = Resampler::for_region(Region::Pal, sample_rates::AUDIO_48_KHZ);
+        assert_eq!(resampler.input_rate(), 1_662_607.0);
     }
 
     #[test]
@@ -0,0 +1,325 @@
+// ROM header database
+//
+// Many iNES dumps carry wrong or dirty headers: garbage left in bytes 7-15 by
+// old tools, or simply the wrong mapper or mirroring. The header database maps
+// the hash of a ROM's PRG-ROM + CHR-ROM to the correct values, which
+// `Cartridge::from_ines_bytes` applies over the header.
+//
+// The bundled list (header_db.toml next to this file) is compiled in. A local
+// `header_db.toml` in the working directory, in the same format, extends it;
+// its entries take precedence.
+
+use super::{Cartridge, Mirroring};
+use crate::region::Region;
+use serde::Deserialize;
+use std::cell::OnceCell;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Database compiled into the emulator
+const BUNDLED_DATABASE: &str = include_str!("header_db.toml");
+
+/// Local database file extending the bundled one
+const LOCAL_DATABASE_FILE: &str = "header_db.toml";
+
+/// Header corrections for one ROM
+///
+/// The ROM is matched by `crc32` or `sha1`; every other field that is set
+/// replaces the value from the iNES header.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeaderEntry {
+    /// Game title, used when logging corrections
+    #[serde(default)]
+    pub name: String,
+    /// CRC-32 of PRG-ROM + CHR-ROM
+    pub crc32: Option<u32>,
+    /// SHA-1 of PRG-ROM + CHR-ROM, as lowercase hexadecimal
+    pub sha1: Option<String>,
+    /// Correct mapper number
+    pub mapper: Option<u16>,
+    /// Correct submapper number
+    pub submapper: Option<u8>,
+    /// Correct mirroring
+    pub mirroring: Option<Mirroring>,
+    /// Whether the cartridge has battery-backed RAM
+    pub battery: Option<bool>,
+    /// Console region the ROM was made for
+    pub region: Option<Region>,
+}
+
+impl HeaderEntry {
+    /// Apply the corrections to a cartridge
+    ///
+    /// Moving the battery flag also moves iNES 1.0 PRG-RAM between the
+    /// volatile and battery-backed sizes.
+    ///
+    /// # Arguments
+    ///
+    /// * `cartridge` - The cartridge loaded from the ROM's header
+    ///
+    /// # Returns
+    ///
+    /// A description of each field that changed (empty if the header was right)
+    pub fn apply(&self, cartridge: &mut Cartridge) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        if let Some(mapper) = self.mapper.filter(|&m| m != cartridge.mapper) {
+            changes.push(format!("mapper {} -> {}", cartridge.mapper, mapper));
+            cartridge.mapper = mapper;
+        }
+        if let Some(submapper) = self.submapper.filter(|&s| s != cartridge.submapper) {
+            changes.push(format!(
+                "submapper {} -> {}",
+                cartridge.submapper, submapper
+            ));
+            cartridge.submapper = submapper;
+        }
+        if let Some(mirroring) = self.mirroring.filter(|&m| m != cartridge.mirroring) {
+            changes.push(format!(
+                "mirroring {:?} -> {:?}",
+                cartridge.mirroring, mirroring
+            ));
+            cartridge.mirroring = mirroring;
+        }
+        if let Some(battery) = self.battery.filter(|&b| b != cartridge.has_battery) {
+            changes.push(format!("battery {} -> {}", cartridge.has_battery, battery));
+            cartridge.has_battery = battery;
+            let ram = &mut cartridge.ram;
+            if battery && ram.prg_nvram == 0 {
+                ram.prg_nvram = std::mem::take(&mut ram.prg_ram);
+            } else if !battery && ram.prg_ram == 0 {
+                ram.prg_ram = std::mem::take(&mut ram.prg_nvram);
+            }
+        }
+        if let Some(region) = self.region.filter(|&r| r != cartridge.region) {
+            changes.push(format!("region {} -> {}", cartridge.region, region));
+            cartridge.region = region;
+        }
+
+        changes
+    }
+}
+
+/// Collection of header corrections keyed by ROM hash
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::cartridge::{Cartridge, HeaderDatabase};
+///
+/// let database = HeaderDatabase::from_toml(
+///     r#"
+///     [[rom]]
+///     name = "Example"
+///     crc32 = 0x12345678
+///     mapper = 4
+///     "#,
+/// )
+/// .unwrap();
+/// assert_eq!(database.len(), 1);
+/// assert!(database.find(&Cartridge::new()).is_none());
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HeaderDatabase {
+    /// Entries in load order; later entries take precedence
+    #[serde(default, rename = "rom")]
+    entries: Vec<HeaderEntry>,
+}
+
+impl HeaderDatabase {
+    /// Create an empty database
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the database compiled into the emulator
+    pub fn bundled() -> Self {
+        Self::from_toml(BUNDLED_DATABASE).expect("Bundled header database is invalid")
+    }
+
+    /// Get the database used by [`Cartridge::from_ines_bytes`]
+    ///
+    /// The bundled database extended with the local `header_db.toml`, loaded
+    /// on first use. A local file that fails to load is reported and ignored.
+    pub fn global() -> &'static HeaderDatabase {
+        static DATABASE: OnceLock<HeaderDatabase> = OnceLock::new();
+        DATABASE.get_or_init(|| {
+            let mut database = Self::bundled();
+            if Path::new(LOCAL_DATABASE_FILE).exists() {
+                match Self::load(LOCAL_DATABASE_FILE) {
+                    Ok(local) => database.extend(local),
+                    Err(e) => eprintln!("Ignoring {}: {}", LOCAL_DATABASE_FILE, e),
+                }
+            }
+            database
+        })
+    }
+
+    /// Parse a database from TOML
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - `[[rom]]` tables in the header_db.toml format
+    ///
+    /// # Returns
+    ///
+    /// The database, or an `InvalidData` error describing the problem
+    pub fn from_toml(contents: &str) -> Result<Self, io::Error> {
+        let mut database: HeaderDatabase =
+            toml::from_str(contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        for entry in &mut database.entries {
+            if let Some(sha1) = &mut entry.sha1 {
+                sha1.make_ascii_lowercase();
+            }
+        }
+        Ok(database)
+    }
+
+    /// Load a database file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to a file in the header_db.toml format
+    ///
+    /// # Returns
+    ///
+    /// Result containing the database or an error
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Add the entries of another database, taking precedence over these
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The database to add
+    pub fn extend(&mut self, other: HeaderDatabase) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Get the number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the database has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Find the corrections for a cartridge
+    ///
+    /// The SHA-1 is only computed if an entry is keyed by it.
+    ///
+    /// # Arguments
+    ///
+    /// * `cartridge` - The cartridge to look up
+    ///
+    /// # Returns
+    ///
+    /// The matching entry, if any
+    pub fn find(&self, cartridge: &Cartridge) -> Option<&HeaderEntry> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let crc32 = cartridge.crc32();
+        let sha1 = OnceCell::new();
+        self.entries.iter().rev().find(|entry| {
+            entry.crc32 == Some(crc32)
+                || entry.sha1.as_ref().is_some_and(|expected| {
+                    *expected == *sha1.get_or_init(|| crate::state::hash::to_hex(&cartridge.sha1()))
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 16KB NROM cartridge with distinct PRG-ROM contents
+    fn test_cartridge() -> Cartridge {
+        Cartridge {
+            prg_rom: (0..16 * 1024).map(|i| i as u8).collect(),
+            chr_rom: vec![0x11; 8 * 1024],
+            ..Cartridge::new()
+        }
+    }
+
+    #[test]
+    fn test_bundled_database_parses() {
+        HeaderDatabase::bundled();
+    }
+
+    #[test]
+    fn test_find_by_crc32_and_sha1() {
+        let cartridge = test_cartridge();
+        let crc32 = cartridge.crc32();
+        let sha1 = crate::state::hash::to_hex(&cartridge.sha1()).to_uppercase();
+
+        let database = HeaderDatabase::from_toml(&format!(
+            "[[rom]]\ncrc32 = {}\nmapper = 1\n\n[[rom]]\nsha1 = \"{}\"\nmapper = 2\n",
+            crc32, sha1
+        ))
+        .unwrap();
+        assert_eq!(database.len(), 2);
+
+        // The later entry wins; SHA-1 matching ignores case
+        assert_eq!(database.find(&cartridge).unwrap().mapper, Some(2));
+
+        let database =
+            HeaderDatabase::from_toml(&format!("[[rom]]\ncrc32 = {}\nmapper = 1\n", crc32))
+                .unwrap();
+        assert_eq!(database.find(&cartridge).unwrap().mapper, Some(1));
+
+        let mut other = test_cartridge();
+        other.prg_rom[0] ^= 0xFF;
+        assert!(database.find(&other).is_none());
+    }
+
+    #[test]
+    fn test_apply_corrections() {
+        let mut cartridge = test_cartridge();
+        cartridge.ram.prg_ram = 8 * 1024;
+
+        let entry = HeaderEntry {
+            mapper: Some(4),
+            submapper: Some(0),
+            mirroring: Some(Mirroring::Vertical),
+            battery: Some(true),
+            region: Some(Region::Pal),
+            ..HeaderEntry::default()
+        };
+        let changes = entry.apply(&mut cartridge);
+        assert_eq!(
+            changes,
+            [
+                "mapper 0 -> 4",
+                "mirroring Horizontal -> Vertical",
+                "battery false -> true",
+                "region NTSC -> PAL",
+            ]
+        );
+        assert_eq!(cartridge.mapper, 4);
+        assert_eq!(cartridge.mirroring, Mirroring::Vertical);
+        assert!(cartridge.has_battery);
+        assert_eq!(
+            (cartridge.ram.prg_ram, cartridge.ram.prg_nvram),
+            (0, 8 * 1024)
+        );
+        assert_eq!(cartridge.region, Region::Pal);
+
+        // Applying again changes nothing
+        assert!(entry.apply(&mut cartridge).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_database_rejected() {
+        let result = HeaderDatabase::from_toml("[[rom]]\ncrc32 = 1\nmaper = 4\n");
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -13,6 +13,7 @@
 // - CHR-ROM: 8KB read-only pattern memory
 // - CHR-RAM: 8KB writable pattern memory
 
+use super::{MapperError, StateReader, StateWriter};
 use crate::cartridge::{Cartridge, Mapper, Mirroring};
 
 /// Mapper 0 implementation (NROM)
@@ -136,6 +137,29 @@ impl Mapper for Mapper0 {
     fn mirroring(&self) -> Mirroring {
         self.mirroring
     }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => Some((address - 0x8000) as usize % self.prg_rom.len()),
+            _ => None,
+        }
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        if self.chr_is_ram {
+            writer.write_bytes(&self.chr_mem);
+        }
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        if self.chr_is_ram {
+            reader.read_bytes_into(&mut self.chr_mem)?;
+        }
+        Ok(reader.finish()?)
+    }
 }
 
 #[cfg(test)]
@@ -163,6 +187,7 @@ mod tests {
             mapper: 0,
             mirroring,
             has_battery: false,
+            ..Cartridge::new()
         }
     }
 
@@ -36,6 +36,7 @@
 //   Bits 0-3: Select PRG-ROM bank
 //   Bit 4: PRG-RAM chip enable (0=enabled, but often ignored)
 
+use super::{MapperError, StateReader, StateWriter};
 use crate::cartridge::{Cartridge, Mapper, Mirroring};
 
 /// PRG-ROM bank size (16KB)
@@ -115,6 +116,12 @@ pub struct Mapper1 {
     /// Number of writes to the shift register (0-4, resets to 0 after 5th write)
     write_count: u8,
 
+    // Write timing
+    /// CPU cycles elapsed (see `cpu_clock`)
+    cpu_cycle: u64,
+    /// CPU cycle of the last serial port write that was accepted
+    last_write_cycle: Option<u64>,
+
     // Internal registers
     /// Control register (mirroring and banking modes)
     control: u8,
@@ -158,6 +165,9 @@ impl Mapper1 {
             shift_register: 0,
             write_count: 0,
 
+            cpu_cycle: 0,
+            last_write_cycle: None,
+
             // Initialize registers to power-on state
             // Control register defaults: last bank mode, 4KB CHR mode, horizontal mirroring
             control: 0x1F, // bits 0-1 = 11 (horizontal), bits 2-3 = 11 (fix last), bit 4 = 1 (4KB CHR)
@@ -345,6 +355,16 @@ impl Mapper for Mapper1 {
             }
             // Mapper registers (via serial write)
             0x8000..=0xFFFF => {
+                // Writes on the cycle after an accepted write are ignored. This
+                // drops the second write of read-modify-write instructions.
+                if self
+                    .last_write_cycle
+                    .is_some_and(|cycle| cycle + 1 == self.cpu_cycle)
+                {
+                    return;
+                }
+                self.last_write_cycle = Some(self.cpu_cycle);
+
                 // Check for reset bit
                 if value & 0x80 != 0 {
                     self.reset_shift_register();
@@ -387,10 +407,21 @@ impl Mapper for Mapper1 {
         // Writes to CHR-ROM are ignored
     }
 
+    fn cpu_clock(&mut self) {
+        self.cpu_cycle += 1;
+    }
+
     fn mirroring(&self) -> Mirroring {
         self.get_mirroring()
     }
 
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => Some(self.map_prg_address(address)),
+            _ => None,
+        }
+    }
+
     fn prg_ram(&self) -> Option<&[u8]> {
         Some(&self.prg_ram)
     }
@@ -398,6 +429,36 @@ impl Mapper for Mapper1 {
     fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
         Some(&mut self.prg_ram)
     }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.write_u8(self.shift_register);
+        writer.write_u8(self.write_count);
+        writer.write_u8(self.control);
+        writer.write_u8(self.chr_bank_0);
+        writer.write_u8(self.chr_bank_1);
+        writer.write_u8(self.prg_bank);
+        writer.write_bytes(&self.prg_ram);
+        if self.chr_is_ram {
+            writer.write_bytes(&self.chr_mem);
+        }
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        self.shift_register = reader.read_u8()?;
+        self.write_count = reader.read_u8()?;
+        self.control = reader.read_u8()?;
+        self.chr_bank_0 = reader.read_u8()?;
+        self.chr_bank_1 = reader.read_u8()?;
+        self.prg_bank = reader.read_u8()?;
+        reader.read_bytes_into(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            reader.read_bytes_into(&mut self.chr_mem)?;
+        }
+        Ok(reader.finish()?)
+    }
 }
 
 #[cfg(test)]
@@ -416,6 +477,7 @@ mod tests {
             mapper: 1,
             mirroring: Mirroring::Horizontal,
             has_battery: false,
+            ..Cartridge::new()
         }
     }
 
@@ -885,6 +947,7 @@ mod tests {
             mapper: 1,
             mirroring: Mirroring::Horizontal,
             has_battery: false,
+            ..Cartridge::new()
         };
 
         let mut mapper = Mapper1::new(cartridge);
@@ -1065,4 +1128,23 @@ mod tests {
         // Verify mirroring
         assert_eq!(mapper.mirroring(), Mirroring::Horizontal);
     }
+
+    #[test]
+    fn test_consecutive_cycle_writes_ignored() {
+        let cartridge = create_test_cartridge(8, 2);
+        let mut mapper = Mapper1::new(cartridge);
+
+        // Load PRG bank 3 (%00011), with the write right after the first bit
+        // landing on the next CPU cycle
+        mapper.cpu_write(0xE000, 0x01);
+        mapper.cpu_clock();
+        mapper.cpu_write(0xE000, 0x00); // ignored
+        for bit in [0x01, 0x00, 0x00, 0x00] {
+            mapper.cpu_clock();
+            mapper.cpu_clock();
+            mapper.cpu_write(0xE000, bit);
+        }
+
+        assert_eq!(mapper.prg_bank, 3);
+    }
 }
@@ -18,6 +18,7 @@
 // Note: Mapper 11 is very similar to Mapper 66, but with different
 // bit assignments and support for more CHR banks.
 
+use super::{MapperError, StateReader, StateWriter};
 use crate::cartridge::{Cartridge, Mapper, Mirroring};
 
 /// PRG-ROM bank size (32KB)
@@ -119,6 +120,30 @@ impl Mapper for Mapper11 {
     fn mirroring(&self) -> Mirroring {
         self.mirroring
     }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => {
+                let bank = (self.prg_bank as usize) % self.prg_banks;
+                Some(bank * PRG_BANK_SIZE + (address - 0x8000) as usize)
+            }
+            _ => None,
+        }
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.write_u8(self.prg_bank);
+        writer.write_u8(self.chr_bank);
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        self.prg_bank = reader.read_u8()?;
+        self.chr_bank = reader.read_u8()?;
+        Ok(reader.finish()?)
+    }
 }
 
 #[cfg(test)]
@@ -137,6 +162,7 @@ mod tests {
             mapper: 11,
             mirroring: Mirroring::Vertical,
             has_battery: false,
+            ..Cartridge::new()
         }
     }
 
@@ -0,0 +1,748 @@
+// Mapper 19 (Namco 163) - PRG/CHR banking, CHR-ROM nametables, cycle IRQ and wavetable audio
+//
+// Memory Layout:
+// - CPU $4800-$4FFF: Internal RAM data port
+// - CPU $5000-$5FFF: IRQ counter
+// - CPU $6000-$7FFF: 8KB PRG-RAM (write-protected in 2KB blocks)
+// - CPU $8000-$9FFF: 8KB PRG-ROM bank (switchable)
+// - CPU $A000-$BFFF: 8KB PRG-ROM bank (switchable)
+// - CPU $C000-$DFFF: 8KB PRG-ROM bank (switchable)
+// - CPU $E000-$FFFF: 8KB PRG-ROM bank (fixed to last bank)
+// - PPU $0000-$1FFF: Eight 1KB CHR-ROM banks
+// - PPU $2000-$2FFF: Each nametable is CIRAM page 0 or 1, or a 1KB CHR-ROM bank
+//
+// Features:
+// - PRG-ROM size: up to 512KB
+// - CHR-ROM size: up to 256KB
+// - 15-bit IRQ counter clocked every CPU cycle
+// - 128 bytes of internal RAM, battery-backed with the PRG-RAM on some boards
+// - Expansion audio: up to 8 wavetable channels playing 4-bit samples from
+//   the internal RAM, whose upper 64 bytes also hold the channel registers
+//
+// Register Interface:
+// - $4800: Internal RAM data at the selected address
+// - $5000: IRQ counter bits 0-7 (R/W, writes acknowledge the IRQ)
+// - $5800: IRQ counter bits 8-14 and enable in bit 7 (R/W, writes acknowledge)
+// - $8000-$B800: CHR banks for $0000-$1C00, one register every $800
+//   (values $E0-$FF select CIRAM unless the $E800 bit for that half is set)
+// - $C000-$D800: Nametable banks for $2000-$2C00, one register every $800
+//   (values $E0-$FF select CIRAM page (value & 1), others a CHR-ROM bank)
+// - $E000: Bits 0-5 PRG bank at $8000, bit 6 sound disable
+// - $E800: Bits 0-5 PRG bank at $A000, bit 6/7 CHR $E0-$FF use ROM for
+//   $0000-$0FFF/$1000-$1FFF
+// - $F000: Bits 0-5 PRG bank at $C000
+// - $F800: PRG-RAM write protect (bits 4-7 must be $4; bits 0-3 protect each
+//   2KB block) and internal RAM address (bits 0-6, bit 7 auto-increment)
+//
+// Audio Registers (internal RAM $40-$7F, channel n at $40 + 8n):
+// - +0/+2/+4 bits 0-1: 18-bit frequency
+// - +1/+3/+5: 24-bit phase
+// - +4 bits 2-7: Wave length (256 - value & $FC samples)
+// - +6: Wave address in samples (two 4-bit samples per byte, low nibble first)
+// - +7: Bits 0-3 volume; $7F bits 4-6 enabled channels - 1 (channels 7 down)
+//
+// Pattern banks pointing at CIRAM ($E0-$FF with the $E800 bit clear) are not
+// emulated and read CHR-ROM instead.
+
+use super::{MapperError, StateReader, StateWriter};
+use crate::cartridge::{Cartridge, Mapper, Mirroring, NametableSource};
+
+/// PRG-ROM bank size (8KB)
+const PRG_BANK_SIZE: usize = 8 * 1024;
+
+/// CHR-ROM 1KB bank size
+const CHR_1KB_BANK_SIZE: usize = 1024;
+
+/// PRG-RAM size (8KB)
+const PRG_RAM_SIZE: usize = 8 * 1024;
+
+/// Internal RAM size (128 bytes)
+const INTERNAL_RAM_SIZE: usize = 128;
+
+/// Largest IRQ counter value; the counter stops and raises the IRQ here
+const IRQ_COUNTER_MAX: u16 = 0x7FFF;
+
+/// CPU cycles spent updating each audio channel
+const CYCLES_PER_CHANNEL: u8 = 15;
+
+/// Output level of one step of a channel's output
+///
+/// One channel at full volume swings about as far as both APU pulse channels
+/// at full volume.
+const N163_STEP_LEVEL: f32 = 0.00125;
+
+/// Namco 163 mapper implementation
+pub struct Mapper19 {
+    /// PRG-ROM data
+    prg_rom: Vec<u8>,
+    /// CHR-ROM data
+    chr_rom: Vec<u8>,
+    /// PRG-RAM followed by the internal RAM, the layout of the `.sav` image
+    ram: Vec<u8>,
+
+    /// 8KB PRG banks at $8000, $A000 and $C000 ($E000 bit 6 is sound disable)
+    prg_registers: [u8; 3],
+    /// 1KB CHR banks for $0000-$1FFF
+    chr_registers: [u8; 8],
+    /// Nametable banks for $2000-$2FFF
+    nametable_registers: [u8; 4],
+    /// PRG-RAM write protect and internal RAM address port ($F800)
+    protect: u8,
+    /// Internal RAM address (auto-increments on reads as well as writes)
+    ram_address: u8,
+
+    /// IRQ counter (15 bits)
+    irq_counter: u16,
+    /// IRQ enable ($5800 bit 7)
+    irq_enabled: bool,
+    /// IRQ pending flag
+    irq_pending: bool,
+
+    /// Channel being updated (7 down to 8 - enabled channels)
+    audio_channel: u8,
+    /// CPU cycles into the current channel update
+    audio_cycle: u8,
+    /// Last output of each channel
+    channel_outputs: [i16; 8],
+
+    /// Number of 8KB PRG banks
+    prg_banks: usize,
+    /// Number of 1KB CHR banks
+    chr_banks: usize,
+}
+
+impl Mapper19 {
+    /// Create a new Namco 163 mapper from a cartridge
+    ///
+    /// # Arguments
+    /// * `cartridge` - The cartridge containing ROM data
+    pub fn new(cartridge: Cartridge) -> Self {
+        let prg_rom_size = cartridge.prg_rom.len();
+        let chr_rom_size = cartridge.chr_rom.len();
+
+        // Calculate number of banks
+        let prg_banks = prg_rom_size / PRG_BANK_SIZE;
+        let chr_banks = chr_rom_size / CHR_1KB_BANK_SIZE;
+
+        assert!(
+            prg_banks >= 1,
+            "Namco 163 requires at least one PRG bank (8KB total, got {} banks)",
+            prg_banks
+        );
+        assert!(
+            chr_banks > 0,
+            "Namco 163 requires at least one CHR bank (got {} banks)",
+            chr_banks
+        );
+
+        // Start with the header's mirroring until the game sets the nametables
+        let nametable_registers = match cartridge.mirroring {
+            Mirroring::Horizontal => [0xE0, 0xE0, 0xE1, 0xE1],
+            _ => [0xE0, 0xE1, 0xE0, 0xE1],
+        };
+
+        Mapper19 {
+            prg_rom: cartridge.prg_rom,
+            chr_rom: cartridge.chr_rom,
+            ram: vec![0; PRG_RAM_SIZE + INTERNAL_RAM_SIZE],
+
+            prg_registers: [0; 3],
+            chr_registers: [0; 8],
+            nametable_registers,
+            protect: 0,
+            ram_address: 0,
+
+            irq_counter: 0,
+            irq_enabled: false,
+            irq_pending: false,
+
+            audio_channel: 7,
+            audio_cycle: 0,
+            channel_outputs: [0; 8],
+
+            prg_banks,
+            chr_banks,
+        }
+    }
+
+    /// Get the 128 bytes of internal RAM
+    fn internal_ram(&self) -> &[u8] {
+        &self.ram[PRG_RAM_SIZE..]
+    }
+
+    /// Get the 128 bytes of internal RAM for writing
+    fn internal_ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram[PRG_RAM_SIZE..]
+    }
+
+    /// Move the internal RAM address past an access if auto-increment is on
+    fn advance_ram_address(&mut self) {
+        if self.ram_address & 0x80 != 0 {
+            self.ram_address = 0x80 | (self.ram_address.wrapping_add(1) & 0x7F);
+        }
+    }
+
+    /// Check if a PRG-RAM address is writable
+    fn prg_ram_writable(&self, address: u16) -> bool {
+        let block = (address - 0x6000) >> 11;
+        self.protect & 0xF0 == 0x40 && self.protect & (1 << block) == 0
+    }
+
+    /// Check if the sound is disabled ($E000 bit 6)
+    fn sound_disabled(&self) -> bool {
+        self.prg_registers[0] & 0x40 != 0
+    }
+
+    /// Get the number of enabled audio channels (1-8)
+    fn enabled_channels(&self) -> u8 {
+        ((self.internal_ram()[0x7F] >> 4) & 0x07) + 1
+    }
+
+    /// Advance one audio channel by a sample and latch its output
+    fn update_channel(&mut self, channel: u8) {
+        let base = 0x40 + channel as usize * 8;
+        let ram = self.internal_ram_mut();
+
+        let frequency =
+            ram[base] as u32 | (ram[base + 2] as u32) << 8 | (ram[base + 4] as u32 & 0x03) << 16;
+        let phase =
+            ram[base + 1] as u32 | (ram[base + 3] as u32) << 8 | (ram[base + 5] as u32) << 16;
+        let length = 256 - (ram[base + 4] & 0xFC) as u32;
+
+        let phase = (phase + frequency) % (length << 16);
+        ram[base + 1] = phase as u8;
+        ram[base + 3] = (phase >> 8) as u8;
+        ram[base + 5] = (phase >> 16) as u8;
+
+        // Two 4-bit samples per byte, low nibble first
+        let index = ((phase >> 16) as usize + ram[base + 6] as usize) & 0xFF;
+        let sample = (ram[index >> 1] >> ((index & 1) * 4)) & 0x0F;
+        let volume = ram[base + 7] & 0x0F;
+
+        self.channel_outputs[channel as usize] = (sample as i16 - 8) * volume as i16;
+    }
+
+    /// Map CPU address to PRG-ROM offset
+    fn map_prg_address(&self, address: u16) -> usize {
+        let bank = match address {
+            0x8000..=0xDFFF => {
+                self.prg_registers[((address - 0x8000) >> 13) as usize] as usize & 0x3F
+            }
+            _ => self.prg_banks - 1,
+        };
+
+        let offset = (address & 0x1FFF) as usize; // 8KB bank offset
+        (bank % self.prg_banks) * PRG_BANK_SIZE + offset
+    }
+
+    /// Map a 1KB CHR-ROM bank and offset to a CHR-ROM index
+    fn map_chr_bank(&self, bank: u8, address: u16) -> usize {
+        let offset = (address & 0x03FF) as usize; // 1KB bank offset
+        (bank as usize % self.chr_banks) * CHR_1KB_BANK_SIZE + offset
+    }
+
+    /// Get the register for a nametable address
+    fn nametable_register(&self, address: u16) -> u8 {
+        self.nametable_registers[((address >> 10) & 0x03) as usize]
+    }
+}
+
+impl Mapper for Mapper19 {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            0x4800..=0x4FFF => self.internal_ram()[(self.ram_address & 0x7F) as usize],
+            0x5000..=0x57FF => self.irq_counter as u8,
+            0x5800..=0x5FFF => (self.irq_counter >> 8) as u8 | (self.irq_enabled as u8) << 7,
+            // PRG-RAM
+            0x6000..=0x7FFF => self.ram[(address - 0x6000) as usize],
+            // PRG-ROM
+            0x8000..=0xFFFF => {
+                let index = self.map_prg_address(address);
+                self.prg_rom[index]
+            }
+            _ => 0,
+        }
+    }
+
+    fn try_cpu_read(&self, address: u16) -> Option<u8> {
+        match address {
+            0x4800..=0xFFFF => Some(self.cpu_read(address)),
+            _ => None,
+        }
+    }
+
+    fn on_cpu_read(&mut self, address: u16, _value: u8) {
+        if let 0x4800..=0x4FFF = address {
+            self.advance_ram_address();
+        }
+    }
+
+    fn cpu_write(&mut self, address: u16, value: u8) {
+        match address {
+            0x4800..=0x4FFF => {
+                let index = (self.ram_address & 0x7F) as usize;
+                self.internal_ram_mut()[index] = value;
+                self.advance_ram_address();
+            }
+            0x5000..=0x57FF => {
+                self.irq_counter = (self.irq_counter & 0x7F00) | value as u16;
+                self.irq_pending = false;
+            }
+            0x5800..=0x5FFF => {
+                self.irq_counter = (self.irq_counter & 0x00FF) | ((value as u16 & 0x7F) << 8);
+                self.irq_enabled = value & 0x80 != 0;
+                self.irq_pending = false;
+            }
+            // PRG-RAM
+            0x6000..=0x7FFF => {
+                if self.prg_ram_writable(address) {
+                    self.ram[(address - 0x6000) as usize] = value;
+                }
+            }
+            // Mapper registers, one every $800
+            0x8000..=0xBFFF => self.chr_registers[((address - 0x8000) >> 11) as usize] = value,
+            0xC000..=0xDFFF => {
+                self.nametable_registers[((address - 0xC000) >> 11) as usize] = value;
+            }
+            0xE000..=0xF7FF => self.prg_registers[((address - 0xE000) >> 11) as usize] = value,
+            0xF800..=0xFFFF => {
+                self.protect = value;
+                self.ram_address = value;
+            }
+            _ => {}
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => {
+                let bank = self.chr_registers[(address as usize >> 10) & 0x07];
+                self.chr_rom[self.map_chr_bank(bank, address)]
+            }
+            _ => 0,
+        }
+    }
+
+    fn ppu_write(&mut self, _address: u16, _value: u8) {
+        // Writes to CHR-ROM are ignored
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.nametable_registers.map(|bank| bank & 0xE1) {
+            [0xE0, 0xE1, 0xE0, 0xE1] => Mirroring::Vertical,
+            [0xE0, 0xE0, 0xE1, 0xE1] => Mirroring::Horizontal,
+            [0xE0, 0xE0, 0xE0, 0xE0] | [0xE1, 0xE1, 0xE1, 0xE1] => Mirroring::SingleScreen,
+            // Any other arrangement maps the four nametables separately
+            _ => Mirroring::FourScreen,
+        }
+    }
+
+    fn nametable_source(&self, address: u16) -> Option<NametableSource> {
+        Some(match self.nametable_register(address) {
+            bank @ 0xE0..=0xFF => NametableSource::Ciram(bank & 0x01),
+            _ => NametableSource::Cartridge,
+        })
+    }
+
+    fn nametable_read(&self, address: u16) -> u8 {
+        let bank = self.nametable_register(address);
+        self.chr_rom[self.map_chr_bank(bank, address)]
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => Some(self.map_prg_address(address)),
+            _ => None,
+        }
+    }
+
+    fn prg_ram(&self) -> Option<&[u8]> {
+        Some(&self.ram)
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.ram)
+    }
+
+    fn audio_ram(&self) -> Option<&[u8]> {
+        Some(self.internal_ram())
+    }
+
+    fn cpu_clock(&mut self) {
+        if self.irq_enabled && self.irq_counter < IRQ_COUNTER_MAX {
+            self.irq_counter += 1;
+            if self.irq_counter == IRQ_COUNTER_MAX {
+                self.irq_pending = true;
+            }
+        }
+
+        if self.sound_disabled() {
+            return;
+        }
+
+        // Channels are updated one at a time, from 7 downwards
+        self.audio_cycle += 1;
+        if self.audio_cycle == CYCLES_PER_CHANNEL {
+            self.audio_cycle = 0;
+            self.update_channel(self.audio_channel);
+
+            let lowest = 8 - self.enabled_channels();
+            self.audio_channel = if self.audio_channel <= lowest {
+                7
+            } else {
+                self.audio_channel - 1
+            };
+        }
+    }
+
+    fn irq_pending(&self) -> bool {
+        self.irq_pending
+    }
+
+    fn irq_acknowledge(&mut self) {
+        self.irq_pending = false;
+    }
+
+    fn audio_output(&self) -> f32 {
+        if self.sound_disabled() {
+            return 0.0;
+        }
+
+        // The chip outputs one channel at a time, switching every 15 CPU
+        // cycles. Sampling that directly would alias the switching, so use its
+        // average as the analog filtering on the board does.
+        let enabled = self.enabled_channels();
+        let sum: i16 = self.channel_outputs[(8 - enabled) as usize..].iter().sum();
+        sum as f32 / enabled as f32 * N163_STEP_LEVEL
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        for &bank in &self.prg_registers {
+            writer.write_u8(bank);
+        }
+        for &bank in &self.chr_registers {
+            writer.write_u8(bank);
+        }
+        for &bank in &self.nametable_registers {
+            writer.write_u8(bank);
+        }
+        writer.write_u8(self.protect);
+        writer.write_u8(self.ram_address);
+        writer.write_u16(self.irq_counter);
+        writer.write_bool(self.irq_enabled);
+        writer.write_bool(self.irq_pending);
+        writer.write_u8(self.audio_channel);
+        writer.write_u8(self.audio_cycle);
+        for &output in &self.channel_outputs {
+            writer.write_u16(output as u16);
+        }
+        writer.write_bytes(&self.ram);
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        for bank in self.prg_registers.iter_mut() {
+            *bank = reader.read_u8()?;
+        }
+        for bank in self.chr_registers.iter_mut() {
+            *bank = reader.read_u8()?;
+        }
+        for bank in self.nametable_registers.iter_mut() {
+            *bank = reader.read_u8()?;
+        }
+        self.protect = reader.read_u8()?;
+        self.ram_address = reader.read_u8()?;
+        self.irq_counter = reader.read_u16()? & IRQ_COUNTER_MAX;
+        self.irq_enabled = reader.read_bool()?;
+        self.irq_pending = reader.read_bool()?;
+        self.audio_channel = reader.read_u8()? & 0x07;
+        self.audio_cycle = reader.read_u8()? % CYCLES_PER_CHANNEL;
+        for output in self.channel_outputs.iter_mut() {
+            *output = reader.read_u16()? as i16;
+        }
+        reader.read_bytes_into(&mut self.ram)?;
+        Ok(reader.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a test cartridge
+    ///
+    /// Each 8KB PRG bank is filled with its bank number and each 1KB CHR bank
+    /// with its bank number.
+    fn create_test_cartridge() -> Cartridge {
+        let prg_rom = (0..32 * PRG_BANK_SIZE)
+            .map(|i| (i / PRG_BANK_SIZE) as u8)
+            .collect();
+        let chr_rom = (0..256 * CHR_1KB_BANK_SIZE)
+            .map(|i| (i / CHR_1KB_BANK_SIZE) as u8)
+            .collect();
+
+        Cartridge {
+            prg_rom,
+            chr_rom,
+            trainer: None,
+            mapper: 19,
+            mirroring: Mirroring::Vertical,
+            has_battery: true,
+            ..Cartridge::new()
+        }
+    }
+
+    /// Clock the mapper for a number of CPU cycles
+    fn run(mapper: &mut Mapper19, cycles: usize) {
+        for _ in 0..cycles {
+            mapper.cpu_clock();
+        }
+    }
+
+    /// Write bytes to internal RAM through the data port
+    fn write_internal_ram(mapper: &mut Mapper19, address: u8, data: &[u8]) {
+        mapper.cpu_write(0xF800, 0x80 | address);
+        for &byte in data {
+            mapper.cpu_write(0x4800, byte);
+        }
+    }
+
+    /// Read the internal RAM data port as a CPU cycle would
+    fn read_internal_ram(mapper: &mut Mapper19) -> u8 {
+        let value = mapper.cpu_read(0x4800);
+        mapper.on_cpu_read(0x4800, value);
+        value
+    }
+
+    #[test]
+    fn test_mapper19_creation() {
+        let mapper = Mapper19::new(create_test_cartridge());
+
+        assert_eq!(mapper.prg_banks, 32);
+        assert_eq!(mapper.chr_banks, 256);
+        assert_eq!(mapper.mirroring(), Mirroring::Vertical);
+        assert_eq!(mapper.cpu_read(0xE000), 31);
+        assert_eq!(mapper.prg_ram().unwrap().len(), 8 * 1024 + 128);
+        assert_eq!(mapper.audio_output(), 0.0);
+    }
+
+    #[test]
+    fn test_prg_bank_switching() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+
+        mapper.cpu_write(0xE000, 4);
+        mapper.cpu_write(0xE800, 5);
+        mapper.cpu_write(0xF000, 6);
+        assert_eq!(mapper.cpu_read(0x8000), 4);
+        assert_eq!(mapper.cpu_read(0xA000), 5);
+        assert_eq!(mapper.cpu_read(0xC000), 6);
+        assert_eq!(mapper.cpu_read(0xE000), 31);
+
+        // The sound disable bit is not part of the bank number
+        mapper.cpu_write(0xE000, 0x44);
+        assert_eq!(mapper.cpu_read(0x8000), 4);
+    }
+
+    #[test]
+    fn test_chr_banking() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+
+        for register in 0..8u16 {
+            mapper.cpu_write(0x8000 + register * 0x800, 0x30 + register as u8);
+        }
+        let banks: Vec<u8> = (0..8).map(|slot| mapper.ppu_read(slot * 0x400)).collect();
+        assert_eq!(banks, [0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37]);
+    }
+
+    #[test]
+    fn test_nametables_from_ciram_and_chr_rom() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+
+        // Horizontal arrangement from CIRAM
+        for (i, bank) in [0xE0, 0xE0, 0xE1, 0xE1].into_iter().enumerate() {
+            mapper.cpu_write(0xC000 + i as u16 * 0x800, bank);
+        }
+        assert_eq!(mapper.mirroring(), Mirroring::Horizontal);
+        assert_eq!(
+            mapper.nametable_source(0x2800),
+            Some(NametableSource::Ciram(1))
+        );
+
+        // A CHR-ROM bank as the third nametable
+        mapper.cpu_write(0xD000, 0x42);
+        assert_eq!(mapper.mirroring(), Mirroring::FourScreen);
+        assert_eq!(
+            mapper.nametable_source(0x2800),
+            Some(NametableSource::Cartridge)
+        );
+        assert_eq!(mapper.nametable_read(0x2BFF), 0x42);
+        assert_eq!(
+            mapper.nametable_source(0x2C00),
+            Some(NametableSource::Ciram(1))
+        );
+    }
+
+    #[test]
+    fn test_prg_ram_write_protect() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+
+        // Writes need $4x in $F800
+        mapper.cpu_write(0x6000, 0x11);
+        assert_eq!(mapper.cpu_read(0x6000), 0x00);
+
+        // Bit 1 protects $6800-$6FFF only
+        mapper.cpu_write(0xF800, 0x42);
+        mapper.cpu_write(0x6000, 0x11);
+        mapper.cpu_write(0x6800, 0x22);
+        assert_eq!(mapper.cpu_read(0x6000), 0x11);
+        assert_eq!(mapper.cpu_read(0x6800), 0x00);
+    }
+
+    #[test]
+    fn test_internal_ram_port_auto_increment() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+
+        write_internal_ram(&mut mapper, 0x7E, &[0xAA, 0xBB, 0xCC]);
+        assert_eq!(mapper.audio_ram().unwrap()[0x7E..], [0xAA, 0xBB]);
+        assert_eq!(mapper.audio_ram().unwrap()[0x00], 0xCC);
+
+        // CPU reads increment too; without bit 7 the address stays put
+        mapper.cpu_write(0xF800, 0xFE);
+        assert_eq!(read_internal_ram(&mut mapper), 0xAA);
+        assert_eq!(read_internal_ram(&mut mapper), 0xBB);
+        mapper.cpu_write(0xF800, 0x7E);
+        assert_eq!(read_internal_ram(&mut mapper), 0xAA);
+        assert_eq!(read_internal_ram(&mut mapper), 0xAA);
+
+        // Peeking the port leaves the address alone
+        mapper.cpu_write(0xF800, 0xFE);
+        assert_eq!(mapper.try_cpu_read(0x4800), Some(0xAA));
+        assert_eq!(mapper.try_cpu_read(0x4800), Some(0xAA));
+    }
+
+    #[test]
+    fn test_internal_ram_is_saved_with_prg_ram() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+        mapper.cpu_write(0xF800, 0x40);
+        mapper.cpu_write(0x7FFF, 0x12);
+        write_internal_ram(&mut mapper, 0x00, &[0x34]);
+
+        // The .sav image is the PRG-RAM followed by the internal RAM
+        let sav = mapper.prg_ram().unwrap().to_vec();
+        assert_eq!(sav[PRG_RAM_SIZE - 1], 0x12);
+        assert_eq!(sav[PRG_RAM_SIZE], 0x34);
+
+        let mut restored = Mapper19::new(create_test_cartridge());
+        restored.prg_ram_mut().unwrap().copy_from_slice(&sav);
+        restored.cpu_write(0xF800, 0x00);
+        assert_eq!(restored.cpu_read(0x7FFF), 0x12);
+        assert_eq!(restored.try_cpu_read(0x4800), Some(0x34));
+    }
+
+    #[test]
+    fn test_irq_counter() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+
+        // Counter at $7FFD, enabled
+        mapper.cpu_write(0x5000, 0xFD);
+        mapper.cpu_write(0x5800, 0xFF);
+        assert_eq!(mapper.cpu_read(0x5800), 0xFF);
+
+        run(&mut mapper, 1);
+        assert!(!mapper.irq_pending());
+        run(&mut mapper, 1);
+        assert!(mapper.irq_pending());
+
+        // The counter stops at $7FFF
+        run(&mut mapper, 10);
+        assert_eq!(mapper.cpu_read(0x5000), 0xFF);
+        assert_eq!(mapper.cpu_read(0x5800), 0xFF);
+
+        // Writing either register acknowledges
+        mapper.cpu_write(0x5000, 0x00);
+        assert!(!mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_irq_counter_disabled() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+
+        mapper.cpu_write(0x5000, 0xFE);
+        mapper.cpu_write(0x5800, 0x7F);
+        run(&mut mapper, 100);
+        assert!(!mapper.irq_pending());
+        assert_eq!(mapper.cpu_read(0x5000), 0xFE);
+    }
+
+    #[test]
+    fn test_wavetable_channel() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+
+        // Wave of 4 samples at address 0: F, 0, 8, 8
+        write_internal_ram(&mut mapper, 0x00, &[0x0F, 0x88]);
+
+        // Channel 7 alone: frequency $10000 (one sample per update), length 4,
+        // wave address 0, volume 15
+        write_internal_ram(
+            &mut mapper,
+            0x78,
+            &[0x00, 0x00, 0x00, 0x00, 0xFD, 0x00, 0x00, 0x0F],
+        );
+
+        let mut outputs = Vec::new();
+        for _ in 0..8 {
+            run(&mut mapper, CYCLES_PER_CHANNEL as usize);
+            outputs.push(mapper.channel_outputs[7]);
+        }
+        assert_eq!(outputs, [-120, 0, 0, 105, -120, 0, 0, 105]);
+        assert!((mapper.audio_output() - 105.0 * N163_STEP_LEVEL).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_time_multiplexed_channels() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+
+        // Constant wave of $F samples
+        write_internal_ram(&mut mapper, 0x00, &[0xFF; 2]);
+
+        // Channel 7 at volume 15 and channel 6 at volume 0, two channels enabled
+        write_internal_ram(&mut mapper, 0x74, &[0xFC, 0x00, 0x00, 0x00]);
+        write_internal_ram(&mut mapper, 0x7C, &[0xFC, 0x00, 0x00, 0x1F]);
+
+        // Channels are updated in turn, 15 cycles each
+        run(&mut mapper, CYCLES_PER_CHANNEL as usize);
+        assert_eq!(mapper.channel_outputs[7], 105);
+        assert_eq!(mapper.audio_channel, 6);
+        run(&mut mapper, CYCLES_PER_CHANNEL as usize);
+        assert_eq!(mapper.channel_outputs[6], 0);
+        assert_eq!(mapper.audio_channel, 7);
+
+        // The output is the average of the enabled channels
+        assert!((mapper.audio_output() - 52.5 * N163_STEP_LEVEL).abs() < 1e-6);
+
+        // Sound disable silences the chip
+        mapper.cpu_write(0xE000, 0x40);
+        assert_eq!(mapper.audio_output(), 0.0);
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let mut mapper = Mapper19::new(create_test_cartridge());
+        mapper.cpu_write(0xE000, 7);
+        mapper.cpu_write(0xC800, 0x10);
+        mapper.cpu_write(0x5800, 0x80);
+        write_internal_ram(&mut mapper, 0x7C, &[0xF0, 0x00, 0x00, 0x0A]);
+        run(&mut mapper, 500);
+        let state = mapper.save_state();
+
+        let mut restored = Mapper19::new(create_test_cartridge());
+        restored.load_state(&state).unwrap();
+        assert_eq!(restored.cpu_read(0x8000), 7);
+        assert_eq!(restored.nametable_read(0x2400), 0x10);
+        assert_eq!(restored.cpu_read(0x5000), mapper.cpu_read(0x5000));
+        assert_eq!(restored.audio_ram(), mapper.audio_ram());
+        assert_eq!(restored.audio_output(), mapper.audio_output());
+    }
+}
@@ -23,6 +23,7 @@
 // - Duck Tales
 // - Metal Gear
 
+use super::{MapperError, StateReader, StateWriter};
 use crate::cartridge::{Cartridge, Mapper, Mirroring};
 
 /// PRG-ROM bank size (16KB)
@@ -162,6 +163,13 @@ impl Mapper for Mapper2 {
         self.mirroring
     }
 
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => Some(self.map_prg_address(address)),
+            _ => None,
+        }
+    }
+
     fn prg_ram(&self) -> Option<&[u8]> {
         // UxROM doesn't typically have PRG-RAM
         None
@@ -171,6 +179,20 @@ impl Mapper for Mapper2 {
         // UxROM doesn't typically have PRG-RAM
         None
     }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.write_u8(self.prg_bank);
+        writer.write_bytes(&self.chr_ram);
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        self.prg_bank = reader.read_u8()?;
+        reader.read_bytes_into(&mut self.chr_ram)?;
+        Ok(reader.finish()?)
+    }
 }
 
 #[cfg(test)]
@@ -189,6 +211,7 @@ mod tests {
             mapper: 2,
             mirroring,
             has_battery: false,
+            ..Cartridge::new()
         }
     }
 
@@ -0,0 +1,769 @@
+// Mappers 21, 22, 23, 25 (Konami VRC2/VRC4) - Fine-grained banking with a CPU-cycle IRQ
+//
+// Memory Layout:
+// - CPU $6000-$7FFF: 8KB PRG-RAM (VRC4), or PRG-RAM / microwire latch (VRC2)
+// - CPU $8000-$9FFF: 8KB PRG-ROM bank (switchable, or fixed to second-to-last)
+// - CPU $A000-$BFFF: 8KB PRG-ROM bank (switchable)
+// - CPU $C000-$DFFF: 8KB PRG-ROM bank (fixed to second-to-last, or switchable)
+// - CPU $E000-$FFFF: 8KB PRG-ROM bank (fixed to last bank)
+// - PPU $0000-$1FFF: Eight 1KB CHR-ROM banks (switchable)
+//
+// Features:
+// - PRG-ROM size: up to 256KB
+// - CHR-ROM size: up to 256KB (VRC2) or 512KB (VRC4)
+// - VRC4 only: PRG swap mode, one-screen mirroring and the VRC IRQ counter
+// - VRC2 only: 1-bit microwire latch at $6000-$6FFF on boards without PRG-RAM
+//
+// Address Decoding:
+// Registers are selected by A12-A15 plus two low address lines, and every
+// board wires different CPU address lines to the chip. The submapper picks the
+// wiring (register bit 0, register bit 1):
+// - 21/1 VRC4a: A1, A2        - 21/2 VRC4c: A6, A7
+// - 22/0 VRC2a: A1, A0 (CHR banks are in 2KB units)
+// - 23/1 VRC4f: A0, A1        - 23/2 VRC4e: A2, A3        - 23/3 VRC2b: A0, A1
+// - 25/1 VRC4b: A1, A0        - 25/2 VRC4d: A3, A2        - 25/3 VRC2c: A1, A0
+// Without a submapper both wirings of the mapper number are ORed together and
+// the board is treated as a VRC4, which also runs the VRC2 games.
+//
+// Register Interface (after decoding):
+// - $8000-$8003: PRG bank at $8000 (or $C000 in swap mode), 5 bits
+// - $9000-$9001: Mirroring (0 = vertical, 1 = horizontal, 2/3 = one-screen A/B)
+//   VRC2 decodes $9000-$9003 as mirroring and only uses bit 0
+// - $9002-$9003: VRC4 only. Bit 1: PRG swap mode, bit 0: PRG-RAM enable
+// - $A000-$A003: PRG bank at $A000, 5 bits
+// - $B000-$E003: CHR banks, written as low/high nibble pairs
+//   $B000/$B001 = bank 0, $B002/$B003 = bank 1, ... $E002/$E003 = bank 7
+// - $F000/$F001: VRC4 IRQ latch low/high nibble
+// - $F002: VRC4 IRQ control
+// - $F003: VRC4 IRQ acknowledge
+
+use super::vrc_irq::VrcIrq;
+use super::{MapperError, StateReader, StateWriter};
+use crate::cartridge::{Cartridge, Mapper, Mirroring, NametableSource};
+
+/// PRG-ROM bank size (8KB)
+const PRG_BANK_SIZE: usize = 8 * 1024;
+
+/// CHR-ROM 1KB bank size
+const CHR_1KB_BANK_SIZE: usize = 1024;
+
+/// PRG-RAM size (8KB)
+const PRG_RAM_SIZE: usize = 8 * 1024;
+
+/// Konami chip on the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Chip {
+    Vrc2,
+    Mapper21,
+}
+
+/// Mappers 21, 22, 23 and 25 implementation (Konami VRC2/VRC4)
+///
+/// VRC2 and VRC4 boards are used by games like:
+/// - Contra (J) (VRC2)
+/// - Gradius II (VRC4)
+/// - Ganbare Goemon 2 (VRC4)
+/// - Tiny Toon Adventures (J) (VRC4)
+pub struct Mapper21 {
+    /// PRG-ROM data
+    prg_rom: Vec<u8>,
+    /// CHR-ROM or CHR-RAM data
+    chr_mem: Vec<u8>,
+    /// PRG-RAM (empty on VRC2 boards that use the microwire latch)
+    prg_ram: Vec<u8>,
+    /// Whether CHR memory is RAM (writable) or ROM (read-only)
+    chr_is_ram: bool,
+
+    // Board configuration
+    /// Chip on the board
+    chip: Chip,
+    /// Address lines that set bit 0 of the register index
+    register_bit0: u16,
+    /// Address lines that set bit 1 of the register index
+    register_bit1: u16,
+    /// Whether CHR banks are in 2KB units (VRC2a)
+    chr_bank_shift: bool,
+    /// Whether the board was guessed from the mapper number alone
+    guessed: bool,
+
+    // Internal registers
+    /// PRG bank registers ($8000 and $A000)
+    prg_banks_select: [u8; 2],
+    /// CHR bank registers (eight 1KB banks)
+    chr_banks_select: [u16; 8],
+    /// Mirroring mode (0-3)
+    mirroring_mode: u8,
+    /// PRG swap mode: $C000 switchable and $8000 fixed
+    prg_swap: bool,
+    /// PRG-RAM enable (VRC4)
+    prg_ram_enabled: bool,
+    /// Microwire latch (VRC2 without PRG-RAM)
+    microwire_latch: u8,
+    /// IRQ counter (VRC4)
+    irq: VrcIrq,
+
+    // Derived state
+    /// Number of 8KB PRG-ROM banks
+    prg_banks: usize,
+    /// Number of 1KB CHR banks
+    chr_banks: usize,
+}
+
+impl Mapper21 {
+    /// Create a new VRC2/VRC4 instance from a cartridge
+    ///
+    /// The mapper and submapper numbers select the chip and its address
+    /// wiring. VRC2 boards only get PRG-RAM when the header declares
+    /// battery-backed RAM; the others expose the microwire latch instead.
+    ///
+    /// # Arguments
+    /// * `cartridge` - The cartridge containing ROM data
+    pub fn new(cartridge: Cartridge) -> Self {
+        let prg_rom_size = cartridge.prg_rom.len();
+        let chr_mem_size = cartridge.chr_rom.len();
+
+        // Calculate number of banks
+        let prg_banks = prg_rom_size / PRG_BANK_SIZE;
+        let chr_banks = chr_mem_size / CHR_1KB_BANK_SIZE;
+
+        assert!(
+            prg_banks >= 2,
+            "VRC2/VRC4 requires at least 2 PRG banks (16KB total, got {} banks)",
+            prg_banks
+        );
+        assert!(
+            chr_banks > 0,
+            "VRC2/VRC4 requires at least one CHR bank (got {} banks)",
+            chr_banks
+        );
+
+        // CHR-RAM is indicated by all zeros in chr_rom
+        let chr_is_ram = chr_mem_size == 8 * 1024 && cartridge.chr_rom.iter().all(|&b| b == 0);
+
+        let (chip, register_bit0, register_bit1, guessed) =
+            Self::board(cartridge.mapper, cartridge.submapper);
+
+        let prg_ram = if chip == Chip::Vrc2 && cartridge.ram.prg_nvram == 0 {
+            Vec::new()
+        } else {
+            vec![0; PRG_RAM_SIZE]
+        };
+
+        Mapper21 {
+            prg_rom: cartridge.prg_rom,
+            chr_mem: cartridge.chr_rom,
+            prg_ram,
+            chr_is_ram,
+
+            chip,
+            register_bit0,
+            register_bit1,
+            chr_bank_shift: cartridge.mapper == 22,
+            guessed,
+
+            prg_banks_select: [0, 0],
+            chr_banks_select: [0; 8],
+            mirroring_mode: match cartridge.mirroring {
+                Mirroring::Horizontal => 1,
+                _ => 0,
+            },
+            prg_swap: false,
+            prg_ram_enabled: false,
+            microwire_latch: 0,
+            irq: VrcIrq::new(),
+
+            prg_banks,
+            chr_banks,
+        }
+    }
+
+    /// Identify the board from the mapper and submapper numbers
+    ///
+    /// # Arguments
+    /// * `mapper` - iNES mapper number (21, 22, 23 or 25)
+    /// * `submapper` - NES 2.0 submapper number, 0 if unknown
+    ///
+    /// # Returns
+    /// The chip, the address lines for register bits 0 and 1, and whether the
+    /// wiring was guessed
+    fn board(mapper: u16, submapper: u8) -> (Chip, u16, u16, bool) {
+        const A0: u16 = 1 << 0;
+        const A1: u16 = 1 << 1;
+        const A2: u16 = 1 << 2;
+        const A3: u16 = 1 << 3;
+        const A6: u16 = 1 << 6;
+        const A7: u16 = 1 << 7;
+
+        match (mapper, submapper) {
+            (21, 1) => (Chip::Mapper21, A1, A2, false),
+            (21, 2) => (Chip::Mapper21, A6, A7, false),
+            (21, _) => (Chip::Mapper21, A1 | A6, A2 | A7, true),
+            (22, _) => (Chip::Vrc2, A1, A0, false),
+            (23, 1) => (Chip::Mapper21, A0, A1, false),
+            (23, 2) => (Chip::Mapper21, A2, A3, false),
+            (23, 3) => (Chip::Vrc2, A0, A1, false),
+            (23, _) => (Chip::Mapper21, A0 | A2, A1 | A3, true),
+            (25, 1) => (Chip::Mapper21, A1, A0, false),
+            (25, 2) => (Chip::Mapper21, A3, A2, false),
+            (25, 3) => (Chip::Vrc2, A1, A0, false),
+            _ => (Chip::Mapper21, A1 | A3, A0 | A2, true),
+        }
+    }
+
+    /// Decode a CPU address to its register ($8000, $8001, ... $F003)
+    fn decode_register(&self, address: u16) -> u16 {
+        let mut register = address & 0xF000;
+        if address & self.register_bit0 != 0 {
+            register |= 1;
+        }
+        if address & self.register_bit1 != 0 {
+            register |= 2;
+        }
+        register
+    }
+
+    /// Check if PRG-RAM responds at $6000-$7FFF
+    ///
+    /// Guessed boards ignore the VRC4 enable bit so VRC2 games keep their RAM.
+    fn prg_ram_accessible(&self) -> bool {
+        !self.prg_ram.is_empty()
+            && (self.chip == Chip::Vrc2 || self.guessed || self.prg_ram_enabled)
+    }
+
+    /// Map CPU address to PRG-ROM offset
+    fn map_prg_address(&self, address: u16) -> usize {
+        let second_last = self.prg_banks - 2;
+        let bank = match (address, self.prg_swap) {
+            (0x8000..=0x9FFF, false) | (0xC000..=0xDFFF, true) => self.prg_banks_select[0] as usize,
+            (0x8000..=0x9FFF, true) | (0xC000..=0xDFFF, false) => second_last,
+            (0xA000..=0xBFFF, _) => self.prg_banks_select[1] as usize,
+            _ => self.prg_banks - 1,
+        };
+
+        let offset = (address & 0x1FFF) as usize; // 8KB bank offset
+        (bank % self.prg_banks) * PRG_BANK_SIZE + offset
+    }
+
+    /// Map PPU address to CHR offset
+    fn map_chr_address(&self, address: u16) -> usize {
+        let mut bank = self.chr_banks_select[(address as usize >> 10) & 0x07] as usize;
+        if self.chr_bank_shift {
+            bank >>= 1;
+        }
+
+        let offset = (address & 0x03FF) as usize; // 1KB bank offset
+        (bank % self.chr_banks) * CHR_1KB_BANK_SIZE + offset
+    }
+
+    /// Write one nibble of a CHR bank register
+    ///
+    /// # Arguments
+    /// * `register` - Decoded register ($B000-$E003)
+    /// * `value` - Value written; only the low nibble (or 5 bits for the high nibble) is used
+    fn write_chr_bank(&mut self, register: u16, value: u8) {
+        // $B000/$B001 -> bank 0, $B002/$B003 -> bank 1, $C000 -> bank 2, ...
+        let index = (((register >> 12) - 0xB) * 2 + ((register >> 1) & 1)) as usize;
+        let bank = &mut self.chr_banks_select[index];
+
+        if register & 1 == 0 {
+            *bank = (*bank & 0x1F0) | (value & 0x0F) as u16;
+        } else {
+            let high_mask = if self.chip == Chip::Mapper21 {
+                0x1F
+            } else {
+                0x0F
+            };
+            *bank = (*bank & 0x0F) | (((value & high_mask) as u16) << 4);
+        }
+    }
+}
+
+impl Mapper for Mapper21 {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            // PRG-RAM
+            0x6000..=0x7FFF if self.prg_ram_accessible() => {
+                let index = (address - 0x6000) as usize;
+                self.prg_ram[index % PRG_RAM_SIZE]
+            }
+            // Microwire latch
+            0x6000..=0x6FFF if self.chip == Chip::Vrc2 && self.prg_ram.is_empty() => {
+                self.microwire_latch
+            }
+            // PRG-ROM
+            0x8000..=0xFFFF => {
+                let index = self.map_prg_address(address);
+                self.prg_rom[index]
+            }
+            _ => 0,
+        }
+    }
+
+    fn try_cpu_read(&self, address: u16) -> Option<u8> {
+        match address {
+            0x6000..=0x7FFF if self.prg_ram_accessible() => Some(self.cpu_read(address)),
+            // Only bit 0 of the latch is driven; the rest reads as 0 here
+            0x6000..=0x6FFF if self.chip == Chip::Vrc2 && self.prg_ram.is_empty() => {
+                Some(self.microwire_latch)
+            }
+            0x8000..=0xFFFF => Some(self.cpu_read(address)),
+            _ => None,
+        }
+    }
+
+    fn cpu_write(&mut self, address: u16, value: u8) {
+        match address {
+            // PRG-RAM
+            0x6000..=0x7FFF if self.prg_ram_accessible() => {
+                let index = (address - 0x6000) as usize;
+                self.prg_ram[index % PRG_RAM_SIZE] = value;
+            }
+            // Microwire latch
+            0x6000..=0x6FFF if self.chip == Chip::Vrc2 && self.prg_ram.is_empty() => {
+                self.microwire_latch = value & 0x01;
+            }
+            // Mapper registers
+            0x8000..=0xFFFF => {
+                let register = self.decode_register(address);
+                match (register, self.chip) {
+                    // PRG bank 0
+                    (0x8000..=0x8003, _) => {
+                        self.prg_banks_select[0] = value & 0x1F;
+                    }
+                    // Mirroring
+                    (0x9000..=0x9003, Chip::Vrc2) => {
+                        self.mirroring_mode = value & 0x01;
+                    }
+                    (0x9000..=0x9001, Chip::Mapper21) => {
+                        self.mirroring_mode = value & 0x03;
+                    }
+                    // PRG swap mode and PRG-RAM enable
+                    (0x9002..=0x9003, Chip::Mapper21) => {
+                        self.prg_swap = value & 0x02 != 0;
+                        self.prg_ram_enabled = value & 0x01 != 0;
+                    }
+                    // PRG bank 1
+                    (0xA000..=0xA003, _) => {
+                        self.prg_banks_select[1] = value & 0x1F;
+                    }
+                    // CHR banks
+                    (0xB000..=0xEFFF, _) => {
+                        self.write_chr_bank(register, value);
+                    }
+                    // IRQ
+                    (0xF000, Chip::Mapper21) => self.irq.write_latch_low(value),
+                    (0xF001, Chip::Mapper21) => self.irq.write_latch_high(value),
+                    (0xF002, Chip::Mapper21) => self.irq.write_control(value),
+                    (0xF003, Chip::Mapper21) => self.irq.acknowledge(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => {
+                let index = self.map_chr_address(address);
+                self.chr_mem[index % self.chr_mem.len()]
+            }
+            _ => 0,
+        }
+    }
+
+    fn ppu_write(&mut self, address: u16, value: u8) {
+        if self.chr_is_ram {
+            if let 0x0000..=0x1FFF = address {
+                let chr_len = self.chr_mem.len();
+                let index = self.map_chr_address(address);
+                self.chr_mem[index % chr_len] = value;
+            }
+        }
+        // Writes to CHR-ROM are ignored
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.mirroring_mode {
+            0 => Mirroring::Vertical,
+            1 => Mirroring::Horizontal,
+            _ => Mirroring::SingleScreen,
+        }
+    }
+
+    fn nametable_source(&self, _address: u16) -> Option<NametableSource> {
+        // One-screen B uses the second page of internal VRAM
+        match self.mirroring_mode {
+            2 => Some(NametableSource::Ciram(0)),
+            3 => Some(NametableSource::Ciram(1)),
+            _ => None,
+        }
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => Some(self.map_prg_address(address)),
+            _ => None,
+        }
+    }
+
+    fn prg_ram(&self) -> Option<&[u8]> {
+        (!self.prg_ram.is_empty()).then_some(self.prg_ram.as_slice())
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        (!self.prg_ram.is_empty()).then_some(self.prg_ram.as_mut_slice())
+    }
+
+    fn cpu_clock(&mut self) {
+        if self.chip == Chip::Mapper21 {
+            self.irq.clock();
+        }
+    }
+
+    fn irq_pending(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn irq_acknowledge(&mut self) {
+        self.irq.clear_pending();
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        for &bank in &self.prg_banks_select {
+            writer.write_u8(bank);
+        }
+        for &bank in &self.chr_banks_select {
+            writer.write_u16(bank);
+        }
+        writer.write_u8(self.mirroring_mode);
+        writer.write_bool(self.prg_swap);
+        writer.write_bool(self.prg_ram_enabled);
+        writer.write_u8(self.microwire_latch);
+        self.irq.save_state(&mut writer);
+        writer.write_bytes(&self.prg_ram);
+        if self.chr_is_ram {
+            writer.write_bytes(&self.chr_mem);
+        }
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        for bank in self.prg_banks_select.iter_mut() {
+            *bank = reader.read_u8()?;
+        }
+        for bank in self.chr_banks_select.iter_mut() {
+            *bank = reader.read_u16()?;
+        }
+        self.mirroring_mode = reader.read_u8()?;
+        self.prg_swap = reader.read_bool()?;
+        self.prg_ram_enabled = reader.read_bool()?;
+        self.microwire_latch = reader.read_u8()?;
+        self.irq.load_state(&mut reader)?;
+        reader.read_bytes_into(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            reader.read_bytes_into(&mut self.chr_mem)?;
+        }
+        Ok(reader.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a test cartridge
+    ///
+    /// Each 8KB PRG bank is filled with its bank number and each 1KB CHR bank
+    /// with its bank number.
+    fn create_test_cartridge(mapper: u16, submapper: u8) -> Cartridge {
+        let prg_rom = (0..32 * PRG_BANK_SIZE)
+            .map(|i| (i / PRG_BANK_SIZE) as u8)
+            .collect();
+        let chr_rom = (0..256 * CHR_1KB_BANK_SIZE)
+            .map(|i| (i / CHR_1KB_BANK_SIZE) as u8)
+            .collect();
+
+        Cartridge {
+            prg_rom,
+            chr_rom,
+            trainer: None,
+            mapper,
+            submapper,
+            mirroring: Mirroring::Vertical,
+            has_battery: false,
+            ..Cartridge::new()
+        }
+    }
+
+    #[test]
+    fn test_mapper21_creation() {
+        let mapper = Mapper21::new(create_test_cartridge(21, 1));
+
+        assert_eq!(mapper.chip, Chip::Mapper21);
+        assert_eq!(mapper.prg_banks, 32);
+        assert_eq!(mapper.chr_banks, 256);
+        assert_eq!(mapper.mirroring(), Mirroring::Vertical);
+
+        // The last two banks are fixed at $C000 and $E000
+        assert_eq!(mapper.cpu_read(0xC000), 30);
+        assert_eq!(mapper.cpu_read(0xE000), 31);
+    }
+
+    #[test]
+    fn test_submapper_address_decoding() {
+        // (mapper, submapper, address of register bit 0, address of register bit 1)
+        let boards = [
+            (21, 1, 0x8002, 0x8004),
+            (21, 2, 0x8040, 0x8080),
+            (22, 0, 0x8002, 0x8001),
+            (23, 1, 0x8001, 0x8002),
+            (23, 2, 0x8004, 0x8008),
+            (23, 3, 0x8001, 0x8002),
+            (25, 1, 0x8002, 0x8001),
+            (25, 2, 0x8008, 0x8004),
+            (25, 3, 0x8002, 0x8001),
+        ];
+
+        for (number, submapper, bit0, bit1) in boards {
+            let mut mapper = Mapper21::new(create_test_cartridge(number, submapper));
+
+            // $B000-$B003: CHR bank 0 low/high, CHR bank 1 low/high
+            mapper.cpu_write(0xB000, 0x05);
+            mapper.cpu_write(0xB000 | (bit0 & 0xFFF), 0x01);
+            mapper.cpu_write(0xB000 | (bit1 & 0xFFF), 0x07);
+            mapper.cpu_write(0xB000 | ((bit0 | bit1) & 0xFFF), 0x02);
+
+            let shift = if number == 22 { 1 } else { 0 };
+            assert_eq!(
+                mapper.ppu_read(0x0000),
+                0x15 >> shift,
+                "Mapper {}.{} bank 0",
+                number,
+                submapper
+            );
+            assert_eq!(
+                mapper.ppu_read(0x0400),
+                0x27 >> shift,
+                "Mapper {}.{} bank 1",
+                number,
+                submapper
+            );
+        }
+    }
+
+    #[test]
+    fn test_guessed_wiring_accepts_both_boards() {
+        // Mapper 23 without a submapper: VRC4f (A0, A1) and VRC4e (A2, A3)
+        let mut mapper = Mapper21::new(create_test_cartridge(23, 0));
+        assert!(mapper.guessed);
+        mapper.cpu_write(0xB002, 0x03);
+        assert_eq!(mapper.ppu_read(0x0400), 3);
+        mapper.cpu_write(0xB008, 0x04);
+        assert_eq!(mapper.ppu_read(0x0400), 4);
+
+        // Mapper 21 without a submapper: VRC4a (A1, A2) and VRC4c (A6, A7)
+        let mut mapper = Mapper21::new(create_test_cartridge(21, 0));
+        mapper.cpu_write(0xB004, 0x06);
+        assert_eq!(mapper.ppu_read(0x0400), 6);
+        mapper.cpu_write(0xB080, 0x07);
+        assert_eq!(mapper.ppu_read(0x0400), 7);
+
+        // Mapper 25 without a submapper: VRC4b (A1, A0) and VRC4d (A3, A2)
+        let mut mapper = Mapper21::new(create_test_cartridge(25, 0));
+        mapper.cpu_write(0xB001, 0x08);
+        assert_eq!(mapper.ppu_read(0x0400), 8);
+        mapper.cpu_write(0xB004, 0x09);
+        assert_eq!(mapper.ppu_read(0x0400), 9);
+    }
+
+    #[test]
+    fn test_prg_bank_switching() {
+        let mut mapper = Mapper21::new(create_test_cartridge(23, 1));
+
+        mapper.cpu_write(0x8000, 5);
+        mapper.cpu_write(0xA000, 9);
+        assert_eq!(mapper.cpu_read(0x8000), 5);
+        assert_eq!(mapper.cpu_read(0xA000), 9);
+        assert_eq!(mapper.cpu_read(0xC000), 30);
+        assert_eq!(mapper.cpu_read(0xE000), 31);
+    }
+
+    #[test]
+    fn test_prg_swap_mode() {
+        let mut mapper = Mapper21::new(create_test_cartridge(23, 1));
+        mapper.cpu_write(0x8000, 5);
+
+        // $9002 bit 1 swaps $8000 and $C000
+        mapper.cpu_write(0x9002, 0x02);
+        assert_eq!(mapper.cpu_read(0x8000), 30);
+        assert_eq!(mapper.cpu_read(0xC000), 5);
+        assert_eq!(mapper.cpu_read(0xE000), 31);
+
+        // VRC2 has no swap mode: $9002 is a mirroring register
+        let mut mapper = Mapper21::new(create_test_cartridge(23, 3));
+        mapper.cpu_write(0x8000, 5);
+        mapper.cpu_write(0x9002, 0x03);
+        assert_eq!(mapper.cpu_read(0x8000), 5);
+        assert_eq!(mapper.mirroring(), Mirroring::Horizontal);
+    }
+
+    #[test]
+    fn test_chr_bank_registers() {
+        let mut mapper = Mapper21::new(create_test_cartridge(23, 1));
+
+        // Each 1KB bank has a low/high nibble pair
+        for bank in 0..8u16 {
+            let register = 0xB000 + (bank / 2) * 0x1000 + (bank % 2) * 2;
+            mapper.cpu_write(register, (bank + 1) as u8);
+            mapper.cpu_write(register + 1, 0x01);
+        }
+        for bank in 0..8u16 {
+            assert_eq!(mapper.ppu_read(bank * 0x400), 0x10 + bank as u8 + 1);
+        }
+
+        // VRC4 CHR banks have 9 bits; the test ROM wraps at 256 banks
+        mapper.cpu_write(0xB001, 0x1F);
+        assert_eq!(mapper.chr_banks_select[0], 0x1F1);
+    }
+
+    #[test]
+    fn test_mirroring_control() {
+        let mut mapper = Mapper21::new(create_test_cartridge(25, 1));
+
+        mapper.cpu_write(0x9000, 1);
+        assert_eq!(mapper.mirroring(), Mirroring::Horizontal);
+        assert_eq!(mapper.nametable_source(0x2000), None);
+
+        mapper.cpu_write(0x9000, 0);
+        assert_eq!(mapper.mirroring(), Mirroring::Vertical);
+
+        // One-screen A and B select a page of internal VRAM
+        mapper.cpu_write(0x9000, 2);
+        assert_eq!(mapper.mirroring(), Mirroring::SingleScreen);
+        assert_eq!(
+            mapper.nametable_source(0x2C00),
+            Some(NametableSource::Ciram(0))
+        );
+        mapper.cpu_write(0x9000, 3);
+        assert_eq!(
+            mapper.nametable_source(0x2000),
+            Some(NametableSource::Ciram(1))
+        );
+    }
+
+    #[test]
+    fn test_prg_ram_enable() {
+        let mut mapper = Mapper21::new(create_test_cartridge(21, 1));
+
+        // Disabled at power-on
+        mapper.cpu_write(0x6000, 0x42);
+        assert_eq!(mapper.try_cpu_read(0x6000), None);
+
+        mapper.cpu_write(0x9004, 0x01);
+        mapper.cpu_write(0x6000, 0x42);
+        assert_eq!(mapper.try_cpu_read(0x6000), Some(0x42));
+
+        // Boards guessed from the mapper number alone keep RAM enabled
+        let mut mapper = Mapper21::new(create_test_cartridge(21, 0));
+        mapper.cpu_write(0x7FFF, 0x24);
+        assert_eq!(mapper.try_cpu_read(0x7FFF), Some(0x24));
+    }
+
+    #[test]
+    fn test_vrc2_microwire_latch() {
+        let mut mapper = Mapper21::new(create_test_cartridge(22, 0));
+        assert_eq!(mapper.chip, Chip::Vrc2);
+        assert!(mapper.prg_ram().is_none());
+
+        mapper.cpu_write(0x6000, 0xFF);
+        assert_eq!(mapper.try_cpu_read(0x6000), Some(0x01));
+        assert_eq!(mapper.try_cpu_read(0x6FFF), Some(0x01));
+        assert_eq!(mapper.try_cpu_read(0x7000), None);
+        mapper.cpu_write(0x6123, 0xFE);
+        assert_eq!(mapper.try_cpu_read(0x6000), Some(0x00));
+
+        // With battery-backed RAM the board has PRG-RAM instead
+        let mut cartridge = create_test_cartridge(23, 3);
+        cartridge.ram.prg_nvram = 8 * 1024;
+        let mut mapper = Mapper21::new(cartridge);
+        mapper.cpu_write(0x6000, 0xFF);
+        assert_eq!(mapper.try_cpu_read(0x6000), Some(0xFF));
+        assert_eq!(mapper.prg_ram().unwrap().len(), PRG_RAM_SIZE);
+    }
+
+    #[test]
+    fn test_irq_cycle_mode() {
+        let mut mapper = Mapper21::new(create_test_cartridge(23, 1));
+
+        // Latch $FC, enabled in CPU cycle mode
+        mapper.cpu_write(0xF000, 0x0C);
+        mapper.cpu_write(0xF001, 0x0F);
+        mapper.cpu_write(0xF002, 0x06);
+
+        for _ in 0..3 {
+            mapper.cpu_clock();
+        }
+        assert!(!mapper.irq_pending());
+        mapper.cpu_clock();
+        assert!(mapper.irq_pending());
+
+        // The acknowledge register clears the IRQ and copies A to E
+        mapper.cpu_write(0xF003, 0);
+        assert!(!mapper.irq_pending());
+        for _ in 0..1000 {
+            mapper.cpu_clock();
+        }
+        assert!(!mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_irq_scanline_mode() {
+        let mut mapper = Mapper21::new(create_test_cartridge(25, 1));
+
+        // Latch $FF: one scanline (113.67 CPU cycles) until the IRQ
+        mapper.cpu_write(0xF000, 0x0F);
+        mapper.cpu_write(0xF002, 0x0F); // Bit 0 of $F002 on VRC4b is register $F001
+        mapper.cpu_write(0xF001, 0x03); // $F002: E and A set, scanline mode
+
+        for _ in 0..113 {
+            mapper.cpu_clock();
+        }
+        assert!(!mapper.irq_pending());
+        mapper.cpu_clock();
+        assert!(mapper.irq_pending());
+
+        // The CPU-side acknowledge only clears the pending flag
+        mapper.irq_acknowledge();
+        assert!(!mapper.irq_pending());
+        for _ in 0..114 {
+            mapper.cpu_clock();
+        }
+        assert!(mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_vrc2_has_no_irq() {
+        let mut mapper = Mapper21::new(create_test_cartridge(23, 3));
+
+        mapper.cpu_write(0xF000, 0x0F);
+        mapper.cpu_write(0xF001, 0x0F);
+        mapper.cpu_write(0xF002, 0x06);
+        for _ in 0..1000 {
+            mapper.cpu_clock();
+        }
+        assert!(!mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_chr_ram_writes() {
+        let mut cartridge = create_test_cartridge(23, 1);
+        cartridge.chr_rom = vec![0; 8 * 1024];
+        let mut mapper = Mapper21::new(cartridge);
+
+        mapper.ppu_write(0x0123, 0x99);
+        assert_eq!(mapper.ppu_read(0x0123), 0x99);
+    }
+}
@@ -0,0 +1,856 @@
+// Mappers 24, 26 (Konami VRC6) - PRG/CHR banking, CPU-cycle IRQ and expansion audio
+//
+// Memory Layout:
+// - CPU $6000-$7FFF: 8KB PRG-RAM (optional, enabled by $B003 bit 7)
+// - CPU $8000-$BFFF: 16KB PRG-ROM bank (switchable)
+// - CPU $C000-$DFFF: 8KB PRG-ROM bank (switchable)
+// - CPU $E000-$FFFF: 8KB PRG-ROM bank (fixed to last bank)
+// - PPU $0000-$1FFF: CHR-ROM in 1KB or 2KB banks, depending on the banking mode
+//
+// Features:
+// - PRG-ROM size: up to 256KB
+// - CHR-ROM size: up to 256KB
+// - VRC IRQ counter (scanline and CPU cycle modes)
+// - Expansion audio: two pulse channels with 8 duty settings and a sawtooth
+//
+// Address Decoding:
+// Mapper 24 (VRC6a) wires A0 and A1 to the chip's register select lines;
+// mapper 26 (VRC6b, Madara and Esper Dream 2) swaps them.
+//
+// Register Interface (after decoding):
+// - $8000-$8003: 16KB PRG bank at $8000
+// - $9000/$A000: Pulse 1/2 control: bit 7 ignore duty, bits 4-6 duty, bits 0-3 volume
+// - $9001/$A001: Pulse 1/2 period low 8 bits
+// - $9002/$A002: Pulse 1/2 bit 7 enable, bits 0-3 period high 4 bits
+// - $9003: Audio control: bit 0 halt, bit 1 period >> 4, bit 2 period >> 8
+// - $B000: Sawtooth accumulator rate (6 bits)
+// - $B001/$B002: Sawtooth period low/high, bit 7 of $B002 enable
+// - $B003: Banking style: bits 0-1 CHR mode, bits 2-3 mirroring,
+//   bit 5 CHR A10 from the PPU in 2KB banks, bit 7 PRG-RAM enable
+// - $C000-$C003: 8KB PRG bank at $C000
+// - $D000-$D003, $E000-$E003: CHR registers R0-R7
+// - $F000: IRQ latch, $F001: IRQ control, $F002: IRQ acknowledge
+//
+// Nametables mapped from CHR-ROM ($B003 bit 4) are not used by any released
+// game and are not emulated; mirroring always comes from bits 2-3.
+
+use super::vrc_irq::VrcIrq;
+use super::{MapperError, StateReader, StateWriter};
+use crate::cartridge::{Cartridge, Mapper, Mirroring, NametableSource};
+use crate::state::StateError;
+
+/// PRG-ROM bank size (8KB)
+const PRG_BANK_SIZE: usize = 8 * 1024;
+
+/// CHR-ROM 1KB bank size
+const CHR_1KB_BANK_SIZE: usize = 1024;
+
+/// PRG-RAM size (8KB)
+const PRG_RAM_SIZE: usize = 8 * 1024;
+
+/// Output level of one VRC6 volume step
+///
+/// The VRC6 DAC is linear, and one step is about as loud as one step of an
+/// APU pulse channel (the linear approximation of the APU pulse mixer).
+const VRC6_STEP_LEVEL: f32 = 0.00752;
+
+// ========================================
+// Audio Channels
+// ========================================
+
+/// VRC6 pulse channel
+#[derive(Debug, Clone, Default)]
+struct Vrc6Pulse {
+    /// Volume (0-15)
+    volume: u8,
+    /// Duty setting (0-7): high for duty + 1 of 16 steps
+    duty: u8,
+    /// Output the volume constantly, ignoring the duty
+    ignore_duty: bool,
+    /// 12-bit period
+    period: u16,
+    /// Channel enable
+    enabled: bool,
+    /// Period divider
+    timer: u16,
+    /// Sequencer step, counting down from 15
+    step: u8,
+}
+
+impl Vrc6Pulse {
+    /// Write one of the channel's three registers
+    fn write(&mut self, register: u16, value: u8) {
+        match register {
+            0 => {
+                self.ignore_duty = value & 0x80 != 0;
+                self.duty = (value >> 4) & 0x07;
+                self.volume = value & 0x0F;
+            }
+            1 => self.period = (self.period & 0x0F00) | value as u16,
+            _ => {
+                self.period = (self.period & 0x00FF) | ((value as u16 & 0x0F) << 8);
+                self.enabled = value & 0x80 != 0;
+                if !self.enabled {
+                    self.step = 15;
+                }
+            }
+        }
+    }
+
+    /// Advance the channel by one CPU cycle
+    fn clock(&mut self, period_shift: u8) {
+        if !self.enabled {
+            return;
+        }
+        if self.timer == 0 {
+            self.timer = self.period >> period_shift;
+            self.step = self.step.wrapping_sub(1) & 0x0F;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    /// Get the channel's output (0-15)
+    fn output(&self) -> u8 {
+        if self.enabled && (self.ignore_duty || self.step <= self.duty) {
+            self.volume
+        } else {
+            0
+        }
+    }
+
+    /// Append the channel's state to a save state
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u8(self.volume);
+        writer.write_u8(self.duty);
+        writer.write_bool(self.ignore_duty);
+        writer.write_u16(self.period);
+        writer.write_bool(self.enabled);
+        writer.write_u16(self.timer);
+        writer.write_u8(self.step);
+    }
+
+    /// Restore state written by `save_state`
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.volume = reader.read_u8()?;
+        self.duty = reader.read_u8()?;
+        self.ignore_duty = reader.read_bool()?;
+        self.period = reader.read_u16()?;
+        self.enabled = reader.read_bool()?;
+        self.timer = reader.read_u16()?;
+        self.step = reader.read_u8()?;
+        Ok(())
+    }
+}
+
+/// VRC6 sawtooth channel
+///
+/// Every second divider clock adds the rate to an 8-bit accumulator; the
+/// seventh addition is replaced by a reset to 0. The top 5 bits are output.
+#[derive(Debug, Clone, Default)]
+struct Vrc6Sawtooth {
+    /// Accumulator rate (6 bits)
+    rate: u8,
+    /// 12-bit period
+    period: u16,
+    /// Channel enable
+    enabled: bool,
+    /// Period divider
+    timer: u16,
+    /// Step within the 14-step cycle
+    step: u8,
+    /// Accumulator
+    accumulator: u8,
+}
+
+impl Vrc6Sawtooth {
+    /// Write one of the channel's three registers
+    fn write(&mut self, register: u16, value: u8) {
+        match register {
+            0 => self.rate = value & 0x3F,
+            1 => self.period = (self.period & 0x0F00) | value as u16,
+            _ => {
+                self.period = (self.period & 0x00FF) | ((value as u16 & 0x0F) << 8);
+                self.enabled = value & 0x80 != 0;
+                if !self.enabled {
+                    self.step = 0;
+                    self.accumulator = 0;
+                }
+            }
+        }
+    }
+
+    /// Advance the channel by one CPU cycle
+    fn clock(&mut self, period_shift: u8) {
+        if !self.enabled {
+            return;
+        }
+        if self.timer == 0 {
+            self.timer = self.period >> period_shift;
+            self.step += 1;
+            if self.step == 14 {
+                self.step = 0;
+                self.accumulator = 0;
+            } else if self.step.is_multiple_of(2) {
+                self.accumulator = self.accumulator.wrapping_add(self.rate);
+            }
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    /// Get the channel's output (0-31)
+    fn output(&self) -> u8 {
+        self.accumulator >> 3
+    }
+
+    /// Append the channel's state to a save state
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u8(self.rate);
+        writer.write_u16(self.period);
+        writer.write_bool(self.enabled);
+        writer.write_u16(self.timer);
+        writer.write_u8(self.step);
+        writer.write_u8(self.accumulator);
+    }
+
+    /// Restore state written by `save_state`
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.rate = reader.read_u8()?;
+        self.period = reader.read_u16()?;
+        self.enabled = reader.read_bool()?;
+        self.timer = reader.read_u16()?;
+        self.step = reader.read_u8()?;
+        self.accumulator = reader.read_u8()?;
+        Ok(())
+    }
+}
+
+// ========================================
+// Mapper
+// ========================================
+
+/// Mappers 24 and 26 implementation (Konami VRC6)
+///
+/// VRC6 is used by games like:
+/// - Akumajou Densetsu (VRC6a)
+/// - Madara (VRC6b)
+/// - Esper Dream 2 (VRC6b)
+pub struct Mapper24 {
+    /// PRG-ROM data
+    prg_rom: Vec<u8>,
+    /// CHR-ROM or CHR-RAM data
+    chr_mem: Vec<u8>,
+    /// PRG-RAM (8KB, battery-backed in some games)
+    prg_ram: Vec<u8>,
+    /// Whether CHR memory is RAM (writable) or ROM (read-only)
+    chr_is_ram: bool,
+    /// Whether A0 and A1 are swapped (VRC6b)
+    swap_address_lines: bool,
+
+    // Internal registers
+    /// 16KB PRG bank at $8000
+    prg_bank_16k: u8,
+    /// 8KB PRG bank at $C000
+    prg_bank_8k: u8,
+    /// CHR registers R0-R7
+    chr_registers: [u8; 8],
+    /// Banking style ($B003)
+    banking_style: u8,
+    /// IRQ counter
+    irq: VrcIrq,
+
+    // Audio
+    /// Pulse channels
+    pulses: [Vrc6Pulse; 2],
+    /// Sawtooth channel
+    sawtooth: Vrc6Sawtooth,
+    /// Audio control ($9003)
+    audio_control: u8,
+
+    // Derived state
+    /// Number of 8KB PRG-ROM banks
+    prg_banks: usize,
+    /// Number of 1KB CHR banks
+    chr_banks: usize,
+}
+
+impl Mapper24 {
+    /// Create a new VRC6 instance from a cartridge
+    ///
+    /// # Arguments
+    /// * `cartridge` - The cartridge containing ROM data
+    pub fn new(cartridge: Cartridge) -> Self {
+        let prg_rom_size = cartridge.prg_rom.len();
+        let chr_mem_size = cartridge.chr_rom.len();
+
+        // Calculate number of banks
+        let prg_banks = prg_rom_size / PRG_BANK_SIZE;
+        let chr_banks = chr_mem_size / CHR_1KB_BANK_SIZE;
+
+        assert!(
+            prg_banks >= 2,
+            "VRC6 requires at least 2 PRG banks (16KB total, got {} banks)",
+            prg_banks
+        );
+        assert!(
+            chr_banks > 0,
+            "VRC6 requires at least one CHR bank (got {} banks)",
+            chr_banks
+        );
+
+        // CHR-RAM is indicated by all zeros in chr_rom
+        let chr_is_ram = chr_mem_size == 8 * 1024 && cartridge.chr_rom.iter().all(|&b| b == 0);
+
+        Mapper24 {
+            prg_rom: cartridge.prg_rom,
+            chr_mem: cartridge.chr_rom,
+            prg_ram: vec![0; PRG_RAM_SIZE],
+            chr_is_ram,
+            swap_address_lines: cartridge.mapper == 26,
+
+            prg_bank_16k: 0,
+            prg_bank_8k: 0,
+            chr_registers: [0; 8],
+            banking_style: match cartridge.mirroring {
+                Mirroring::Horizontal => 0x04,
+                _ => 0x00,
+            },
+            irq: VrcIrq::new(),
+
+            pulses: [Vrc6Pulse::default(), Vrc6Pulse::default()],
+            sawtooth: Vrc6Sawtooth::default(),
+            audio_control: 0,
+
+            prg_banks,
+            chr_banks,
+        }
+    }
+
+    /// Decode a CPU address to its register ($8000, $8001, ... $F003)
+    fn decode_register(&self, address: u16) -> u16 {
+        let lines = if self.swap_address_lines {
+            ((address & 0x01) << 1) | ((address & 0x02) >> 1)
+        } else {
+            address & 0x03
+        };
+        (address & 0xF000) | lines
+    }
+
+    /// Check if PRG-RAM is enabled
+    fn prg_ram_enabled(&self) -> bool {
+        self.banking_style & 0x80 != 0
+    }
+
+    /// Get the mirroring bits of the banking style (0-3)
+    fn mirroring_mode(&self) -> u8 {
+        (self.banking_style >> 2) & 0x03
+    }
+
+    /// Map CPU address to PRG-ROM offset
+    fn map_prg_address(&self, address: u16) -> usize {
+        let bank = match address {
+            0x8000..=0xBFFF => {
+                (self.prg_bank_16k as usize & 0x0F) * 2 + ((address as usize >> 13) & 1)
+            }
+            0xC000..=0xDFFF => self.prg_bank_8k as usize & 0x1F,
+            _ => self.prg_banks - 1,
+        };
+
+        let offset = (address & 0x1FFF) as usize; // 8KB bank offset
+        (bank % self.prg_banks) * PRG_BANK_SIZE + offset
+    }
+
+    /// Map PPU address to CHR offset
+    fn map_chr_address(&self, address: u16) -> usize {
+        let slot = (address as usize >> 10) & 0x07;
+
+        // Which register serves the slot, and whether it is half of a 2KB bank
+        let (register, two_kb) = match (self.banking_style & 0x03, slot) {
+            (0, _) => (slot, false),
+            (1, _) => (slot / 2, true),
+            (_, 0..=3) => (slot, false),
+            (_, _) => (4 + (slot - 4) / 2, true),
+        };
+
+        let mut bank = self.chr_registers[register] as usize;
+        if two_kb && self.banking_style & 0x20 != 0 {
+            // CHR A10 comes from the PPU
+            bank = (bank & !1) | (slot & 1);
+        }
+
+        let offset = (address & 0x03FF) as usize; // 1KB bank offset
+        (bank % self.chr_banks) * CHR_1KB_BANK_SIZE + offset
+    }
+
+    /// Get the divider shift selected by the audio control register
+    fn period_shift(&self) -> u8 {
+        if self.audio_control & 0x04 != 0 {
+            8
+        } else if self.audio_control & 0x02 != 0 {
+            4
+        } else {
+            0
+        }
+    }
+}
+
+impl Mapper for Mapper24 {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            // PRG-RAM
+            0x6000..=0x7FFF if self.prg_ram_enabled() => self.prg_ram[(address - 0x6000) as usize],
+            // PRG-ROM
+            0x8000..=0xFFFF => {
+                let index = self.map_prg_address(address);
+                self.prg_rom[index]
+            }
+            _ => 0,
+        }
+    }
+
+    fn try_cpu_read(&self, address: u16) -> Option<u8> {
+        match address {
+            // Disabled PRG-RAM leaves the data bus floating
+            0x6000..=0x7FFF if !self.prg_ram_enabled() => None,
+            0x6000..=0xFFFF => Some(self.cpu_read(address)),
+            _ => None,
+        }
+    }
+
+    fn cpu_write(&mut self, address: u16, value: u8) {
+        match address {
+            // PRG-RAM
+            0x6000..=0x7FFF => {
+                if self.prg_ram_enabled() {
+                    self.prg_ram[(address - 0x6000) as usize] = value;
+                }
+            }
+            // Mapper registers
+            0x8000..=0xFFFF => {
+                let register = self.decode_register(address);
+                match register {
+                    0x8000..=0x8003 => self.prg_bank_16k = value,
+                    0x9003 => self.audio_control = value,
+                    0x9000..=0x9002 => self.pulses[0].write(register & 0x03, value),
+                    0xA000..=0xA002 => self.pulses[1].write(register & 0x03, value),
+                    0xB000..=0xB002 => self.sawtooth.write(register & 0x03, value),
+                    0xB003 => self.banking_style = value,
+                    0xC000..=0xC003 => self.prg_bank_8k = value,
+                    0xD000..=0xD003 => self.chr_registers[(register & 0x03) as usize] = value,
+                    0xE000..=0xE003 => {
+                        self.chr_registers[4 + (register & 0x03) as usize] = value;
+                    }
+                    0xF000 => self.irq.write_latch(value),
+                    0xF001 => self.irq.write_control(value),
+                    0xF002 => self.irq.acknowledge(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => {
+                let index = self.map_chr_address(address);
+                self.chr_mem[index % self.chr_mem.len()]
+            }
+            _ => 0,
+        }
+    }
+
+    fn ppu_write(&mut self, address: u16, value: u8) {
+        if self.chr_is_ram {
+            if let 0x0000..=0x1FFF = address {
+                let chr_len = self.chr_mem.len();
+                let index = self.map_chr_address(address);
+                self.chr_mem[index % chr_len] = value;
+            }
+        }
+        // Writes to CHR-ROM are ignored
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.mirroring_mode() {
+            0 => Mirroring::Vertical,
+            1 => Mirroring::Horizontal,
+            _ => Mirroring::SingleScreen,
+        }
+    }
+
+    fn nametable_source(&self, _address: u16) -> Option<NametableSource> {
+        // One-screen B uses the second page of internal VRAM
+        match self.mirroring_mode() {
+            2 => Some(NametableSource::Ciram(0)),
+            3 => Some(NametableSource::Ciram(1)),
+            _ => None,
+        }
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => Some(self.map_prg_address(address)),
+            _ => None,
+        }
+    }
+
+    fn prg_ram(&self) -> Option<&[u8]> {
+        Some(&self.prg_ram)
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.prg_ram)
+    }
+
+    fn cpu_clock(&mut self) {
+        self.irq.clock();
+
+        // The halt bit stops all three dividers
+        if self.audio_control & 0x01 == 0 {
+            let shift = self.period_shift();
+            for pulse in &mut self.pulses {
+                pulse.clock(shift);
+            }
+            self.sawtooth.clock(shift);
+        }
+    }
+
+    fn irq_pending(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn irq_acknowledge(&mut self) {
+        self.irq.clear_pending();
+    }
+
+    fn audio_output(&self) -> f32 {
+        let sum = self.pulses[0].output() + self.pulses[1].output() + self.sawtooth.output();
+        sum as f32 * VRC6_STEP_LEVEL
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.write_u8(self.prg_bank_16k);
+        writer.write_u8(self.prg_bank_8k);
+        for &bank in &self.chr_registers {
+            writer.write_u8(bank);
+        }
+        writer.write_u8(self.banking_style);
+        self.irq.save_state(&mut writer);
+        for pulse in &self.pulses {
+            pulse.save_state(&mut writer);
+        }
+        self.sawtooth.save_state(&mut writer);
+        writer.write_u8(self.audio_control);
+        writer.write_bytes(&self.prg_ram);
+        if self.chr_is_ram {
+            writer.write_bytes(&self.chr_mem);
+        }
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        self.prg_bank_16k = reader.read_u8()?;
+        self.prg_bank_8k = reader.read_u8()?;
+        for bank in self.chr_registers.iter_mut() {
+            *bank = reader.read_u8()?;
+        }
+        self.banking_style = reader.read_u8()?;
+        self.irq.load_state(&mut reader)?;
+        for pulse in self.pulses.iter_mut() {
+            pulse.load_state(&mut reader)?;
+        }
+        self.sawtooth.load_state(&mut reader)?;
+        self.audio_control = reader.read_u8()?;
+        reader.read_bytes_into(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            reader.read_bytes_into(&mut self.chr_mem)?;
+        }
+        Ok(reader.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a test cartridge
+    ///
+    /// Each 8KB PRG bank is filled with its bank number and each 1KB CHR bank
+    /// with its bank number.
+    fn create_test_cartridge(mapper: u16) -> Cartridge {
+        let prg_rom = (0..32 * PRG_BANK_SIZE)
+            .map(|i| (i / PRG_BANK_SIZE) as u8)
+            .collect();
+        let chr_rom = (0..128 * CHR_1KB_BANK_SIZE)
+            .map(|i| (i / CHR_1KB_BANK_SIZE) as u8)
+            .collect();
+
+        Cartridge {
+            prg_rom,
+            chr_rom,
+            trainer: None,
+            mapper,
+            mirroring: Mirroring::Vertical,
+            has_battery: false,
+            ..Cartridge::new()
+        }
+    }
+
+    /// Clock the mapper for a number of CPU cycles
+    fn run(mapper: &mut Mapper24, cycles: usize) {
+        for _ in 0..cycles {
+            mapper.cpu_clock();
+        }
+    }
+
+    #[test]
+    fn test_mapper24_creation() {
+        let mapper = Mapper24::new(create_test_cartridge(24));
+
+        assert_eq!(mapper.prg_banks, 32);
+        assert_eq!(mapper.chr_banks, 128);
+        assert_eq!(mapper.mirroring(), Mirroring::Vertical);
+        assert_eq!(mapper.cpu_read(0xE000), 31);
+        assert_eq!(mapper.audio_output(), 0.0);
+    }
+
+    #[test]
+    fn test_prg_bank_switching() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+
+        // 16KB bank 3 = 8KB banks 6 and 7
+        mapper.cpu_write(0x8000, 3);
+        mapper.cpu_write(0xC000, 20);
+        assert_eq!(mapper.cpu_read(0x8000), 6);
+        assert_eq!(mapper.cpu_read(0xA000), 7);
+        assert_eq!(mapper.cpu_read(0xC000), 20);
+        assert_eq!(mapper.cpu_read(0xE000), 31);
+    }
+
+    #[test]
+    fn test_vrc6b_swaps_address_lines() {
+        let mut mapper = Mapper24::new(create_test_cartridge(26));
+
+        // On VRC6b $D001 is R2 and $D002 is R1
+        mapper.cpu_write(0xD001, 9);
+        mapper.cpu_write(0xD002, 5);
+        assert_eq!(mapper.ppu_read(0x0400), 5);
+        assert_eq!(mapper.ppu_read(0x0800), 9);
+
+        // $B003 is the same on both boards
+        mapper.cpu_write(0xB003, 0x84);
+        assert_eq!(mapper.mirroring(), Mirroring::Horizontal);
+    }
+
+    #[test]
+    fn test_chr_banking_modes() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+        for register in 0..8u16 {
+            let address = 0xD000 + (register / 4) * 0x1000 + register % 4;
+            mapper.cpu_write(address, 0x10 + register as u8 * 2);
+        }
+
+        // Mode 0: eight 1KB banks
+        mapper.cpu_write(0xB003, 0x20);
+        let banks: Vec<u8> = (0..8).map(|slot| mapper.ppu_read(slot * 0x400)).collect();
+        assert_eq!(banks, [0x10, 0x12, 0x14, 0x16, 0x18, 0x1A, 0x1C, 0x1E]);
+
+        // Mode 1: four 2KB banks from R0-R3, A10 from the PPU
+        mapper.cpu_write(0xB003, 0x21);
+        let banks: Vec<u8> = (0..8).map(|slot| mapper.ppu_read(slot * 0x400)).collect();
+        assert_eq!(banks, [0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17]);
+
+        // Without bit 5 both halves of a 2KB bank use the register as is
+        mapper.cpu_write(0xB003, 0x01);
+        assert_eq!(mapper.ppu_read(0x0400), 0x10);
+
+        // Mode 2: 1KB banks R0-R3, then 2KB banks R4 and R5
+        mapper.cpu_write(0xB003, 0x22);
+        let banks: Vec<u8> = (0..8).map(|slot| mapper.ppu_read(slot * 0x400)).collect();
+        assert_eq!(banks, [0x10, 0x12, 0x14, 0x16, 0x18, 0x19, 0x1A, 0x1B]);
+    }
+
+    #[test]
+    fn test_mirroring_control() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+
+        mapper.cpu_write(0xB003, 0x24);
+        assert_eq!(mapper.mirroring(), Mirroring::Horizontal);
+        mapper.cpu_write(0xB003, 0x20);
+        assert_eq!(mapper.mirroring(), Mirroring::Vertical);
+        assert_eq!(mapper.nametable_source(0x2000), None);
+
+        mapper.cpu_write(0xB003, 0x28);
+        assert_eq!(mapper.mirroring(), Mirroring::SingleScreen);
+        assert_eq!(
+            mapper.nametable_source(0x2400),
+            Some(NametableSource::Ciram(0))
+        );
+        mapper.cpu_write(0xB003, 0x2C);
+        assert_eq!(
+            mapper.nametable_source(0x2400),
+            Some(NametableSource::Ciram(1))
+        );
+    }
+
+    #[test]
+    fn test_prg_ram_enable() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+
+        mapper.cpu_write(0x6000, 0x42);
+        assert_eq!(mapper.try_cpu_read(0x6000), None);
+
+        mapper.cpu_write(0xB003, 0xA0);
+        mapper.cpu_write(0x6000, 0x42);
+        assert_eq!(mapper.try_cpu_read(0x6000), Some(0x42));
+        assert_eq!(mapper.prg_ram().unwrap()[0], 0x42);
+    }
+
+    #[test]
+    fn test_irq_cycle_mode() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+
+        mapper.cpu_write(0xF000, 0xF0);
+        mapper.cpu_write(0xF001, 0x07); // A, E, cycle mode
+
+        run(&mut mapper, 15);
+        assert!(!mapper.irq_pending());
+        run(&mut mapper, 1);
+        assert!(mapper.irq_pending());
+
+        // Acknowledge keeps the counter running since A was set
+        mapper.cpu_write(0xF002, 0);
+        assert!(!mapper.irq_pending());
+        run(&mut mapper, 16);
+        assert!(mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_pulse_duty_cycle() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+
+        // Duty 3 (4/16 high), volume 10, period 0 (one step per cycle)
+        mapper.cpu_write(0x9000, 0x3A);
+        mapper.cpu_write(0x9001, 0x00);
+        mapper.cpu_write(0x9002, 0x80);
+
+        let mut high = 0;
+        for _ in 0..16 {
+            mapper.cpu_clock();
+            match mapper.pulses[0].output() {
+                10 => high += 1,
+                0 => {}
+                other => panic!("Unexpected pulse output {}", other),
+            }
+        }
+        assert_eq!(high, 4);
+
+        // Ignore-duty mode outputs the volume constantly
+        mapper.cpu_write(0x9000, 0x8A);
+        for _ in 0..16 {
+            mapper.cpu_clock();
+            assert_eq!(mapper.pulses[0].output(), 10);
+        }
+
+        // Disabling the channel silences it
+        mapper.cpu_write(0x9002, 0x00);
+        assert_eq!(mapper.pulses[0].output(), 0);
+    }
+
+    #[test]
+    fn test_pulse_period() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+
+        // Period 9: the sequencer steps every 10 CPU cycles
+        mapper.cpu_write(0xA000, 0x0F);
+        mapper.cpu_write(0xA001, 0x09);
+        mapper.cpu_write(0xA002, 0x80);
+
+        mapper.cpu_clock();
+        let step = mapper.pulses[1].step;
+        run(&mut mapper, 9);
+        assert_eq!(mapper.pulses[1].step, step);
+        mapper.cpu_clock();
+        assert_eq!(mapper.pulses[1].step, step.wrapping_sub(1) & 0x0F);
+    }
+
+    #[test]
+    fn test_sawtooth_accumulator() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+
+        // Rate 42, period 0: the accumulator climbs 42 every second clock
+        mapper.cpu_write(0xB000, 42);
+        mapper.cpu_write(0xB001, 0x00);
+        mapper.cpu_write(0xB002, 0x80);
+
+        let outputs: Vec<u8> = (0..14)
+            .map(|_| {
+                mapper.cpu_clock();
+                mapper.sawtooth.output()
+            })
+            .collect();
+        assert_eq!(
+            outputs,
+            [0, 5, 5, 10, 10, 15, 15, 21, 21, 26, 26, 31, 31, 0]
+        );
+    }
+
+    #[test]
+    fn test_audio_halt_and_frequency_shift() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+        mapper.cpu_write(0x9000, 0x0F);
+        mapper.cpu_write(0x9001, 0x20);
+        mapper.cpu_write(0x9002, 0x80);
+
+        // Halt freezes the sequencer
+        mapper.cpu_write(0x9003, 0x01);
+        let step = mapper.pulses[0].step;
+        run(&mut mapper, 1000);
+        assert_eq!(mapper.pulses[0].step, step);
+
+        // Period $020 >> 4 = 2: one step every 3 cycles
+        mapper.cpu_write(0x9003, 0x02);
+        assert_eq!(mapper.period_shift(), 4);
+        mapper.pulses[0].timer = 0;
+        run(&mut mapper, 1);
+        let step = mapper.pulses[0].step;
+        run(&mut mapper, 3);
+        assert_eq!(mapper.pulses[0].step, step.wrapping_sub(1) & 0x0F);
+
+        // The 256x bit takes precedence
+        mapper.cpu_write(0x9003, 0x06);
+        assert_eq!(mapper.period_shift(), 8);
+    }
+
+    #[test]
+    fn test_audio_output_level() {
+        let mut mapper = Mapper24::new(create_test_cartridge(24));
+
+        // Both pulses at constant volume 15
+        mapper.cpu_write(0x9000, 0x8F);
+        mapper.cpu_write(0x9002, 0x80);
+        mapper.cpu_write(0xA000, 0x8F);
+        mapper.cpu_write(0xA002, 0x80);
+
+        let level = mapper.audio_output();
+        assert!((level - 30.0 * VRC6_STEP_LEVEL).abs() < 1e-6);
+
+        // About as loud as the APU's two pulses at full volume
+        let apu_pulses = 95.88 / (8128.0 / 30.0 + 100.0);
+        assert!((level - apu_pulses).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_chr_ram_writes() {
+        let mut cartridge = create_test_cartridge(24);
+        cartridge.chr_rom = vec![0; 8 * 1024];
+        let mut mapper = Mapper24::new(cartridge);
+
+        mapper.ppu_write(0x0123, 0x99);
+        assert_eq!(mapper.ppu_read(0x0123), 0x99);
+    }
+}
@@ -20,6 +20,7 @@
 // - Paperboy
 // - Q*bert
 
+use super::{MapperError, StateReader, StateWriter};
 use crate::cartridge::{Cartridge, Mapper, Mirroring};
 
 /// CHR-ROM bank size (8KB)
@@ -144,6 +145,13 @@ impl Mapper for Mapper3 {
         self.mirroring
     }
 
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => Some((address - 0x8000) as usize % self.prg_rom.len()),
+            _ => None,
+        }
+    }
+
     fn prg_ram(&self) -> Option<&[u8]> {
         // CNROM doesn't typically have PRG-RAM
         None
@@ -153,6 +161,18 @@ impl Mapper for Mapper3 {
         // CNROM doesn't typically have PRG-RAM
         None
     }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.write_u8(self.chr_bank);
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        self.chr_bank = reader.read_u8()?;
+        Ok(reader.finish()?)
+    }
 }
 
 #[cfg(test)]
@@ -171,6 +191,7 @@ mod tests {
             mapper: 3,
             mirroring,
             has_battery: false,
+            ..Cartridge::new()
         }
     }
 
@@ -49,6 +49,7 @@
 // - $E001-$FFFF (odd): IRQ enable register
 //   Enables IRQ generation
 
+use super::{MapperError, StateReader, StateWriter};
 use crate::cartridge::{Cartridge, Mapper, Mirroring};
 
 /// PRG-ROM bank size (8KB)
@@ -60,6 +61,9 @@ const CHR_1KB_BANK_SIZE: usize = 1024;
 /// PRG-RAM size (8KB)
 const PRG_RAM_SIZE: usize = 8 * 1024;
 
+/// Minimum number of PPU dots A12 must stay low before a rise clocks the IRQ counter
+const A12_LOW_FILTER_DOTS: u64 = 10;
+
 /// Mapper 4 implementation (MMC3)
 ///
 /// MMC3 is one of the most common NES mappers, used by games like:
@@ -99,6 +103,10 @@ pub struct Mapper4 {
     irq_enabled: bool,
     /// IRQ pending flag (set when counter reaches 0)
     irq_pending: bool,
+    /// Last observed level of PPU address line A12
+    a12_high: bool,
+    /// PPU dot at which A12 last went low
+    a12_low_since: u64,
 
     // Derived state
     /// Number of 8KB PRG-ROM banks
@@ -153,6 +161,8 @@ impl Mapper4 {
             irq_reload: false,
             irq_enabled: false,
             irq_pending: false,
+            a12_high: false,
+            a12_low_since: 0,
 
             prg_banks,
             chr_banks,
@@ -285,13 +295,10 @@ impl Mapper4 {
         (bank % self.chr_banks) * CHR_1KB_BANK_SIZE + offset
     }
 
-    /// Clock the IRQ counter (called by PPU on A12 rise, typically each scanline)
+    /// Clock the IRQ counter (called on a filtered A12 rise, typically each scanline)
     ///
-    /// Note: This is a simplified implementation. Full MMC3 IRQ emulation
-    /// requires tracking A12 transitions from the PPU, which would need
-    /// integration with the PPU scanline rendering. For now, this method
-    /// can be called externally when needed.
-    #[allow(dead_code)]
+    /// With background patterns at $0000 and sprites at $1000 this happens
+    /// once per scanline, during the sprite fetches at dot ~260.
     pub fn clock_irq_counter(&mut self) {
         if self.irq_counter == 0 || self.irq_reload {
             self.irq_counter = self.irq_latch;
@@ -306,15 +313,13 @@ impl Mapper4 {
     }
 
     /// Check if an IRQ is pending
-    #[allow(dead_code)]
     pub fn irq_pending(&self) -> bool {
-        self.irq_pending
+        Mapper::irq_pending(self)
     }
 
     /// Clear the pending IRQ
-    #[allow(dead_code)]
     pub fn clear_irq(&mut self) {
-        self.irq_pending = false;
+        self.irq_acknowledge();
     }
 }
 
@@ -328,7 +333,7 @@ impl Mapper for Mapper4 {
                     let index = (address - 0x6000) as usize;
                     self.prg_ram[index % PRG_RAM_SIZE]
                 } else {
-                    0 // PRG-RAM disabled, return open bus (simplified as 0)
+                    0 // PRG-RAM disabled (open bus, see try_cpu_read)
                 }
             }
             // PRG-ROM
@@ -340,6 +345,15 @@ impl Mapper for Mapper4 {
         }
     }
 
+    fn try_cpu_read(&self, address: u16) -> Option<u8> {
+        match address {
+            // Disabled PRG-RAM leaves the data bus floating
+            0x6000..=0x7FFF if self.prg_ram_protect & 0x80 == 0 => None,
+            0x6000..=0xFFFF => Some(self.cpu_read(address)),
+            _ => None,
+        }
+    }
+
     fn cpu_write(&mut self, address: u16, value: u8) {
         match address {
             // PRG-RAM
@@ -423,6 +437,13 @@ impl Mapper for Mapper4 {
         self.mirroring
     }
 
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => Some(self.map_prg_address(address)),
+            _ => None,
+        }
+    }
+
     fn prg_ram(&self) -> Option<&[u8]> {
         Some(&self.prg_ram)
     }
@@ -430,6 +451,75 @@ impl Mapper for Mapper4 {
     fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
         Some(&mut self.prg_ram)
     }
+
+    fn irq_pending(&self) -> bool {
+        self.irq_pending
+    }
+
+    fn irq_acknowledge(&mut self) {
+        self.irq_pending = false;
+    }
+
+    fn on_ppu_address(&mut self, addr: u16, ppu_cycle: u64) {
+        let a12_high = addr & 0x1000 != 0;
+
+        if a12_high && !self.a12_high {
+            // The MMC3 filters A12 with M2, so only rises that follow a
+            // sufficiently long low period clock the counter. This ignores
+            // the short low pulses between background pattern fetches.
+            if ppu_cycle.wrapping_sub(self.a12_low_since) >= A12_LOW_FILTER_DOTS {
+                self.clock_irq_counter();
+            }
+        } else if !a12_high && self.a12_high {
+            self.a12_low_since = ppu_cycle;
+        }
+
+        self.a12_high = a12_high;
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.write_u8(self.bank_select);
+        for &bank in &self.bank_registers {
+            writer.write_u8(bank);
+        }
+        writer.write_mirroring(self.mirroring);
+        writer.write_u8(self.prg_ram_protect);
+        writer.write_u8(self.irq_latch);
+        writer.write_u8(self.irq_counter);
+        writer.write_bool(self.irq_reload);
+        writer.write_bool(self.irq_enabled);
+        writer.write_bool(self.irq_pending);
+        writer.write_bool(self.a12_high);
+        writer.write_u64(self.a12_low_since);
+        writer.write_bytes(&self.prg_ram);
+        if self.chr_is_ram {
+            writer.write_bytes(&self.chr_mem);
+        }
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        self.bank_select = reader.read_u8()?;
+        for bank in self.bank_registers.iter_mut() {
+            *bank = reader.read_u8()?;
+        }
+        self.mirroring = reader.read_mirroring()?;
+        self.prg_ram_protect = reader.read_u8()?;
+        self.irq_latch = reader.read_u8()?;
+        self.irq_counter = reader.read_u8()?;
+        self.irq_reload = reader.read_bool()?;
+        self.irq_enabled = reader.read_bool()?;
+        self.irq_pending = reader.read_bool()?;
+        self.a12_high = reader.read_bool()?;
+        self.a12_low_since = reader.read_u64()?;
+        reader.read_bytes_into(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            reader.read_bytes_into(&mut self.chr_mem)?;
+        }
+        Ok(reader.finish()?)
+    }
 }
 
 #[cfg(test)]
@@ -448,6 +538,7 @@ mod tests {
             mapper: 4,
             mirroring: Mirroring::Horizontal,
             has_battery: false,
+            ..Cartridge::new()
         }
     }
 
@@ -656,6 +747,7 @@ mod tests {
         // PRG-RAM disabled by default
         mapper.cpu_write(0x6000, 0x42);
         assert_eq!(mapper.cpu_read(0x6000), 0); // Should read 0 (disabled)
+        assert_eq!(mapper.try_cpu_read(0x6000), None); // Open bus on the CPU side
 
         // Enable PRG-RAM (bit 7) and make writable (bit 6)
         mapper.cpu_write(0xA001, 0xC0);
@@ -663,6 +755,7 @@ mod tests {
         // Now writes should work
         mapper.cpu_write(0x6000, 0x42);
         assert_eq!(mapper.cpu_read(0x6000), 0x42);
+        assert_eq!(mapper.try_cpu_read(0x6000), Some(0x42));
 
         // Make read-only (clear bit 6)
         mapper.cpu_write(0xA001, 0x80);
@@ -730,6 +823,58 @@ mod tests {
 
         mapper.clock_irq_counter(); // Counter = 0, IRQ triggered
         assert!(mapper.irq_pending());
+
+        // The IRQ stays asserted until acknowledged
+        mapper.clock_irq_counter(); // Counter = 3 (reloaded)
+        assert!(mapper.irq_pending());
+
+        mapper.irq_acknowledge();
+        assert!(!mapper.irq_pending());
+        assert!(mapper.irq_enabled, "Acknowledging must not disable IRQs");
+    }
+
+    #[test]
+    fn test_a12_rise_clocks_irq_counter() {
+        let cartridge = create_test_cartridge(16, 128);
+        let mut mapper = Mapper4::new(cartridge);
+
+        mapper.cpu_write(0xC000, 0x01); // Latch = 1
+        mapper.cpu_write(0xC001, 0x00); // Reload
+        mapper.cpu_write(0xE001, 0x00); // Enable IRQ
+
+        // Scanline 0: background fetch (A12 low), then sprite fetch (A12 high)
+        mapper.on_ppu_address(0x0000, 100);
+        mapper.on_ppu_address(0x1000, 357); // Counter reloaded to 1
+        assert_eq!(mapper.irq_counter, 1);
+        assert!(!mapper.irq_pending());
+
+        // Scanline 1: same pattern, counter reaches 0
+        mapper.on_ppu_address(0x0000, 441);
+        mapper.on_ppu_address(0x1000, 698);
+        assert_eq!(mapper.irq_counter, 0);
+        assert!(mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_a12_short_low_pulse_is_filtered() {
+        let cartridge = create_test_cartridge(16, 128);
+        let mut mapper = Mapper4::new(cartridge);
+
+        mapper.cpu_write(0xC000, 0x05);
+        mapper.cpu_write(0xC001, 0x00);
+
+        mapper.on_ppu_address(0x0000, 0);
+        mapper.on_ppu_address(0x1000, 20); // Clocks: counter = 5
+        assert_eq!(mapper.irq_counter, 5);
+
+        // Nametable fetch between pattern fetches only drops A12 for a few dots
+        mapper.on_ppu_address(0x2000, 21);
+        mapper.on_ppu_address(0x1008, 25);
+        assert_eq!(mapper.irq_counter, 5, "Short A12 low pulse must not clock");
+
+        // Staying high does not clock again
+        mapper.on_ppu_address(0x1FFF, 40);
+        assert_eq!(mapper.irq_counter, 5);
     }
 
     #[test]
@@ -745,6 +890,7 @@ mod tests {
             mapper: 4,
             mirroring: Mirroring::Horizontal,
             has_battery: false,
+            ..Cartridge::new()
         };
 
         let mut mapper = Mapper4::new(cartridge);
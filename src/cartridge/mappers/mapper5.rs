@@ -0,0 +1,1357 @@
+// Mapper 5 (MMC5) - Nintendo's most capable NES mapper
+//
+// Memory Layout:
+// - CPU $5000-$5015: Expansion audio (two pulse channels and PCM)
+// - CPU $5100-$5130: PRG/CHR banking and nametable control
+// - CPU $5200-$5206: Vertical split, scanline IRQ and multiplier
+// - CPU $5C00-$5FFF: 1KB ExRAM
+// - CPU $6000-$7FFF: 8KB PRG-RAM bank
+// - CPU $8000-$FFFF: PRG-ROM or PRG-RAM in 8KB, 16KB or 32KB banks
+// - PPU $0000-$1FFF: CHR in 1KB, 2KB, 4KB or 8KB banks
+// - PPU $2000-$2FFF: Each nametable is CIRAM page 0 or 1, ExRAM or fill mode
+//
+// Features:
+// - Four PRG banking modes; $8000-$DFFF banks can select PRG-RAM
+// - Four CHR banking modes, with separate sprite and background banks when
+//   8x16 sprites are enabled (the MMC5 snoops PPUCTRL for the sprite size)
+// - ExRAM as an extra nametable, extended attributes (a palette and 4KB CHR
+//   bank per tile) or plain CPU RAM
+// - Fill mode: a nametable showing a single tile and palette
+// - Vertical split: a column of tiles drawn from ExRAM with its own scroll
+// - Scanline IRQ and an 8x8 -> 16-bit unsigned multiplier
+// - Two pulse channels (no sweep) and an 8-bit PCM channel
+// - PRG-RAM: up to 64KB, always allocated at full size here
+//
+// Register Interface:
+// - $5000-$5007: Pulse 1 and 2, as APU $4000-$4007 (sweep registers unused)
+// - $5010: PCM mode (bit 0: 1 = read mode) and IRQ enable (bit 7)
+// - $5011: PCM raw output (write mode; 0 is ignored)
+// - $5015: Pulse channel enables (W) / length counter status (R)
+// - $5100: PRG mode (0 = 32KB, 1 = 16KB+16KB, 2 = 16KB+8KB+8KB, 3 = 8KB x4)
+// - $5101: CHR mode (0 = 8KB, 1 = 4KB, 2 = 2KB, 3 = 1KB)
+// - $5102/$5103: PRG-RAM write protect (writable when they hold 2 and 1)
+// - $5104: ExRAM mode (0 = nametable, 1 = extended attributes,
+//   2 = CPU RAM, 3 = CPU ROM)
+// - $5105: Nametable mapping, 2 bits per nametable (0/1 = CIRAM page,
+//   2 = ExRAM, 3 = fill mode)
+// - $5106/$5107: Fill mode tile and palette
+// - $5113: PRG-RAM bank at $6000
+// - $5114-$5117: PRG banks (bit 7 set = ROM; $5117 is always ROM)
+// - $5120-$5127: CHR banks for sprites (and everything in 8x8 mode)
+// - $5128-$512B: CHR banks for the background in 8x16 mode
+// - $5130: Upper CHR bank bits
+// - $5200: Split control (bit 7: enable, bit 6: right side, bits 0-4: tile)
+// - $5201: Split vertical scroll
+// - $5202: Split 4KB CHR bank
+// - $5203: IRQ scanline compare value
+// - $5204: IRQ enable (W) / IRQ pending and in-frame flags (R, acknowledges)
+// - $5205/$5206: Multiplicand and multiplier (W) / product low and high (R)
+
+use super::{MapperError, StateReader, StateWriter};
+use crate::apu::PulseChannel;
+use crate::cartridge::{Cartridge, Mapper, Mirroring, NametableSource};
+
+/// PRG bank size (8KB)
+const PRG_BANK_SIZE: usize = 8 * 1024;
+
+/// CHR-ROM 1KB bank size
+const CHR_1KB_BANK_SIZE: usize = 1024;
+
+/// PRG-RAM size (64KB, the largest any MMC5 board carries)
+const PRG_RAM_SIZE: usize = 64 * 1024;
+
+/// ExRAM size (1KB)
+const EXRAM_SIZE: usize = 1024;
+
+/// PPU dots per scanline
+const DOTS_PER_SCANLINE: u64 = 341;
+
+/// Number of visible scanlines
+const VISIBLE_SCANLINES: u16 = 240;
+
+/// CPU cycles between clocks of the audio envelopes and length counters (240 Hz)
+const AUDIO_FRAME_PERIOD: u16 = 7457;
+
+/// PRG memory selected for a CPU address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PrgTarget {
+    /// Offset into PRG-ROM
+    Rom(usize),
+    /// Offset into PRG-RAM
+    Ram(usize),
+}
+
+/// Mapper 5 implementation (MMC5)
+///
+/// MMC5 is used by games like:
+/// - Castlevania III: Dracula's Curse
+/// - Just Breed
+/// - Uncharted Waters and other Koei strategy games
+/// - Laser Invasion
+pub struct Mapper5 {
+    /// PRG-ROM data
+    prg_rom: Vec<u8>,
+    /// CHR-ROM or CHR-RAM data
+    chr_mem: Vec<u8>,
+    /// PRG-RAM (64KB)
+    prg_ram: Vec<u8>,
+    /// Expansion RAM (1KB)
+    exram: Vec<u8>,
+    /// Whether CHR memory is RAM (writable) or ROM (read-only)
+    chr_is_ram: bool,
+
+    // Banking registers
+    /// PRG banking mode ($5100)
+    prg_mode: u8,
+    /// CHR banking mode ($5101)
+    chr_mode: u8,
+    /// PRG-RAM write protect values ($5102, $5103)
+    prg_ram_protect: [u8; 2],
+    /// ExRAM mode ($5104)
+    exram_mode: u8,
+    /// Nametable mapping ($5105)
+    nametable_mapping: u8,
+    /// Fill mode tile ($5106)
+    fill_tile: u8,
+    /// Fill mode palette ($5107)
+    fill_palette: u8,
+    /// PRG bank registers ($5113-$5117)
+    prg_banks: [u8; 5],
+    /// CHR bank registers ($5120-$512B), including the upper bits
+    chr_banks: [u16; 12],
+    /// Upper CHR bank bits ($5130)
+    chr_upper: u8,
+    /// Whether the background set ($5128-$512B) was written last
+    background_banks_last: bool,
+
+    // Split screen
+    /// Split control ($5200)
+    split_control: u8,
+    /// Split vertical scroll ($5201)
+    split_scroll: u8,
+    /// Split 4KB CHR bank ($5202)
+    split_bank: u8,
+
+    // Scanline IRQ
+    /// Scanline compare value ($5203)
+    irq_compare: u8,
+    /// IRQ enabled flag ($5204 bit 7)
+    irq_enabled: bool,
+    /// IRQ pending flag (cleared by reading $5204)
+    irq_pending: bool,
+    /// Whether the PPU is rendering a frame (cleared by the NMI vector fetch)
+    in_frame: bool,
+    /// Scanline counter
+    scanline_counter: u8,
+
+    // Multiplier
+    /// Multiplicand ($5205)
+    multiplicand: u8,
+    /// Multiplier ($5206)
+    multiplier: u8,
+
+    // Snooped PPU state
+    /// Whether 8x16 sprites are enabled (PPUCTRL bit 5)
+    sprite_8x16: bool,
+    /// Current scanline, from the PPU's scanline notification
+    scanline: u16,
+    /// Dot within the scanline of the last PPU fetch
+    fetch_dot: u16,
+    /// Screen column of the background tile being fetched
+    tile_column: u8,
+    /// Whether the background tile being fetched belongs to the split region
+    tile_in_split: bool,
+    /// ExRAM byte for the background tile being fetched
+    tile_exram: u8,
+    /// Line within the split region (0-239) of the tile being fetched
+    split_y: u16,
+
+    // Audio
+    /// Pulse channels 1 and 2
+    pulses: [PulseChannel; 2],
+    /// PCM control ($5010)
+    pcm_control: u8,
+    /// PCM output level
+    pcm_output: u8,
+    /// PCM IRQ flag (a zero was read in PCM read mode)
+    pcm_irq: bool,
+    /// Whether the pulse timers run on the current CPU cycle
+    audio_odd_cycle: bool,
+    /// CPU cycles since the last envelope/length counter clock
+    audio_frame_counter: u16,
+
+    // Derived state
+    /// Number of 8KB PRG-ROM banks
+    prg_bank_count: usize,
+}
+
+impl Mapper5 {
+    /// Create a new Mapper5 instance from a cartridge
+    ///
+    /// # Arguments
+    /// * `cartridge` - The cartridge containing ROM data
+    pub fn new(cartridge: Cartridge) -> Self {
+        let prg_bank_count = cartridge.prg_rom.len() / PRG_BANK_SIZE;
+
+        // Validate bank counts to prevent divide-by-zero
+        assert!(
+            prg_bank_count > 0,
+            "Mapper5 requires at least one PRG bank (8KB, got {} bytes)",
+            cartridge.prg_rom.len()
+        );
+        assert!(!cartridge.chr_rom.is_empty(), "Mapper5 requires CHR memory");
+
+        // CHR-RAM is indicated by all zeros in chr_rom
+        let chr_is_ram =
+            cartridge.chr_rom.len() == 8 * 1024 && cartridge.chr_rom.iter().all(|&b| b == 0);
+
+        Mapper5 {
+            prg_rom: cartridge.prg_rom,
+            chr_mem: cartridge.chr_rom,
+            prg_ram: vec![0; PRG_RAM_SIZE],
+            exram: vec![0; EXRAM_SIZE],
+            chr_is_ram,
+
+            // Power-on state: 8KB PRG banks, all pointing at the last bank
+            prg_mode: 3,
+            chr_mode: 0,
+            prg_ram_protect: [0, 0],
+            exram_mode: 0,
+            nametable_mapping: 0,
+            fill_tile: 0,
+            fill_palette: 0,
+            prg_banks: [0, 0xFF, 0xFF, 0xFF, 0xFF],
+            chr_banks: [0; 12],
+            chr_upper: 0,
+            background_banks_last: false,
+
+            split_control: 0,
+            split_scroll: 0,
+            split_bank: 0,
+
+            irq_compare: 0,
+            irq_enabled: false,
+            irq_pending: false,
+            in_frame: false,
+            scanline_counter: 0,
+
+            multiplicand: 0xFF,
+            multiplier: 0xFF,
+
+            sprite_8x16: false,
+            scanline: 0,
+            fetch_dot: 0,
+            tile_column: 0,
+            tile_in_split: false,
+            tile_exram: 0,
+            split_y: 0,
+
+            pulses: [PulseChannel::new(0), PulseChannel::new(0)],
+            pcm_control: 0,
+            pcm_output: 0,
+            pcm_irq: false,
+            audio_odd_cycle: false,
+            audio_frame_counter: 0,
+
+            prg_bank_count,
+        }
+    }
+
+    /// Map a CPU address in $6000-$FFFF to PRG-ROM or PRG-RAM
+    fn map_prg_address(&self, address: u16) -> PrgTarget {
+        if address < 0x8000 {
+            let bank = (self.prg_banks[0] & 0x07) as usize;
+            return PrgTarget::Ram(bank * PRG_BANK_SIZE + (address & 0x1FFF) as usize);
+        }
+
+        // (bank register, number of 8KB banks it spans minus one)
+        let (register, span_mask) = match (self.prg_mode, address) {
+            (0, _) => (4, 3),
+            (1, 0x8000..=0xBFFF) => (2, 1),
+            (1, _) => (4, 1),
+            (2, 0x8000..=0xBFFF) => (2, 1),
+            (2, 0xC000..=0xDFFF) => (3, 0),
+            (2, _) => (4, 0),
+            _ => (1 + ((address - 0x8000) >> 13) as usize, 0),
+        };
+
+        let value = self.prg_banks[register];
+        let bank =
+            (value & 0x7F & !span_mask) as usize | ((address >> 13) as usize & span_mask as usize);
+        let offset = (address & 0x1FFF) as usize;
+
+        // $5117 always selects ROM; the others choose with bit 7
+        if register == 4 || value & 0x80 != 0 {
+            PrgTarget::Rom((bank % self.prg_bank_count) * PRG_BANK_SIZE + offset)
+        } else {
+            PrgTarget::Ram((bank & 0x07) * PRG_BANK_SIZE + offset)
+        }
+    }
+
+    /// Check if PRG-RAM writes are enabled ($5102 = 2 and $5103 = 1)
+    fn prg_ram_writable(&self) -> bool {
+        self.prg_ram_protect == [0x02, 0x01]
+    }
+
+    /// Map a PPU address to a CHR offset using one of the bank register sets
+    ///
+    /// # Arguments
+    /// * `address` - PPU address ($0000-$1FFF)
+    /// * `background` - Use the background set ($5128-$512B) instead of $5120-$5127
+    fn map_chr_address(&self, address: u16, background: bool) -> usize {
+        let page_size = (8 * CHR_1KB_BANK_SIZE) >> self.chr_mode;
+        let page = address as usize / page_size;
+
+        // Each page uses the last register of its range: in 2KB mode the
+        // pages use $5121, $5123, $5125 and $5127. The background set only has
+        // four registers, repeated for both pattern tables.
+        let register = (page + 1) * (8 >> self.chr_mode) - 1;
+        let register = if background {
+            8 + (register & 0x03)
+        } else {
+            register
+        };
+
+        let bank = self.chr_banks[register] as usize;
+        (bank * page_size + address as usize % page_size) % self.chr_mem.len()
+    }
+
+    /// Check if the background CHR banks apply to a PPU fetch
+    ///
+    /// With 8x8 sprites only $5120-$5127 are used. With 8x16 sprites the
+    /// background set is used for background fetches while rendering, and
+    /// outside rendering the set that was written last applies.
+    fn uses_background_banks(&self) -> bool {
+        if !self.sprite_8x16 {
+            false
+        } else if self.in_frame {
+            is_background_dot(self.fetch_dot)
+        } else {
+            self.background_banks_last
+        }
+    }
+
+    /// Check if the PPU is fetching a background tile while rendering
+    fn fetching_background(&self) -> bool {
+        self.in_frame && is_background_dot(self.fetch_dot)
+    }
+
+    /// Start a background tile on its nametable fetch
+    ///
+    /// Works out which screen column the tile lands in, whether the split
+    /// region covers it, and latches its ExRAM byte for extended attributes.
+    fn start_background_tile(&mut self, address: u16) {
+        // Dots 321-336 prefetch the first two tiles of the next scanline
+        let dot = self.fetch_dot;
+        let (column, line) = if dot >= 321 {
+            ((dot - 321) / 8, self.scanline + 1)
+        } else {
+            ((dot - 1) / 8 + 2, self.scanline)
+        };
+        self.tile_column = column as u8;
+
+        let split_tile = self.split_control & 0x1F;
+        let split_side = if self.split_control & 0x40 != 0 {
+            self.tile_column >= split_tile
+        } else {
+            self.tile_column < split_tile
+        };
+        self.tile_in_split = self.split_control & 0x80 != 0 && self.exram_mode <= 1 && split_side;
+
+        // The split region scrolls on its own, wrapping at the bottom of the screen
+        let split_y = self.split_scroll as u16 + line;
+        self.split_y = if split_y >= VISIBLE_SCANLINES {
+            split_y - VISIBLE_SCANLINES
+        } else {
+            split_y
+        };
+
+        self.tile_exram = self.exram[(address & 0x03FF) as usize];
+    }
+
+    /// Get the split region's nametable or attribute byte for the current tile
+    ///
+    /// Attribute bytes repeat the tile's palette in all four quadrants, since
+    /// the PPU picks the quadrant from its own scroll position.
+    fn split_nametable_byte(&self, attribute: bool) -> u8 {
+        let row = (self.split_y / 8) as usize;
+        let column = (self.tile_column & 0x1F) as usize;
+
+        if attribute {
+            let byte = self.exram[0x3C0 + (row / 4) * 8 + column / 4];
+            let shift = ((row & 0x02) << 1) | (column & 0x02);
+            ((byte >> shift) & 0x03) * 0x55
+        } else {
+            self.exram[row * 32 + column]
+        }
+    }
+
+    /// Map a background pattern fetch made while rendering
+    ///
+    /// Split tiles come from the split CHR bank at the split's fine Y, and
+    /// extended attribute mode selects a 4KB bank per tile from ExRAM.
+    fn map_background_pattern(&self, address: u16) -> usize {
+        let offset = if self.tile_in_split {
+            let fine_y = self.split_y as usize & 0x07;
+            self.split_bank as usize * 0x1000 + (address as usize & 0x0FF8) + fine_y
+        } else if self.exram_mode == 1 {
+            let bank = (self.tile_exram & 0x3F) as usize | (self.chr_upper as usize) << 6;
+            bank * 0x1000 + (address as usize & 0x0FFF)
+        } else {
+            return self.map_chr_address(address, self.uses_background_banks());
+        };
+        offset % self.chr_mem.len()
+    }
+
+    /// Map a PPU pattern fetch to a CHR offset
+    fn map_pattern_fetch(&self, address: u16) -> usize {
+        if self.fetching_background() {
+            self.map_background_pattern(address)
+        } else {
+            self.map_chr_address(address, self.uses_background_banks())
+        }
+    }
+
+    /// Read a register or ExRAM without side effects
+    ///
+    /// # Returns
+    /// The byte driven by the MMC5, or None for open bus
+    fn peek_register(&self, address: u16) -> Option<u8> {
+        match address {
+            0x5010 => Some((self.pcm_irq as u8) << 7 | (self.pcm_control & 0x01)),
+            0x5015 => {
+                let pulse1 = self.pulses[0].length_counter.is_active() as u8;
+                let pulse2 = self.pulses[1].length_counter.is_active() as u8;
+                Some(pulse1 | pulse2 << 1)
+            }
+            0x5204 => Some((self.irq_pending as u8) << 7 | (self.in_frame as u8) << 6),
+            0x5205 => Some(self.product() as u8),
+            0x5206 => Some((self.product() >> 8) as u8),
+            // ExRAM is only readable by the CPU in modes 2 and 3
+            0x5C00..=0x5FFF if self.exram_mode >= 2 => {
+                Some(self.exram[(address - 0x5C00) as usize])
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the multiplier result
+    fn product(&self) -> u16 {
+        self.multiplicand as u16 * self.multiplier as u16
+    }
+
+    /// Write an MMC5 register in $5000-$5FFF
+    fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            // Pulse channels ($5001 and $5005 would be sweep; MMC5 has none)
+            0x5000 | 0x5004 => self.pulses[(address >> 2) as usize & 1].write_register_0(value),
+            0x5002 | 0x5006 => self.pulses[(address >> 2) as usize & 1].write_register_2(value),
+            0x5003 | 0x5007 => self.pulses[(address >> 2) as usize & 1].write_register_3(value),
+            0x5010 => self.pcm_control = value & 0x81,
+            0x5011 => {
+                // Zero is ignored in write mode, and all writes in read mode
+                if self.pcm_control & 0x01 == 0 && value != 0 {
+                    self.pcm_output = value;
+                }
+            }
+            0x5015 => {
+                self.pulses[0].set_enabled(value & 0x01 != 0);
+                self.pulses[1].set_enabled(value & 0x02 != 0);
+            }
+
+            0x5100 => self.prg_mode = value & 0x03,
+            0x5101 => self.chr_mode = value & 0x03,
+            0x5102 => self.prg_ram_protect[0] = value & 0x03,
+            0x5103 => self.prg_ram_protect[1] = value & 0x03,
+            0x5104 => self.exram_mode = value & 0x03,
+            0x5105 => self.nametable_mapping = value,
+            0x5106 => self.fill_tile = value,
+            0x5107 => self.fill_palette = value & 0x03,
+            0x5113..=0x5117 => self.prg_banks[(address - 0x5113) as usize] = value,
+            0x5120..=0x512B => {
+                let register = (address - 0x5120) as usize;
+                self.chr_banks[register] = value as u16 | (self.chr_upper as u16) << 8;
+                self.background_banks_last = register >= 8;
+            }
+            0x5130 => self.chr_upper = value & 0x03,
+
+            0x5200 => self.split_control = value,
+            0x5201 => self.split_scroll = value,
+            0x5202 => self.split_bank = value,
+            0x5203 => self.irq_compare = value,
+            0x5204 => self.irq_enabled = value & 0x80 != 0,
+            0x5205 => self.multiplicand = value,
+            0x5206 => self.multiplier = value,
+
+            0x5C00..=0x5FFF => {
+                let index = (address - 0x5C00) as usize;
+                match self.exram_mode {
+                    // While the PPU owns ExRAM, writes outside rendering store 0
+                    0 | 1 => self.exram[index] = if self.in_frame { value } else { 0 },
+                    2 => self.exram[index] = value,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Get the nametable mapping (0-3) for a nametable address
+    fn nametable_select(&self, address: u16) -> u8 {
+        let table = (address >> 10) & 0x03;
+        (self.nametable_mapping >> (table * 2)) & 0x03
+    }
+}
+
+impl Mapper for Mapper5 {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            0x5000..=0x5FFF => self.peek_register(address).unwrap_or(0),
+            0x6000..=0xFFFF => match self.map_prg_address(address) {
+                PrgTarget::Rom(offset) => self.prg_rom[offset],
+                PrgTarget::Ram(offset) => self.prg_ram[offset],
+            },
+            _ => 0,
+        }
+    }
+
+    fn try_cpu_read(&self, address: u16) -> Option<u8> {
+        match address {
+            0x5000..=0x5FFF => self.peek_register(address),
+            0x6000..=0xFFFF => Some(self.cpu_read(address)),
+            _ => None,
+        }
+    }
+
+    fn on_cpu_read(&mut self, address: u16, value: u8) {
+        match address {
+            0x5010 => self.pcm_irq = false,
+            0x5204 => self.irq_pending = false,
+            0x8000..=0xBFFF if self.pcm_control & 0x01 != 0 => {
+                // PCM read mode plays the bytes the CPU reads; zero raises the IRQ
+                if value == 0 {
+                    self.pcm_irq = true;
+                } else {
+                    self.pcm_output = value;
+                }
+            }
+            // The NMI vector fetch marks the end of the frame
+            0xFFFA | 0xFFFB => {
+                self.in_frame = false;
+                self.irq_pending = false;
+            }
+            _ => {}
+        }
+    }
+
+    fn cpu_write(&mut self, address: u16, value: u8) {
+        match address {
+            0x5000..=0x5FFF => self.write_register(address, value),
+            0x6000..=0xFFFF => {
+                if let PrgTarget::Ram(offset) = self.map_prg_address(address) {
+                    if self.prg_ram_writable() {
+                        self.prg_ram[offset] = value;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => {
+                let background = self.sprite_8x16 && self.background_banks_last;
+                self.chr_mem[self.map_chr_address(address, background)]
+            }
+            _ => 0,
+        }
+    }
+
+    fn ppu_fetch(&mut self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => self.chr_mem[self.map_pattern_fetch(address)],
+            _ => 0,
+        }
+    }
+
+    fn ppu_write(&mut self, address: u16, value: u8) {
+        if self.chr_is_ram {
+            if let 0x0000..=0x1FFF = address {
+                let index = self.map_pattern_fetch(address);
+                self.chr_mem[index] = value;
+            }
+        }
+        // Writes to CHR-ROM are ignored
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.nametable_mapping {
+            0x44 => Mirroring::Vertical,
+            0x50 => Mirroring::Horizontal,
+            0x00 | 0x55 => Mirroring::SingleScreen,
+            // Any other arrangement maps the four nametables separately
+            _ => Mirroring::FourScreen,
+        }
+    }
+
+    fn nametable_source(&self, address: u16) -> Option<NametableSource> {
+        Some(match self.nametable_select(address) {
+            page @ (0 | 1) => NametableSource::Ciram(page),
+            _ => NametableSource::Cartridge,
+        })
+    }
+
+    fn nametable_read(&self, address: u16) -> u8 {
+        let offset = (address & 0x03FF) as usize;
+        match self.nametable_select(address) {
+            // ExRAM only serves as a nametable in modes 0 and 1
+            2 if self.exram_mode <= 1 => self.exram[offset],
+            2 => 0,
+            // Fill mode
+            _ if offset >= 0x3C0 => self.fill_palette * 0x55,
+            _ => self.fill_tile,
+        }
+    }
+
+    fn nametable_write(&mut self, address: u16, value: u8) {
+        if self.nametable_select(address) == 2 && self.exram_mode <= 1 {
+            self.exram[(address & 0x03FF) as usize] = value;
+        }
+    }
+
+    fn nametable_fetch(&mut self, address: u16) -> Option<u8> {
+        if !self.fetching_background() {
+            return None;
+        }
+
+        let attribute = address & 0x03FF >= 0x03C0;
+        if self.tile_in_split {
+            Some(self.split_nametable_byte(attribute))
+        } else if self.exram_mode == 1 && attribute {
+            // Extended attributes: the palette comes from the tile's ExRAM byte
+            Some((self.tile_exram >> 6) * 0x55)
+        } else {
+            None
+        }
+    }
+
+    fn on_ppu_address(&mut self, addr: u16, ppu_cycle: u64) {
+        self.fetch_dot = (ppu_cycle % DOTS_PER_SCANLINE) as u16;
+
+        // A nametable (not attribute) fetch starts each background tile
+        let nametable = (0x2000..=0x3EFF).contains(&addr) && addr & 0x03FF < 0x03C0;
+        if nametable && self.fetching_background() {
+            self.start_background_tile(addr);
+        }
+    }
+
+    fn on_scanline(&mut self, scanline: u16, rendering_enabled: bool) {
+        self.scanline = scanline;
+
+        if !rendering_enabled || scanline >= VISIBLE_SCANLINES {
+            self.in_frame = false;
+            return;
+        }
+
+        if self.in_frame {
+            self.scanline_counter = self.scanline_counter.wrapping_add(1);
+            if self.scanline_counter == self.irq_compare {
+                self.irq_pending = true;
+            }
+        } else {
+            // First rendered scanline of the frame
+            self.in_frame = true;
+            self.scanline_counter = 0;
+        }
+    }
+
+    fn on_ppu_register_write(&mut self, address: u16, value: u8) {
+        match address {
+            // PPUCTRL: sprite size
+            0x2000 => self.sprite_8x16 = value & 0x20 != 0,
+            // PPUMASK: turning rendering off ends the frame
+            0x2001 if value & 0x18 == 0 => self.in_frame = false,
+            _ => {}
+        }
+    }
+
+    fn cpu_clock(&mut self) {
+        // The pulse timers run at the APU rate, half the CPU clock
+        self.audio_odd_cycle = !self.audio_odd_cycle;
+        if self.audio_odd_cycle {
+            for pulse in &mut self.pulses {
+                pulse.clock_timer();
+            }
+        }
+
+        // Envelopes and length counters are clocked at a fixed 240 Hz
+        self.audio_frame_counter += 1;
+        if self.audio_frame_counter >= AUDIO_FRAME_PERIOD {
+            self.audio_frame_counter = 0;
+            for pulse in &mut self.pulses {
+                pulse.clock_envelope();
+                pulse.clock_length_counter();
+            }
+        }
+    }
+
+    fn audio_output(&self) -> f32 {
+        // Mixed like the APU's pulse channels and DMC
+        let pulse_sum = (self.pulses[0].output() + self.pulses[1].output()) as f32;
+        let pulse_out = if pulse_sum == 0.0 {
+            0.0
+        } else {
+            95.88 / (8128.0 / pulse_sum + 100.0)
+        };
+
+        let pcm = self.pcm_output as f32 / 2.0;
+        let pcm_out = if pcm == 0.0 {
+            0.0
+        } else {
+            159.79 / (22638.0 / pcm + 100.0)
+        };
+
+        pulse_out + pcm_out
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => match self.map_prg_address(address) {
+                PrgTarget::Rom(offset) => Some(offset),
+                PrgTarget::Ram(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn prg_ram(&self) -> Option<&[u8]> {
+        Some(&self.prg_ram)
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.prg_ram)
+    }
+
+    fn irq_pending(&self) -> bool {
+        (self.irq_enabled && self.irq_pending) || (self.pcm_control & 0x80 != 0 && self.pcm_irq)
+    }
+
+    fn irq_acknowledge(&mut self) {
+        self.irq_pending = false;
+        self.pcm_irq = false;
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.write_u8(self.prg_mode);
+        writer.write_u8(self.chr_mode);
+        writer.write_u8(self.prg_ram_protect[0]);
+        writer.write_u8(self.prg_ram_protect[1]);
+        writer.write_u8(self.exram_mode);
+        writer.write_u8(self.nametable_mapping);
+        writer.write_u8(self.fill_tile);
+        writer.write_u8(self.fill_palette);
+        for &bank in &self.prg_banks {
+            writer.write_u8(bank);
+        }
+        for &bank in &self.chr_banks {
+            writer.write_u16(bank);
+        }
+        writer.write_u8(self.chr_upper);
+        writer.write_bool(self.background_banks_last);
+        writer.write_u8(self.split_control);
+        writer.write_u8(self.split_scroll);
+        writer.write_u8(self.split_bank);
+        writer.write_u8(self.irq_compare);
+        writer.write_bool(self.irq_enabled);
+        writer.write_bool(self.irq_pending);
+        writer.write_bool(self.in_frame);
+        writer.write_u8(self.scanline_counter);
+        writer.write_u8(self.multiplicand);
+        writer.write_u8(self.multiplier);
+        writer.write_bool(self.sprite_8x16);
+        writer.write_u16(self.scanline);
+        writer.write_u16(self.fetch_dot);
+        writer.write_u8(self.tile_column);
+        writer.write_bool(self.tile_in_split);
+        writer.write_u16(self.split_y);
+        writer.write_u8(self.tile_exram);
+        for pulse in &self.pulses {
+            pulse.save_state(&mut writer);
+        }
+        writer.write_u8(self.pcm_control);
+        writer.write_u8(self.pcm_output);
+        writer.write_bool(self.pcm_irq);
+        writer.write_bool(self.audio_odd_cycle);
+        writer.write_u16(self.audio_frame_counter);
+        writer.write_bytes(&self.exram);
+        writer.write_bytes(&self.prg_ram);
+        if self.chr_is_ram {
+            writer.write_bytes(&self.chr_mem);
+        }
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        self.prg_mode = reader.read_u8()? & 0x03;
+        self.chr_mode = reader.read_u8()? & 0x03;
+        self.prg_ram_protect[0] = reader.read_u8()?;
+        self.prg_ram_protect[1] = reader.read_u8()?;
+        self.exram_mode = reader.read_u8()?;
+        self.nametable_mapping = reader.read_u8()?;
+        self.fill_tile = reader.read_u8()?;
+        self.fill_palette = reader.read_u8()? & 0x03;
+        for bank in self.prg_banks.iter_mut() {
+            *bank = reader.read_u8()?;
+        }
+        for bank in self.chr_banks.iter_mut() {
+            *bank = reader.read_u16()?;
+        }
+        self.chr_upper = reader.read_u8()?;
+        self.background_banks_last = reader.read_bool()?;
+        self.split_control = reader.read_u8()?;
+        self.split_scroll = reader.read_u8()?;
+        self.split_bank = reader.read_u8()?;
+        self.irq_compare = reader.read_u8()?;
+        self.irq_enabled = reader.read_bool()?;
+        self.irq_pending = reader.read_bool()?;
+        self.in_frame = reader.read_bool()?;
+        self.scanline_counter = reader.read_u8()?;
+        self.multiplicand = reader.read_u8()?;
+        self.multiplier = reader.read_u8()?;
+        self.sprite_8x16 = reader.read_bool()?;
+        self.scanline = reader.read_u16()?;
+        self.fetch_dot = reader.read_u16()?;
+        self.tile_column = reader.read_u8()?;
+        self.tile_in_split = reader.read_bool()?;
+        self.split_y = reader.read_u16()?;
+        self.tile_exram = reader.read_u8()?;
+        for pulse in self.pulses.iter_mut() {
+            pulse.load_state(&mut reader)?;
+        }
+        self.pcm_control = reader.read_u8()?;
+        self.pcm_output = reader.read_u8()?;
+        self.pcm_irq = reader.read_bool()?;
+        self.audio_odd_cycle = reader.read_bool()?;
+        self.audio_frame_counter = reader.read_u16()?;
+        reader.read_bytes_into(&mut self.exram)?;
+        reader.read_bytes_into(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            reader.read_bytes_into(&mut self.chr_mem)?;
+        }
+        Ok(reader.finish()?)
+    }
+}
+
+/// Check if a dot fetches background tiles (as opposed to sprite patterns)
+fn is_background_dot(dot: u16) -> bool {
+    matches!(dot, 1..=256 | 321..=336)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a test cartridge
+    ///
+    /// Every byte of PRG-ROM holds its 8KB bank number and every byte of
+    /// CHR-ROM its 1KB bank number.
+    fn create_test_cartridge(prg_banks: usize, chr_banks: usize) -> Cartridge {
+        let prg_rom = (0..prg_banks * PRG_BANK_SIZE)
+            .map(|i| (i / PRG_BANK_SIZE) as u8)
+            .collect();
+        let chr_rom = (0..chr_banks * CHR_1KB_BANK_SIZE)
+            .map(|i| (i / CHR_1KB_BANK_SIZE) as u8)
+            .collect();
+
+        Cartridge {
+            prg_rom,
+            chr_rom,
+            trainer: None,
+            mapper: 5,
+            mirroring: Mirroring::Horizontal,
+            has_battery: false,
+            ..Cartridge::new()
+        }
+    }
+
+    /// Create an MMC5 with 256KB PRG-ROM and 256KB CHR-ROM
+    fn create_mapper() -> Mapper5 {
+        Mapper5::new(create_test_cartridge(32, 256))
+    }
+
+    /// Place an address on the PPU bus at a dot of the current scanline
+    fn fetch_at(mapper: &mut Mapper5, addr: u16, dot: u16) {
+        let ppu_cycle = mapper.scanline as u64 * DOTS_PER_SCANLINE + dot as u64;
+        mapper.on_ppu_address(addr, ppu_cycle);
+    }
+
+    /// Read a byte the way the CPU does, side effects included
+    fn cpu_cycle_read(mapper: &mut Mapper5, address: u16) -> Option<u8> {
+        let value = mapper.try_cpu_read(address);
+        mapper.on_cpu_read(address, value.unwrap_or(0));
+        value
+    }
+
+    /// Start rendering scanline `scanline` of a frame
+    fn start_frame(mapper: &mut Mapper5, scanline: u16) {
+        for line in 0..=scanline {
+            mapper.on_scanline(line, true);
+        }
+    }
+
+    #[test]
+    fn test_mapper5_power_on_state() {
+        let mapper = create_mapper();
+
+        // 8KB mode with every bank on the last PRG-ROM bank
+        assert_eq!(mapper.prg_mode, 3);
+        for addr in [0x8000, 0xA000, 0xC000, 0xFFFF] {
+            assert_eq!(mapper.cpu_read(addr), 31);
+        }
+        assert!(!mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_prg_mode_0_32kb() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5100, 0);
+        mapper.cpu_write(0x5117, 0x85); // Low two bits ignored
+
+        assert_eq!(mapper.cpu_read(0x8000), 4);
+        assert_eq!(mapper.cpu_read(0xA000), 5);
+        assert_eq!(mapper.cpu_read(0xC000), 6);
+        assert_eq!(mapper.cpu_read(0xE000), 7);
+    }
+
+    #[test]
+    fn test_prg_mode_1_16kb() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5100, 1);
+        mapper.cpu_write(0x5115, 0x83);
+        mapper.cpu_write(0x5117, 0x8B);
+
+        assert_eq!(mapper.cpu_read(0x8000), 2);
+        assert_eq!(mapper.cpu_read(0xA000), 3);
+        assert_eq!(mapper.cpu_read(0xC000), 10);
+        assert_eq!(mapper.cpu_read(0xE000), 11);
+    }
+
+    #[test]
+    fn test_prg_mode_2_16kb_8kb_8kb() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5100, 2);
+        mapper.cpu_write(0x5115, 0x84);
+        mapper.cpu_write(0x5116, 0x89);
+        mapper.cpu_write(0x5117, 0x0C); // $5117 always selects ROM
+
+        assert_eq!(mapper.cpu_read(0x8000), 4);
+        assert_eq!(mapper.cpu_read(0xA000), 5);
+        assert_eq!(mapper.cpu_read(0xC000), 9);
+        assert_eq!(mapper.cpu_read(0xE000), 12);
+        assert_eq!(mapper.prg_rom_offset(0xE000), Some(12 * PRG_BANK_SIZE));
+    }
+
+    #[test]
+    fn test_prg_mode_3_8kb() {
+        let mut mapper = create_mapper();
+        for (i, bank) in [0x81u8, 0x82, 0x83, 0x84].into_iter().enumerate() {
+            mapper.cpu_write(0x5114 + i as u16, bank);
+        }
+
+        assert_eq!(mapper.cpu_read(0x8000), 1);
+        assert_eq!(mapper.cpu_read(0xA000), 2);
+        assert_eq!(mapper.cpu_read(0xC000), 3);
+        assert_eq!(mapper.cpu_read(0xE000), 4);
+    }
+
+    #[test]
+    fn test_prg_ram_write_protect() {
+        let mut mapper = create_mapper();
+
+        // Writes are ignored until $5102/$5103 hold 2 and 1
+        mapper.cpu_write(0x6000, 0x42);
+        assert_eq!(mapper.cpu_read(0x6000), 0);
+
+        mapper.cpu_write(0x5102, 0x02);
+        mapper.cpu_write(0x5103, 0x01);
+        mapper.cpu_write(0x6000, 0x42);
+        assert_eq!(mapper.cpu_read(0x6000), 0x42);
+
+        mapper.cpu_write(0x5103, 0x00);
+        mapper.cpu_write(0x6000, 0x99);
+        assert_eq!(mapper.cpu_read(0x6000), 0x42);
+    }
+
+    #[test]
+    fn test_prg_ram_banking() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5102, 0x02);
+        mapper.cpu_write(0x5103, 0x01);
+
+        // Write through $6000 in RAM bank 3
+        mapper.cpu_write(0x5113, 0x03);
+        mapper.cpu_write(0x6010, 0x5A);
+
+        // Map RAM bank 3 at $8000 (bit 7 clear selects RAM)
+        mapper.cpu_write(0x5114, 0x03);
+        assert_eq!(mapper.cpu_read(0x8010), 0x5A);
+        assert_eq!(mapper.prg_rom_offset(0x8010), None);
+        mapper.cpu_write(0x8011, 0xA5);
+        assert_eq!(mapper.cpu_read(0x6011), 0xA5);
+
+        assert_eq!(mapper.prg_ram().unwrap()[3 * PRG_BANK_SIZE + 0x10], 0x5A);
+    }
+
+    #[test]
+    fn test_chr_modes() {
+        let mut mapper = create_mapper();
+        for i in 0..8 {
+            mapper.cpu_write(0x5120 + i, 0x10 + i as u8);
+        }
+
+        // 8KB mode uses $5127
+        mapper.cpu_write(0x5101, 0);
+        assert_eq!(mapper.ppu_read(0x0000), 0x17 * 8);
+        assert_eq!(mapper.ppu_read(0x1C00), 0x17 * 8 + 7);
+
+        // 4KB mode uses $5123 and $5127
+        mapper.cpu_write(0x5101, 1);
+        assert_eq!(mapper.ppu_read(0x0000), 0x13 * 4);
+        assert_eq!(mapper.ppu_read(0x1400), 0x17 * 4 + 1);
+
+        // 2KB mode uses the odd registers
+        mapper.cpu_write(0x5101, 2);
+        assert_eq!(mapper.ppu_read(0x0800), 0x13 * 2);
+        assert_eq!(mapper.ppu_read(0x1C00), 0x17 * 2 + 1);
+
+        // 1KB mode uses every register
+        mapper.cpu_write(0x5101, 3);
+        for i in 0..8u16 {
+            assert_eq!(mapper.ppu_read(i * 0x400), 0x10 + i as u8);
+        }
+    }
+
+    #[test]
+    fn test_chr_upper_bits() {
+        let mut mapper = Mapper5::new(create_test_cartridge(32, 1024));
+        mapper.cpu_write(0x5101, 3);
+
+        // $5130 applies when the bank register is written
+        mapper.cpu_write(0x5130, 0x02);
+        mapper.cpu_write(0x5120, 0x05);
+        mapper.cpu_write(0x5130, 0x00);
+        assert_eq!(mapper.chr_banks[0], 0x205);
+        assert_eq!(mapper.ppu_read(0x0000), 0x05); // Bank 0x205 of 1024
+    }
+
+    #[test]
+    fn test_8x16_sprites_use_separate_banks() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5101, 3);
+        mapper.cpu_write(0x5120, 0x20); // Sprite set
+        mapper.cpu_write(0x5128, 0x40); // Background set
+
+        // 8x8 sprites: everything uses $5120-$5127
+        start_frame(&mut mapper, 10);
+        fetch_at(&mut mapper, 0x0000, 5);
+        assert_eq!(mapper.ppu_fetch(0x0000), 0x20);
+
+        // 8x16 sprites: background fetches use $5128-$512B
+        mapper.on_ppu_register_write(0x2000, 0x20);
+        fetch_at(&mut mapper, 0x0000, 5);
+        assert_eq!(mapper.ppu_fetch(0x0000), 0x40);
+        fetch_at(&mut mapper, 0x1000, 325);
+        assert_eq!(mapper.ppu_fetch(0x1000), 0x40);
+
+        // ...and sprite fetches use $5120-$5127
+        fetch_at(&mut mapper, 0x0000, 257);
+        assert_eq!(mapper.ppu_fetch(0x0000), 0x20);
+
+        // Outside rendering the set written last applies
+        mapper.on_scanline(241, false);
+        assert_eq!(mapper.ppu_read(0x0000), 0x40);
+        mapper.cpu_write(0x5120, 0x21);
+        assert_eq!(mapper.ppu_read(0x0000), 0x21);
+    }
+
+    #[test]
+    fn test_exram_cpu_access_by_mode() {
+        let mut mapper = create_mapper();
+
+        // Mode 2: plain RAM
+        mapper.cpu_write(0x5104, 2);
+        mapper.cpu_write(0x5C10, 0x77);
+        assert_eq!(mapper.try_cpu_read(0x5C10), Some(0x77));
+
+        // Mode 3: read-only
+        mapper.cpu_write(0x5104, 3);
+        mapper.cpu_write(0x5C10, 0x11);
+        assert_eq!(mapper.try_cpu_read(0x5C10), Some(0x77));
+
+        // Modes 0/1: not readable, and writes outside rendering store 0
+        mapper.cpu_write(0x5104, 0);
+        assert_eq!(mapper.try_cpu_read(0x5C10), None);
+        mapper.cpu_write(0x5C10, 0x22);
+        assert_eq!(mapper.exram[0x10], 0x00);
+
+        start_frame(&mut mapper, 0);
+        mapper.cpu_write(0x5C10, 0x22);
+        assert_eq!(mapper.exram[0x10], 0x22);
+    }
+
+    #[test]
+    fn test_nametable_mapping() {
+        let mut mapper = create_mapper();
+
+        // Vertical arrangement: CIRAM 0, 1, 0, 1
+        mapper.cpu_write(0x5105, 0x44);
+        assert_eq!(mapper.mirroring(), Mirroring::Vertical);
+        assert_eq!(
+            mapper.nametable_source(0x2400),
+            Some(NametableSource::Ciram(1))
+        );
+        assert_eq!(
+            mapper.nametable_source(0x2800),
+            Some(NametableSource::Ciram(0))
+        );
+
+        // ExRAM at $2400 and fill mode at $2C00
+        mapper.cpu_write(0x5105, 0b11_00_10_00);
+        assert_eq!(mapper.mirroring(), Mirroring::FourScreen);
+        assert_eq!(
+            mapper.nametable_source(0x2400),
+            Some(NametableSource::Cartridge)
+        );
+        mapper.nametable_write(0x2405, 0x33);
+        assert_eq!(mapper.nametable_read(0x2405), 0x33);
+        assert_eq!(mapper.exram[5], 0x33);
+
+        // ExRAM is not a nametable in modes 2 and 3
+        mapper.cpu_write(0x5104, 2);
+        assert_eq!(mapper.nametable_read(0x2405), 0x00);
+    }
+
+    #[test]
+    fn test_fill_mode() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5105, 0xFF);
+        mapper.cpu_write(0x5106, 0x42);
+        mapper.cpu_write(0x5107, 0x02);
+
+        assert_eq!(mapper.nametable_read(0x2000), 0x42);
+        assert_eq!(mapper.nametable_read(0x2BBF), 0x42);
+        assert_eq!(mapper.nametable_read(0x23C0), 0xAA);
+
+        // Writes to a fill-mode nametable go nowhere
+        mapper.nametable_write(0x2000, 0x99);
+        assert_eq!(mapper.nametable_read(0x2000), 0x42);
+    }
+
+    #[test]
+    fn test_extended_attributes() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5104, 1);
+        mapper.cpu_write(0x5130, 0x01);
+        start_frame(&mut mapper, 20);
+        mapper.cpu_write(0x5C00 + 0x45, 0b10_000011); // Palette 2, bank 3
+
+        // Nametable fetch latches the ExRAM byte at the same offset
+        fetch_at(&mut mapper, 0x2045, 1);
+        assert_eq!(mapper.nametable_fetch(0x2045), None);
+
+        // Attribute fetch returns the tile's palette in every quadrant
+        fetch_at(&mut mapper, 0x23C1, 3);
+        assert_eq!(mapper.nametable_fetch(0x23C1), Some(0xAA));
+
+        // Pattern fetches use 4KB bank (upper bits << 6) | 3 = 67
+        fetch_at(&mut mapper, 0x0012, 5);
+        assert_eq!(mapper.ppu_fetch(0x0012), (67 * 4) as u8);
+
+        // Sprite fetches use the normal banks
+        mapper.cpu_write(0x5127, 0x09);
+        fetch_at(&mut mapper, 0x0012, 257);
+        assert_eq!(mapper.ppu_fetch(0x0012), 0x09 * 8);
+    }
+
+    #[test]
+    fn test_vertical_split() {
+        let mut mapper = create_mapper();
+
+        // Split on the left 4 tiles, scrolled down 8 lines, CHR bank 2
+        mapper.cpu_write(0x5200, 0x84);
+        mapper.cpu_write(0x5201, 8);
+        mapper.cpu_write(0x5202, 2);
+        start_frame(&mut mapper, 5);
+        for i in 0..0x3C0 {
+            mapper.cpu_write(0x5C00 + i, 0x00);
+        }
+        mapper.exram[32 + 3] = 0x7E; // Row 1, column 3
+        mapper.exram[0x3C0] = 0b00_00_01_00; // Top-right quadrant of block 0
+
+        // Column 3 is fetched at dots 9-15 and lies in the split
+        fetch_at(&mut mapper, 0x2000, 9);
+        assert_eq!(mapper.nametable_fetch(0x2000), Some(0x7E));
+        fetch_at(&mut mapper, 0x23C0, 11);
+        assert_eq!(mapper.nametable_fetch(0x23C0), Some(0x55));
+
+        // Pattern fetch: split bank, split fine Y ((8 + 5) % 8 = 5)
+        fetch_at(&mut mapper, 0x17E0, 13);
+        let offset = mapper.map_pattern_fetch(0x17E0);
+        assert_eq!(offset, 2 * 0x1000 + 0x7E0 + 5);
+
+        // Column 4 is outside the split
+        fetch_at(&mut mapper, 0x2004, 17);
+        assert_eq!(mapper.nametable_fetch(0x2004), None);
+
+        // Right-side split covers column 4 onward
+        mapper.cpu_write(0x5200, 0xC4);
+        fetch_at(&mut mapper, 0x2004, 17);
+        assert!(mapper.nametable_fetch(0x2004).is_some());
+    }
+
+    #[test]
+    fn test_scanline_irq() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5203, 100);
+        mapper.cpu_write(0x5204, 0x80);
+
+        start_frame(&mut mapper, 99);
+        assert!(!mapper.irq_pending());
+        assert_eq!(mapper.try_cpu_read(0x5204), Some(0x40)); // In frame
+
+        mapper.on_scanline(100, true);
+        assert!(mapper.irq_pending());
+
+        // Peeking $5204 reports the IRQ without acknowledging it
+        assert_eq!(mapper.try_cpu_read(0x5204), Some(0xC0));
+        assert!(mapper.irq_pending());
+
+        // A CPU read acknowledges it
+        assert_eq!(cpu_cycle_read(&mut mapper, 0x5204), Some(0xC0));
+        assert!(!mapper.irq_pending());
+
+        // Vblank ends the frame
+        mapper.on_scanline(240, true);
+        assert_eq!(mapper.try_cpu_read(0x5204), Some(0x00));
+    }
+
+    #[test]
+    fn test_scanline_irq_disabled_sets_flag_only() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5203, 10);
+
+        start_frame(&mut mapper, 10);
+        assert!(!mapper.irq_pending());
+        assert_eq!(mapper.cpu_read(0x5204), 0xC0);
+
+        // Enabling the IRQ asserts the pending flag
+        mapper.cpu_write(0x5204, 0x80);
+        assert!(mapper.irq_pending());
+
+        // The NMI vector fetch clears it and ends the frame
+        let _ = cpu_cycle_read(&mut mapper, 0xFFFA);
+        assert!(!mapper.irq_pending());
+        assert_eq!(mapper.cpu_read(0x5204), 0x00);
+    }
+
+    #[test]
+    fn test_rendering_disabled_ends_frame() {
+        let mut mapper = create_mapper();
+        start_frame(&mut mapper, 50);
+        assert!(mapper.in_frame);
+
+        mapper.on_ppu_register_write(0x2001, 0x00);
+        assert!(!mapper.in_frame);
+    }
+
+    #[test]
+    fn test_multiplier() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5205, 200);
+        mapper.cpu_write(0x5206, 150);
+
+        assert_eq!(mapper.try_cpu_read(0x5205), Some((30000 & 0xFF) as u8));
+        assert_eq!(mapper.try_cpu_read(0x5206), Some((30000 >> 8) as u8));
+    }
+
+    #[test]
+    fn test_pulse_channels() {
+        let mut mapper = create_mapper();
+        assert_eq!(mapper.audio_output(), 0.0);
+
+        // Constant volume 15, 50% duty, period 4 (too low for the APU sweep)
+        mapper.cpu_write(0x5015, 0x01);
+        mapper.cpu_write(0x5000, 0xBF);
+        mapper.cpu_write(0x5002, 0x04);
+        mapper.cpu_write(0x5003, 0x08);
+        assert_eq!(mapper.try_cpu_read(0x5015), Some(0x01));
+
+        let mut outputs = Vec::new();
+        for _ in 0..64 {
+            mapper.cpu_clock();
+            outputs.push(mapper.audio_output());
+        }
+        assert!(outputs.iter().any(|&level| level > 0.0));
+        assert!(outputs.contains(&0.0));
+
+        // Disabling the channel silences it and clears the length counter
+        mapper.cpu_write(0x5015, 0x00);
+        assert_eq!(mapper.try_cpu_read(0x5015), Some(0x00));
+        assert_eq!(mapper.audio_output(), 0.0);
+    }
+
+    #[test]
+    fn test_pcm_write_mode() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5011, 0x80);
+        let level = mapper.audio_output();
+        assert!(level > 0.0);
+
+        // Zero is ignored
+        mapper.cpu_write(0x5011, 0x00);
+        assert_eq!(mapper.audio_output(), level);
+    }
+
+    #[test]
+    fn test_pcm_read_mode_irq() {
+        let mut mapper = Mapper5::new(Cartridge {
+            prg_rom: vec![0; 4 * PRG_BANK_SIZE],
+            ..create_test_cartridge(4, 8)
+        });
+        mapper.cpu_write(0x5010, 0x81);
+
+        // Peeking does not play the byte
+        assert_eq!(mapper.try_cpu_read(0x8000), Some(0));
+        assert!(!mapper.irq_pending());
+
+        // Reading a zero from $8000-$BFFF raises the PCM IRQ
+        assert_eq!(cpu_cycle_read(&mut mapper, 0x8000), Some(0));
+        assert!(mapper.irq_pending());
+
+        // Reading $5010 reports and acknowledges it
+        assert_eq!(cpu_cycle_read(&mut mapper, 0x5010), Some(0x81));
+        assert!(!mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_save_and_load_state() {
+        let mut mapper = create_mapper();
+        mapper.cpu_write(0x5100, 1);
+        mapper.cpu_write(0x5104, 2);
+        mapper.cpu_write(0x5C00, 0x12);
+        mapper.cpu_write(0x5205, 3);
+        mapper.cpu_write(0x5011, 0x40);
+        start_frame(&mut mapper, 3);
+        let state = mapper.save_state();
+
+        let mut restored = create_mapper();
+        restored.load_state(&state).unwrap();
+        assert_eq!(restored.save_state(), state);
+        assert_eq!(restored.try_cpu_read(0x5C00), Some(0x12));
+        assert_eq!(restored.audio_output(), mapper.audio_output());
+        assert!(restored.in_frame);
+    }
+}
@@ -15,6 +15,7 @@
 //   Bits 0-2: Select 32KB PRG-ROM bank
 //   Bit 4: One-screen mirroring (0 = lower bank, 1 = upper bank)
 
+use super::{MapperError, StateReader, StateWriter};
 use crate::cartridge::{Cartridge, Mapper, Mirroring};
 
 /// PRG-ROM bank size (32KB)
@@ -112,6 +113,32 @@ impl Mapper for Mapper7 {
         // (proper implementation would need to distinguish between lower/upper)
         Mirroring::SingleScreen
     }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => {
+                let bank = (self.prg_bank as usize) % self.prg_banks;
+                Some(bank * PRG_BANK_SIZE + (address - 0x8000) as usize)
+            }
+            _ => None,
+        }
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.write_u8(self.prg_bank);
+        writer.write_bool(self.mirroring_select);
+        writer.write_bytes(&self.chr_ram);
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        self.prg_bank = reader.read_u8()?;
+        self.mirroring_select = reader.read_bool()?;
+        reader.read_bytes_into(&mut self.chr_ram)?;
+        Ok(reader.finish()?)
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +157,7 @@ mod tests {
             mapper: 7,
             mirroring: Mirroring::SingleScreen,
             has_battery: false,
+            ..Cartridge::new()
         }
     }
 
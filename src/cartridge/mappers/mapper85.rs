@@ -0,0 +1,577 @@
+// Mapper 85 (Konami VRC7) - PRG/CHR banking, CPU-cycle IRQ and FM expansion audio
+//
+// Memory Layout:
+// - CPU $6000-$7FFF: 8KB PRG-RAM (optional, enabled by $E000 bit 7)
+// - CPU $8000-$9FFF: 8KB PRG-ROM bank (switchable)
+// - CPU $A000-$BFFF: 8KB PRG-ROM bank (switchable)
+// - CPU $C000-$DFFF: 8KB PRG-ROM bank (switchable)
+// - CPU $E000-$FFFF: 8KB PRG-ROM bank (fixed to last bank)
+// - PPU $0000-$1FFF: Eight 1KB CHR banks (ROM or RAM)
+//
+// Features:
+// - PRG-ROM size: up to 512KB
+// - CHR size: up to 256KB
+// - VRC IRQ counter (scanline and CPU cycle modes)
+// - Expansion audio: six-channel FM synthesizer derived from the YM2413 (OPLL)
+//
+// Address Decoding:
+// Each register pair is told apart by one address line: A4 on VRC7a
+// (Lagrange Point, submapper 2) and A3 on VRC7b (Tiny Toon Adventures 2,
+// submapper 1). Without a submapper both lines are decoded.
+//
+// Register Interface (second register of each pair after the slash):
+// - $8000/$8010: 8KB PRG banks at $8000/$A000
+// - $9000: 8KB PRG bank at $C000
+// - $9010/$9030: Audio register select/data
+// - $A000-$D010: CHR registers R0-R7
+// - $E000: Bits 0-1 mirroring (vertical, horizontal, one-screen A/B),
+//   bit 6 audio reset (silences and holds the synthesizer), bit 7 PRG-RAM enable
+// - $E010: IRQ latch
+// - $F000/$F010: IRQ control/acknowledge
+
+use super::opll::Opll;
+use super::vrc_irq::VrcIrq;
+use super::{MapperError, StateReader, StateWriter};
+use crate::cartridge::{Cartridge, Mapper, Mirroring, NametableSource};
+
+/// PRG-ROM bank size (8KB)
+const PRG_BANK_SIZE: usize = 8 * 1024;
+
+/// CHR 1KB bank size
+const CHR_1KB_BANK_SIZE: usize = 1024;
+
+/// PRG-RAM size (8KB)
+const PRG_RAM_SIZE: usize = 8 * 1024;
+
+/// Output level of one synthesizer step
+///
+/// A channel at full volume peaks at 2048, a little louder than an APU pulse
+/// channel at full volume.
+const VRC7_SAMPLE_LEVEL: f32 = 0.0000727;
+
+/// Konami VRC7 mapper implementation
+pub struct Mapper85 {
+    /// PRG-ROM data
+    prg_rom: Vec<u8>,
+    /// CHR memory (ROM or RAM)
+    chr_mem: Vec<u8>,
+    /// PRG-RAM
+    prg_ram: Vec<u8>,
+    /// Whether CHR memory is RAM (writable)
+    chr_is_ram: bool,
+    /// Address line(s) selecting the second register of each pair
+    second_register_mask: u16,
+
+    /// 8KB PRG banks at $8000, $A000 and $C000
+    prg_banks_8k: [u8; 3],
+    /// 1KB CHR banks R0-R7
+    chr_registers: [u8; 8],
+    /// Control register ($E000)
+    control: u8,
+    /// IRQ counter
+    irq: VrcIrq,
+    /// FM synthesizer
+    opll: Opll,
+
+    /// Number of 8KB PRG banks
+    prg_banks: usize,
+    /// Number of 1KB CHR banks
+    chr_banks: usize,
+}
+
+impl Mapper85 {
+    /// Create a new VRC7 mapper from a cartridge
+    ///
+    /// # Arguments
+    /// * `cartridge` - The cartridge containing ROM data
+    pub fn new(cartridge: Cartridge) -> Self {
+        let prg_rom_size = cartridge.prg_rom.len();
+        let chr_mem_size = cartridge.chr_rom.len();
+
+        // Calculate number of banks
+        let prg_banks = prg_rom_size / PRG_BANK_SIZE;
+        let chr_banks = chr_mem_size / CHR_1KB_BANK_SIZE;
+
+        assert!(
+            prg_banks >= 1,
+            "VRC7 requires at least one PRG bank (8KB total, got {} banks)",
+            prg_banks
+        );
+        assert!(
+            chr_banks > 0,
+            "VRC7 requires at least one CHR bank (got {} banks)",
+            chr_banks
+        );
+
+        // CHR-RAM is indicated by all zeros in chr_rom
+        let chr_is_ram = chr_mem_size == 8 * 1024 && cartridge.chr_rom.iter().all(|&b| b == 0);
+
+        let second_register_mask = match cartridge.submapper {
+            1 => 0x08,
+            2 => 0x10,
+            _ => 0x18,
+        };
+
+        Mapper85 {
+            prg_rom: cartridge.prg_rom,
+            chr_mem: cartridge.chr_rom,
+            prg_ram: vec![0; PRG_RAM_SIZE],
+            chr_is_ram,
+            second_register_mask,
+
+            prg_banks_8k: [0; 3],
+            chr_registers: [0; 8],
+            control: match cartridge.mirroring {
+                Mirroring::Horizontal => 0x01,
+                _ => 0x00,
+            },
+            irq: VrcIrq::new(),
+            opll: Opll::new(),
+
+            prg_banks,
+            chr_banks,
+        }
+    }
+
+    /// Check if an address selects the second register of its pair
+    fn is_second_register(&self, address: u16) -> bool {
+        address & self.second_register_mask != 0
+    }
+
+    /// Check if PRG-RAM is enabled
+    fn prg_ram_enabled(&self) -> bool {
+        self.control & 0x80 != 0
+    }
+
+    /// Check if the synthesizer is held in reset
+    fn audio_reset(&self) -> bool {
+        self.control & 0x40 != 0
+    }
+
+    /// Map CPU address to PRG-ROM offset
+    fn map_prg_address(&self, address: u16) -> usize {
+        let bank = match address {
+            0x8000..=0xDFFF => {
+                self.prg_banks_8k[((address - 0x8000) >> 13) as usize] as usize & 0x3F
+            }
+            _ => self.prg_banks - 1,
+        };
+
+        let offset = (address & 0x1FFF) as usize; // 8KB bank offset
+        (bank % self.prg_banks) * PRG_BANK_SIZE + offset
+    }
+
+    /// Map PPU address to CHR offset
+    fn map_chr_address(&self, address: u16) -> usize {
+        let bank = self.chr_registers[(address as usize >> 10) & 0x07] as usize;
+        let offset = (address & 0x03FF) as usize; // 1KB bank offset
+        (bank % self.chr_banks) * CHR_1KB_BANK_SIZE + offset
+    }
+}
+
+impl Mapper for Mapper85 {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            // PRG-RAM
+            0x6000..=0x7FFF if self.prg_ram_enabled() => self.prg_ram[(address - 0x6000) as usize],
+            // PRG-ROM
+            0x8000..=0xFFFF => {
+                let index = self.map_prg_address(address);
+                self.prg_rom[index]
+            }
+            _ => 0,
+        }
+    }
+
+    fn try_cpu_read(&self, address: u16) -> Option<u8> {
+        match address {
+            // Disabled PRG-RAM leaves the data bus floating
+            0x6000..=0x7FFF if !self.prg_ram_enabled() => None,
+            0x6000..=0xFFFF => Some(self.cpu_read(address)),
+            _ => None,
+        }
+    }
+
+    fn cpu_write(&mut self, address: u16, value: u8) {
+        match address {
+            // PRG-RAM
+            0x6000..=0x7FFF => {
+                if self.prg_ram_enabled() {
+                    self.prg_ram[(address - 0x6000) as usize] = value;
+                }
+            }
+            // Audio registers decode A4 and A5 on both boards
+            0x9010 | 0x9030 => {
+                // Writes are ignored while the synthesizer is held in reset
+                if self.audio_reset() {
+                    return;
+                }
+                if address & 0x20 == 0 {
+                    self.opll.write_address(value);
+                } else {
+                    self.opll.write_data(value);
+                }
+            }
+            // Mapper registers
+            0x8000..=0xFFFF => {
+                let second = self.is_second_register(address);
+                match (address & 0xF000, second) {
+                    (0x8000, false) => self.prg_banks_8k[0] = value,
+                    (0x8000, true) => self.prg_banks_8k[1] = value,
+                    (0x9000, false) => self.prg_banks_8k[2] = value,
+                    (0xA000..=0xD000, _) => {
+                        let register = ((address - 0xA000) >> 12) as usize * 2 + second as usize;
+                        self.chr_registers[register] = value;
+                    }
+                    (0xE000, false) => {
+                        let was_reset = self.audio_reset();
+                        self.control = value;
+                        if self.audio_reset() && !was_reset {
+                            self.opll = Opll::new();
+                        }
+                    }
+                    (0xE000, true) => self.irq.write_latch(value),
+                    (0xF000, false) => self.irq.write_control(value),
+                    (0xF000, true) => self.irq.acknowledge(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => {
+                let index = self.map_chr_address(address);
+                self.chr_mem[index % self.chr_mem.len()]
+            }
+            _ => 0,
+        }
+    }
+
+    fn ppu_write(&mut self, address: u16, value: u8) {
+        if self.chr_is_ram {
+            if let 0x0000..=0x1FFF = address {
+                let chr_len = self.chr_mem.len();
+                let index = self.map_chr_address(address);
+                self.chr_mem[index % chr_len] = value;
+            }
+        }
+        // Writes to CHR-ROM are ignored
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.control & 0x03 {
+            0 => Mirroring::Vertical,
+            1 => Mirroring::Horizontal,
+            _ => Mirroring::SingleScreen,
+        }
+    }
+
+    fn nametable_source(&self, _address: u16) -> Option<NametableSource> {
+        // One-screen B uses the second page of internal VRAM
+        match self.control & 0x03 {
+            2 => Some(NametableSource::Ciram(0)),
+            3 => Some(NametableSource::Ciram(1)),
+            _ => None,
+        }
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF => Some(self.map_prg_address(address)),
+            _ => None,
+        }
+    }
+
+    fn prg_ram(&self) -> Option<&[u8]> {
+        Some(&self.prg_ram)
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.prg_ram)
+    }
+
+    fn cpu_clock(&mut self) {
+        self.irq.clock();
+        if !self.audio_reset() {
+            self.opll.clock();
+        }
+    }
+
+    fn irq_pending(&self) -> bool {
+        self.irq.pending()
+    }
+
+    fn irq_acknowledge(&mut self) {
+        self.irq.clear_pending();
+    }
+
+    fn audio_output(&self) -> f32 {
+        if self.audio_reset() {
+            return 0.0;
+        }
+        self.opll.output() as f32 * VRC7_SAMPLE_LEVEL
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        for &bank in &self.prg_banks_8k {
+            writer.write_u8(bank);
+        }
+        for &bank in &self.chr_registers {
+            writer.write_u8(bank);
+        }
+        writer.write_u8(self.control);
+        self.irq.save_state(&mut writer);
+        self.opll.save_state(&mut writer);
+        writer.write_bytes(&self.prg_ram);
+        if self.chr_is_ram {
+            writer.write_bytes(&self.chr_mem);
+        }
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        for bank in self.prg_banks_8k.iter_mut() {
+            *bank = reader.read_u8()?;
+        }
+        for bank in self.chr_registers.iter_mut() {
+            *bank = reader.read_u8()?;
+        }
+        self.control = reader.read_u8()?;
+        self.irq.load_state(&mut reader)?;
+        self.opll.load_state(&mut reader)?;
+        reader.read_bytes_into(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            reader.read_bytes_into(&mut self.chr_mem)?;
+        }
+        Ok(reader.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a test cartridge
+    ///
+    /// Each 8KB PRG bank is filled with its bank number and each 1KB CHR bank
+    /// with its bank number.
+    fn create_test_cartridge(submapper: u8) -> Cartridge {
+        let prg_rom = (0..64 * PRG_BANK_SIZE)
+            .map(|i| (i / PRG_BANK_SIZE) as u8)
+            .collect();
+        let chr_rom = (0..128 * CHR_1KB_BANK_SIZE)
+            .map(|i| (i / CHR_1KB_BANK_SIZE) as u8)
+            .collect();
+
+        Cartridge {
+            prg_rom,
+            chr_rom,
+            trainer: None,
+            mapper: 85,
+            submapper,
+            mirroring: Mirroring::Vertical,
+            has_battery: false,
+            ..Cartridge::new()
+        }
+    }
+
+    /// Clock the mapper for a number of CPU cycles
+    fn run(mapper: &mut Mapper85, cycles: usize) {
+        for _ in 0..cycles {
+            mapper.cpu_clock();
+        }
+    }
+
+    /// Play a pure sine on channel 0 (custom patch, silent modulator)
+    fn play_sine(mapper: &mut Mapper85) {
+        let writes = [
+            (0x00, 0x01),
+            (0x01, 0x21),
+            (0x02, 0x3F),
+            (0x05, 0xF0),
+            (0x30, 0x00),
+            (0x10, 0x00),
+            (0x20, 0x19),
+        ];
+        for (register, value) in writes {
+            mapper.cpu_write(0x9010, register);
+            mapper.cpu_write(0x9030, value);
+        }
+    }
+
+    #[test]
+    fn test_mapper85_creation() {
+        let mapper = Mapper85::new(create_test_cartridge(0));
+
+        assert_eq!(mapper.prg_banks, 64);
+        assert_eq!(mapper.chr_banks, 128);
+        assert_eq!(mapper.mirroring(), Mirroring::Vertical);
+        assert_eq!(mapper.cpu_read(0xE000), 63);
+        assert_eq!(mapper.audio_output(), 0.0);
+    }
+
+    #[test]
+    fn test_prg_bank_switching_vrc7a() {
+        let mut mapper = Mapper85::new(create_test_cartridge(2));
+
+        mapper.cpu_write(0x8000, 5);
+        mapper.cpu_write(0x8010, 6);
+        mapper.cpu_write(0x9000, 7);
+        assert_eq!(mapper.cpu_read(0x8000), 5);
+        assert_eq!(mapper.cpu_read(0xA000), 6);
+        assert_eq!(mapper.cpu_read(0xC000), 7);
+        assert_eq!(mapper.cpu_read(0xFFFF), 63);
+
+        // A3 is not decoded on VRC7a
+        mapper.cpu_write(0x8008, 9);
+        assert_eq!(mapper.cpu_read(0x8000), 9);
+        assert_eq!(mapper.cpu_read(0xA000), 6);
+    }
+
+    #[test]
+    fn test_chr_banking_vrc7b() {
+        let mut mapper = Mapper85::new(create_test_cartridge(1));
+
+        for register in 0..8u16 {
+            let address = 0xA000 + (register / 2) * 0x1000 + (register % 2) * 0x08;
+            mapper.cpu_write(address, 0x20 + register as u8);
+        }
+        let banks: Vec<u8> = (0..8).map(|slot| mapper.ppu_read(slot * 0x400)).collect();
+        assert_eq!(banks, [0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27]);
+
+        // $A010 is the first register on VRC7b
+        mapper.cpu_write(0xA010, 0x40);
+        assert_eq!(mapper.ppu_read(0x0000), 0x40);
+        assert_eq!(mapper.ppu_read(0x0400), 0x21);
+    }
+
+    #[test]
+    fn test_mirroring_control() {
+        let mut mapper = Mapper85::new(create_test_cartridge(0));
+
+        mapper.cpu_write(0xE000, 0x01);
+        assert_eq!(mapper.mirroring(), Mirroring::Horizontal);
+        mapper.cpu_write(0xE000, 0x00);
+        assert_eq!(mapper.mirroring(), Mirroring::Vertical);
+        assert_eq!(mapper.nametable_source(0x2000), None);
+
+        mapper.cpu_write(0xE000, 0x02);
+        assert_eq!(mapper.mirroring(), Mirroring::SingleScreen);
+        assert_eq!(
+            mapper.nametable_source(0x2400),
+            Some(NametableSource::Ciram(0))
+        );
+        mapper.cpu_write(0xE000, 0x03);
+        assert_eq!(
+            mapper.nametable_source(0x2400),
+            Some(NametableSource::Ciram(1))
+        );
+    }
+
+    #[test]
+    fn test_prg_ram_enable() {
+        let mut mapper = Mapper85::new(create_test_cartridge(0));
+
+        mapper.cpu_write(0x6000, 0x42);
+        assert_eq!(mapper.try_cpu_read(0x6000), None);
+
+        mapper.cpu_write(0xE000, 0x80);
+        mapper.cpu_write(0x6000, 0x42);
+        assert_eq!(mapper.try_cpu_read(0x6000), Some(0x42));
+        assert_eq!(mapper.prg_ram().unwrap()[0], 0x42);
+    }
+
+    #[test]
+    fn test_irq_cycle_mode() {
+        let mut mapper = Mapper85::new(create_test_cartridge(2));
+
+        mapper.cpu_write(0xE010, 0xF0);
+        mapper.cpu_write(0xF000, 0x07); // A, E, cycle mode
+
+        run(&mut mapper, 15);
+        assert!(!mapper.irq_pending());
+        run(&mut mapper, 1);
+        assert!(mapper.irq_pending());
+
+        mapper.cpu_write(0xF010, 0);
+        assert!(!mapper.irq_pending());
+        run(&mut mapper, 16);
+        assert!(mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_audio_clocked_from_cpu() {
+        let mut mapper = Mapper85::new(create_test_cartridge(2));
+        play_sine(&mut mapper);
+
+        // Nothing until the first sample 36 cycles later
+        run(&mut mapper, 35);
+        assert_eq!(mapper.audio_output(), 0.0);
+        run(&mut mapper, 1);
+        assert!(mapper.audio_output() > 0.0);
+
+        // The quarter-wave peak is the full 2048
+        run(&mut mapper, 36 * 63);
+        assert!((mapper.audio_output() - 2048.0 * VRC7_SAMPLE_LEVEL).abs() < 1e-6);
+
+        // The negative half of the wave is kept for the mixer
+        run(&mut mapper, 36 * 128);
+        assert!(mapper.audio_output() < 0.0);
+    }
+
+    #[test]
+    fn test_audio_reset_silences_synthesizer() {
+        let mut mapper = Mapper85::new(create_test_cartridge(2));
+        play_sine(&mut mapper);
+        run(&mut mapper, 36 * 10);
+        assert_ne!(mapper.audio_output(), 0.0);
+
+        // Reset silences the chip and ignores register writes while held
+        mapper.cpu_write(0xE000, 0x40);
+        assert_eq!(mapper.audio_output(), 0.0);
+        play_sine(&mut mapper);
+        mapper.cpu_write(0xE000, 0x00);
+        run(&mut mapper, 36 * 10);
+        assert_eq!(mapper.audio_output(), 0.0);
+
+        play_sine(&mut mapper);
+        run(&mut mapper, 36 * 10);
+        assert_ne!(mapper.audio_output(), 0.0);
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let mut mapper = Mapper85::new(create_test_cartridge(2));
+        mapper.cpu_write(0x8000, 3);
+        mapper.cpu_write(0xB010, 12);
+        play_sine(&mut mapper);
+        run(&mut mapper, 1000);
+        let state = mapper.save_state();
+
+        let mut restored = Mapper85::new(create_test_cartridge(2));
+        restored.load_state(&state).unwrap();
+        assert_eq!(restored.cpu_read(0x8000), 3);
+        assert_eq!(restored.ppu_read(0x0C00), 12);
+
+        run(&mut mapper, 500);
+        run(&mut restored, 500);
+        assert_eq!(restored.audio_output(), mapper.audio_output());
+    }
+
+    #[test]
+    fn test_chr_ram_writes() {
+        let mut cartridge = create_test_cartridge(0);
+        cartridge.chr_rom = vec![0; 8 * 1024];
+        let mut mapper = Mapper85::new(cartridge);
+
+        mapper.cpu_write(0xA000, 3);
+        mapper.ppu_write(0x0010, 0x5A);
+        assert_eq!(mapper.ppu_read(0x0010), 0x5A);
+        assert_eq!(mapper.chr_mem[3 * CHR_1KB_BANK_SIZE + 0x10], 0x5A);
+    }
+}
@@ -27,9 +27,11 @@
 // - Reading from $0FE8-$0FEF sets latch 0 to $FE
 // - Reading from $1FD8-$1FDF sets latch 1 to $FD
 // - Reading from $1FE8-$1FEF sets latch 1 to $FE
+// - The latch switches after the fetch, so the $FD/$FE tile itself uses the old bank
+// - Only PPU fetches (Mapper::ppu_fetch) affect the latches; ppu_read is a pure peek
 
+use super::{MapperError, StateReader, StateWriter};
 use crate::cartridge::{Cartridge, Mapper, Mirroring};
-use std::cell::Cell;
 
 /// PRG-ROM bank size (8KB)
 const PRG_BANK_SIZE: usize = 8 * 1024;
@@ -63,9 +65,9 @@ pub struct Mapper9 {
 
     // Latch state
     /// Latch 0 state (false = $FD, true = $FE)
-    latch_0: Cell<bool>,
+    latch_0: bool,
     /// Latch 1 state (false = $FD, true = $FE)
-    latch_1: Cell<bool>,
+    latch_1: bool,
 
     // Derived state
     /// Number of 8KB PRG-ROM banks
@@ -95,8 +97,8 @@ impl Mapper9 {
             chr_bank_1_fd: 0,
             chr_bank_1_fe: 0,
             mirroring: cartridge.mirroring,
-            latch_0: Cell::new(false), // Start with $FD
-            latch_1: Cell::new(false), // Start with $FD
+            latch_0: false, // Start with $FD
+            latch_1: false, // Start with $FD
             prg_banks,
             chr_banks,
         }
@@ -104,7 +106,7 @@ impl Mapper9 {
 
     /// Get the current CHR bank for $0000-$0FFF
     fn get_chr_bank_0(&self) -> u8 {
-        if self.latch_0.get() {
+        if self.latch_0 {
             self.chr_bank_0_fe
         } else {
             self.chr_bank_0_fd
@@ -113,7 +115,7 @@ impl Mapper9 {
 
     /// Get the current CHR bank for $1000-$1FFF
     fn get_chr_bank_1(&self) -> u8 {
-        if self.latch_1.get() {
+        if self.latch_1 {
             self.chr_bank_1_fe
         } else {
             self.chr_bank_1_fd
@@ -121,21 +123,21 @@ impl Mapper9 {
     }
 
     /// Update latch based on PPU access address
-    fn update_latch(&self, address: u16) {
+    fn update_latch(&mut self, address: u16) {
         match address {
             // Latch 0 (for $0000-$0FFF)
             0x0FD8..=0x0FDF => {
-                self.latch_0.set(false); // Set to $FD
+                self.latch_0 = false; // Set to $FD
             }
             0x0FE8..=0x0FEF => {
-                self.latch_0.set(true); // Set to $FE
+                self.latch_0 = true; // Set to $FE
             }
             // Latch 1 (for $1000-$1FFF)
             0x1FD8..=0x1FDF => {
-                self.latch_1.set(false); // Set to $FD
+                self.latch_1 = false; // Set to $FD
             }
             0x1FE8..=0x1FEF => {
-                self.latch_1.set(true); // Set to $FE
+                self.latch_1 = true; // Set to $FE
             }
             _ => {}
         }
@@ -196,9 +198,6 @@ impl Mapper for Mapper9 {
     }
 
     fn ppu_read(&self, address: u16) -> u8 {
-        // Update latches based on address (this is the critical behavior for MMC2)
-        self.update_latch(address);
-
         match address {
             0x0000..=0x0FFF => {
                 let offset = address as usize;
@@ -214,6 +213,14 @@ impl Mapper for Mapper9 {
         }
     }
 
+    fn ppu_fetch(&mut self, address: u16) -> u8 {
+        // The fetch itself still sees the old bank; the latch switches
+        // afterwards (this is the critical behavior for MMC2)
+        let value = self.ppu_read(address);
+        self.update_latch(address);
+        value
+    }
+
     fn ppu_write(&mut self, address: u16, _value: u8) {
         // Update latches on PPU access
         self.update_latch(address);
@@ -223,6 +230,46 @@ impl Mapper for Mapper9 {
     fn mirroring(&self) -> Mirroring {
         self.mirroring
     }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x8000..=0x9FFF => {
+                let bank = (self.prg_bank as usize) % self.prg_banks;
+                Some(bank * PRG_BANK_SIZE + (address - 0x8000) as usize)
+            }
+            0xA000..=0xFFFF => {
+                let base = (self.prg_banks - 3) * PRG_BANK_SIZE;
+                Some(base + (address - 0xA000) as usize)
+            }
+            _ => None,
+        }
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        writer.write_u8(self.prg_bank);
+        writer.write_u8(self.chr_bank_0_fd);
+        writer.write_u8(self.chr_bank_0_fe);
+        writer.write_u8(self.chr_bank_1_fd);
+        writer.write_u8(self.chr_bank_1_fe);
+        writer.write_mirroring(self.mirroring);
+        writer.write_bool(self.latch_0);
+        writer.write_bool(self.latch_1);
+        writer.into_bytes()
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+        let mut reader = StateReader::new(data);
+        self.prg_bank = reader.read_u8()?;
+        self.chr_bank_0_fd = reader.read_u8()?;
+        self.chr_bank_0_fe = reader.read_u8()?;
+        self.chr_bank_1_fd = reader.read_u8()?;
+        self.chr_bank_1_fe = reader.read_u8()?;
+        self.mirroring = reader.read_mirroring()?;
+        self.latch_0 = reader.read_bool()?;
+        self.latch_1 = reader.read_bool()?;
+        Ok(reader.finish()?)
+    }
 }
 
 #[cfg(test)]
@@ -241,6 +288,7 @@ mod tests {
             mapper: 9,
             mirroring: Mirroring::Vertical,
             has_battery: false,
+            ..Cartridge::new()
         }
     }
 
@@ -252,8 +300,8 @@ mod tests {
         assert_eq!(mapper.prg_banks, 16);
         assert_eq!(mapper.chr_banks, 32);
         assert_eq!(mapper.prg_bank, 0);
-        assert!(!mapper.latch_0.get());
-        assert!(!mapper.latch_1.get());
+        assert!(!mapper.latch_0);
+        assert!(!mapper.latch_1);
     }
 
     #[test]
@@ -320,33 +368,33 @@ mod tests {
     #[test]
     fn test_latch_update_ranges() {
         let cartridge = create_test_cartridge(16, 32);
-        let mapper = Mapper9::new(cartridge);
+        let mut mapper = Mapper9::new(cartridge);
 
         // Test latch 0 FD range
-        assert!(!mapper.latch_0.get());
+        assert!(!mapper.latch_0);
         mapper.update_latch(0x0FD8);
-        assert!(!mapper.latch_0.get());
+        assert!(!mapper.latch_0);
         mapper.update_latch(0x0FDF);
-        assert!(!mapper.latch_0.get());
+        assert!(!mapper.latch_0);
 
         // Test latch 0 FE range
         mapper.update_latch(0x0FE8);
-        assert!(mapper.latch_0.get());
+        assert!(mapper.latch_0);
         mapper.update_latch(0x0FEF);
-        assert!(mapper.latch_0.get());
+        assert!(mapper.latch_0);
 
         // Test latch 1 FD range
-        mapper.latch_1.set(true);
+        mapper.latch_1 = true;
         mapper.update_latch(0x1FD8);
-        assert!(!mapper.latch_1.get());
+        assert!(!mapper.latch_1);
         mapper.update_latch(0x1FDF);
-        assert!(!mapper.latch_1.get());
+        assert!(!mapper.latch_1);
 
         // Test latch 1 FE range
         mapper.update_latch(0x1FE8);
-        assert!(mapper.latch_1.get());
+        assert!(mapper.latch_1);
         mapper.update_latch(0x1FEF);
-        assert!(mapper.latch_1.get());
+        assert!(mapper.latch_1);
     }
 
     #[test]
@@ -425,4 +473,38 @@ mod tests {
         assert_eq!(mapper.ppu_read(0x0000), 4); // Latch 0 unchanged
         assert_eq!(mapper.ppu_read(0x1000), 8);
     }
+
+    #[test]
+    fn test_ppu_fetch_switches_latch_after_read() {
+        let mut cartridge = create_test_cartridge(16, 32);
+
+        // Tag the $FE tile row in each bank with the bank number
+        for bank in 0..32 {
+            cartridge.chr_rom[bank * CHR_BANK_SIZE + 0x0FE8] = bank as u8;
+            cartridge.chr_rom[bank * CHR_BANK_SIZE] = bank as u8;
+        }
+
+        let mut mapper = Mapper9::new(cartridge);
+        mapper.cpu_write(0xB000, 0x03); // Latch 0 FD
+        mapper.cpu_write(0xC000, 0x04); // Latch 0 FE
+
+        // The fetch of tile $FE itself still comes from the $FD bank
+        assert_eq!(mapper.ppu_fetch(0x0FE8), 3);
+        assert!(mapper.latch_0);
+
+        // Later fetches use the $FE bank
+        assert_eq!(mapper.ppu_fetch(0x0000), 4);
+    }
+
+    #[test]
+    fn test_ppu_read_has_no_side_effects() {
+        let cartridge = create_test_cartridge(16, 32);
+        let mapper = Mapper9::new(cartridge);
+
+        // Debugger peeks must not flip the latches
+        mapper.ppu_read(0x0FE8);
+        mapper.ppu_read(0x1FE8);
+        assert!(!mapper.latch_0);
+        assert!(!mapper.latch_1);
+    }
 }
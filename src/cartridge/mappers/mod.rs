@@ -2,39 +2,59 @@
 //
 // This module contains the mapper factory and individual mapper implementations.
 // Each mapper handles memory mapping and banking for different cartridge types.
+//
+// Files are named after the mapper number. A chip family spread over several
+// numbers lives under the lowest one (mapper21 covers 21, 22, 23 and 25), and
+// its header lists the variants. Pieces shared between mappers, such as the VRC
+// IRQ counter and the OPLL synthesizer, are named after the hardware.
 
 mod mapper0;
 mod mapper1;
 mod mapper10;
 mod mapper11;
+mod mapper19;
 mod mapper2;
+mod mapper21;
+mod mapper24;
 mod mapper3;
 mod mapper4;
+mod mapper5;
 mod mapper66;
 mod mapper7;
+mod mapper85;
 mod mapper9;
+mod opll;
+mod vrc_irq;
 
 use super::{Cartridge, Mapper};
 
 // Re-export mapper implementations for use in tests and direct instantiation
+pub use crate::state::{StateReader, StateWriter};
 pub use mapper0::Mapper0;
 pub use mapper1::Mapper1;
 pub use mapper10::Mapper10;
 pub use mapper11::Mapper11;
+pub use mapper19::Mapper19;
 pub use mapper2::Mapper2;
+pub use mapper21::Mapper21;
+pub use mapper24::Mapper24;
 pub use mapper3::Mapper3;
 pub use mapper4::Mapper4;
+pub use mapper5::Mapper5;
 pub use mapper66::Mapper66;
 pub use mapper7::Mapper7;
+pub use mapper85::Mapper85;
 pub use mapper9::Mapper9;
 
 /// Error type for mapper creation
 #[derive(Debug)]
 pub enum MapperError {
     /// The requested mapper number is not supported
-    UnsupportedMapper(u8),
+    UnsupportedMapper(u16),
     /// Invalid cartridge configuration for the mapper
     InvalidConfiguration(String),
+    /// Saved mapper state could not be restored
+    InvalidState(String),
 }
 
 impl std::fmt::Display for MapperError {
@@ -46,16 +66,27 @@ impl std::fmt::Display for MapperError {
             MapperError::InvalidConfiguration(msg) => {
                 write!(f, "Invalid mapper configuration: {}", msg)
             }
+            MapperError::InvalidState(msg) => {
+                write!(f, "Invalid mapper state: {}", msg)
+            }
         }
     }
 }
 
 impl std::error::Error for MapperError {}
 
+impl From<crate::state::StateError> for MapperError {
+    fn from(e: crate::state::StateError) -> Self {
+        MapperError::InvalidState(e.to_string())
+    }
+}
+
 /// Create a mapper instance based on the mapper number in the cartridge
 ///
 /// This factory function creates the appropriate mapper implementation for the
-/// given cartridge. The mapper number is determined from the iNES header.
+/// given cartridge. The mapper number is determined from the iNES header;
+/// mappers with several board variants read the NES 2.0 submapper and RAM
+/// sizes from the cartridge.
 ///
 /// # Arguments
 /// * `cartridge` - The cartridge to create a mapper for
@@ -81,11 +112,16 @@ pub fn create_mapper(cartridge: Cartridge) -> Result<Box<dyn Mapper>, MapperErro
         2 => Ok(Box::new(Mapper2::new(cartridge))),
         3 => Ok(Box::new(Mapper3::new(cartridge))),
         4 => Ok(Box::new(Mapper4::new(cartridge))),
+        5 => Ok(Box::new(Mapper5::new(cartridge))),
         7 => Ok(Box::new(Mapper7::new(cartridge))),
         9 => Ok(Box::new(Mapper9::new(cartridge))),
         10 => Ok(Box::new(Mapper10::new(cartridge))),
         11 => Ok(Box::new(Mapper11::new(cartridge))),
+        19 => Ok(Box::new(Mapper19::new(cartridge))),
+        21 | 22 | 23 | 25 => Ok(Box::new(Mapper21::new(cartridge))),
+        24 | 26 => Ok(Box::new(Mapper24::new(cartridge))),
         66 => Ok(Box::new(Mapper66::new(cartridge))),
+        85 => Ok(Box::new(Mapper85::new(cartridge))),
         mapper_num => Err(MapperError::UnsupportedMapper(mapper_num)),
     }
 }
@@ -105,6 +141,7 @@ mod tests {
             mapper: 0,
             mirroring: Mirroring::Horizontal,
             has_battery: false,
+            ..Cartridge::new()
         };
 
         let result = create_mapper(cartridge);
@@ -124,6 +161,7 @@ mod tests {
             mapper: 1,
             mirroring: Mirroring::Horizontal,
             has_battery: false,
+            ..Cartridge::new()
         };
 
         let result = create_mapper(cartridge);
@@ -144,6 +182,7 @@ mod tests {
             mapper: 2,
             mirroring: Mirroring::Vertical,
             has_battery: false,
+            ..Cartridge::new()
         };
 
         let result = create_mapper(cartridge);
@@ -163,6 +202,7 @@ mod tests {
             mapper: 3,
             mirroring: Mirroring::Horizontal,
             has_battery: false,
+            ..Cartridge::new()
         };
 
         let result = create_mapper(cartridge);
@@ -179,5 +219,110 @@ mod tests {
 
         let result = create_mapper(cartridge);
         assert!(matches!(result, Err(MapperError::UnsupportedMapper(99))));
+
+        // NES 2.0 mapper numbers are 12-bit: 256 is not NROM
+        let mut cartridge = Cartridge::new();
+        cartridge.mapper = 256;
+        let result = create_mapper(cartridge);
+        assert!(matches!(result, Err(MapperError::UnsupportedMapper(256))));
+    }
+
+    /// Every supported mapper number
+    const SUPPORTED_MAPPERS: [u16; 19] = [
+        0, 1, 2, 3, 4, 5, 7, 9, 10, 11, 19, 21, 22, 23, 24, 25, 26, 66, 85,
+    ];
+
+    /// Create a cartridge for state tests, with CHR-RAM (all zeros) or patterned CHR-ROM
+    fn create_state_test_cartridge(mapper: u16, chr_ram: bool) -> Cartridge {
+        // NROM and CNROM only support up to 32KB of PRG-ROM, NROM only 8KB of CHR
+        let prg_size = if matches!(mapper, 0 | 3) { 32 } else { 128 } * 1024;
+        let chr_size = if mapper == 0 { 8 } else { 64 } * 1024;
+
+        let prg_rom = (0..prg_size).map(|i| (i / 1024) as u8).collect();
+        let chr_rom = if chr_ram {
+            vec![0; 8 * 1024]
+        } else {
+            (0..chr_size).map(|i| (i / 1024) as u8 ^ 0x5A).collect()
+        };
+
+        Cartridge {
+            prg_rom,
+            chr_rom,
+            trainer: None,
+            mapper,
+            mirroring: Mirroring::Vertical,
+            has_battery: false,
+            ..Cartridge::new()
+        }
+    }
+
+    /// Drive a mapper through a pseudo-random sequence of register and memory writes
+    fn scramble_mapper(mapper: &mut dyn Mapper, seed: u32) {
+        let mut rng = seed;
+        let mut next = || {
+            rng = rng.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (rng >> 8) as u16
+        };
+
+        for _ in 0..200 {
+            let addr = 0x6000 + next() % 0xA000;
+            mapper.cpu_write(addr, next() as u8);
+            mapper.ppu_write(next() % 0x2000, next() as u8);
+            mapper.ppu_fetch(next() % 0x2000);
+            mapper.on_ppu_address(next() % 0x2000, next() as u64);
+        }
+    }
+
+    /// Snapshot everything observable from outside the mapper
+    fn observe_mapper(mapper: &dyn Mapper) -> (Vec<u8>, Vec<u8>, Mirroring, bool) {
+        let cpu: Vec<u8> = (0x6000..=0xFFFF).map(|a| mapper.cpu_read(a)).collect();
+        let ppu: Vec<u8> = (0x0000..0x2000).map(|a| mapper.ppu_read(a)).collect();
+        (cpu, ppu, mapper.mirroring(), mapper.irq_pending())
+    }
+
+    #[test]
+    fn test_all_mappers_state_roundtrip() {
+        for &number in &SUPPORTED_MAPPERS {
+            for chr_ram in [false, true] {
+                let mut original =
+                    create_mapper(create_state_test_cartridge(number, chr_ram)).unwrap();
+                scramble_mapper(original.as_mut(), number as u32 + 1);
+                let state = original.save_state();
+
+                let mut restored =
+                    create_mapper(create_state_test_cartridge(number, chr_ram)).unwrap();
+                restored
+                    .load_state(&state)
+                    .unwrap_or_else(|e| panic!("Mapper {}: {}", number, e));
+
+                assert!(
+                    observe_mapper(original.as_ref()) == observe_mapper(restored.as_ref()),
+                    "Mapper {} (chr_ram={}) did not round-trip",
+                    number,
+                    chr_ram
+                );
+                assert_eq!(restored.save_state(), state, "Mapper {}", number);
+
+                // Both copies keep evolving identically after the restore
+                scramble_mapper(original.as_mut(), 99);
+                scramble_mapper(restored.as_mut(), 99);
+                assert_eq!(restored.save_state(), original.save_state());
+            }
+        }
+    }
+
+    #[test]
+    fn test_mapper_load_state_rejects_truncated_state() {
+        for &number in &SUPPORTED_MAPPERS {
+            let mut mapper = create_mapper(create_state_test_cartridge(number, true)).unwrap();
+            let state = mapper.save_state();
+
+            let result = mapper.load_state(&state[..state.len() - 1]);
+            assert!(
+                matches!(result, Err(MapperError::InvalidState(_))),
+                "Mapper {} accepted a truncated state",
+                number
+            );
+        }
     }
 }
@@ -0,0 +1,862 @@
+// Yamaha YM2413 (OPLL) derived FM synthesizer - VRC7 expansion audio
+//
+// The VRC7 contains a cut-down OPLL: six two-operator FM channels, 15
+// built-in instrument patches and one user-defined patch, without the
+// rhythm mode. The synthesizer produces one sample every 36 CPU cycles
+// (3.58 MHz / 72, about 49.7 kHz).
+//
+// Each channel is a modulator operator feeding the phase of a carrier
+// operator. Operators work in the log domain like the real chip: a log-sine
+// table gives the waveform's attenuation, the envelope, level and key scaling
+// attenuations are added to it, and an exponential table turns the sum back
+// into a linear 12-bit sample.
+//
+// Registers (selected through $9010 and written through $9030 on the VRC7):
+// - $00-$07: Custom patch (same layout as the built-in patches, see `Patch`)
+// - $10-$15: F-number low 8 bits
+// - $20-$25: Bit 5 sustain, bit 4 key on, bits 1-3 block, bit 0 F-number bit 8
+// - $30-$35: Bits 4-7 instrument (0 = custom patch), bits 0-3 volume (3 dB steps)
+
+use super::{StateReader, StateWriter};
+use crate::state::StateError;
+use std::f64::consts::PI;
+use std::sync::OnceLock;
+
+/// CPU cycles per synthesizer sample
+const CYCLES_PER_SAMPLE: u8 = 36;
+
+/// Number of FM channels
+const CHANNELS: usize = 6;
+
+/// Maximum attenuation in 0.375 dB steps, treated as silence
+const MAX_ATTENUATION: u8 = 127;
+
+/// Samples per tremolo cycle (about 3.7 Hz)
+const AM_PERIOD: u32 = 13_436;
+
+/// Samples per vibrato cycle (about 6.4 Hz)
+const PM_PERIOD: u32 = 7_768;
+
+/// Built-in instrument patches 1-15
+const BUILTIN_PATCHES: [[u8; 8]; 15] = [
+    [0x03, 0x21, 0x05, 0x06, 0xE8, 0x81, 0x42, 0x27], // Buzzy bell
+    [0x13, 0x41, 0x14, 0x0D, 0xD8, 0xF6, 0x23, 0x12], // Guitar
+    [0x11, 0x11, 0x08, 0x08, 0xFA, 0xB2, 0x20, 0x12], // Wurly
+    [0x31, 0x61, 0x0C, 0x07, 0xA8, 0x64, 0x61, 0x27], // Flute
+    [0x32, 0x21, 0x1E, 0x06, 0xE1, 0x76, 0x01, 0x28], // Clarinet
+    [0x02, 0x01, 0x06, 0x00, 0xA3, 0xE2, 0xF4, 0xF4], // Synth
+    [0x21, 0x61, 0x1D, 0x07, 0x82, 0x81, 0x11, 0x07], // Trumpet
+    [0x23, 0x21, 0x22, 0x17, 0xA2, 0x72, 0x01, 0x17], // Organ
+    [0x35, 0x11, 0x25, 0x00, 0x40, 0x73, 0x72, 0x01], // Bells
+    [0xB5, 0x01, 0x0F, 0x0F, 0xA8, 0xA5, 0x51, 0x02], // Vibes
+    [0x17, 0xC1, 0x24, 0x07, 0xF8, 0xF8, 0x22, 0x12], // Vibraphone
+    [0x71, 0x23, 0x11, 0x06, 0x65, 0x74, 0x18, 0x16], // Tutti
+    [0x01, 0x02, 0xD3, 0x05, 0xC9, 0x95, 0x03, 0x02], // Fretless
+    [0x61, 0x63, 0x0C, 0x00, 0x94, 0xC0, 0x33, 0xF6], // Synth bass
+    [0x21, 0x72, 0x0D, 0x00, 0xC1, 0xD5, 0x56, 0x06], // Sweep
+];
+
+/// Frequency multipliers in halves (MULT 0 is x0.5)
+const MULTIPLIERS: [u32; 16] = [1, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 20, 24, 24, 30, 30];
+
+/// Key scale attenuation at 6 dB/octave for block 7, by F-number bits 5-8 (0.375 dB steps)
+const KSL_TABLE: [u8; 16] = [
+    0, 48, 64, 74, 80, 86, 90, 94, 96, 100, 102, 104, 106, 108, 110, 112,
+];
+
+/// Envelope increment patterns, by the low 2 bits of the rate
+const EG_PATTERNS: [[u8; 8]; 4] = [
+    [0, 1, 0, 1, 0, 1, 0, 1],
+    [0, 1, 0, 1, 1, 1, 0, 1],
+    [0, 1, 1, 1, 0, 1, 1, 1],
+    [0, 1, 1, 1, 1, 1, 1, 1],
+];
+
+/// Vibrato F-number offsets in 1/256ths over one cycle
+const PM_TABLE: [i32; 8] = [0, 1, 2, 1, 0, -1, -2, -1];
+
+// ========================================
+// Waveform Tables
+// ========================================
+
+/// Log-domain waveform tables
+struct Tables {
+    /// -log2(sin) of a quarter sine wave, in 1/256ths
+    log_sin: [u16; 256],
+    /// 2^(-x/256), scaled to 2048
+    exp: [u16; 256],
+}
+
+/// Get the waveform tables, built on first use
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut log_sin = [0; 256];
+        let mut exp = [0; 256];
+        for (i, entry) in log_sin.iter_mut().enumerate() {
+            let sin = ((i as f64 + 0.5) * PI / 512.0).sin();
+            *entry = (-sin.log2() * 256.0).round() as u16;
+        }
+        for (i, entry) in exp.iter_mut().enumerate() {
+            *entry = (2f64.powf(-(i as f64) / 256.0) * 2048.0).round() as u16;
+        }
+        Tables { log_sin, exp }
+    })
+}
+
+/// Compute one operator sample
+///
+/// # Arguments
+/// * `phase` - Waveform position, 1024 steps per cycle (wraps)
+/// * `attenuation` - Total attenuation in 0.375 dB steps
+/// * `rectified` - Whether the negative half of the wave is silenced
+///
+/// # Returns
+/// The signed sample (-2048 to 2048)
+fn operator_output(phase: i32, attenuation: u8, rectified: bool) -> i16 {
+    if attenuation >= MAX_ATTENUATION {
+        return 0;
+    }
+
+    let index = (phase & 0x3FF) as usize;
+    let negative = index & 0x200 != 0;
+    if negative && rectified {
+        return 0;
+    }
+
+    let quarter = if index & 0x100 != 0 {
+        0xFF - (index & 0xFF)
+    } else {
+        index & 0xFF
+    };
+
+    let tables = tables();
+    let total = tables.log_sin[quarter] as u32 + ((attenuation as u32) << 4);
+    let shift = total >> 8;
+    let magnitude = if shift >= 12 {
+        0
+    } else {
+        (tables.exp[(total & 0xFF) as usize] >> shift) as i16
+    };
+
+    if negative {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+// ========================================
+// Patches
+// ========================================
+
+/// Settings for one operator of a patch
+#[derive(Debug, Clone, Copy)]
+struct OperatorPatch {
+    /// Tremolo enable
+    tremolo: bool,
+    /// Vibrato enable
+    vibrato: bool,
+    /// Sustained envelope (holds at the sustain level until key off)
+    sustained: bool,
+    /// Key scale rate: envelope rates scale with the full block and F-number
+    key_scale_rate: bool,
+    /// Frequency multiplier index
+    multiple: u8,
+    /// Key scale level (0 = off, 1 = 1.5, 2 = 3, 3 = 6 dB/octave)
+    key_scale_level: u8,
+    /// Half-wave rectified waveform
+    rectified: bool,
+    /// Attack rate
+    attack: u8,
+    /// Decay rate
+    decay: u8,
+    /// Sustain level (3 dB steps)
+    sustain_level: u8,
+    /// Release rate
+    release: u8,
+}
+
+/// A two-operator instrument
+///
+/// Byte layout (modulator first where a register covers both operators):
+/// - 0/1: Bit 7 tremolo, bit 6 vibrato, bit 5 sustained, bit 4 KSR, bits 0-3 MULT
+/// - 2: Bits 6-7 modulator KSL, bits 0-5 modulator total level (0.75 dB steps)
+/// - 3: Bits 6-7 carrier KSL, bit 4 carrier rectified, bit 3 modulator
+///   rectified, bits 0-2 feedback
+/// - 4/5: Bits 4-7 attack rate, bits 0-3 decay rate
+/// - 6/7: Bits 4-7 sustain level, bits 0-3 release rate
+#[derive(Debug, Clone, Copy)]
+struct Patch {
+    /// Modulator and carrier settings
+    operators: [OperatorPatch; 2],
+    /// Modulator total level (0.75 dB steps)
+    total_level: u8,
+    /// Modulator self-feedback (0 = off)
+    feedback: u8,
+}
+
+impl Patch {
+    /// Decode the 8 patch bytes
+    fn decode(bytes: &[u8]) -> Self {
+        let operator = |i: usize| OperatorPatch {
+            tremolo: bytes[i] & 0x80 != 0,
+            vibrato: bytes[i] & 0x40 != 0,
+            sustained: bytes[i] & 0x20 != 0,
+            key_scale_rate: bytes[i] & 0x10 != 0,
+            multiple: bytes[i] & 0x0F,
+            key_scale_level: bytes[2 + i] >> 6,
+            rectified: bytes[3] & (0x08 << i) != 0,
+            attack: bytes[4 + i] >> 4,
+            decay: bytes[4 + i] & 0x0F,
+            sustain_level: bytes[6 + i] >> 4,
+            release: bytes[6 + i] & 0x0F,
+        };
+
+        Patch {
+            operators: [operator(0), operator(1)],
+            total_level: bytes[2] & 0x3F,
+            feedback: bytes[3] & 0x07,
+        }
+    }
+}
+
+// ========================================
+// Operators
+// ========================================
+
+/// Envelope generator phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EnvelopeState {
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+    Off,
+}
+
+impl EnvelopeState {
+    /// Decode a state saved by `save_state`
+    fn from_u8(value: u8) -> Result<Self, StateError> {
+        Ok(match value {
+            0 => EnvelopeState::Attack,
+            1 => EnvelopeState::Decay,
+            2 => EnvelopeState::Sustain,
+            3 => EnvelopeState::Release,
+            4 => EnvelopeState::Off,
+            other => {
+                return Err(StateError::Invalid(format!(
+                    "invalid OPLL envelope state {}",
+                    other
+                )))
+            }
+        })
+    }
+}
+
+/// Compute an envelope rate from a 4-bit patch rate and the key scaling
+fn effective_rate(rate: u8, key_scale: u8) -> u8 {
+    if rate == 0 {
+        0
+    } else {
+        (rate * 4 + key_scale).min(63)
+    }
+}
+
+/// Get the envelope step for a rate at the given envelope counter value
+fn envelope_increment(rate: u8, counter: u32) -> u8 {
+    if rate == 0 {
+        return 0;
+    }
+
+    let high = rate >> 2;
+    let pattern = &EG_PATTERNS[(rate & 0x03) as usize];
+    if high < 13 {
+        // Low rates step once every 2^(13 - high) samples
+        let shift = 13 - high;
+        if counter & ((1 << shift) - 1) != 0 {
+            return 0;
+        }
+        pattern[((counter >> shift) & 7) as usize]
+    } else if high < 15 {
+        (1 + pattern[(counter & 7) as usize]) << (high - 13)
+    } else {
+        4
+    }
+}
+
+/// One FM operator
+#[derive(Debug, Clone)]
+struct Operator {
+    /// Phase accumulator (20 bits per cycle)
+    phase: u32,
+    /// Envelope attenuation (0.375 dB steps)
+    envelope: u8,
+    /// Envelope phase
+    state: EnvelopeState,
+    /// Most recent output
+    output: i16,
+    /// Output before the most recent one (modulator feedback)
+    previous_output: i16,
+}
+
+impl Default for Operator {
+    fn default() -> Self {
+        Self {
+            phase: 0,
+            envelope: MAX_ATTENUATION,
+            state: EnvelopeState::Off,
+            output: 0,
+            previous_output: 0,
+        }
+    }
+}
+
+impl Operator {
+    /// Start the attack phase
+    fn key_on(&mut self) {
+        self.phase = 0;
+        self.state = EnvelopeState::Attack;
+    }
+
+    /// Start the release phase
+    fn key_off(&mut self) {
+        if self.state != EnvelopeState::Off {
+            self.state = EnvelopeState::Release;
+        }
+    }
+
+    /// Advance the envelope by one sample
+    fn update_envelope(
+        &mut self,
+        patch: &OperatorPatch,
+        key_scale: u8,
+        channel_sustain: bool,
+        counter: u32,
+    ) {
+        let key_scale = if patch.key_scale_rate {
+            key_scale
+        } else {
+            key_scale >> 2
+        };
+
+        match self.state {
+            EnvelopeState::Attack => {
+                let rate = effective_rate(patch.attack, key_scale);
+                if rate >= 60 {
+                    self.envelope = 0;
+                } else {
+                    // Exponential approach towards full volume, rounded up so
+                    // the last few steps still reach 0
+                    let step = envelope_increment(rate, counter) as u32;
+                    let delta = ((self.envelope as u32 + 1) * step).div_ceil(16);
+                    self.envelope = self.envelope.saturating_sub(delta as u8);
+                }
+                if self.envelope == 0 {
+                    self.state = EnvelopeState::Decay;
+                }
+            }
+            EnvelopeState::Decay => {
+                let rate = effective_rate(patch.decay, key_scale);
+                self.increase_envelope(envelope_increment(rate, counter));
+                if self.envelope >= patch.sustain_level * 8 {
+                    self.state = EnvelopeState::Sustain;
+                }
+            }
+            EnvelopeState::Sustain => {
+                // Percussive envelopes keep decaying at the release rate
+                if !patch.sustained {
+                    let rate = effective_rate(patch.release, key_scale);
+                    self.increase_envelope(envelope_increment(rate, counter));
+                }
+            }
+            EnvelopeState::Release => {
+                let release = if channel_sustain {
+                    5
+                } else if patch.sustained {
+                    patch.release
+                } else {
+                    7
+                };
+                let rate = effective_rate(release, key_scale);
+                self.increase_envelope(envelope_increment(rate, counter));
+            }
+            EnvelopeState::Off => {}
+        }
+    }
+
+    /// Attenuate the envelope, switching the operator off at silence
+    fn increase_envelope(&mut self, step: u8) {
+        self.envelope = self.envelope.saturating_add(step).min(MAX_ATTENUATION);
+        if self.envelope == MAX_ATTENUATION && self.state != EnvelopeState::Decay {
+            self.state = EnvelopeState::Off;
+        }
+    }
+
+    /// Advance the phase by one sample
+    fn advance_phase(&mut self, patch: &OperatorPatch, fnum: u16, block: u8, vibrato: i32) {
+        let mut fnum = fnum as i32;
+        if patch.vibrato {
+            fnum += (fnum * vibrato) >> 8;
+        }
+        let increment = ((fnum as u32 * MULTIPLIERS[patch.multiple as usize]) << block) >> 1;
+        self.phase = (self.phase + increment) & 0xFFFFF;
+    }
+
+    /// Get the total attenuation for the current sample
+    fn attenuation(
+        &self,
+        patch: &OperatorPatch,
+        level: u8,
+        fnum: u16,
+        block: u8,
+        tremolo: u8,
+    ) -> u8 {
+        if self.state == EnvelopeState::Off {
+            return MAX_ATTENUATION;
+        }
+
+        let mut total = self.envelope as u32 + level as u32;
+        if patch.key_scale_level != 0 {
+            let full = KSL_TABLE[(fnum >> 5) as usize] as i32 - 16 * (7 - block as i32);
+            total += (full.max(0) >> (3 - patch.key_scale_level)) as u32;
+        }
+        if patch.tremolo {
+            total += tremolo as u32;
+        }
+        total.min(MAX_ATTENUATION as u32) as u8
+    }
+
+    /// Append the operator's state to a save state
+    fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u32(self.phase);
+        writer.write_u8(self.envelope);
+        writer.write_u8(self.state as u8);
+        writer.write_u16(self.output as u16);
+        writer.write_u16(self.previous_output as u16);
+    }
+
+    /// Restore state written by `save_state`
+    fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.phase = reader.read_u32()?;
+        self.envelope = reader.read_u8()?;
+        self.state = EnvelopeState::from_u8(reader.read_u8()?)?;
+        self.output = reader.read_u16()? as i16;
+        self.previous_output = reader.read_u16()? as i16;
+        Ok(())
+    }
+}
+
+// ========================================
+// Synthesizer
+// ========================================
+
+/// OPLL-style FM synthesizer
+#[derive(Debug, Clone)]
+pub(super) struct Opll {
+    /// Selected register
+    address: u8,
+    /// Register file ($00-$35)
+    registers: [u8; 0x40],
+    /// Modulator and carrier of each channel
+    operators: [[Operator; 2]; CHANNELS],
+    /// CPU cycles since the last sample
+    divider: u8,
+    /// Envelope counter, incremented every sample
+    envelope_counter: u32,
+    /// Tremolo position in samples
+    am_counter: u32,
+    /// Vibrato position in samples
+    pm_counter: u32,
+    /// Sum of the channel outputs for the current sample
+    output: i32,
+}
+
+impl Opll {
+    /// Create a silent synthesizer
+    pub(super) fn new() -> Self {
+        Self {
+            address: 0,
+            registers: [0; 0x40],
+            operators: Default::default(),
+            divider: 0,
+            envelope_counter: 0,
+            am_counter: 0,
+            pm_counter: 0,
+            output: 0,
+        }
+    }
+
+    /// Select the register for the next data write
+    pub(super) fn write_address(&mut self, value: u8) {
+        self.address = value;
+    }
+
+    /// Write the selected register
+    pub(super) fn write_data(&mut self, value: u8) {
+        let address = self.address as usize;
+        match address {
+            0x00..=0x07 | 0x10..=0x15 | 0x30..=0x35 => self.registers[address] = value,
+            0x20..=0x25 => {
+                let was_on = self.registers[address] & 0x10 != 0;
+                self.registers[address] = value;
+                let channel = address - 0x20;
+                match (was_on, value & 0x10 != 0) {
+                    (false, true) => self.operators[channel]
+                        .iter_mut()
+                        .for_each(Operator::key_on),
+                    (true, false) => self.operators[channel]
+                        .iter_mut()
+                        .for_each(Operator::key_off),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Advance the synthesizer by one CPU cycle
+    pub(super) fn clock(&mut self) {
+        self.divider += 1;
+        if self.divider == CYCLES_PER_SAMPLE {
+            self.divider = 0;
+            self.generate_sample();
+        }
+    }
+
+    /// Get the current output, the sum of the six channels
+    ///
+    /// # Returns
+    /// The signed sample (each channel contributes -2048 to 2048)
+    pub(super) fn output(&self) -> i32 {
+        self.output
+    }
+
+    /// Get the patch played by a channel
+    fn patch(&self, channel: usize) -> Patch {
+        match self.registers[0x30 + channel] >> 4 {
+            0 => Patch::decode(&self.registers[0x00..0x08]),
+            instrument => Patch::decode(&BUILTIN_PATCHES[instrument as usize - 1]),
+        }
+    }
+
+    /// Compute the next sample of every channel
+    fn generate_sample(&mut self) {
+        self.envelope_counter = self.envelope_counter.wrapping_add(1);
+        self.am_counter = (self.am_counter + 1) % AM_PERIOD;
+        self.pm_counter = (self.pm_counter + 1) % PM_PERIOD;
+
+        // Triangle tremolo of up to 4.875 dB, stepped vibrato of up to 2/256
+        let position = self.am_counter * 26 / AM_PERIOD;
+        let tremolo = if position < 13 {
+            position
+        } else {
+            26 - position
+        } as u8;
+        let vibrato = PM_TABLE[(self.pm_counter * 8 / PM_PERIOD) as usize];
+
+        self.output = (0..CHANNELS)
+            .map(|channel| self.generate_channel(channel, tremolo, vibrato) as i32)
+            .sum();
+    }
+
+    /// Compute the next sample of one channel
+    fn generate_channel(&mut self, channel: usize, tremolo: u8, vibrato: i32) -> i16 {
+        let fnum = self.registers[0x10 + channel] as u16
+            | ((self.registers[0x20 + channel] as u16 & 0x01) << 8);
+        let block = (self.registers[0x20 + channel] >> 1) & 0x07;
+        let sustain = self.registers[0x20 + channel] & 0x20 != 0;
+        let volume = self.registers[0x30 + channel] & 0x0F;
+        let patch = self.patch(channel);
+        let key_scale = (block << 1) | (fnum >> 8) as u8;
+        let counter = self.envelope_counter;
+
+        let [modulator, carrier] = &mut self.operators[channel];
+        let [modulator_patch, carrier_patch] = &patch.operators;
+
+        // Modulator, with self-feedback
+        modulator.update_envelope(modulator_patch, key_scale, sustain, counter);
+        modulator.advance_phase(modulator_patch, fnum, block, vibrato);
+        let feedback = if patch.feedback == 0 {
+            0
+        } else {
+            (modulator.output as i32 + modulator.previous_output as i32) >> (9 - patch.feedback)
+        };
+        let attenuation =
+            modulator.attenuation(modulator_patch, patch.total_level * 2, fnum, block, tremolo);
+        let modulation = operator_output(
+            (modulator.phase >> 10) as i32 + feedback,
+            attenuation,
+            modulator_patch.rectified,
+        );
+        modulator.previous_output = modulator.output;
+        modulator.output = modulation;
+
+        // Carrier, phase-modulated by the modulator
+        carrier.update_envelope(carrier_patch, key_scale, sustain, counter);
+        carrier.advance_phase(carrier_patch, fnum, block, vibrato);
+        let attenuation = carrier.attenuation(carrier_patch, volume * 8, fnum, block, tremolo);
+        carrier.output = operator_output(
+            (carrier.phase >> 10) as i32 + modulation as i32,
+            attenuation,
+            carrier_patch.rectified,
+        );
+        carrier.output
+    }
+
+    /// Append the synthesizer's state to a save state
+    pub(super) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u8(self.address);
+        writer.write_bytes(&self.registers);
+        for operator in self.operators.iter().flatten() {
+            operator.save_state(writer);
+        }
+        writer.write_u8(self.divider);
+        writer.write_u32(self.envelope_counter);
+        writer.write_u32(self.am_counter);
+        writer.write_u32(self.pm_counter);
+        writer.write_u32(self.output as u32);
+    }
+
+    /// Restore state written by `save_state`
+    pub(super) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.address = reader.read_u8()?;
+        reader.read_bytes_into(&mut self.registers)?;
+        for operator in self.operators.iter_mut().flatten() {
+            operator.load_state(reader)?;
+        }
+        self.divider = reader.read_u8()?;
+        self.envelope_counter = reader.read_u32()?;
+        self.am_counter = reader.read_u32()? % AM_PERIOD;
+        self.pm_counter = reader.read_u32()? % PM_PERIOD;
+        self.output = reader.read_u32()? as i32;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write a sequence of (register, value) pairs
+    fn write_registers(opll: &mut Opll, writes: &[(u8, u8)]) {
+        for &(register, value) in writes {
+            opll.write_address(register);
+            opll.write_data(value);
+        }
+    }
+
+    /// Clock the synthesizer from the CPU and collect one output per sample
+    fn render(opll: &mut Opll, samples: usize) -> Vec<i32> {
+        (0..samples)
+            .map(|_| {
+                for _ in 0..CYCLES_PER_SAMPLE {
+                    opll.clock();
+                }
+                opll.output()
+            })
+            .collect()
+    }
+
+    /// Custom patch with a silent modulator and an instant, sustained carrier
+    const SINE_PATCH: [(u8, u8); 8] = [
+        (0x00, 0x01), // Modulator: MULT 1
+        (0x01, 0x21), // Carrier: sustained, MULT 1
+        (0x02, 0x3F), // Modulator total level 63
+        (0x03, 0x00),
+        (0x04, 0x00), // Modulator never attacks
+        (0x05, 0xF0), // Carrier attack 15, decay 0
+        (0x06, 0x00),
+        (0x07, 0x0F), // Carrier sustain level 0, release 15
+    ];
+
+    /// Play channel 0 at F-number 256, block 4 (256 samples per cycle)
+    const SINE_NOTE: [(u8, u8); 3] = [(0x30, 0x00), (0x10, 0x00), (0x20, 0x19)];
+
+    #[test]
+    fn test_silent_until_key_on() {
+        let mut opll = Opll::new();
+        write_registers(&mut opll, &[(0x30, 0x10), (0x10, 0x80), (0x20, 0x08)]);
+        assert!(render(&mut opll, 200).iter().all(|&sample| sample == 0));
+    }
+
+    #[test]
+    fn test_one_sample_every_36_cycles() {
+        let mut opll = Opll::new();
+        write_registers(&mut opll, &SINE_PATCH);
+        write_registers(&mut opll, &SINE_NOTE);
+
+        for _ in 0..CYCLES_PER_SAMPLE - 1 {
+            opll.clock();
+        }
+        assert_eq!(opll.output(), 0);
+        opll.clock();
+        assert_ne!(opll.output(), 0);
+    }
+
+    #[test]
+    fn test_sine_patch_matches_reference() {
+        let mut opll = Opll::new();
+        write_registers(&mut opll, &SINE_PATCH);
+        write_registers(&mut opll, &SINE_NOTE);
+
+        // The phase advances 4 table steps per sample
+        let samples = render(&mut opll, 512);
+        for (n, &sample) in samples.iter().enumerate() {
+            let index = (4 * (n + 1) % 1024) as f64;
+            let expected = 2048.0 * ((index + 0.5) * PI / 512.0).sin();
+            assert!(
+                (sample as f64 - expected).abs() <= 4.0,
+                "Sample {}: {} (expected {:.1})",
+                n,
+                sample,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_volume_attenuates_in_3db_steps() {
+        let peak = |volume: u8| {
+            let mut opll = Opll::new();
+            write_registers(&mut opll, &SINE_PATCH);
+            write_registers(&mut opll, &SINE_NOTE);
+            write_registers(&mut opll, &[(0x30, volume)]);
+            render(&mut opll, 256).into_iter().max().unwrap()
+        };
+
+        // 6 dB halves the amplitude; 45 dB is 2^-7.5
+        assert_eq!(peak(0), 2048);
+        assert!((peak(2) - 1024).abs() <= 2);
+        assert_eq!(peak(15), 11);
+    }
+
+    #[test]
+    fn test_key_off_releases_to_silence() {
+        let mut opll = Opll::new();
+        write_registers(&mut opll, &SINE_PATCH);
+        write_registers(&mut opll, &SINE_NOTE);
+        render(&mut opll, 64);
+
+        // Release rate 15 fades out within a few dozen samples
+        write_registers(&mut opll, &[(0x20, 0x09)]);
+        let samples = render(&mut opll, 128);
+        assert!(samples[..8].iter().any(|&sample| sample != 0));
+        assert!(samples[64..].iter().all(|&sample| sample == 0));
+        assert_eq!(opll.operators[0][1].state, EnvelopeState::Off);
+
+        // With the channel's sustain bit the release is much slower
+        write_registers(&mut opll, &[(0x20, 0x19)]);
+        render(&mut opll, 64);
+        write_registers(&mut opll, &[(0x20, 0x29)]);
+        let samples = render(&mut opll, 128);
+        assert!(samples[64..].iter().any(|&sample| sample != 0));
+    }
+
+    #[test]
+    fn test_attack_envelope_rises() {
+        let mut opll = Opll::new();
+        write_registers(&mut opll, &SINE_PATCH);
+        write_registers(&mut opll, &[(0x05, 0x90)]); // Carrier attack 9
+        write_registers(&mut opll, &SINE_NOTE);
+
+        // The peak of each cycle grows until the note reaches full volume
+        let samples = render(&mut opll, 256 * 12);
+        let peaks: Vec<i32> = samples
+            .chunks(256)
+            .map(|cycle| cycle.iter().copied().max().unwrap())
+            .collect();
+        assert!(peaks.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(peaks[0] < 512);
+        assert_eq!(*peaks.last().unwrap(), 2048);
+    }
+
+    #[test]
+    fn test_modulator_shapes_waveform() {
+        let mut sine = Opll::new();
+        write_registers(&mut sine, &SINE_PATCH);
+        write_registers(&mut sine, &SINE_NOTE);
+
+        // Same patch with an audible, instant modulator
+        let mut fm = Opll::new();
+        write_registers(&mut fm, &SINE_PATCH);
+        write_registers(&mut fm, &[(0x02, 0x10), (0x04, 0xF0)]);
+        write_registers(&mut fm, &SINE_NOTE);
+
+        let sine = render(&mut sine, 256);
+        let fm = render(&mut fm, 256);
+        assert_ne!(sine, fm);
+        assert!(fm.iter().all(|sample| sample.abs() <= 2048));
+    }
+
+    #[test]
+    fn test_builtin_patch_reference_buffer() {
+        // Flute (instrument 4) on channel 2, volume 0, F-number $120, block 3
+        let mut opll = Opll::new();
+        write_registers(&mut opll, &[(0x32, 0x40), (0x12, 0x20), (0x22, 0x17)]);
+
+        // Every 128th sample while the note swells in
+        let samples: Vec<i32> = render(&mut opll, 8192).into_iter().step_by(128).collect();
+        assert_eq!(samples, REFERENCE_FLUTE);
+    }
+
+    #[test]
+    fn test_custom_patch_is_instrument_zero() {
+        // Instrument 0 follows the custom patch registers
+        let mut custom = Opll::new();
+        write_registers(&mut custom, &[(0x00, 0x31), (0x01, 0x61), (0x02, 0x0C)]);
+        write_registers(&mut custom, &[(0x03, 0x07), (0x04, 0xA8), (0x05, 0x64)]);
+        write_registers(&mut custom, &[(0x06, 0x61), (0x07, 0x27)]);
+        write_registers(&mut custom, &[(0x32, 0x00), (0x12, 0x20), (0x22, 0x17)]);
+
+        // Loaded with the flute's bytes it sounds like the built-in flute
+        let mut flute = Opll::new();
+        write_registers(&mut flute, &[(0x32, 0x40), (0x12, 0x20), (0x22, 0x17)]);
+
+        assert_eq!(render(&mut custom, 1000), render(&mut flute, 1000));
+    }
+
+    #[test]
+    fn test_channels_are_summed() {
+        let mut one = Opll::new();
+        write_registers(&mut one, &SINE_PATCH);
+        write_registers(&mut one, &SINE_NOTE);
+
+        let mut two = Opll::new();
+        write_registers(&mut two, &SINE_PATCH);
+        write_registers(&mut two, &[(0x35, 0x00), (0x15, 0x00), (0x25, 0x19)]);
+        write_registers(&mut two, &SINE_NOTE);
+
+        let single = render(&mut one, 128);
+        let double = render(&mut two, 128);
+        for (a, b) in single.iter().zip(&double) {
+            assert_eq!(2 * a, *b);
+        }
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let mut opll = Opll::new();
+        write_registers(&mut opll, &[(0x33, 0x25), (0x13, 0x80), (0x23, 0x1B)]);
+        render(&mut opll, 300);
+
+        let mut writer = StateWriter::new();
+        opll.save_state(&mut writer);
+        let state = writer.into_bytes();
+
+        let mut restored = Opll::new();
+        let mut reader = StateReader::new(&state);
+        restored.load_state(&mut reader).unwrap();
+        reader.finish().unwrap();
+
+        assert_eq!(render(&mut restored, 500), render(&mut opll, 500));
+    }
+
+    /// Reference output of the flute test
+    const REFERENCE_FLUTE: [i32; 64] = [
+        0, 0, 1, 2, 7, 2, -11, -17, 34, -6, -53, 59, 36, -63, -62, 122, -8, -153, 110, 89, -121,
+        -199, 255, -10, -283, 170, 183, -195, -393, 507, 17, -512, 115, 399, -259, -669, 752, 123,
+        -658, -134, 686, -258, -980, 962, 379, -748, -632, 1105, -63, -1208, 815, 748, -589, -1224,
+        1440, 272, -1244, 406, 1160, -376, -1690, 1765, 632, -1275,
+    ];
+}
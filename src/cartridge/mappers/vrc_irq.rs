@@ -0,0 +1,216 @@
+// Konami VRC IRQ counter - Shared by VRC4, VRC6 and VRC7
+//
+// The counter is clocked by the CPU, not the PPU. In scanline mode a
+// prescaler divides the CPU clock by 113.667 (341 PPU dots / 3) to approximate
+// scanlines; in cycle mode the counter is clocked every CPU cycle. The counter
+// counts up from the latch and raises the IRQ when it overflows from $FF.
+//
+// Registers (the address decoding is up to each mapper):
+// - Latch: 8-bit reload value (VRC4 writes it as two nibbles)
+// - Control: bit 0 (A) = enable after acknowledge, bit 1 (E) = enable,
+//   bit 2 (M) = mode (0 = scanline, 1 = CPU cycle)
+// - Acknowledge: clears the pending IRQ and copies A into E
+
+use super::{StateReader, StateWriter};
+use crate::state::StateError;
+
+/// Prescaler period in thirds of a CPU cycle (one scanline is 341 dots)
+const PRESCALER_PERIOD: i16 = 341;
+
+/// Konami VRC IRQ counter
+#[derive(Debug, Clone, Default)]
+pub(super) struct VrcIrq {
+    /// Reload value
+    latch: u8,
+    /// Current count
+    counter: u8,
+    /// Scanline prescaler, in thirds of a CPU cycle
+    prescaler: i16,
+    /// Enable flag (E)
+    enabled: bool,
+    /// Enable-after-acknowledge flag (A)
+    enable_after_ack: bool,
+    /// CPU cycle mode (M)
+    cycle_mode: bool,
+    /// IRQ pending flag
+    pending: bool,
+}
+
+impl VrcIrq {
+    /// Create a disabled IRQ counter
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the whole reload value
+    pub(super) fn write_latch(&mut self, value: u8) {
+        self.latch = value;
+    }
+
+    /// Write the low 4 bits of the reload value
+    pub(super) fn write_latch_low(&mut self, value: u8) {
+        self.latch = (self.latch & 0xF0) | (value & 0x0F);
+    }
+
+    /// Write the high 4 bits of the reload value
+    pub(super) fn write_latch_high(&mut self, value: u8) {
+        self.latch = (self.latch & 0x0F) | (value << 4);
+    }
+
+    /// Write the control register
+    ///
+    /// Enabling the counter reloads it and resets the prescaler. The write
+    /// also acknowledges a pending IRQ.
+    pub(super) fn write_control(&mut self, value: u8) {
+        self.enable_after_ack = value & 0x01 != 0;
+        self.enabled = value & 0x02 != 0;
+        self.cycle_mode = value & 0x04 != 0;
+        self.pending = false;
+
+        if self.enabled {
+            self.counter = self.latch;
+            self.prescaler = PRESCALER_PERIOD;
+        }
+    }
+
+    /// Acknowledge the IRQ, restoring the enable flag from A
+    pub(super) fn acknowledge(&mut self) {
+        self.pending = false;
+        self.enabled = self.enable_after_ack;
+    }
+
+    /// Clear the pending IRQ without touching the configuration
+    pub(super) fn clear_pending(&mut self) {
+        self.pending = false;
+    }
+
+    /// Check if the IRQ line is asserted
+    pub(super) fn pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Advance the counter by one CPU cycle
+    pub(super) fn clock(&mut self) {
+        if !self.enabled {
+            return;
+        }
+
+        if self.cycle_mode {
+            self.clock_counter();
+        } else {
+            // Three PPU dots per CPU cycle
+            self.prescaler -= 3;
+            if self.prescaler <= 0 {
+                self.prescaler += PRESCALER_PERIOD;
+                self.clock_counter();
+            }
+        }
+    }
+
+    /// Count up, reloading and raising the IRQ on overflow
+    fn clock_counter(&mut self) {
+        if self.counter == 0xFF {
+            self.counter = self.latch;
+            self.pending = true;
+        } else {
+            self.counter += 1;
+        }
+    }
+
+    /// Append the counter's state to a save state
+    pub(super) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u8(self.latch);
+        writer.write_u8(self.counter);
+        writer.write_u16(self.prescaler as u16);
+        writer.write_bool(self.enabled);
+        writer.write_bool(self.enable_after_ack);
+        writer.write_bool(self.cycle_mode);
+        writer.write_bool(self.pending);
+    }
+
+    /// Restore state written by `save_state`
+    pub(super) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.latch = reader.read_u8()?;
+        self.counter = reader.read_u8()?;
+        self.prescaler = reader.read_u16()? as i16;
+        self.enabled = reader.read_bool()?;
+        self.enable_after_ack = reader.read_bool()?;
+        self.cycle_mode = reader.read_bool()?;
+        self.pending = reader.read_bool()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_mode_counts_cpu_cycles() {
+        let mut irq = VrcIrq::new();
+        irq.latch = 0xFD;
+        irq.write_control(0x06); // Enabled, cycle mode
+
+        // $FD -> $FE -> $FF -> overflow
+        irq.clock();
+        irq.clock();
+        assert!(!irq.pending());
+        irq.clock();
+        assert!(irq.pending());
+        assert_eq!(irq.counter, 0xFD);
+    }
+
+    #[test]
+    fn test_scanline_mode_uses_prescaler() {
+        let mut irq = VrcIrq::new();
+        irq.latch = 0xFE;
+        irq.write_control(0x02); // Enabled, scanline mode
+
+        // Two scanlines are 682 dots, a little under 228 CPU cycles
+        for _ in 0..227 {
+            irq.clock();
+        }
+        assert!(!irq.pending());
+        irq.clock();
+        assert!(irq.pending());
+    }
+
+    #[test]
+    fn test_acknowledge_restores_enable_from_a() {
+        let mut irq = VrcIrq::new();
+        irq.latch = 0xFF;
+        irq.write_control(0x04 | 0x02); // E set, A clear
+
+        irq.clock();
+        assert!(irq.pending());
+
+        irq.acknowledge();
+        assert!(!irq.pending());
+        assert!(!irq.enabled);
+
+        // With A set the counter keeps running after the acknowledge
+        irq.write_control(0x04 | 0x02 | 0x01);
+        irq.clock();
+        irq.acknowledge();
+        assert!(irq.enabled);
+    }
+
+    #[test]
+    fn test_latch_nibbles() {
+        let mut irq = VrcIrq::new();
+        irq.write_latch_low(0x0C);
+        irq.write_latch_high(0x0A);
+        assert_eq!(irq.latch, 0xAC);
+    }
+
+    #[test]
+    fn test_disabled_counter_does_not_run() {
+        let mut irq = VrcIrq::new();
+        irq.latch = 0xFF;
+        irq.write_control(0x04);
+        for _ in 0..1000 {
+            irq.clock();
+        }
+        assert!(!irq.pending());
+    }
+}
@@ -1,8 +1,14 @@
 // Cartridge module - ROM loading and mapper implementation
 // This module will contain cartridge and mapper implementations
 
+mod database;
 pub mod mappers;
 
+pub use database::{HeaderDatabase, HeaderEntry};
+
+use crate::region::Region;
+use crate::state::hash::{self, Crc32};
+use serde::{Deserialize, Serialize};
 use std::io::{self, Read};
 
 /// iNES file format magic number: "NES" + MS-DOS EOF
@@ -21,7 +27,7 @@ const PRG_ROM_BANK_SIZE: usize = 16 * 1024;
 const CHR_ROM_BANK_SIZE: usize = 8 * 1024;
 
 /// Mirroring type for nametables
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Mirroring {
     /// Horizontal mirroring (vertical arrangement)
     Horizontal,
@@ -33,6 +39,18 @@ pub enum Mirroring {
     SingleScreen,
 }
 
+/// Memory selected for a 1KB nametable
+///
+/// See [`Mapper::nametable_source`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NametableSource {
+    /// One of the two 1KB pages of the PPU's internal VRAM (0 or 1)
+    Ciram(u8),
+    /// Memory on the cartridge, accessed through [`Mapper::nametable_read`]
+    /// and [`Mapper::nametable_write`]
+    Cartridge,
+}
+
 /// iNES ROM format errors
 #[derive(Debug)]
 pub enum INesError {
@@ -44,8 +62,6 @@ pub enum INesError {
     FileTooSmall,
     /// Invalid file size (doesn't match header specifications)
     InvalidFileSize,
-    /// Unsupported format (iNES 2.0 is not currently supported)
-    UnsupportedFormat,
 }
 
 impl From<io::Error> for INesError {
@@ -63,35 +79,79 @@ impl std::fmt::Display for INesError {
             INesError::InvalidFileSize => {
                 write!(f, "File size doesn't match header specifications")
             }
-            INesError::UnsupportedFormat => {
-                write!(
-                    f,
-                    "Unsupported format: iNES 2.0 is not currently supported, only iNES 1.0"
-                )
-            }
         }
     }
 }
 
 impl std::error::Error for INesError {}
 
+/// Cartridge RAM sizes in bytes
+///
+/// NES 2.0 headers give each size explicitly. For iNES 1.0 headers they are
+/// inferred: 8KB of PRG-RAM (battery-backed if the battery flag is set) and
+/// 8KB of CHR-RAM when the ROM has no CHR-ROM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RamSizes {
+    /// Volatile PRG-RAM
+    pub prg_ram: usize,
+    /// Battery-backed PRG-RAM (PRG-NVRAM or EEPROM)
+    pub prg_nvram: usize,
+    /// Volatile CHR-RAM
+    pub chr_ram: usize,
+    /// Battery-backed CHR-RAM
+    pub chr_nvram: usize,
+}
+
+/// Console the ROM was made for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConsoleType {
+    /// Nintendo Entertainment System / Family Computer
+    #[default]
+    Nes,
+    /// Nintendo VS. System
+    VsSystem {
+        /// PPU type (0 = RP2C03B, 1 = RP2C03G, 2-6 = RP2C04-0001..0004, ...)
+        ppu: u8,
+        /// Hardware type (0 = Unisystem, 1 = RBI Baseball, 2 = TKO Boxing, ...)
+        hardware: u8,
+    },
+    /// PlayChoice-10
+    Playchoice10,
+    /// Extended console type (NES 2.0 byte 13, e.g. 3 = VT01 famiclone)
+    Extended(u8),
+}
+
 /// iNES header structure (16 bytes)
+///
+/// The raw bytes are kept as read; the accessor methods interpret them as
+/// iNES 1.0 or NES 2.0 depending on `is_ines2`. Headers with garbage in bytes
+/// 7-15 (written by old tools, e.g. "DiskDude!") have those bytes cleared.
 #[derive(Debug, Clone)]
 pub struct INesHeader {
-    /// PRG-ROM size in 16KB units
+    /// PRG-ROM size in 16KB units (NES 2.0: LSB of the size)
     pub prg_rom_banks: u8,
-    /// CHR-ROM size in 8KB units (0 = CHR-RAM)
+    /// CHR-ROM size in 8KB units, 0 = CHR-RAM (NES 2.0: LSB of the size)
     pub chr_rom_banks: u8,
     /// Flags 6
     pub flags6: u8,
     /// Flags 7
     pub flags7: u8,
-    /// PRG-RAM size in 8KB units (0 = 8KB for compatibility)
-    pub prg_ram_size: u8,
-    /// Flags 9 (TV system)
+    /// Flags 8 (iNES 1.0: PRG-RAM size in 8KB units; NES 2.0: mapper MSB and submapper)
+    pub flags8: u8,
+    /// Flags 9 (iNES 1.0: TV system; NES 2.0: PRG/CHR-ROM size MSB)
     pub flags9: u8,
-    /// Flags 10 (unofficial)
+    /// Flags 10 (iNES 1.0: unofficial; NES 2.0: PRG-RAM/NVRAM size)
     pub flags10: u8,
+    /// Flags 11 (NES 2.0 CHR-RAM/NVRAM size)
+    pub flags11: u8,
+    /// Flags 12 (NES 2.0 CPU/PPU timing)
+    pub flags12: u8,
+    /// Flags 13 (NES 2.0 VS. System type or extended console type)
+    pub flags13: u8,
+    /// Flags 14 (NES 2.0 miscellaneous ROM count)
+    pub flags14: u8,
+    /// Flags 15 (NES 2.0 default expansion device)
+    pub flags15: u8,
 }
 
 impl INesHeader {
@@ -106,20 +166,129 @@ impl INesHeader {
             return Err(INesError::InvalidMagic);
         }
 
+        // iNES 1.0 leaves bytes 12-15 zero. Anything else that is not NES 2.0
+        // is an archaic or dirty header, where only bytes 4-6 can be trusted.
+        let mut bytes: [u8; INES_HEADER_SIZE] = bytes[..INES_HEADER_SIZE].try_into().unwrap();
+        let nes2 = bytes[7] & 0x0C == 0x08;
+        let ines1 = bytes[7] & 0x0C == 0x00 && bytes[12..16] == [0; 4];
+        if !nes2 && !ines1 {
+            bytes[7..].fill(0);
+        }
+
         Ok(INesHeader {
             prg_rom_banks: bytes[4],
             chr_rom_banks: bytes[5],
             flags6: bytes[6],
             flags7: bytes[7],
-            prg_ram_size: bytes[8],
+            flags8: bytes[8],
             flags9: bytes[9],
             flags10: bytes[10],
+            flags11: bytes[11],
+            flags12: bytes[12],
+            flags13: bytes[13],
+            flags14: bytes[14],
+            flags15: bytes[15],
         })
     }
 
-    /// Get mapper number (combined from flags 6 and 7)
-    pub fn mapper_number(&self) -> u8 {
-        (self.flags7 & 0xF0) | (self.flags6 >> 4)
+    /// Get mapper number
+    ///
+    /// iNES 1.0 combines the nibbles of flags 6 and 7 into an 8-bit number;
+    /// NES 2.0 adds four more bits from flags 8.
+    pub fn mapper_number(&self) -> u16 {
+        let mapper = ((self.flags7 & 0xF0) | (self.flags6 >> 4)) as u16;
+        if self.is_ines2() {
+            mapper | ((self.flags8 & 0x0F) as u16) << 8
+        } else {
+            mapper
+        }
+    }
+
+    /// Get submapper number (NES 2.0 only, 0 otherwise)
+    pub fn submapper(&self) -> u8 {
+        if self.is_ines2() {
+            self.flags8 >> 4
+        } else {
+            0
+        }
+    }
+
+    /// Get PRG-ROM size in bytes
+    pub fn prg_rom_size(&self) -> usize {
+        if self.is_ines2() {
+            Self::nes2_rom_size(self.prg_rom_banks, self.flags9 & 0x0F, PRG_ROM_BANK_SIZE)
+        } else {
+            self.prg_rom_banks as usize * PRG_ROM_BANK_SIZE
+        }
+    }
+
+    /// Get CHR-ROM size in bytes (0 = CHR-RAM)
+    pub fn chr_rom_size(&self) -> usize {
+        if self.is_ines2() {
+            Self::nes2_rom_size(self.chr_rom_banks, self.flags9 >> 4, CHR_ROM_BANK_SIZE)
+        } else {
+            self.chr_rom_banks as usize * CHR_ROM_BANK_SIZE
+        }
+    }
+
+    /// Decode an NES 2.0 ROM size
+    ///
+    /// An MSB nibble of $F selects the exponent-multiplier form, where the LSB
+    /// byte is `EEEEEEMM` and the size is `2^E * (MM * 2 + 1)` bytes. Sizes too
+    /// large to address saturate, so the file size check rejects them.
+    ///
+    /// # Arguments
+    /// * `lsb` - Size LSB from byte 4 or 5
+    /// * `msb` - Size MSB nibble from byte 9
+    /// * `unit` - Bank size in bytes for the plain form
+    fn nes2_rom_size(lsb: u8, msb: u8, unit: usize) -> usize {
+        if msb == 0x0F {
+            let multiplier = (lsb & 0x03) as usize * 2 + 1;
+            1usize
+                .checked_shl((lsb >> 2) as u32)
+                .and_then(|size| size.checked_mul(multiplier))
+                .unwrap_or(usize::MAX)
+        } else {
+            (((msb as usize) << 8) | lsb as usize) * unit
+        }
+    }
+
+    /// Get the cartridge RAM sizes
+    ///
+    /// NES 2.0 encodes each size as a shift count: 0 means none, otherwise
+    /// the size is `64 << shift` bytes.
+    pub fn ram_sizes(&self) -> RamSizes {
+        let shift_size = |shift: u8| if shift == 0 { 0 } else { 64usize << shift };
+
+        if self.is_ines2() {
+            RamSizes {
+                prg_ram: shift_size(self.flags10 & 0x0F),
+                prg_nvram: shift_size(self.flags10 >> 4),
+                chr_ram: shift_size(self.flags11 & 0x0F),
+                chr_nvram: shift_size(self.flags11 >> 4),
+            }
+        } else {
+            // A PRG-RAM size of 0 means 8KB for compatibility
+            let prg_ram = self.flags8.max(1) as usize * 8 * 1024;
+            let chr_ram = if self.chr_rom_banks == 0 {
+                CHR_ROM_BANK_SIZE
+            } else {
+                0
+            };
+            if self.has_battery() {
+                RamSizes {
+                    prg_nvram: prg_ram,
+                    chr_ram,
+                    ..RamSizes::default()
+                }
+            } else {
+                RamSizes {
+                    prg_ram,
+                    chr_ram,
+                    ..RamSizes::default()
+                }
+            }
+        }
     }
 
     /// Get mirroring type
@@ -146,16 +315,78 @@ impl INesHeader {
         self.flags6 & 0x04 != 0
     }
 
-    /// Check if this is iNES 2.0 format
+    /// Get the console region the ROM was made for
+    ///
+    /// NES 2.0 headers give the CPU/PPU timing in flags 12; iNES 1.0 headers
+    /// only tell NTSC from PAL in bit 0 of flags 9. Multi-region ROMs run as
+    /// NTSC.
+    ///
+    /// # Returns
+    ///
+    /// The region selected by the header
+    pub fn region(&self) -> Region {
+        if self.is_ines2() {
+            match self.flags12 & 0x03 {
+                1 => Region::Pal,
+                3 => Region::Dendy,
+                _ => Region::Ntsc,
+            }
+        } else if self.flags9 & 0x01 != 0 {
+            Region::Pal
+        } else {
+            Region::Ntsc
+        }
+    }
+
+    /// Get the console type
     ///
-    /// iNES 2.0 files have different header interpretations and are currently not supported.
-    /// Files detected as iNES 2.0 will be rejected during loading.
+    /// Bits 0-1 of flags 7 select the console in both formats; only NES 2.0
+    /// describes the VS. System hardware and extended types in flags 13.
+    pub fn console_type(&self) -> ConsoleType {
+        let ines2 = self.is_ines2();
+        match self.flags7 & 0x03 {
+            0 => ConsoleType::Nes,
+            1 if ines2 => ConsoleType::VsSystem {
+                ppu: self.flags13 & 0x0F,
+                hardware: self.flags13 >> 4,
+            },
+            1 => ConsoleType::VsSystem {
+                ppu: 0,
+                hardware: 0,
+            },
+            2 => ConsoleType::Playchoice10,
+            _ if ines2 => ConsoleType::Extended(self.flags13 & 0x0F),
+            // iNES 1.0 only defines the VS. System bit; treat both bits as VS.
+            _ => ConsoleType::VsSystem {
+                ppu: 0,
+                hardware: 0,
+            },
+        }
+    }
+
+    /// Get the default expansion device (NES 2.0 only, 0 = unspecified)
+    ///
+    /// 1 is the standard controllers; see the NES 2.0 specification for the
+    /// full list.
+    pub fn expansion_device(&self) -> u8 {
+        if self.is_ines2() {
+            self.flags15 & 0x3F
+        } else {
+            0
+        }
+    }
+
+    /// Check if this is NES 2.0 format
+    ///
+    /// NES 2.0 headers set bits 2-3 of flags 7 to `10`. They extend the mapper
+    /// number, ROM sizes and RAM sizes, and describe the console in bytes 8-15.
     pub fn is_ines2(&self) -> bool {
         (self.flags7 & 0x0C) == 0x08
     }
 }
 
 /// Cartridge structure representing a loaded ROM
+#[derive(Debug, Clone)]
 pub struct Cartridge {
     /// PRG-ROM data (program memory)
     pub prg_rom: Vec<u8>,
@@ -163,12 +394,24 @@ pub struct Cartridge {
     pub chr_rom: Vec<u8>,
     /// Trainer data (if present)
     pub trainer: Option<Vec<u8>>,
-    /// Mapper number
-    pub mapper: u8,
+    /// Mapper number (12-bit with NES 2.0)
+    pub mapper: u16,
+    /// Submapper number selecting a board variant (NES 2.0, 0 otherwise)
+    pub submapper: u8,
     /// Mirroring type
     pub mirroring: Mirroring,
     /// Battery-backed RAM present
     pub has_battery: bool,
+    /// PRG and CHR RAM sizes
+    pub ram: RamSizes,
+    /// Console region the ROM was made for
+    pub region: Region,
+    /// Console type (NES, VS. System, ...)
+    pub console_type: ConsoleType,
+    /// Default expansion device (NES 2.0, 0 = unspecified)
+    pub expansion_device: u8,
+    /// Header fields corrected from the header database at load time
+    pub header_corrections: Vec<String>,
 }
 
 impl Cartridge {
@@ -179,15 +422,42 @@ impl Cartridge {
             chr_rom: Vec::new(),
             trainer: None,
             mapper: 0,
+            submapper: 0,
             mirroring: Mirroring::Horizontal,
             has_battery: false,
+            ram: RamSizes::default(),
+            region: Region::Ntsc,
+            console_type: ConsoleType::Nes,
+            expansion_device: 0,
+            header_corrections: Vec::new(),
         }
     }
 
     /// Load a ROM from iNES format bytes
     ///
-    /// Note: Only iNES 1.0 format is currently supported. iNES 2.0 files will be rejected.
+    /// Both iNES 1.0 and NES 2.0 headers are accepted. Header fields known to
+    /// be wrong for this ROM are corrected from [`HeaderDatabase::global`].
     pub fn from_ines_bytes(data: &[u8]) -> Result<Self, INesError> {
+        Self::from_ines_bytes_with_database(data, HeaderDatabase::global())
+    }
+
+    /// Load a ROM from iNES format bytes, correcting the header from `database`
+    ///
+    /// Each correction applied is recorded in
+    /// [`Cartridge::header_corrections`].
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The iNES file contents
+    /// * `database` - Header corrections to apply
+    ///
+    /// # Returns
+    ///
+    /// The loaded cartridge, or an error if the file is invalid
+    pub fn from_ines_bytes_with_database(
+        data: &[u8],
+        database: &HeaderDatabase,
+    ) -> Result<Self, INesError> {
         if data.len() < INES_HEADER_SIZE {
             return Err(INesError::FileTooSmall);
         }
@@ -195,21 +465,19 @@ impl Cartridge {
         // Parse header
         let header = INesHeader::from_bytes(&data[0..INES_HEADER_SIZE])?;
 
-        // Check for unsupported iNES 2.0 format
-        if header.is_ines2() {
-            return Err(INesError::UnsupportedFormat);
-        }
-
         // Calculate expected sizes
-        let prg_rom_size = header.prg_rom_banks as usize * PRG_ROM_BANK_SIZE;
-        let chr_rom_size = header.chr_rom_banks as usize * CHR_ROM_BANK_SIZE;
+        let prg_rom_size = header.prg_rom_size();
+        let chr_rom_size = header.chr_rom_size();
         let trainer_size = if header.has_trainer() {
             TRAINER_SIZE
         } else {
             0
         };
 
-        let expected_size = INES_HEADER_SIZE + trainer_size + prg_rom_size + chr_rom_size;
+        // NES 2.0 exponent sizes can be huge, so don't let the sum wrap
+        let expected_size = (INES_HEADER_SIZE + trainer_size)
+            .saturating_add(prg_rom_size)
+            .saturating_add(chr_rom_size);
 
         // Validate file size
         if data.len() < expected_size {
@@ -239,14 +507,26 @@ impl Cartridge {
             vec![0; CHR_ROM_BANK_SIZE]
         };
 
-        Ok(Cartridge {
+        let mut cartridge = Cartridge {
             prg_rom,
             chr_rom,
             trainer,
             mapper: header.mapper_number(),
+            submapper: header.submapper(),
             mirroring: header.mirroring(),
             has_battery: header.has_battery(),
-        })
+            ram: header.ram_sizes(),
+            region: header.region(),
+            console_type: header.console_type(),
+            expansion_device: header.expansion_device(),
+            header_corrections: Vec::new(),
+        };
+
+        if let Some(entry) = database.find(&cartridge) {
+            cartridge.header_corrections = entry.apply(&mut cartridge);
+        }
+
+        Ok(cartridge)
     }
 
     /// Load a ROM from a reader implementing Read
@@ -288,6 +568,70 @@ impl Cartridge {
     pub fn has_trainer(&self) -> bool {
         self.trainer.is_some()
     }
+
+    /// ROM data identifying the game: the PRG-ROM followed by the CHR-ROM
+    ///
+    /// When the header declared no CHR-ROM, the 8KB CHR area is CHR-RAM
+    /// allocated at load time and only the PRG-ROM contributes, matching the
+    /// file contents.
+    fn rom_data(&self) -> [&[u8]; 2] {
+        let chr_is_ram =
+            self.ram.chr_ram + self.ram.chr_nvram > 0 && self.chr_rom.len() == CHR_ROM_BANK_SIZE;
+        if chr_is_ram {
+            [&self.prg_rom, &[]]
+        } else {
+            [&self.prg_rom, &self.chr_rom]
+        }
+    }
+
+    /// MD5 of the ROM data, as used by FCEUX to identify games
+    ///
+    /// Covers the PRG-ROM followed by the CHR-ROM.
+    ///
+    /// # Returns
+    ///
+    /// The 16-byte MD5 digest
+    pub fn md5(&self) -> [u8; 16] {
+        let mut hasher = hash::Md5::new();
+        self.rom_data().iter().for_each(|data| hasher.update(data));
+        hasher.finish()
+    }
+
+    /// CRC-32 of the ROM data, as listed by No-Intro and NesCartDB
+    ///
+    /// Covers the PRG-ROM followed by the CHR-ROM, so it does not depend on
+    /// the header. Useful as a stable name for files belonging to the game.
+    ///
+    /// # Returns
+    ///
+    /// The CRC-32 checksum
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::Cartridge;
+    ///
+    /// let cartridge = Cartridge::new();
+    /// println!("{:08X}", cartridge.crc32());
+    /// ```
+    pub fn crc32(&self) -> u32 {
+        let mut hasher = Crc32::new();
+        self.rom_data().iter().for_each(|data| hasher.update(data));
+        hasher.finish()
+    }
+
+    /// SHA-1 of the ROM data, as listed by No-Intro and NesCartDB
+    ///
+    /// Covers the PRG-ROM followed by the CHR-ROM.
+    ///
+    /// # Returns
+    ///
+    /// The 20-byte SHA-1 digest
+    pub fn sha1(&self) -> [u8; 20] {
+        let mut hasher = hash::Sha1::new();
+        self.rom_data().iter().for_each(|data| hasher.update(data));
+        hasher.finish()
+    }
 }
 
 impl Default for Cartridge {
@@ -348,6 +692,15 @@ impl Default for Cartridge {
 ///     fn mirroring(&self) -> Mirroring {
 ///         self.mirroring
 ///     }
+///
+///     fn save_state(&self) -> Vec<u8> {
+///         // NROM with CHR-ROM has no mutable state
+///         Vec::new()
+///     }
+///
+///     fn load_state(&mut self, data: &[u8]) -> Result<(), MapperError> {
+///         StateReader::new(data).finish()
+///     }
 /// }
 /// ```
 ///
@@ -370,6 +723,38 @@ pub trait Mapper {
     /// The byte at the specified address, or 0 if the address is not mapped
     fn cpu_read(&self, address: u16) -> u8;
 
+    /// Read a byte from CPU address space as seen on the data bus
+    ///
+    /// Returns None where nothing on the cartridge answers the read, such as
+    /// missing or disabled PRG-RAM; the CPU then sees open bus (the last value
+    /// on the data bus). The default answers $8000-$FFFF, and $6000-$7FFF when
+    /// the mapper has PRG-RAM.
+    ///
+    /// # Arguments
+    /// * `address` - CPU address to read from ($4020-$FFFF)
+    ///
+    /// # Returns
+    /// The byte driven by the cartridge, or None for open bus
+    fn try_cpu_read(&self, address: u16) -> Option<u8> {
+        match address {
+            0x6000..=0x7FFF if self.prg_ram().is_some() => Some(self.cpu_read(address)),
+            0x8000..=0xFFFF => Some(self.cpu_read(address)),
+            _ => None,
+        }
+    }
+
+    /// Observe a CPU read of cartridge space
+    ///
+    /// Called by `Bus::cpu_read` after the byte is read, and only for timed CPU
+    /// reads. `cpu_read` and `try_cpu_read` are also used to peek memory for
+    /// the debugger, so they must not change state; registers that react to
+    /// being read (acknowledge-on-read, auto-incrementing ports) do it here.
+    ///
+    /// # Arguments
+    /// * `address` - CPU address that was read ($4020-$FFFF)
+    /// * `value` - Byte the CPU saw on the data bus
+    fn on_cpu_read(&mut self, _address: u16, _value: u8) {}
+
     /// Write a byte to CPU address space ($6000-$FFFF)
     ///
     /// Many mappers use writes to specific addresses to control banking and other features.
@@ -382,6 +767,9 @@ pub trait Mapper {
 
     /// Read a byte from PPU address space ($0000-$1FFF)
     ///
+    /// This is a side-effect-free peek, suitable for debuggers and viewers.
+    /// Fetches made by the PPU itself go through `ppu_fetch`.
+    ///
     /// # Arguments
     /// * `address` - PPU address to read from
     ///
@@ -389,6 +777,21 @@ pub trait Mapper {
     /// The byte at the specified address
     fn ppu_read(&self, address: u16) -> u8;
 
+    /// Fetch a byte from PPU address space ($0000-$1FFF) as the PPU does
+    ///
+    /// Called for pattern fetches made by the rendering pipeline and PPUDATA.
+    /// Mappers whose state reacts to PPU reads (e.g. the MMC2/MMC4 CHR latches)
+    /// override this; the default simply forwards to `ppu_read`.
+    ///
+    /// # Arguments
+    /// * `address` - PPU address to fetch from
+    ///
+    /// # Returns
+    /// The byte at the specified address
+    fn ppu_fetch(&mut self, address: u16) -> u8 {
+        self.ppu_read(address)
+    }
+
     /// Write a byte to PPU address space ($0000-$1FFF)
     ///
     /// For CHR-ROM, writes are typically ignored. For CHR-RAM, writes update the RAM.
@@ -425,6 +828,183 @@ pub trait Mapper {
     fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
         None
     }
+
+    /// Get the expansion audio chip's internal RAM, if present
+    ///
+    /// Used by debugging tools to inspect sound memory that is not on the CPU
+    /// bus, such as the Namco 163 wavetable RAM.
+    ///
+    /// # Returns
+    /// Optional reference to the audio RAM
+    fn audio_ram(&self) -> Option<&[u8]> {
+        None
+    }
+
+    /// Notify the mapper that one CPU cycle has elapsed
+    ///
+    /// Called once per CPU cycle, after that cycle's bus access. Mappers with
+    /// CPU-cycle counters or write timing rules hook in here.
+    fn cpu_clock(&mut self) {}
+
+    /// Get the PRG-ROM offset currently mapped at a CPU address
+    ///
+    /// Used by debugging tools to report the active bank mapping.
+    ///
+    /// # Arguments
+    /// * `address` - CPU address ($8000-$FFFF)
+    ///
+    /// # Returns
+    /// The byte offset into PRG-ROM, or None if the address is not backed by
+    /// PRG-ROM (or the mapper does not report its mapping)
+    fn prg_rom_offset(&self, _address: u16) -> Option<usize> {
+        None
+    }
+
+    /// Check if the mapper is asserting the CPU IRQ line
+    ///
+    /// The IRQ line is level-triggered: the mapper keeps it asserted until the
+    /// interrupt is acknowledged (usually by a write to one of its registers).
+    ///
+    /// # Returns
+    /// true if the mapper has an IRQ pending
+    fn irq_pending(&self) -> bool {
+        false
+    }
+
+    /// Acknowledge a pending mapper IRQ
+    ///
+    /// Releases the IRQ line without otherwise changing the IRQ configuration,
+    /// as the mapper's own acknowledge register would. Called on reset.
+    fn irq_acknowledge(&mut self) {}
+
+    /// Save the mapper's internal state
+    ///
+    /// Captures everything that can change at runtime (bank registers, IRQ
+    /// counters, shift registers, PRG-RAM and CHR-RAM) but not the ROM itself.
+    /// Use `StateWriter` to build the blob.
+    ///
+    /// # Returns
+    /// An opaque state blob to pass back to `load_state`
+    fn save_state(&self) -> Vec<u8>;
+
+    /// Get the layout version of the `save_state` blob
+    ///
+    /// Save states record it next to the blob, and a blob written with another
+    /// version is rejected before it reaches `load_state`. Bump it whenever the
+    /// blob layout changes.
+    fn state_version(&self) -> u16 {
+        1
+    }
+
+    /// Restore the mapper's internal state
+    ///
+    /// # Arguments
+    /// * `data` - A state blob produced by `save_state` on the same mapper type
+    ///
+    /// # Errors
+    /// Returns `MapperError::InvalidState` if the blob is truncated or does not
+    /// match this mapper's memory sizes
+    fn load_state(&mut self, data: &[u8]) -> Result<(), mappers::MapperError>;
+
+    /// Observe an address placed on the PPU address bus
+    ///
+    /// Called for every PPU memory access made by the rendering pipeline
+    /// (background and sprite fetches) and by the CPU through PPUADDR/PPUDATA.
+    /// Mappers that watch the bus (e.g. MMC3 counting A12 rising edges) use
+    /// this to drive their scanline counters.
+    ///
+    /// # Arguments
+    /// * `addr` - PPU address ($0000-$3FFF)
+    /// * `ppu_cycle` - Monotonic PPU dot count at the time of the access
+    fn on_ppu_address(&mut self, _addr: u16, _ppu_cycle: u64) {}
+
+    /// Notification that the PPU has started a new scanline
+    ///
+    /// Called at dot 0 of every scanline, including VBlank and pre-render.
+    ///
+    /// # Arguments
+    /// * `scanline` - Scanline that is starting (0-261, or 0-311 on PAL and Dendy)
+    /// * `rendering_enabled` - Whether background or sprite rendering is on
+    fn on_scanline(&mut self, _scanline: u16, _rendering_enabled: bool) {}
+
+    /// Select the memory behind a nametable address
+    ///
+    /// Most boards only steer the PPU's internal VRAM through `mirroring` and
+    /// keep the default. Mappers that map each nametable separately or carry
+    /// their own nametable memory (e.g. MMC5) override this.
+    ///
+    /// # Arguments
+    /// * `address` - PPU address ($2000-$2FFF)
+    ///
+    /// # Returns
+    /// The memory for the address's nametable, or None to arrange internal
+    /// VRAM according to `mirroring`
+    fn nametable_source(&self, _address: u16) -> Option<NametableSource> {
+        None
+    }
+
+    /// Read a nametable byte from cartridge memory
+    ///
+    /// Only called for addresses whose `nametable_source` is
+    /// `NametableSource::Cartridge`. Like `ppu_read`, this is a
+    /// side-effect-free peek.
+    ///
+    /// # Arguments
+    /// * `address` - PPU address ($2000-$2FFF)
+    ///
+    /// # Returns
+    /// The byte at the specified address
+    fn nametable_read(&self, _address: u16) -> u8 {
+        0
+    }
+
+    /// Write a nametable byte to cartridge memory
+    ///
+    /// Only called for addresses whose `nametable_source` is
+    /// `NametableSource::Cartridge`.
+    ///
+    /// # Arguments
+    /// * `address` - PPU address ($2000-$2FFF)
+    /// * `value` - Byte value to write
+    fn nametable_write(&mut self, _address: u16, _value: u8) {}
+
+    /// Substitute a nametable byte fetched by the PPU
+    ///
+    /// Called after `on_ppu_address` for every nametable and attribute fetch
+    /// made through the PPU's fetch path. Mappers that replace tiles while
+    /// rendering (e.g. the MMC5 split screen and extended attributes) return
+    /// the byte the PPU should see.
+    ///
+    /// # Arguments
+    /// * `address` - PPU address ($2000-$2FFF)
+    ///
+    /// # Returns
+    /// The replacement byte, or None to read the address's nametable source
+    fn nametable_fetch(&mut self, _address: u16) -> Option<u8> {
+        None
+    }
+
+    /// Observe a CPU write to a PPU register
+    ///
+    /// The cartridge connector carries the whole CPU bus, so mappers can
+    /// snoop writes meant for the PPU (MMC5 watches PPUCTRL and PPUMASK).
+    ///
+    /// # Arguments
+    /// * `address` - PPU register address ($2000-$2007)
+    /// * `value` - Byte value written
+    fn on_ppu_register_write(&mut self, _address: u16, _value: u8) {}
+
+    /// Get the cartridge's expansion audio output
+    ///
+    /// Some cartridges generate sound that the console mixes with the APU.
+    /// The mapper scales its chip to the chip's loudness relative to the APU,
+    /// on the same scale as `Apu::output`; the mixers add it to the 2A03 mix.
+    ///
+    /// # Returns
+    /// The current expansion audio level, 0.0 for cartridges without sound
+    fn audio_output(&self) -> f32 {
+        0.0
+    }
 }
 
 #[cfg(test)]
@@ -510,6 +1090,40 @@ mod tests {
         assert_eq!(parsed.mapper_number(), 4);
     }
 
+    #[test]
+    fn test_region_detection() {
+        // iNES 1.0: bit 0 of flags 9
+        let mut header = create_test_header(2, 1, 0, Mirroring::Horizontal, false, false);
+        assert_eq!(
+            INesHeader::from_bytes(&header).unwrap().region(),
+            Region::Ntsc
+        );
+        header[9] = 0x01;
+        assert_eq!(
+            INesHeader::from_bytes(&header).unwrap().region(),
+            Region::Pal
+        );
+
+        // NES 2.0: bits 0-1 of flags 12
+        header[7] = 0x08;
+        for (timing, region) in [
+            (0, Region::Ntsc),
+            (1, Region::Pal),
+            (2, Region::Ntsc), // Multi-region
+            (3, Region::Dendy),
+        ] {
+            header[12] = timing;
+            assert_eq!(INesHeader::from_bytes(&header).unwrap().region(), region);
+        }
+
+        // The region is carried into the cartridge
+        let mut rom_data = create_test_header(1, 1, 0, Mirroring::Horizontal, false, false);
+        rom_data[9] = 0x01;
+        rom_data.extend(vec![0; 24 * 1024]);
+        let cartridge = Cartridge::from_ines_bytes(&rom_data).unwrap();
+        assert_eq!(cartridge.region, Region::Pal);
+    }
+
     #[test]
     fn test_mirroring_detection() {
         // Test horizontal mirroring
@@ -617,29 +1231,249 @@ mod tests {
     }
 
     #[test]
-    fn test_ines2_format_rejected() {
-        // Create a valid iNES 2.0 header
+    fn test_ines2_format_loaded() {
+        // Create a valid NES 2.0 header for mapper $123, submapper 5
         let mut header = vec![0u8; INES_HEADER_SIZE];
         header[0..4].copy_from_slice(&INES_MAGIC);
         header[4] = 2; // PRG-ROM banks
         header[5] = 1; // CHR-ROM banks
-        header[6] = 0; // Flags 6
-        header[7] = 0x08; // Flags 7: bits 2-3 = 10 indicates iNES 2.0
+        header[6] = 0x32; // Flags 6: mapper low nibble 3, battery
+        header[7] = 0x28; // Flags 7: mapper nibble 2, bits 2-3 = 10 indicates NES 2.0
+        header[8] = 0x51; // Submapper 5, mapper MSB 1
+        header[10] = 0x70; // 8KB PRG-NVRAM
+        header[12] = 0x01; // PAL
+        header[15] = 0x01; // Standard controllers
 
         // Create complete ROM data
         let mut rom_data = header;
         rom_data.extend(vec![0xAA; 32 * 1024]); // PRG-ROM
         rom_data.extend(vec![0xBB; 8 * 1024]); // CHR-ROM
 
-        // Verify the header is detected as iNES 2.0
+        // Verify the header is detected as NES 2.0
         let parsed_header = INesHeader::from_bytes(&rom_data[0..INES_HEADER_SIZE]).unwrap();
         assert!(parsed_header.is_ines2());
 
-        // Attempt to load should fail with UnsupportedFormat
-        let result = Cartridge::from_ines_bytes(&rom_data);
-        assert!(
-            matches!(result, Err(INesError::UnsupportedFormat)),
-            "Expected UnsupportedFormat error for iNES 2.0 file"
+        let cartridge = Cartridge::from_ines_bytes(&rom_data).unwrap();
+        assert_eq!(cartridge.mapper, 0x123);
+        assert_eq!(cartridge.submapper, 5);
+        assert_eq!(cartridge.prg_rom_size(), 32 * 1024);
+        assert_eq!(cartridge.chr_rom_size(), 8 * 1024);
+        assert!(cartridge.has_battery);
+        assert_eq!(
+            cartridge.ram,
+            RamSizes {
+                prg_nvram: 8 * 1024,
+                ..RamSizes::default()
+            }
+        );
+        assert_eq!(cartridge.region, Region::Pal);
+        assert_eq!(cartridge.console_type, ConsoleType::Nes);
+        assert_eq!(cartridge.expansion_device, 1);
+    }
+
+    #[test]
+    fn test_ines1_ignores_nes2_fields() {
+        // Bytes 8-15 must not change the meaning of an iNES 1.0 header
+        let mut header = create_test_header(2, 0, 4, Mirroring::Horizontal, false, true);
+        header[8] = 0x51;
+        let parsed = INesHeader::from_bytes(&header).unwrap();
+        assert_eq!(parsed.mapper_number(), 4);
+        assert_eq!(parsed.submapper(), 0);
+        assert_eq!(parsed.expansion_device(), 0);
+
+        // 0x51 8KB units of battery-backed PRG-RAM, and 8KB CHR-RAM
+        assert_eq!(
+            parsed.ram_sizes(),
+            RamSizes {
+                prg_nvram: 0x51 * 8 * 1024,
+                chr_ram: 8 * 1024,
+                ..RamSizes::default()
+            }
+        );
+
+        // A PRG-RAM size of 0 means 8KB
+        let header = create_test_header(2, 1, 0, Mirroring::Horizontal, false, false);
+        assert_eq!(
+            INesHeader::from_bytes(&header).unwrap().ram_sizes(),
+            RamSizes {
+                prg_ram: 8 * 1024,
+                ..RamSizes::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_nes2_rom_sizes() {
+        let mut header = create_test_header(0, 0, 0, Mirroring::Horizontal, false, false);
+        header[7] = 0x08;
+
+        // MSB nibbles extend the bank counts
+        header[4] = 0x02;
+        header[5] = 0x03;
+        header[9] = 0x12;
+        let parsed = INesHeader::from_bytes(&header).unwrap();
+        assert_eq!(parsed.prg_rom_size(), 0x202 * PRG_ROM_BANK_SIZE);
+        assert_eq!(parsed.chr_rom_size(), 0x103 * CHR_ROM_BANK_SIZE);
+
+        // Exponent-multiplier form: 2^14 * 3 = 48KB PRG, 2^10 * 1 = 1KB CHR
+        header[4] = (14 << 2) | 1;
+        header[5] = 10 << 2;
+        header[9] = 0xFF;
+        let parsed = INesHeader::from_bytes(&header).unwrap();
+        assert_eq!(parsed.prg_rom_size(), 48 * 1024);
+        assert_eq!(parsed.chr_rom_size(), 1024);
+
+        let mut rom_data = header.clone();
+        rom_data.extend(vec![0xAA; 48 * 1024 + 1024]);
+        let cartridge = Cartridge::from_ines_bytes(&rom_data).unwrap();
+        assert_eq!(cartridge.prg_rom_size(), 48 * 1024);
+        assert_eq!(cartridge.chr_rom_size(), 1024);
+
+        // Sizes too large to address are rejected instead of overflowing
+        header[4] = 0xFF;
+        let result = Cartridge::from_ines_bytes(&header);
+        assert!(matches!(result, Err(INesError::InvalidFileSize)));
+    }
+
+    #[test]
+    fn test_nes2_ram_sizes() {
+        let mut header = create_test_header(1, 0, 0, Mirroring::Horizontal, false, true);
+        header[7] = 0x08;
+        header[10] = 0x97; // 32KB PRG-NVRAM, 8KB PRG-RAM
+        header[11] = 0x09; // 32KB CHR-RAM
+        assert_eq!(
+            INesHeader::from_bytes(&header).unwrap().ram_sizes(),
+            RamSizes {
+                prg_ram: 8 * 1024,
+                prg_nvram: 32 * 1024,
+                chr_ram: 32 * 1024,
+                chr_nvram: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_console_type_detection() {
+        let mut header = create_test_header(1, 1, 0, Mirroring::Horizontal, false, false);
+
+        // iNES 1.0 only has the VS. System and PlayChoice-10 bits
+        header[7] = 0x01;
+        assert_eq!(
+            INesHeader::from_bytes(&header).unwrap().console_type(),
+            ConsoleType::VsSystem {
+                ppu: 0,
+                hardware: 0
+            }
+        );
+        header[7] = 0x02;
+        assert_eq!(
+            INesHeader::from_bytes(&header).unwrap().console_type(),
+            ConsoleType::Playchoice10
+        );
+
+        // NES 2.0 describes the VS. System and extended consoles in byte 13
+        header[7] = 0x09;
+        header[13] = 0x23;
+        assert_eq!(
+            INesHeader::from_bytes(&header).unwrap().console_type(),
+            ConsoleType::VsSystem {
+                ppu: 3,
+                hardware: 2
+            }
+        );
+        header[7] = 0x0B;
+        assert_eq!(
+            INesHeader::from_bytes(&header).unwrap().console_type(),
+            ConsoleType::Extended(3)
+        );
+    }
+
+    #[test]
+    fn test_dirty_header_bytes_ignored() {
+        // "DiskDude!" in bytes 7-15 would otherwise add $40 to the mapper
+        // number and select PAL
+        let mut header = create_test_header(2, 1, 4, Mirroring::Vertical, false, true);
+        header[7..16].copy_from_slice(b"DiskDude!");
+        let parsed = INesHeader::from_bytes(&header).unwrap();
+        assert_eq!(parsed.mapper_number(), 4);
+        assert_eq!(parsed.region(), Region::Ntsc);
+        assert_eq!(parsed.mirroring(), Mirroring::Vertical);
+        assert!(parsed.has_battery());
+
+        // Archaic headers (bits 2-3 of flags 7 = 01) are treated the same way
+        let mut header = create_test_header(2, 1, 4, Mirroring::Vertical, false, false);
+        header[7] = 0x54;
+        assert_eq!(INesHeader::from_bytes(&header).unwrap().mapper_number(), 4);
+    }
+
+    #[test]
+    fn test_rom_hashes_cover_prg_and_chr() {
+        let mut rom_data = create_test_header(1, 1, 0, Mirroring::Horizontal, false, false);
+        rom_data.extend(vec![0xAA; 16 * 1024]);
+        rom_data.extend(vec![0xBB; 8 * 1024]);
+        let cartridge = Cartridge::from_ines_bytes(&rom_data).unwrap();
+
+        let rom = &rom_data[INES_HEADER_SIZE..];
+        assert_eq!(cartridge.crc32(), crate::state::crc32(rom));
+        assert_eq!(cartridge.sha1(), hash::sha1(rom));
+        assert_eq!(cartridge.md5(), hash::md5(rom));
+
+        // The header does not take part in the hashes
+        rom_data[6] |= 0x01;
+        let remirrored = Cartridge::from_ines_bytes(&rom_data).unwrap();
+        assert_eq!(remirrored.crc32(), cartridge.crc32());
+
+        // CHR-RAM carts hash their PRG-ROM only
+        let mut rom_data = create_test_header(1, 0, 0, Mirroring::Horizontal, false, false);
+        rom_data.extend(vec![0xAA; 16 * 1024]);
+        let cartridge = Cartridge::from_ines_bytes(&rom_data).unwrap();
+        assert_eq!(
+            cartridge.crc32(),
+            crate::state::crc32(&rom_data[INES_HEADER_SIZE..])
         );
+
+        // A blank CHR-ROM bank is still ROM and is hashed
+        let mut rom_data = create_test_header(1, 1, 0, Mirroring::Horizontal, false, false);
+        rom_data.extend(vec![0xAA; 16 * 1024]);
+        rom_data.extend(vec![0x00; 8 * 1024]);
+        let cartridge = Cartridge::from_ines_bytes(&rom_data).unwrap();
+        assert_eq!(
+            cartridge.crc32(),
+            crate::state::crc32(&rom_data[INES_HEADER_SIZE..])
+        );
+    }
+
+    #[test]
+    fn test_header_database_corrections_applied() {
+        let mut rom_data = create_test_header(2, 1, 0, Mirroring::Horizontal, false, false);
+        rom_data.extend(vec![0xAA; 32 * 1024]);
+        rom_data.extend(vec![0xBB; 8 * 1024]);
+        let crc32 = crate::state::crc32(&rom_data[INES_HEADER_SIZE..]);
+
+        let database = HeaderDatabase::from_toml(&format!(
+            "[[rom]]\nname = \"Test\"\ncrc32 = {}\nmapper = 1\nmirroring = \"Vertical\"\nbattery = true\n",
+            crc32
+        ))
+        .unwrap();
+        let cartridge = Cartridge::from_ines_bytes_with_database(&rom_data, &database).unwrap();
+        assert_eq!(cartridge.mapper, 1);
+        assert_eq!(cartridge.mirroring, Mirroring::Vertical);
+        assert!(cartridge.has_battery);
+        assert_eq!(cartridge.ram.prg_nvram, 8 * 1024);
+        assert_eq!(
+            cartridge.header_corrections,
+            [
+                "mapper 0 -> 1",
+                "mirroring Horizontal -> Vertical",
+                "battery false -> true"
+            ]
+        );
+
+        // Other ROMs are loaded as their header says
+        rom_data[INES_HEADER_SIZE] = 0;
+        let cartridge = Cartridge::from_ines_bytes_with_database(&rom_data, &database).unwrap();
+        assert_eq!(cartridge.mapper, 0);
+        assert_eq!(cartridge.mirroring, Mirroring::Horizontal);
+        assert!(cartridge.header_corrections.is_empty());
     }
 }
@@ -1,7 +1,7 @@
 // Addressing modes module for 6502 CPU
 // Implements all 13 addressing modes used by the 6502 processor
 
-use crate::bus::Bus;
+use crate::cpu::CpuBus;
 
 /// Result of an addressing mode calculation
 ///
@@ -45,6 +45,18 @@ impl AddressingResult {
         self.page_crossed = crossed;
         self
     }
+
+    /// Get the address an indexed mode reads before fixing the high byte
+    ///
+    /// The 6502 adds the index to the low byte first. When that carries into
+    /// the next page, the first read goes to the previous page.
+    pub fn uncorrected_address(&self) -> u16 {
+        if self.page_crossed {
+            self.address.wrapping_sub(0x0100)
+        } else {
+            self.address
+        }
+    }
 }
 
 /// Addressing modes supported by the 6502
@@ -134,7 +146,7 @@ impl super::Cpu {
     /// The operand is the byte immediately following the opcode.
     /// Format: LDA #$01
     /// Returns the immediate value and increments PC.
-    pub fn addr_immediate(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_immediate<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let value = bus.read(self.pc);
         self.pc = self.pc.wrapping_add(1);
         AddressingResult::immediate(value)
@@ -149,7 +161,7 @@ impl super::Cpu {
     /// Uses only one byte for the address, limiting it to page 0.
     /// Format: LDA $80 (reads from $0080)
     /// Faster than absolute addressing (2 bytes vs 3 bytes).
-    pub fn addr_zero_page(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_zero_page<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let addr = bus.read(self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
         AddressingResult::new(addr)
@@ -164,10 +176,13 @@ impl super::Cpu {
     /// Adds X register to zero page address with wrapping.
     /// Format: LDA $80,X (if X=5, reads from $0085)
     /// Wraps within zero page: $FF + 2 = $01 (not $0101).
-    pub fn addr_zero_page_x(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_zero_page_x<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let base = bus.read(self.pc);
         self.pc = self.pc.wrapping_add(1);
 
+        // The CPU reads the unindexed address while it adds X
+        bus.read(base as u16);
+
         // Add X register and wrap within zero page (0x00-0xFF)
         let addr = base.wrapping_add(self.x) as u16;
         AddressingResult::new(addr)
@@ -182,10 +197,13 @@ impl super::Cpu {
     /// Adds Y register to zero page address with wrapping.
     /// Format: LDX $80,Y (if Y=5, reads from $0085)
     /// Wraps within zero page: $FF + 2 = $01 (not $0101).
-    pub fn addr_zero_page_y(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_zero_page_y<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let base = bus.read(self.pc);
         self.pc = self.pc.wrapping_add(1);
 
+        // The CPU reads the unindexed address while it adds Y
+        bus.read(base as u16);
+
         // Add Y register and wrap within zero page (0x00-0xFF)
         let addr = base.wrapping_add(self.y) as u16;
         AddressingResult::new(addr)
@@ -201,7 +219,7 @@ impl super::Cpu {
     /// The offset is added to PC to calculate the branch target.
     /// Format: BNE $1234 (offset is calculated by assembler)
     /// Range: -128 to +127 bytes from the instruction after the branch.
-    pub fn addr_relative(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_relative<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let offset = bus.read(self.pc) as i8;
         self.pc = self.pc.wrapping_add(1);
 
@@ -227,7 +245,7 @@ impl super::Cpu {
     /// Uses a full 16-bit address to access any location in memory.
     /// Format: LDA $8000 (reads from $8000)
     /// Address is stored in little-endian format (low byte first).
-    pub fn addr_absolute(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_absolute<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let lo = bus.read(self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
 
@@ -247,7 +265,7 @@ impl super::Cpu {
     /// Adds X register to a 16-bit base address.
     /// Format: LDA $8000,X (if X=5, reads from $8005)
     /// Page boundary crossing adds an extra cycle for some instructions.
-    pub fn addr_absolute_x(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_absolute_x<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let lo = bus.read(self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
 
@@ -272,7 +290,7 @@ impl super::Cpu {
     /// Adds Y register to a 16-bit base address.
     /// Format: LDA $8000,Y (if Y=5, reads from $8005)
     /// Page boundary crossing adds an extra cycle for some instructions.
-    pub fn addr_absolute_y(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_absolute_y<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let lo = bus.read(self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
 
@@ -300,7 +318,7 @@ impl super::Cpu {
     /// IMPORTANT BUG: The 6502 has a bug with page boundary wrapping.
     /// If the pointer is at $xxFF, the high byte is read from $xx00 instead of $(xx+1)00.
     /// For example: JMP ($02FF) reads low byte from $02FF and high byte from $0200 (not $0300).
-    pub fn addr_indirect(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_indirect<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let ptr_lo = bus.read(self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
 
@@ -341,10 +359,13 @@ impl super::Cpu {
     /// 3. Use pointer as the effective address
     ///
     /// Used primarily with X register for table lookups.
-    pub fn addr_indexed_indirect(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_indexed_indirect<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let base = bus.read(self.pc);
         self.pc = self.pc.wrapping_add(1);
 
+        // The CPU reads the unindexed pointer while it adds X
+        bus.read(base as u16);
+
         // Add X register to base address and wrap within zero page
         let ptr = base.wrapping_add(self.x);
 
@@ -374,7 +395,7 @@ impl super::Cpu {
     ///
     /// Used for accessing data structures with a base pointer.
     /// Page boundary crossing adds an extra cycle for some instructions.
-    pub fn addr_indirect_indexed(&mut self, bus: &mut Bus) -> AddressingResult {
+    pub fn addr_indirect_indexed<B: CpuBus>(&mut self, bus: &mut B) -> AddressingResult {
         let ptr = bus.read(self.pc);
         self.pc = self.pc.wrapping_add(1);
 
@@ -395,6 +416,7 @@ impl super::Cpu {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bus::Bus;
     use crate::cpu::Cpu;
 
     /// Helper function to create a test bus with data
@@ -0,0 +1,223 @@
+// CPU bus interface
+//
+// The 6502 core only talks to memory through the `CpuBus` trait: every cycle
+// is either one read or one write, and a halted CPU still lets time pass with
+// `tick`. The NES `Bus` implements it by clocking the PPU and APU on each
+// access; `FlatBus` is a plain 64KB memory for running the core on its own.
+
+use crate::bus::Bus;
+
+/// Memory interface used by the CPU
+///
+/// Each call is one CPU cycle. Implementations that emulate a whole system
+/// advance the other components as part of the access.
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::cpu::{Cpu, FlatBus};
+///
+/// let mut bus = FlatBus::new();
+/// bus.load(0x0200, &[0xA9, 0x42]); // LDA #$42
+///
+/// let mut cpu = Cpu::new();
+/// cpu.pc = 0x0200;
+/// assert_eq!(cpu.step(&mut bus), 2);
+/// assert_eq!(cpu.a, 0x42);
+/// ```
+pub trait CpuBus {
+    /// Read a byte as one CPU cycle
+    ///
+    /// # Arguments
+    /// * `addr` - The 16-bit address to read from
+    ///
+    /// # Returns
+    /// The byte on the data bus
+    fn read(&mut self, addr: u16) -> u8;
+
+    /// Write a byte as one CPU cycle
+    ///
+    /// # Arguments
+    /// * `addr` - The 16-bit address to write to
+    /// * `data` - The byte value to write
+    fn write(&mut self, addr: u16, data: u8);
+
+    /// Let one CPU cycle pass without a bus access
+    ///
+    /// Used while the CPU is halted by a KIL opcode.
+    fn tick(&mut self);
+}
+
+impl CpuBus for Bus {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.cpu_read(addr)
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.cpu_write(addr, data);
+    }
+
+    fn tick(&mut self) {
+        self.cpu_tick();
+    }
+}
+
+/// Flat 64KB memory with no devices attached
+///
+/// Every address is plain RAM. Useful for running the CPU core outside the
+/// NES, for example in single-step conformance tests.
+pub struct FlatBus {
+    /// The whole 16-bit address space
+    memory: Box<[u8; 0x10000]>,
+
+    /// Number of CPU cycles elapsed
+    cycles: u64,
+}
+
+impl FlatBus {
+    /// Create a flat bus with all memory cleared
+    pub fn new() -> Self {
+        FlatBus {
+            memory: Box::new([0; 0x10000]),
+            cycles: 0,
+        }
+    }
+
+    /// Copy bytes into memory without spending cycles
+    ///
+    /// # Arguments
+    /// * `addr` - Address of the first byte (wraps at $FFFF)
+    /// * `data` - Bytes to copy
+    pub fn load(&mut self, addr: u16, data: &[u8]) {
+        for (i, &byte) in data.iter().enumerate() {
+            self.memory[addr.wrapping_add(i as u16) as usize] = byte;
+        }
+    }
+
+    /// Read a byte without spending a cycle
+    ///
+    /// # Arguments
+    /// * `addr` - The 16-bit address to read from
+    ///
+    /// # Returns
+    /// The byte at the address
+    pub fn peek(&self, addr: u16) -> u8 {
+        self.memory[addr as usize]
+    }
+
+    /// Get the number of CPU cycles elapsed
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+}
+
+impl Default for FlatBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpuBus for FlatBus {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.cycles += 1;
+        self.memory[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.cycles += 1;
+        self.memory[addr as usize] = data;
+    }
+
+    fn tick(&mut self) {
+        self.cycles += 1;
+    }
+}
+
+/// Bus wrapper that counts the cycles spent through it
+///
+/// The CPU wraps the bus it is given in one of these for each instruction or
+/// interrupt, so cycle counts come from the accesses actually made.
+pub(crate) struct CycleCounter<'a, B: CpuBus> {
+    bus: &'a mut B,
+    cycles: u64,
+}
+
+impl<'a, B: CpuBus> CycleCounter<'a, B> {
+    /// Start counting cycles on `bus`
+    pub(crate) fn new(bus: &'a mut B) -> Self {
+        CycleCounter { bus, cycles: 0 }
+    }
+
+    /// Number of cycles spent so far
+    pub(crate) fn cycles(&self) -> u64 {
+        self.cycles
+    }
+}
+
+impl<B: CpuBus> CpuBus for CycleCounter<'_, B> {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.cycles += 1;
+        self.bus.read(addr)
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.cycles += 1;
+        self.bus.write(addr, data);
+    }
+
+    fn tick(&mut self) {
+        self.cycles += 1;
+        self.bus.tick();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::Cpu;
+
+    #[test]
+    fn test_flat_bus_counts_cycles() {
+        let mut bus = FlatBus::new();
+        bus.load(0xFFFF, &[0x12, 0x34]);
+        assert_eq!(bus.peek(0xFFFF), 0x12);
+        assert_eq!(bus.peek(0x0000), 0x34);
+        assert_eq!(bus.cycles(), 0);
+
+        assert_eq!(bus.read(0x0000), 0x34);
+        bus.write(0x1234, 0x56);
+        bus.tick();
+        assert_eq!(bus.peek(0x1234), 0x56);
+        assert_eq!(bus.cycles(), 3);
+    }
+
+    #[test]
+    fn test_cpu_runs_on_flat_bus() {
+        // JSR $0300; ... $0300: INC $10; RTS
+        let mut bus = FlatBus::new();
+        bus.load(0x0200, &[0x20, 0x00, 0x03]);
+        bus.load(0x0300, &[0xE6, 0x10, 0x60]);
+
+        let mut cpu = Cpu::new();
+        cpu.pc = 0x0200;
+        assert_eq!(cpu.step(&mut bus), 6);
+        assert_eq!(cpu.step(&mut bus), 5);
+        assert_eq!(cpu.step(&mut bus), 6);
+
+        assert_eq!(cpu.pc, 0x0203);
+        assert_eq!(bus.peek(0x0010), 0x01);
+        assert_eq!(bus.cycles(), 17);
+        assert_eq!(cpu.cycles, 17);
+    }
+
+    #[test]
+    fn test_cycle_counter_forwards_accesses() {
+        let mut bus = FlatBus::new();
+        let mut counter = CycleCounter::new(&mut bus);
+        counter.write(0x0010, 0x42);
+        assert_eq!(counter.read(0x0010), 0x42);
+        counter.tick();
+        assert_eq!(counter.cycles(), 3);
+        assert_eq!(bus.cycles(), 3);
+    }
+}
@@ -2,18 +2,41 @@
 
 use crate::bus::Bus;
 use crate::cpu::addressing::AddressingMode;
+use crate::cpu::bus::CycleCounter;
 use crate::cpu::opcodes::OPCODE_TABLE;
-use crate::cpu::Cpu;
+use crate::cpu::{Cpu, CpuBus};
 
 impl Cpu {
     /// Execute one CPU instruction
     ///
-    /// This function fetches the next opcode, decodes it, executes the instruction,
-    /// and updates the cycle counter.
+    /// This function fetches the next opcode, decodes it and executes the
+    /// instruction one bus cycle at a time. Every cycle is a read or a write
+    /// through [`CpuBus`]; on the NES [`Bus`] each access clocks the PPU and
+    /// APU, so memory-mapped registers see each access (including the dummy
+    /// reads and writes the 6502 makes) at the cycle it happens on.
+    ///
+    /// A jammed CPU does not fetch anything and just burns one cycle per call,
+    /// so the rest of the system keeps running.
     ///
     /// # Returns
     /// The number of cycles consumed by this instruction
-    pub fn step(&mut self, bus: &mut Bus) -> u8 {
+    pub fn step<B: CpuBus>(&mut self, bus: &mut B) -> u8 {
+        let mut bus = CycleCounter::new(bus);
+
+        if self.jammed {
+            bus.tick();
+        } else {
+            self.execute_next(&mut bus);
+        }
+
+        let cycles = bus.cycles() as u8;
+        self.cycles = self.cycles.wrapping_add(cycles as u64);
+
+        cycles
+    }
+
+    /// Fetch, decode and execute the instruction at PC
+    fn execute_next<B: CpuBus>(&mut self, bus: &mut B) {
         // Fetch opcode from current PC
         let opcode = bus.read(self.pc);
         let opcode_info = &OPCODE_TABLE[opcode as usize];
@@ -23,8 +46,15 @@ impl Cpu {
 
         // Calculate effective address based on addressing mode
         let addr_result = match opcode_info.mode {
-            AddressingMode::Implied => self.addr_implied(),
-            AddressingMode::Accumulator => self.addr_accumulator(),
+            AddressingMode::Implied => {
+                // The second cycle always reads the byte after the opcode
+                bus.read(self.pc);
+                self.addr_implied()
+            }
+            AddressingMode::Accumulator => {
+                bus.read(self.pc);
+                self.addr_accumulator()
+            }
             AddressingMode::Immediate => self.addr_immediate(bus),
             AddressingMode::ZeroPage => self.addr_zero_page(bus),
             AddressingMode::ZeroPageX => self.addr_zero_page_x(bus),
@@ -38,34 +68,37 @@ impl Cpu {
             AddressingMode::IndirectIndexed => self.addr_indirect_indexed(bus),
         };
 
-        // Execute the instruction (may return extra cycles for branches)
-        let extra_cycles = self.execute_instruction(opcode, &addr_result, bus);
-
-        // Calculate actual cycles (base + page crossing penalty + branch cycles)
-        let mut cycles = opcode_info.cycles;
-        if opcode_info.page_cycle
-            && addr_result.page_crossed
-            && opcode_info.mode != AddressingMode::Relative
+        // Indexed modes first read from the address before the carry into the
+        // high byte is applied. Reads (the opcodes with a page-cross penalty)
+        // only do this when a page is crossed; writes and read-modify-write
+        // instructions always do.
+        if matches!(
+            opcode_info.mode,
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY | AddressingMode::IndirectIndexed
+        ) && (addr_result.page_crossed || !opcode_info.page_cycle)
         {
-            cycles += 1;
+            bus.read(addr_result.uncorrected_address());
         }
-        cycles += extra_cycles;
 
-        // Update total cycle counter
-        self.cycles = self.cycles.wrapping_add(cycles as u64);
+        // Execute the instruction
+        let interrupt_disable = self.get_interrupt_disable();
+        self.execute_instruction(opcode, &addr_result, bus);
 
-        cycles
+        // The IRQ poll happens before CLI, SEI and PLP update the I flag
+        self.irq_inhibit = match opcode {
+            0x58 | 0x78 | 0x28 => interrupt_disable,
+            _ => self.get_interrupt_disable(),
+        };
     }
 
     /// Execute a specific instruction based on its opcode
-    /// Returns the number of extra cycles consumed (used by branch instructions)
     #[allow(clippy::too_many_lines)]
-    fn execute_instruction(
+    fn execute_instruction<B: CpuBus>(
         &mut self,
         opcode: u8,
         addr_result: &crate::cpu::addressing::AddressingResult,
-        bus: &mut Bus,
-    ) -> u8 {
+        bus: &mut B,
+    ) {
         match opcode {
             // Load/Store instructions
             0xA9 | 0xA5 | 0xB5 | 0xAD | 0xBD | 0xB9 | 0xA1 | 0xB1 => self.lda(bus, addr_result),
@@ -106,26 +139,56 @@ impl Cpu {
             0xE0 | 0xE4 | 0xEC => self.cpx(bus, addr_result),
             0xC0 | 0xC4 | 0xCC => self.cpy(bus, addr_result),
 
-            // Branch instructions (return extra cycles)
-            0x90 => return self.bcc(bus, addr_result),
-            0xB0 => return self.bcs(bus, addr_result),
-            0xF0 => return self.beq(bus, addr_result),
-            0x30 => return self.bmi(bus, addr_result),
-            0xD0 => return self.bne(bus, addr_result),
-            0x10 => return self.bpl(bus, addr_result),
-            0x50 => return self.bvc(bus, addr_result),
-            0x70 => return self.bvs(bus, addr_result),
+            // Branch instructions
+            0x90 => {
+                self.bcc(bus, addr_result);
+            }
+            0xB0 => {
+                self.bcs(bus, addr_result);
+            }
+            0xF0 => {
+                self.beq(bus, addr_result);
+            }
+            0x30 => {
+                self.bmi(bus, addr_result);
+            }
+            0xD0 => {
+                self.bne(bus, addr_result);
+            }
+            0x10 => {
+                self.bpl(bus, addr_result);
+            }
+            0x50 => {
+                self.bvc(bus, addr_result);
+            }
+            0x70 => {
+                self.bvs(bus, addr_result);
+            }
 
             // Jump/Subroutine instructions
-            0x4C | 0x6C => return self.jmp(bus, addr_result),
-            0x20 => return self.jsr(bus, addr_result),
-            0x60 => return self.rts(bus, addr_result),
+            0x4C | 0x6C => {
+                self.jmp(bus, addr_result);
+            }
+            0x20 => {
+                self.jsr(bus, addr_result);
+            }
+            0x60 => {
+                self.rts(bus, addr_result);
+            }
 
             // Stack instructions
-            0x48 => return self.pha(bus, addr_result),
-            0x68 => return self.pla(bus, addr_result),
-            0x08 => return self.php(bus, addr_result),
-            0x28 => return self.plp(bus, addr_result),
+            0x48 => {
+                self.pha(bus, addr_result);
+            }
+            0x68 => {
+                self.pla(bus, addr_result);
+            }
+            0x08 => {
+                self.php(bus, addr_result);
+            }
+            0x28 => {
+                self.plp(bus, addr_result);
+            }
             0x9A => self.txs(),
             0xBA => self.tsx(),
 
@@ -136,26 +199,81 @@ impl Cpu {
             0x98 => self.tya(),
 
             // Flag instructions
-            0x18 => return self.clc(bus, addr_result),
-            0xD8 => return self.cld(bus, addr_result),
-            0x58 => return self.cli(bus, addr_result),
-            0xB8 => return self.clv(bus, addr_result),
-            0x38 => return self.sec(bus, addr_result),
-            0xF8 => return self.sed(bus, addr_result),
-            0x78 => return self.sei(bus, addr_result),
+            0x18 => {
+                self.clc(bus, addr_result);
+            }
+            0xD8 => {
+                self.cld(bus, addr_result);
+            }
+            0x58 => {
+                self.cli(bus, addr_result);
+            }
+            0xB8 => {
+                self.clv(bus, addr_result);
+            }
+            0x38 => {
+                self.sec(bus, addr_result);
+            }
+            0xF8 => {
+                self.sed(bus, addr_result);
+            }
+            0x78 => {
+                self.sei(bus, addr_result);
+            }
 
             // Miscellaneous instructions
-            0x00 => return self.brk(bus, addr_result),
-            0x40 => return self.rti(bus, addr_result),
-            0xEA => return self.nop(bus, addr_result),
+            0x00 => {
+                self.brk(bus, addr_result);
+            }
+            0x40 => {
+                self.rti(bus, addr_result);
+            }
+            0xEA => {
+                self.nop(bus, addr_result);
+            }
+
+            // Unofficial combined load/store instructions
+            0xA7 | 0xB7 | 0xAF | 0xBF | 0xA3 | 0xB3 => self.lax(bus, addr_result),
+            0x87 | 0x97 | 0x8F | 0x83 => self.sax(bus, addr_result),
+            0xBB => self.las(bus, addr_result),
 
-            // Unofficial/unimplemented opcodes - treat as NOP for now
-            _ => {
-                // For unimplemented opcodes, just do nothing
-                // In a real emulator, you might want to log a warning here
+            // Unofficial read-modify-write instructions
+            0x07 | 0x17 | 0x0F | 0x1F | 0x1B | 0x03 | 0x13 => self.slo(bus, addr_result),
+            0x27 | 0x37 | 0x2F | 0x3F | 0x3B | 0x23 | 0x33 => self.rla(bus, addr_result),
+            0x47 | 0x57 | 0x4F | 0x5F | 0x5B | 0x43 | 0x53 => self.sre(bus, addr_result),
+            0x67 | 0x77 | 0x6F | 0x7F | 0x7B | 0x63 | 0x73 => self.rra(bus, addr_result),
+            0xC7 | 0xD7 | 0xCF | 0xDF | 0xDB | 0xC3 | 0xD3 => self.dcp(bus, addr_result),
+            0xE7 | 0xF7 | 0xEF | 0xFF | 0xFB | 0xE3 | 0xF3 => self.isb(bus, addr_result),
+
+            // Unofficial immediate instructions
+            0x0B | 0x2B => self.anc(bus, addr_result),
+            0x4B => self.alr(bus, addr_result),
+            0x6B => self.arr(bus, addr_result),
+            0xCB => self.axs(bus, addr_result),
+            0xEB => self.sbc(bus, addr_result),
+            0x8B => self.xaa(bus, addr_result),
+            0xAB => self.lxa(bus, addr_result),
+
+            // Unofficial unstable stores
+            0x9C => self.shy(bus, addr_result),
+            0x9E => self.shx(bus, addr_result),
+            0x93 | 0x9F => self.ahx(bus, addr_result),
+            0x9B => self.tas(bus, addr_result),
+
+            // Unofficial NOPs that read their operand
+            0x80 | 0x82 | 0x89 | 0xC2 | 0xE2 | 0x04 | 0x44 | 0x64 | 0x14 | 0x34 | 0x54 | 0x74
+            | 0xD4 | 0xF4 | 0x0C | 0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => {
+                self.nop_read(bus, addr_result)
             }
+
+            // Unofficial processor halt
+            0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2 | 0xF2 => {
+                self.kil(bus, addr_result)
+            }
+
+            // Unofficial single-byte NOPs
+            _ => {}
         }
-        0 // No extra cycles for non-branch instructions
     }
 
     /// Generate a trace log line in Nestest format
@@ -189,13 +307,24 @@ impl Cpu {
         };
 
         // Disassemble the instruction with operand
+        // Unofficial opcodes are marked with a '*' in the column before the mnemonic
         let disassembly = self.disassemble_instruction(pc, bus, opcode_info, byte2, byte3);
+        let marker = if opcode_info.unofficial { '*' } else { ' ' };
 
         // Format the trace line (pad disassembly to 32 characters from start)
         // The format is: "XXXX  HH HH HH  " (16 chars) + disassembly (padded to 48 chars total)
         format!(
-            "{:04X}  {}  {:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
-            pc, hex_bytes, disassembly, self.a, self.x, self.y, self.status, self.sp, self.cycles
+            "{:04X}  {} {}{:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
+            pc,
+            hex_bytes,
+            marker,
+            disassembly,
+            self.a,
+            self.x,
+            self.y,
+            self.status,
+            self.sp,
+            self.cycles
         )
     }
 
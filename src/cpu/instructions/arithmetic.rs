@@ -1,8 +1,8 @@
 // Arithmetic instructions for 6502 CPU
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 impl Cpu {
     // ========================================
@@ -26,7 +26,7 @@ impl Cpu {
     /// * `bus` - The memory bus to read from
     /// * `addr_result` - The addressing result containing the memory address or immediate value
     #[inline]
-    pub fn adc(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn adc<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         let carry = if self.get_carry() { 1 } else { 0 };
 
@@ -70,7 +70,7 @@ impl Cpu {
     /// * `bus` - The memory bus to read from
     /// * `addr_result` - The addressing result containing the memory address or immediate value
     #[inline]
-    pub fn sbc(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn sbc<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
 
         // SBC is equivalent to ADC with the one's complement of the value
@@ -112,10 +112,11 @@ impl Cpu {
     /// * `bus` - The memory bus to read from and write to
     /// * `addr_result` - The addressing result containing the memory address
     #[inline]
-    pub fn inc(&self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn inc<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = bus.read(addr_result.address);
         let result = value.wrapping_add(1);
-        bus.write(addr_result.address, result);
+        self.write_modified(bus, addr_result.address, value, result);
+        self.update_zero_and_negative_flags(result);
     }
 
     /// Helper method to update flags for INC instruction
@@ -136,10 +137,11 @@ impl Cpu {
     /// * `bus` - The memory bus to read from and write to
     /// * `addr_result` - The addressing result containing the memory address
     #[inline]
-    pub fn dec(&self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn dec<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = bus.read(addr_result.address);
         let result = value.wrapping_sub(1);
-        bus.write(addr_result.address, result);
+        self.write_modified(bus, addr_result.address, value, result);
+        self.update_zero_and_negative_flags(result);
     }
 
     /// Helper method to update flags for DEC instruction
@@ -576,6 +578,23 @@ mod tests {
         assert!(!cpu.get_negative(), "Negative flag should be clear");
     }
 
+    #[test]
+    fn test_inc_dec_update_flags_without_helper() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        let addr_result = AddressingResult::new(0x1234);
+
+        bus.write(0x1234, 0x7F);
+        cpu.inc(&mut bus, &addr_result);
+        assert!(cpu.get_negative(), "INC should set N by itself");
+        assert!(!cpu.get_zero(), "INC should clear Z by itself");
+
+        bus.write(0x1234, 0x01);
+        cpu.dec(&mut bus, &addr_result);
+        assert!(cpu.get_zero(), "DEC should set Z by itself");
+        assert!(!cpu.get_negative(), "DEC should clear N by itself");
+    }
+
     // ========================================
     // Arithmetic Instruction Tests - INX
     // ========================================
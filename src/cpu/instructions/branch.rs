@@ -2,9 +2,9 @@
 // These instructions perform conditional branches based on processor status flags.
 // All branch instructions use relative addressing mode and do not modify any flags.
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 impl Cpu {
     // ========================================
@@ -40,8 +40,8 @@ impl Cpu {
     /// CMP #$10    ; Compare A with $10
     /// BCC label   ; Branch if A < $10 (carry clear)
     /// ```
-    pub fn bcc(&mut self, _bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
-        self.branch(!self.get_carry(), addr_result)
+    pub fn bcc<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) -> u8 {
+        self.branch(bus, !self.get_carry(), addr_result)
     }
 
     /// BCS - Branch if Carry Set
@@ -73,8 +73,8 @@ impl Cpu {
     /// CMP #$10    ; Compare A with $10
     /// BCS label   ; Branch if A >= $10 (carry set)
     /// ```
-    pub fn bcs(&mut self, _bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
-        self.branch(self.get_carry(), addr_result)
+    pub fn bcs<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) -> u8 {
+        self.branch(bus, self.get_carry(), addr_result)
     }
 
     /// BEQ - Branch if Equal (Zero Set)
@@ -106,8 +106,8 @@ impl Cpu {
     /// LDA counter ; Load counter value
     /// BEQ done    ; Branch if counter is zero
     /// ```
-    pub fn beq(&mut self, _bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
-        self.branch(self.get_zero(), addr_result)
+    pub fn beq<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) -> u8 {
+        self.branch(bus, self.get_zero(), addr_result)
     }
 
     /// BNE - Branch if Not Equal (Zero Clear)
@@ -140,8 +140,8 @@ impl Cpu {
     ///     DEX         ; Decrement X
     ///     BNE loop    ; Continue loop while X != 0
     /// ```
-    pub fn bne(&mut self, _bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
-        self.branch(!self.get_zero(), addr_result)
+    pub fn bne<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) -> u8 {
+        self.branch(bus, !self.get_zero(), addr_result)
     }
 
     /// BMI - Branch if Minus (Negative Set)
@@ -173,8 +173,8 @@ impl Cpu {
     /// LDA value   ; Load value
     /// BMI negative ; Branch if bit 7 is set (negative)
     /// ```
-    pub fn bmi(&mut self, _bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
-        self.branch(self.get_negative(), addr_result)
+    pub fn bmi<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) -> u8 {
+        self.branch(bus, self.get_negative(), addr_result)
     }
 
     /// BPL - Branch if Plus (Negative Clear)
@@ -206,8 +206,8 @@ impl Cpu {
     /// LDA value   ; Load value
     /// BPL positive ; Branch if bit 7 is clear (positive)
     /// ```
-    pub fn bpl(&mut self, _bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
-        self.branch(!self.get_negative(), addr_result)
+    pub fn bpl<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) -> u8 {
+        self.branch(bus, !self.get_negative(), addr_result)
     }
 
     /// BVC - Branch if Overflow Clear
@@ -239,8 +239,8 @@ impl Cpu {
     /// ADC #$10    ; Add with carry
     /// BVC no_overflow ; Branch if no signed overflow
     /// ```
-    pub fn bvc(&mut self, _bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
-        self.branch(!self.get_overflow(), addr_result)
+    pub fn bvc<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) -> u8 {
+        self.branch(bus, !self.get_overflow(), addr_result)
     }
 
     /// BVS - Branch if Overflow Set
@@ -272,8 +272,8 @@ impl Cpu {
     /// ADC #$70    ; Add with carry
     /// BVS overflow ; Branch if signed overflow occurred
     /// ```
-    pub fn bvs(&mut self, _bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
-        self.branch(self.get_overflow(), addr_result)
+    pub fn bvs<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) -> u8 {
+        self.branch(bus, self.get_overflow(), addr_result)
     }
 
     // ========================================
@@ -291,24 +291,40 @@ impl Cpu {
     /// - +1 cycle if branch is taken (returned as 1)
     /// - +1 cycle if branch crosses page boundary (returned as 2 total)
     ///
+    /// The extra cycles are spent on dummy reads: the byte after the branch
+    /// while the offset is added, then the target address before its high byte
+    /// is fixed when a page is crossed.
+    ///
     /// # Arguments
+    /// * `bus` - The memory bus for the dummy reads
     /// * `condition` - Whether to take the branch
     /// * `addr_result` - The addressing result containing the branch target and page cross info
     ///
     /// # Returns
     /// Additional cycles: 0 if not taken, 1 if taken, 2 if taken and crossed page
     #[inline]
-    fn branch(&mut self, condition: bool, addr_result: &AddressingResult) -> u8 {
+    fn branch<B: CpuBus>(
+        &mut self,
+        bus: &mut B,
+        condition: bool,
+        addr_result: &AddressingResult,
+    ) -> u8 {
         if condition {
-            // Branch is taken - update PC to target address
-            self.pc = addr_result.address;
+            // Branch is taken - the next opcode is read while the offset is added
+            bus.read(self.pc);
+            let target = addr_result.address;
 
             // Add 1 cycle for taken branch, plus 1 more if page boundary crossed
-            if addr_result.page_crossed {
+            let extra_cycles = if addr_result.page_crossed {
+                bus.read((self.pc & 0xFF00) | (target & 0x00FF));
                 2
             } else {
                 1
-            }
+            };
+
+            // Update PC to target address
+            self.pc = target;
+            extra_cycles
         } else {
             // Branch not taken - no additional cycles
             0
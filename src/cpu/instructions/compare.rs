@@ -2,9 +2,9 @@
 // These instructions perform subtraction without storing the result,
 // only updating the processor status flags.
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 impl Cpu {
     // ========================================
@@ -36,7 +36,7 @@ impl Cpu {
     /// CMP: A - M = 0x50 - 0x30 = 0x20
     /// Result: C=1 (A >= M), Z=0 (A != M), N=0 (bit 7 is 0)
     /// ```
-    pub fn cmp(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn cmp<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         self.compare(self.a, value);
     }
@@ -66,7 +66,7 @@ impl Cpu {
     /// CPX: X - M = 0x30 - 0x50 = 0xE0 (wraps around)
     /// Result: C=0 (X < M), Z=0 (X != M), N=1 (bit 7 is 1)
     /// ```
-    pub fn cpx(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn cpx<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         self.compare(self.x, value);
     }
@@ -96,7 +96,7 @@ impl Cpu {
     /// CPY: Y - M = 0x50 - 0x50 = 0x00
     /// Result: C=1 (Y >= M), Z=1 (Y == M), N=0 (bit 7 is 0)
     /// ```
-    pub fn cpy(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn cpy<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         self.compare(self.y, value);
     }
@@ -1,10 +1,10 @@
 // Flag manipulation instructions for 6502 CPU
 // These instructions directly set or clear specific processor status flags.
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::flags;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 impl Cpu {
     // ========================================
@@ -38,7 +38,7 @@ impl Cpu {
     /// # Common Usage
     /// Used before ADC (Add with Carry) operations to ensure a clean addition
     /// without any previous carry. Also used for multi-byte arithmetic.
-    pub fn clc(&mut self, _bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn clc<B: CpuBus>(&mut self, _bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         self.clear_flag(flags::CARRY);
         0
     }
@@ -70,7 +70,7 @@ impl Cpu {
     /// # Common Usage
     /// Used before SBC (Subtract with Carry) operations. In the 6502, SBC works
     /// with an inverted borrow, so SEC is used to indicate "no borrow".
-    pub fn sec(&mut self, _bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn sec<B: CpuBus>(&mut self, _bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         self.set_flag(flags::CARRY);
         0
     }
@@ -106,7 +106,7 @@ impl Cpu {
     /// # Important Note
     /// This instruction only affects IRQ interrupts. NMI (Non-Maskable Interrupt)
     /// cannot be disabled and will always trigger regardless of the I flag state.
-    pub fn cli(&mut self, _bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn cli<B: CpuBus>(&mut self, _bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         self.clear_flag(flags::INTERRUPT_DISABLE);
         0
     }
@@ -140,7 +140,7 @@ impl Cpu {
     /// This instruction only affects IRQ interrupts. NMI (Non-Maskable Interrupt)
     /// cannot be disabled and will always trigger regardless of the I flag state.
     /// The I flag is automatically set when an interrupt occurs.
-    pub fn sei(&mut self, _bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn sei<B: CpuBus>(&mut self, _bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         self.set_flag(flags::INTERRUPT_DISABLE);
         0
     }
@@ -178,7 +178,7 @@ impl Cpu {
     /// (the 2A03/2A07 CPU). The flag can be set and cleared, but ADC and SBC
     /// always operate in binary mode. This instruction is included for compatibility
     /// with the standard 6502 instruction set.
-    pub fn cld(&mut self, _bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn cld<B: CpuBus>(&mut self, _bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         self.clear_flag(flags::DECIMAL);
         0
     }
@@ -214,7 +214,7 @@ impl Cpu {
     /// always operate in binary mode. This instruction is included for compatibility
     /// with the standard 6502 instruction set, but has no effect on calculations
     /// in the NES.
-    pub fn sed(&mut self, _bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn sed<B: CpuBus>(&mut self, _bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         self.set_flag(flags::DECIMAL);
         0
     }
@@ -254,7 +254,7 @@ impl Cpu {
     /// flag is typically set automatically by ADC and SBC operations when a
     /// signed overflow occurs, or by the BIT instruction based on bit 6 of the
     /// tested value.
-    pub fn clv(&mut self, _bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn clv<B: CpuBus>(&mut self, _bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         self.clear_flag(flags::OVERFLOW);
         0
     }
@@ -1,9 +1,9 @@
 // Jump and Subroutine instructions for 6502 CPU
 // These instructions perform unconditional jumps and subroutine calls/returns.
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 /// Stack base address (Stack lives at $0100-$01FF)
 const STACK_BASE: u16 = 0x0100;
@@ -22,7 +22,7 @@ impl Cpu {
     /// * `bus` - The memory bus for writing the value
     /// * `value` - The byte to push onto the stack
     #[inline]
-    pub(crate) fn stack_push(&mut self, bus: &mut Bus, value: u8) {
+    pub(crate) fn stack_push<B: CpuBus>(&mut self, bus: &mut B, value: u8) {
         let addr = STACK_BASE | (self.sp as u16);
         bus.write(addr, value);
         self.sp = self.sp.wrapping_sub(1);
@@ -39,12 +39,21 @@ impl Cpu {
     /// # Returns
     /// The byte pulled from the stack
     #[inline]
-    pub(crate) fn stack_pop(&mut self, bus: &mut Bus) -> u8 {
+    pub(crate) fn stack_pop<B: CpuBus>(&mut self, bus: &mut B) -> u8 {
         self.sp = self.sp.wrapping_add(1);
         let addr = STACK_BASE | (self.sp as u16);
         bus.read(addr)
     }
 
+    /// Read the current stack slot without pulling it
+    ///
+    /// Pulls, RTS, RTI and JSR spend a cycle reading the stack while the
+    /// stack pointer is being adjusted.
+    #[inline]
+    pub(crate) fn stack_dummy_read<B: CpuBus>(&self, bus: &mut B) {
+        bus.read(STACK_BASE | (self.sp as u16));
+    }
+
     /// Push a 16-bit value onto the stack (high byte first)
     ///
     /// Used by JSR to push the return address onto the stack.
@@ -54,7 +63,7 @@ impl Cpu {
     /// * `bus` - The memory bus for writing the value
     /// * `value` - The 16-bit value to push onto the stack
     #[inline]
-    pub(crate) fn stack_push_u16(&mut self, bus: &mut Bus, value: u16) {
+    pub(crate) fn stack_push_u16<B: CpuBus>(&mut self, bus: &mut B, value: u16) {
         let hi = (value >> 8) as u8;
         let lo = (value & 0xFF) as u8;
         self.stack_push(bus, hi);
@@ -72,7 +81,7 @@ impl Cpu {
     /// # Returns
     /// The 16-bit value pulled from the stack
     #[inline]
-    pub(crate) fn stack_pop_u16(&mut self, bus: &mut Bus) -> u16 {
+    pub(crate) fn stack_pop_u16<B: CpuBus>(&mut self, bus: &mut B) -> u16 {
         let lo = self.stack_pop(bus) as u16;
         let hi = self.stack_pop(bus) as u16;
         (hi << 8) | lo
@@ -115,7 +124,7 @@ impl Cpu {
     /// If the pointer is at a page boundary ($xxFF), the high byte
     /// wraps to $xx00 instead of $(xx+1)00. This bug is emulated
     /// in the addr_indirect addressing mode implementation.
-    pub fn jmp(&mut self, _bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
+    pub fn jmp<B: CpuBus>(&mut self, _bus: &mut B, addr_result: &AddressingResult) -> u8 {
         self.pc = addr_result.address;
         0
     }
@@ -156,10 +165,11 @@ impl Cpu {
     /// to the return address it pulls from the stack. This design allows
     /// the programmer to modify the return address on the stack to implement
     /// tricks like computed jumps or skipping bytes.
-    pub fn jsr(&mut self, bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
+    pub fn jsr<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) -> u8 {
         // Push return address - 1 (current PC - 1)
         // At this point, PC points to the next instruction after JSR
         let return_addr = self.pc.wrapping_sub(1);
+        self.stack_dummy_read(bus);
         self.stack_push_u16(bus, return_addr);
 
         // Jump to subroutine address
@@ -197,11 +207,14 @@ impl Cpu {
     /// # Implementation Note
     /// Since JSR pushes PC-1, RTS must add 1 to the pulled address to get
     /// the correct return location. This is a 6502 convention.
-    pub fn rts(&mut self, bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn rts<B: CpuBus>(&mut self, bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         // Pull return address from stack
+        self.stack_dummy_read(bus);
         let return_addr = self.stack_pop_u16(bus);
 
-        // Add 1 to get the actual return address (JSR pushes PC-1)
+        // Add 1 to get the actual return address (JSR pushes PC-1). The CPU
+        // reads the pulled address once while incrementing it.
+        bus.read(return_addr);
         self.pc = return_addr.wrapping_add(1);
         0
     }
@@ -1,8 +1,8 @@
 // Load and Store instructions for 6502 CPU
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 impl Cpu {
     // ========================================
@@ -22,7 +22,7 @@ impl Cpu {
     /// * `bus` - The memory bus to read from
     /// * `addr_result` - The addressing result containing the memory address or immediate value
     #[inline]
-    pub fn lda(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn lda<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         self.a = value;
         self.update_zero_and_negative_flags(value);
@@ -39,7 +39,7 @@ impl Cpu {
     /// * `bus` - The memory bus to read from
     /// * `addr_result` - The addressing result containing the memory address or immediate value
     #[inline]
-    pub fn ldx(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn ldx<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         self.x = value;
         self.update_zero_and_negative_flags(value);
@@ -56,7 +56,7 @@ impl Cpu {
     /// * `bus` - The memory bus to read from
     /// * `addr_result` - The addressing result containing the memory address or immediate value
     #[inline]
-    pub fn ldy(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn ldy<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         self.y = value;
         self.update_zero_and_negative_flags(value);
@@ -79,7 +79,7 @@ impl Cpu {
     /// * `bus` - The memory bus to write to
     /// * `addr_result` - The addressing result containing the memory address
     #[inline]
-    pub fn sta(&self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn sta<B: CpuBus>(&self, bus: &mut B, addr_result: &AddressingResult) {
         bus.write(addr_result.address, self.a);
     }
 
@@ -94,7 +94,7 @@ impl Cpu {
     /// * `bus` - The memory bus to write to
     /// * `addr_result` - The addressing result containing the memory address
     #[inline]
-    pub fn stx(&self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn stx<B: CpuBus>(&self, bus: &mut B, addr_result: &AddressingResult) {
         bus.write(addr_result.address, self.x);
     }
 
@@ -109,7 +109,7 @@ impl Cpu {
     /// * `bus` - The memory bus to write to
     /// * `addr_result` - The addressing result containing the memory address
     #[inline]
-    pub fn sty(&self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn sty<B: CpuBus>(&self, bus: &mut B, addr_result: &AddressingResult) {
         bus.write(addr_result.address, self.y);
     }
 }
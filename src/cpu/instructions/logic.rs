@@ -1,8 +1,8 @@
 // Logic and bit operation instructions for 6502 CPU
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 impl Cpu {
     // ========================================
@@ -29,7 +29,7 @@ impl Cpu {
     /// * `bus` - The memory bus to read from
     /// * `addr_result` - The addressing result containing the memory address or immediate value
     #[inline]
-    pub fn and(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn and<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         self.a &= value;
         self.update_zero_and_negative_flags(self.a);
@@ -55,7 +55,7 @@ impl Cpu {
     /// * `bus` - The memory bus to read from
     /// * `addr_result` - The addressing result containing the memory address or immediate value
     #[inline]
-    pub fn ora(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn ora<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         self.a |= value;
         self.update_zero_and_negative_flags(self.a);
@@ -82,7 +82,7 @@ impl Cpu {
     /// * `bus` - The memory bus to read from
     /// * `addr_result` - The addressing result containing the memory address or immediate value
     #[inline]
-    pub fn eor(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn eor<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
         self.a ^= value;
         self.update_zero_and_negative_flags(self.a);
@@ -120,7 +120,7 @@ impl Cpu {
     /// BIT instruction does not support immediate addressing mode.
     /// It always reads from memory, never from an immediate value.
     #[inline]
-    pub fn bit(&mut self, bus: &mut Bus, addr_result: &AddressingResult) {
+    pub fn bit<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
         let value = self.read_operand(bus, addr_result);
 
         // Test if A & M is zero
@@ -1,11 +1,11 @@
 // Miscellaneous instructions for 6502 CPU
 // These instructions include NOP, BRK (software interrupt), and RTI (return from interrupt).
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::flags;
 use crate::cpu::vectors;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 impl Cpu {
     // ========================================
@@ -33,7 +33,7 @@ impl Cpu {
     /// ```text
     /// NOP         ; Do nothing, wait 2 cycles
     /// ```
-    pub fn nop(&mut self, _bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn nop<B: CpuBus>(&mut self, _bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         // Do nothing - this is the entire purpose of NOP
         0
     }
@@ -44,7 +44,7 @@ impl Cpu {
     /// or for debugging purposes.
     ///
     /// Operation:
-    /// 1. PC is incremented by 2 (to skip the padding byte after BRK)
+    /// 1. PC is incremented past the padding byte after BRK
     /// 2. Push PC high byte to stack
     /// 3. Push PC low byte to stack
     /// 4. Push status flags with B flag set to stack
@@ -71,14 +71,16 @@ impl Cpu {
     ///
     /// # Implementation Note
     /// According to 6502 specification:
-    /// - PC is incremented by 2 before being pushed (BRK has a padding byte)
+    /// - The pushed PC is the BRK address + 2 (BRK has a padding byte). PC
+    ///   already points at the padding byte when this is called: it was read
+    ///   as the second cycle of the instruction.
     /// - The B flag is set to 1 in the pushed status register
     /// - The I flag is set after pushing status
     /// - The actual CPU status register's B flag is not modified
     /// - After BRK, execution continues from the address stored at $FFFE-$FFFF
-    pub fn brk(&mut self, bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
-        // Increment PC by 2 (BRK has a padding byte after it)
-        self.pc = self.pc.wrapping_add(2);
+    pub fn brk<B: CpuBus>(&mut self, bus: &mut B, _addr_result: &AddressingResult) -> u8 {
+        // Skip the padding byte after BRK
+        self.pc = self.pc.wrapping_add(1);
 
         // Push PC to stack (high byte first, then low byte)
         self.stack_push_u16(bus, self.pc);
@@ -141,8 +143,9 @@ impl Cpu {
     /// to distinguish between BRK (B=1) and hardware interrupts IRQ/NMI (B=0).
     ///
     /// The UNUSED flag (bit 5) is always set to 1 in the status register after pulling.
-    pub fn rti(&mut self, bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn rti<B: CpuBus>(&mut self, bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         // Pull status flags from stack
+        self.stack_dummy_read(bus);
         let status_from_stack = self.stack_pop(bus);
 
         // Save the current B flag before updating status
@@ -239,8 +242,8 @@ mod tests {
         bus.write(0xFFFE, (irq_handler_addr & 0xFF) as u8); // Low byte
         bus.write(0xFFFF, (irq_handler_addr >> 8) as u8); // High byte
 
-        // Set initial PC and status
-        cpu.pc = 0x1000;
+        // Set initial PC (just past the BRK opcode at $1000) and status
+        cpu.pc = 0x1001;
         cpu.set_carry(true);
         cpu.set_zero(false);
         let initial_sp = cpu.sp;
@@ -366,7 +369,8 @@ mod tests {
         bus.write(0xFFFE, 0x00);
         bus.write(0xFFFF, 0x90);
 
-        cpu.pc = 0x1234;
+        // BRK opcode at $1234
+        cpu.pc = 0x1235;
         let initial_sp = cpu.sp;
 
         // Execute BRK
@@ -615,7 +619,7 @@ mod tests {
 
         // Set initial state
         let original_pc = 0x1000;
-        cpu.pc = original_pc;
+        cpu.pc = original_pc + 1;
         cpu.set_carry(true);
         cpu.set_zero(false);
         cpu.set_overflow(true);
@@ -681,14 +685,14 @@ mod tests {
         let initial_sp = cpu.sp;
 
         // First interrupt (BRK)
-        cpu.pc = 0x1000;
+        cpu.pc = 0x1001;
         cpu.set_carry(true);
         cpu.brk(&mut bus, &AddressingResult::new(0));
 
         let sp_after_first_brk = cpu.sp;
 
         // Second interrupt (BRK) - nested
-        cpu.pc = 0x2000;
+        cpu.pc = 0x2001;
         cpu.set_zero(true);
         cpu.brk(&mut bus, &AddressingResult::new(0));
 
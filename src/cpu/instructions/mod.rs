@@ -12,9 +12,10 @@ pub mod miscellaneous;
 pub mod shift_rotate;
 pub mod stack;
 pub mod transfer;
+pub mod unofficial;
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
+use crate::cpu::CpuBus;
 
 impl crate::cpu::Cpu {
     // ========================================
@@ -26,11 +27,32 @@ impl crate::cpu::Cpu {
     /// If the addressing result contains an immediate value, returns that value.
     /// Otherwise, reads from the address specified in the addressing result.
     #[inline]
-    pub(crate) fn read_operand(&self, bus: &mut Bus, addr_result: &AddressingResult) -> u8 {
+    pub(crate) fn read_operand<B: CpuBus>(
+        &self,
+        bus: &mut B,
+        addr_result: &AddressingResult,
+    ) -> u8 {
         if let Some(value) = addr_result.value {
             value
         } else {
             bus.read(addr_result.address)
         }
     }
+
+    /// Helper function to write back the result of a read-modify-write instruction
+    ///
+    /// The 6502 writes the unmodified value back on the cycle after the read,
+    /// then writes the result on the next cycle. Registers that react to writes
+    /// (such as the MMC1 serial port) see both.
+    #[inline]
+    pub(crate) fn write_modified<B: CpuBus>(
+        &self,
+        bus: &mut B,
+        address: u16,
+        original: u8,
+        result: u8,
+    ) {
+        bus.write(address, original);
+        bus.write(address, result);
+    }
 }
@@ -1,8 +1,8 @@
 // Shift and rotate instructions for 6502 CPU
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 impl Cpu {
     // ========================================
@@ -34,7 +34,12 @@ impl Cpu {
     /// # Note
     /// This instruction can operate on either the accumulator or a memory location.
     /// When operating on the accumulator, is_accumulator should be true.
-    pub fn asl(&mut self, bus: &mut Bus, addr_result: &AddressingResult, is_accumulator: bool) {
+    pub fn asl<B: CpuBus>(
+        &mut self,
+        bus: &mut B,
+        addr_result: &AddressingResult,
+        is_accumulator: bool,
+    ) {
         let value = if is_accumulator {
             self.a
         } else {
@@ -54,7 +59,7 @@ impl Cpu {
         if is_accumulator {
             self.a = result;
         } else {
-            bus.write(addr_result.address, result);
+            self.write_modified(bus, addr_result.address, value, result);
         }
     }
 
@@ -83,7 +88,12 @@ impl Cpu {
     /// # Note
     /// This instruction can operate on either the accumulator or a memory location.
     /// After LSR, the Negative flag is always clear since bit 7 is always 0.
-    pub fn lsr(&mut self, bus: &mut Bus, addr_result: &AddressingResult, is_accumulator: bool) {
+    pub fn lsr<B: CpuBus>(
+        &mut self,
+        bus: &mut B,
+        addr_result: &AddressingResult,
+        is_accumulator: bool,
+    ) {
         let value = if is_accumulator {
             self.a
         } else {
@@ -103,7 +113,7 @@ impl Cpu {
         if is_accumulator {
             self.a = result;
         } else {
-            bus.write(addr_result.address, result);
+            self.write_modified(bus, addr_result.address, value, result);
         }
     }
 
@@ -137,7 +147,12 @@ impl Cpu {
     /// # Note
     /// ROL differs from ASL in that it rotates the old Carry flag into bit 0,
     /// while ASL always sets bit 0 to 0.
-    pub fn rol(&mut self, bus: &mut Bus, addr_result: &AddressingResult, is_accumulator: bool) {
+    pub fn rol<B: CpuBus>(
+        &mut self,
+        bus: &mut B,
+        addr_result: &AddressingResult,
+        is_accumulator: bool,
+    ) {
         let value = if is_accumulator {
             self.a
         } else {
@@ -160,7 +175,7 @@ impl Cpu {
         if is_accumulator {
             self.a = result;
         } else {
-            bus.write(addr_result.address, result);
+            self.write_modified(bus, addr_result.address, value, result);
         }
     }
 
@@ -190,7 +205,12 @@ impl Cpu {
     /// # Note
     /// ROR differs from LSR in that it rotates the old Carry flag into bit 7,
     /// while LSR always sets bit 7 to 0.
-    pub fn ror(&mut self, bus: &mut Bus, addr_result: &AddressingResult, is_accumulator: bool) {
+    pub fn ror<B: CpuBus>(
+        &mut self,
+        bus: &mut B,
+        addr_result: &AddressingResult,
+        is_accumulator: bool,
+    ) {
         let value = if is_accumulator {
             self.a
         } else {
@@ -213,7 +233,7 @@ impl Cpu {
         if is_accumulator {
             self.a = result;
         } else {
-            bus.write(addr_result.address, result);
+            self.write_modified(bus, addr_result.address, value, result);
         }
     }
 }
@@ -1,10 +1,10 @@
 // Stack operation instructions for 6502 CPU
 // These instructions handle pushing and pulling values to/from the stack.
 
-use crate::bus::Bus;
 use crate::cpu::addressing::AddressingResult;
 use crate::cpu::flags;
 use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
 
 impl Cpu {
     // ========================================
@@ -34,7 +34,7 @@ impl Cpu {
     /// LDA #$42    ; Load $42 into accumulator
     /// PHA         ; Push $42 onto stack
     /// ```
-    pub fn pha(&mut self, bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn pha<B: CpuBus>(&mut self, bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         self.stack_push(bus, self.a);
         0
     }
@@ -63,7 +63,8 @@ impl Cpu {
     /// ```text
     /// PLA         ; Pull value from stack into accumulator
     /// ```
-    pub fn pla(&mut self, bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn pla<B: CpuBus>(&mut self, bus: &mut B, _addr_result: &AddressingResult) -> u8 {
+        self.stack_dummy_read(bus);
         self.a = self.stack_pop(bus);
         self.update_zero_and_negative_flags(self.a);
         0
@@ -97,7 +98,7 @@ impl Cpu {
     /// The 6502 always pushes the status register with the B flag set to 1 and
     /// the UNUSED flag set to 1 (bits 4 and 5). This means the pushed value is
     /// (status | 0x30). The actual CPU status register is not modified.
-    pub fn php(&mut self, bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn php<B: CpuBus>(&mut self, bus: &mut B, _addr_result: &AddressingResult) -> u8 {
         // Push status with B flag and UNUSED flag set to 1
         // B flag (bit 4) and UNUSED flag (bit 5) must be set when pushed
         let status_to_push = self.status | flags::BREAK | flags::UNUSED;
@@ -145,7 +146,8 @@ impl Cpu {
     /// 2. The B flag (bit 4) from the stack is ignored (not copied to status register)
     ///
     /// This is important for RTI (Return from Interrupt) which behaves differently.
-    pub fn plp(&mut self, bus: &mut Bus, _addr_result: &AddressingResult) -> u8 {
+    pub fn plp<B: CpuBus>(&mut self, bus: &mut B, _addr_result: &AddressingResult) -> u8 {
+        self.stack_dummy_read(bus);
         let status_from_stack = self.stack_pop(bus);
 
         // Save the current B flag before updating status
@@ -0,0 +1,812 @@
+// Unofficial (undocumented) instructions for 6502 CPU
+//
+// The NMOS 6502 decodes every opcode byte, and the 105 bytes that are not
+// documented still do something: most combine two official operations that
+// share decoding logic (e.g. SLO = ASL + ORA). Several commercial games and
+// most CPU test ROMs (nestest, instr_test-v5) rely on them.
+//
+// The "unstable" stores (SHY, SHX, TAS, AHX) AND the stored value with the
+// high byte of the base address plus one, and use that value as the high byte
+// of the target address when the index crosses a page. XAA and LXA mix in an
+// analog "magic" constant; 0xEE matches the behaviour most test suites expect.
+
+use crate::cpu::addressing::AddressingResult;
+use crate::cpu::Cpu;
+use crate::cpu::CpuBus;
+
+/// Constant ORed into A by the unstable XAA and LXA instructions
+const MAGIC: u8 = 0xEE;
+
+impl Cpu {
+    // ========================================
+    // Combined Load/Store Instructions
+    // ========================================
+
+    /// LAX - Load Accumulator and X Register
+    ///
+    /// Loads a value from memory into both A and X.
+    ///
+    /// Formula: A = X = M
+    ///
+    /// Flags affected: Z, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to read from
+    /// * `addr_result` - The addressing result containing the memory address
+    #[inline]
+    pub fn lax<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = self.read_operand(bus, addr_result);
+        self.a = value;
+        self.x = value;
+        self.update_zero_and_negative_flags(value);
+    }
+
+    /// SAX - Store Accumulator AND X Register
+    ///
+    /// Stores A & X into memory.
+    ///
+    /// Flags affected: None
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to write to
+    /// * `addr_result` - The addressing result containing the memory address
+    #[inline]
+    pub fn sax<B: CpuBus>(&self, bus: &mut B, addr_result: &AddressingResult) {
+        bus.write(addr_result.address, self.a & self.x);
+    }
+
+    /// LAS - Load Accumulator, X and Stack Pointer
+    ///
+    /// ANDs a value from memory with the stack pointer and stores the result
+    /// in A, X and SP.
+    ///
+    /// Formula: A = X = SP = M & SP
+    ///
+    /// Flags affected: Z, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to read from
+    /// * `addr_result` - The addressing result containing the memory address
+    #[inline]
+    pub fn las<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = self.read_operand(bus, addr_result) & self.sp;
+        self.a = value;
+        self.x = value;
+        self.sp = value;
+        self.update_zero_and_negative_flags(value);
+    }
+
+    // ========================================
+    // Read-Modify-Write Combinations
+    // ========================================
+
+    /// SLO - Arithmetic Shift Left, then OR with Accumulator
+    ///
+    /// Formula: M = M << 1, A = A | M
+    ///
+    /// Flags affected: C, Z, N
+    /// - C: Set to bit 7 of the original value
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to read from and write to
+    /// * `addr_result` - The addressing result containing the memory address
+    pub fn slo<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = bus.read(addr_result.address);
+        let result = value << 1;
+        self.write_modified(bus, addr_result.address, value, result);
+
+        self.set_carry(value & 0x80 != 0);
+        self.ora(bus, &AddressingResult::immediate(result));
+    }
+
+    /// RLA - Rotate Left, then AND with Accumulator
+    ///
+    /// Formula: M = (M << 1) | C, A = A & M
+    ///
+    /// Flags affected: C, Z, N
+    /// - C: Set to bit 7 of the original value
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to read from and write to
+    /// * `addr_result` - The addressing result containing the memory address
+    pub fn rla<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = bus.read(addr_result.address);
+        let result = (value << 1) | self.get_carry() as u8;
+        self.write_modified(bus, addr_result.address, value, result);
+
+        self.set_carry(value & 0x80 != 0);
+        self.and(bus, &AddressingResult::immediate(result));
+    }
+
+    /// SRE - Logical Shift Right, then Exclusive OR with Accumulator
+    ///
+    /// Formula: M = M >> 1, A = A ^ M
+    ///
+    /// Flags affected: C, Z, N
+    /// - C: Set to bit 0 of the original value
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to read from and write to
+    /// * `addr_result` - The addressing result containing the memory address
+    pub fn sre<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = bus.read(addr_result.address);
+        let result = value >> 1;
+        self.write_modified(bus, addr_result.address, value, result);
+
+        self.set_carry(value & 0x01 != 0);
+        self.eor(bus, &AddressingResult::immediate(result));
+    }
+
+    /// RRA - Rotate Right, then Add with Carry
+    ///
+    /// The carry shifted out of the rotate is the carry into the addition.
+    ///
+    /// Formula: M = (M >> 1) | (C << 7), A = A + M + C
+    ///
+    /// Flags affected: C, Z, V, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to read from and write to
+    /// * `addr_result` - The addressing result containing the memory address
+    pub fn rra<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = bus.read(addr_result.address);
+        let result = (value >> 1) | ((self.get_carry() as u8) << 7);
+        self.write_modified(bus, addr_result.address, value, result);
+
+        self.set_carry(value & 0x01 != 0);
+        self.adc(bus, &AddressingResult::immediate(result));
+    }
+
+    /// DCP - Decrement Memory, then Compare with Accumulator
+    ///
+    /// Formula: M = M - 1, compare A with M
+    ///
+    /// Flags affected: C, Z, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to read from and write to
+    /// * `addr_result` - The addressing result containing the memory address
+    pub fn dcp<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = bus.read(addr_result.address);
+        let result = value.wrapping_sub(1);
+        self.write_modified(bus, addr_result.address, value, result);
+
+        self.cmp(bus, &AddressingResult::immediate(result));
+    }
+
+    /// ISB - Increment Memory, then Subtract with Carry
+    ///
+    /// Also known as ISC or INS.
+    ///
+    /// Formula: M = M + 1, A = A - M - (1 - C)
+    ///
+    /// Flags affected: C, Z, V, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to read from and write to
+    /// * `addr_result` - The addressing result containing the memory address
+    pub fn isb<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = bus.read(addr_result.address);
+        let result = value.wrapping_add(1);
+        self.write_modified(bus, addr_result.address, value, result);
+
+        self.sbc(bus, &AddressingResult::immediate(result));
+    }
+
+    // ========================================
+    // Immediate Combinations
+    // ========================================
+
+    /// ANC - AND, then copy N into C
+    ///
+    /// Formula: A = A & M, C = bit 7 of A
+    ///
+    /// Flags affected: C, Z, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus (unused for immediate mode)
+    /// * `addr_result` - The addressing result containing the immediate value
+    #[inline]
+    pub fn anc<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        self.and(bus, addr_result);
+        self.set_carry(self.a & 0x80 != 0);
+    }
+
+    /// ALR - AND, then Logical Shift Right
+    ///
+    /// Also known as ASR.
+    ///
+    /// Formula: A = (A & M) >> 1
+    ///
+    /// Flags affected: C, Z, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus (unused for immediate mode)
+    /// * `addr_result` - The addressing result containing the immediate value
+    #[inline]
+    pub fn alr<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = self.a & self.read_operand(bus, addr_result);
+        self.set_carry(value & 0x01 != 0);
+        self.a = value >> 1;
+        self.update_zero_and_negative_flags(self.a);
+    }
+
+    /// ARR - AND, then Rotate Right
+    ///
+    /// The flags come from the adder rather than the shifter: C is bit 6 of
+    /// the result and V is bit 6 XOR bit 5.
+    ///
+    /// Formula: A = ((A & M) >> 1) | (C << 7)
+    ///
+    /// Flags affected: C, Z, V, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus (unused for immediate mode)
+    /// * `addr_result` - The addressing result containing the immediate value
+    #[inline]
+    pub fn arr<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = self.a & self.read_operand(bus, addr_result);
+        self.a = (value >> 1) | ((self.get_carry() as u8) << 7);
+        self.update_zero_and_negative_flags(self.a);
+        self.set_carry(self.a & 0x40 != 0);
+        self.set_overflow(((self.a >> 6) ^ (self.a >> 5)) & 0x01 != 0);
+    }
+
+    /// AXS - (A AND X) minus immediate into X
+    ///
+    /// Also known as SBX. Works like CMP, but on A & X, and keeps the result.
+    /// The carry flag is ignored on input.
+    ///
+    /// Formula: X = (A & X) - M
+    ///
+    /// Flags affected: C, Z, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus (unused for immediate mode)
+    /// * `addr_result` - The addressing result containing the immediate value
+    #[inline]
+    pub fn axs<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = self.read_operand(bus, addr_result);
+        let masked = self.a & self.x;
+        self.set_carry(masked >= value);
+        self.x = masked.wrapping_sub(value);
+        self.update_zero_and_negative_flags(self.x);
+    }
+
+    /// XAA - Transfer X to A, then AND (unstable)
+    ///
+    /// Also known as ANE.
+    ///
+    /// Formula: A = (A | MAGIC) & X & M
+    ///
+    /// Flags affected: Z, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus (unused for immediate mode)
+    /// * `addr_result` - The addressing result containing the immediate value
+    #[inline]
+    pub fn xaa<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = self.read_operand(bus, addr_result);
+        self.a = (self.a | MAGIC) & self.x & value;
+        self.update_zero_and_negative_flags(self.a);
+    }
+
+    /// LXA - Load A and X from immediate (unstable)
+    ///
+    /// Also known as ATX or LAX immediate.
+    ///
+    /// Formula: A = X = (A | MAGIC) & M
+    ///
+    /// Flags affected: Z, N
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus (unused for immediate mode)
+    /// * `addr_result` - The addressing result containing the immediate value
+    #[inline]
+    pub fn lxa<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        let value = self.read_operand(bus, addr_result);
+        self.a = (self.a | MAGIC) & value;
+        self.x = self.a;
+        self.update_zero_and_negative_flags(self.a);
+    }
+
+    // ========================================
+    // Unstable Stores
+    // ========================================
+
+    /// SHY - Store Y AND (high byte of address + 1)
+    ///
+    /// Flags affected: None
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to write to
+    /// * `addr_result` - The addressing result containing the memory address
+    #[inline]
+    pub fn shy<B: CpuBus>(&self, bus: &mut B, addr_result: &AddressingResult) {
+        Self::store_high_and(bus, addr_result, self.y);
+    }
+
+    /// SHX - Store X AND (high byte of address + 1)
+    ///
+    /// Flags affected: None
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to write to
+    /// * `addr_result` - The addressing result containing the memory address
+    #[inline]
+    pub fn shx<B: CpuBus>(&self, bus: &mut B, addr_result: &AddressingResult) {
+        Self::store_high_and(bus, addr_result, self.x);
+    }
+
+    /// AHX - Store A AND X AND (high byte of address + 1)
+    ///
+    /// Also known as SHA.
+    ///
+    /// Flags affected: None
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to write to
+    /// * `addr_result` - The addressing result containing the memory address
+    #[inline]
+    pub fn ahx<B: CpuBus>(&self, bus: &mut B, addr_result: &AddressingResult) {
+        Self::store_high_and(bus, addr_result, self.a & self.x);
+    }
+
+    /// TAS - Transfer A AND X to SP, then store SP AND (high byte of address + 1)
+    ///
+    /// Also known as SHS.
+    ///
+    /// Flags affected: None
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to write to
+    /// * `addr_result` - The addressing result containing the memory address
+    #[inline]
+    pub fn tas<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        self.sp = self.a & self.x;
+        Self::store_high_and(bus, addr_result, self.sp);
+    }
+
+    /// Shared store logic of SHY, SHX, AHX and TAS
+    ///
+    /// Stores `value & (H + 1)`, where H is the high byte of the base address
+    /// before indexing. When indexing crossed a page, the stored value also
+    /// replaces the high byte of the target address.
+    fn store_high_and<B: CpuBus>(bus: &mut B, addr_result: &AddressingResult, value: u8) {
+        let address = addr_result.address;
+        let high = (address >> 8) as u8;
+        let base_high_plus_one = if addr_result.page_crossed {
+            high
+        } else {
+            high.wrapping_add(1)
+        };
+
+        let result = value & base_high_plus_one;
+        let target = if addr_result.page_crossed {
+            ((result as u16) << 8) | (address & 0x00FF)
+        } else {
+            address
+        };
+        bus.write(target, result);
+    }
+
+    // ========================================
+    // Unofficial NOPs
+    // ========================================
+
+    /// NOP with an operand (DOP/TOP)
+    ///
+    /// Reads the operand and discards it. The read still happens on hardware,
+    /// so it can have side effects on memory-mapped registers.
+    ///
+    /// Flags affected: None
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus to read from
+    /// * `addr_result` - The addressing result containing the memory address or immediate value
+    #[inline]
+    pub fn nop_read<B: CpuBus>(&mut self, bus: &mut B, addr_result: &AddressingResult) {
+        self.read_operand(bus, addr_result);
+    }
+
+    // ========================================
+    // Processor Halt
+    // ========================================
+
+    /// KIL - Halt the processor (JAM)
+    ///
+    /// The CPU locks up with the data bus stuck at $FF and never fetches
+    /// another instruction. Interrupts are ignored; only RESET recovers.
+    /// PC is left pointing at the KIL opcode.
+    ///
+    /// # Arguments
+    /// * `_bus` - Unused (implied addressing mode)
+    /// * `_addr_result` - Unused (implied addressing mode)
+    #[inline]
+    pub fn kil<B: CpuBus>(&mut self, _bus: &mut B, _addr_result: &AddressingResult) {
+        self.pc = self.pc.wrapping_sub(1);
+        self.jammed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Bus;
+
+    /// Run `program` at $0200 for one instruction
+    fn step(cpu: &mut Cpu, bus: &mut Bus, program: &[u8]) -> u8 {
+        for (i, &byte) in program.iter().enumerate() {
+            bus.write(0x0200 + i as u16, byte);
+        }
+        cpu.pc = 0x0200;
+        cpu.step(bus)
+    }
+
+    // ========================================
+    // Combined Load/Store Tests
+    // ========================================
+
+    #[test]
+    fn test_lax_loads_a_and_x() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        bus.write(0x0010, 0x80);
+
+        let cycles = step(&mut cpu, &mut bus, &[0xA7, 0x10]); // LAX $10
+
+        assert_eq!(cpu.a, 0x80);
+        assert_eq!(cpu.x, 0x80);
+        assert!(cpu.get_negative());
+        assert_eq!(cycles, 3);
+        assert_eq!(cpu.pc, 0x0202);
+    }
+
+    #[test]
+    fn test_lax_page_cross_penalty() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.y = 0x01;
+
+        let cycles = step(&mut cpu, &mut bus, &[0xBF, 0xFF, 0x00]); // LAX $00FF,Y
+        assert_eq!(cycles, 5);
+
+        let cycles = step(&mut cpu, &mut bus, &[0xBF, 0x00, 0x00]); // LAX $0000,Y
+        assert_eq!(cycles, 4);
+    }
+
+    #[test]
+    fn test_sax_stores_a_and_x() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0xF0;
+        cpu.x = 0x3C;
+        let status = cpu.status;
+
+        step(&mut cpu, &mut bus, &[0x87, 0x10]); // SAX $10
+
+        assert_eq!(bus.read(0x0010), 0x30);
+        assert_eq!(cpu.status, status, "SAX does not affect flags");
+    }
+
+    #[test]
+    fn test_las() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.sp = 0xF0;
+        bus.write(0x0310, 0x3F);
+
+        step(&mut cpu, &mut bus, &[0xBB, 0x10, 0x03]); // LAS $0310,Y
+
+        assert_eq!(cpu.a, 0x30);
+        assert_eq!(cpu.x, 0x30);
+        assert_eq!(cpu.sp, 0x30);
+    }
+
+    // ========================================
+    // Read-Modify-Write Tests
+    // ========================================
+
+    #[test]
+    fn test_slo() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0x01;
+        bus.write(0x0010, 0x81);
+
+        let cycles = step(&mut cpu, &mut bus, &[0x07, 0x10]); // SLO $10
+
+        assert_eq!(bus.read(0x0010), 0x02);
+        assert_eq!(cpu.a, 0x03);
+        assert!(cpu.get_carry());
+        assert_eq!(cycles, 5);
+    }
+
+    #[test]
+    fn test_rla() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0xFF;
+        cpu.set_carry(true);
+        bus.write(0x0010, 0x40);
+
+        step(&mut cpu, &mut bus, &[0x27, 0x10]); // RLA $10
+
+        assert_eq!(bus.read(0x0010), 0x81);
+        assert_eq!(cpu.a, 0x81);
+        assert!(!cpu.get_carry());
+        assert!(cpu.get_negative());
+    }
+
+    #[test]
+    fn test_sre() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0x01;
+        bus.write(0x0010, 0x03);
+
+        step(&mut cpu, &mut bus, &[0x47, 0x10]); // SRE $10
+
+        assert_eq!(bus.read(0x0010), 0x01);
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.get_carry());
+        assert!(cpu.get_zero());
+    }
+
+    #[test]
+    fn test_rra_uses_rotated_out_carry() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0x10;
+        cpu.set_carry(false);
+        bus.write(0x0010, 0x03);
+
+        step(&mut cpu, &mut bus, &[0x67, 0x10]); // RRA $10
+
+        // M = 0x01, carry out = 1, A = 0x10 + 0x01 + 1
+        assert_eq!(bus.read(0x0010), 0x01);
+        assert_eq!(cpu.a, 0x12);
+        assert!(!cpu.get_carry());
+    }
+
+    #[test]
+    fn test_dcp() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0x40;
+        bus.write(0x0010, 0x41);
+
+        step(&mut cpu, &mut bus, &[0xC7, 0x10]); // DCP $10
+
+        assert_eq!(bus.read(0x0010), 0x40);
+        assert_eq!(cpu.a, 0x40);
+        assert!(cpu.get_zero());
+        assert!(cpu.get_carry());
+    }
+
+    #[test]
+    fn test_isb() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0x10;
+        cpu.set_carry(true);
+        bus.write(0x0010, 0x04);
+
+        step(&mut cpu, &mut bus, &[0xE7, 0x10]); // ISB $10
+
+        assert_eq!(bus.read(0x0010), 0x05);
+        assert_eq!(cpu.a, 0x0B);
+        assert!(cpu.get_carry());
+    }
+
+    #[test]
+    fn test_rmw_indexed_has_no_page_penalty() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.x = 0x01;
+        cpu.y = 0x01;
+
+        assert_eq!(step(&mut cpu, &mut bus, &[0xDF, 0xFF, 0x00]), 7); // DCP $00FF,X
+        assert_eq!(step(&mut cpu, &mut bus, &[0xFB, 0xFF, 0x00]), 7); // ISB $00FF,Y
+        assert_eq!(step(&mut cpu, &mut bus, &[0x13, 0x10]), 8); // SLO ($10),Y
+    }
+
+    // ========================================
+    // Immediate Combination Tests
+    // ========================================
+
+    #[test]
+    fn test_anc_copies_negative_into_carry() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0xC0;
+
+        step(&mut cpu, &mut bus, &[0x0B, 0x80]); // ANC #$80
+
+        assert_eq!(cpu.a, 0x80);
+        assert!(cpu.get_carry());
+        assert!(cpu.get_negative());
+    }
+
+    #[test]
+    fn test_alr() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0xFF;
+
+        step(&mut cpu, &mut bus, &[0x4B, 0x03]); // ALR #$03
+
+        assert_eq!(cpu.a, 0x01);
+        assert!(cpu.get_carry());
+    }
+
+    #[test]
+    fn test_arr_flags() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0xFF;
+        cpu.set_carry(true);
+
+        step(&mut cpu, &mut bus, &[0x6B, 0xC0]); // ARR #$C0
+
+        // (0xC0 >> 1) | 0x80 = 0xE0: bit 6 set, bit 5 set
+        assert_eq!(cpu.a, 0xE0);
+        assert!(cpu.get_carry());
+        assert!(!cpu.get_overflow());
+        assert!(cpu.get_negative());
+
+        cpu.a = 0xFF;
+        cpu.set_carry(false);
+        step(&mut cpu, &mut bus, &[0x6B, 0x80]); // ARR #$80
+
+        // 0x80 >> 1 = 0x40: bit 6 set, bit 5 clear
+        assert_eq!(cpu.a, 0x40);
+        assert!(cpu.get_carry());
+        assert!(cpu.get_overflow());
+    }
+
+    #[test]
+    fn test_axs() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0x0F;
+        cpu.x = 0xFC;
+        cpu.set_carry(false);
+
+        step(&mut cpu, &mut bus, &[0xCB, 0x02]); // AXS #$02
+
+        assert_eq!(cpu.x, 0x0A);
+        assert_eq!(cpu.a, 0x0F);
+        assert!(cpu.get_carry());
+
+        step(&mut cpu, &mut bus, &[0xCB, 0x0B]); // AXS #$0B
+        assert_eq!(cpu.x, 0xFF);
+        assert!(!cpu.get_carry());
+        assert!(cpu.get_negative());
+    }
+
+    #[test]
+    fn test_unofficial_sbc_matches_official() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0x50;
+        cpu.set_carry(true);
+
+        step(&mut cpu, &mut bus, &[0xEB, 0x10]); // SBC #$10
+
+        assert_eq!(cpu.a, 0x40);
+        assert!(cpu.get_carry());
+    }
+
+    #[test]
+    fn test_xaa_and_lxa() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0x00;
+        cpu.x = 0x0F;
+
+        step(&mut cpu, &mut bus, &[0x8B, 0xFF]); // XAA #$FF
+        assert_eq!(cpu.a, 0x0E);
+
+        cpu.a = 0x01;
+        step(&mut cpu, &mut bus, &[0xAB, 0x33]); // LXA #$33
+        assert_eq!(cpu.a, 0x23);
+        assert_eq!(cpu.x, 0x23);
+    }
+
+    // ========================================
+    // Unstable Store Tests
+    // ========================================
+
+    #[test]
+    fn test_shy_without_page_cross() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.x = 0x01;
+        cpu.y = 0xFF;
+
+        let cycles = step(&mut cpu, &mut bus, &[0x9C, 0x10, 0x03]); // SHY $0310,X
+
+        assert_eq!(bus.read(0x0311), 0x04);
+        assert_eq!(cycles, 5);
+    }
+
+    #[test]
+    fn test_shx_page_cross_corrupts_high_byte() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.x = 0x05;
+        cpu.y = 0x02;
+
+        step(&mut cpu, &mut bus, &[0x9E, 0xFF, 0x02]); // SHX $02FF,Y
+
+        // Value = X & (0x02 + 1) = 0x01, stored at $0101 instead of $0301
+        assert_eq!(bus.read(0x0101), 0x01);
+        assert_eq!(bus.read(0x0301), 0x00);
+    }
+
+    #[test]
+    fn test_ahx_and_tas() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.a = 0xFF;
+        cpu.x = 0x0F;
+
+        step(&mut cpu, &mut bus, &[0x9F, 0x00, 0x03]); // AHX $0300,Y
+        assert_eq!(bus.read(0x0300), 0x04);
+
+        step(&mut cpu, &mut bus, &[0x9B, 0x10, 0x03]); // TAS $0310,Y
+        assert_eq!(cpu.sp, 0x0F);
+        assert_eq!(bus.read(0x0310), 0x04);
+    }
+
+    // ========================================
+    // Unofficial NOP Tests
+    // ========================================
+
+    #[test]
+    fn test_unofficial_nop_sizes_and_cycles() {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new();
+        cpu.x = 0x01;
+        let a = cpu.a;
+        let status = cpu.status;
+
+        assert_eq!(step(&mut cpu, &mut bus, &[0x1A]), 2);
+        assert_eq!(cpu.pc, 0x0201);
+        assert_eq!(step(&mut cpu, &mut bus, &[0x80, 0x12]), 2);
+        assert_eq!(cpu.pc, 0x0202);
+        assert_eq!(step(&mut cpu, &mut bus, &[0x04, 0x12]), 3);
+        assert_eq!(step(&mut cpu, &mut bus, &[0x14, 0x12]), 4);
+        assert_eq!(step(&mut cpu, &mut bus, &[0x0C, 0x00, 0x03]), 4);
+        assert_eq!(cpu.pc, 0x0203);
+        assert_eq!(step(&mut cpu, &mut bus, &[0x1C, 0x00, 0x03]), 4);
+        assert_eq!(step(&mut cpu, &mut bus, &[0x1C, 0xFF, 0x03]), 5);
+
+        assert_eq!(cpu.a, a);
+        assert_eq!(cpu.status, status);
+    }
+
+    // ========================================
+    // Processor Halt Tests
+    // ========================================
+
+    #[test]
+    fn test_kil_jams_cpu() {
+        for opcode in [
+            0x02, 0x12, 0x22, 0x32, 0x42, 0x52, 0x62, 0x72, 0x92, 0xB2, 0xD2, 0xF2,
+        ] {
+            let mut cpu = Cpu::new();
+            let mut bus = Bus::new();
+            step(&mut cpu, &mut bus, &[opcode, 0xEA]);
+            assert!(cpu.is_jammed(), "opcode {:02X}", opcode);
+            assert_eq!(cpu.pc, 0x0200);
+
+            // Further steps burn a cycle without executing anything
+            let cycles = cpu.cycles;
+            assert_eq!(cpu.step(&mut bus), 1);
+            assert_eq!(cpu.pc, 0x0200);
+            assert_eq!(cpu.cycles, cycles + 1);
+        }
+    }
+}
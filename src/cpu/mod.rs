@@ -3,10 +3,15 @@
 
 // Sub-modules
 pub mod addressing;
+pub mod bus;
 pub mod execute;
 pub mod instructions;
 pub mod opcodes;
 
+pub use bus::{CpuBus, FlatBus};
+
+use bus::CycleCounter;
+
 /// Processor Status Flags (P register)
 ///
 /// Bit layout:
@@ -66,6 +71,15 @@ pub struct Cpu {
 
     // Cycle counter
     pub cycles: u64, // Total number of cycles executed
+
+    // Interrupt polling
+    // Value of the I flag seen by the IRQ poll at the end of the last instruction.
+    // CLI, SEI and PLP change I after the poll, so their effect is delayed by one
+    // instruction.
+    pub(crate) irq_inhibit: bool,
+
+    // Set when a KIL opcode halts the processor. Only RESET recovers.
+    pub(crate) jammed: bool,
 }
 
 impl Cpu {
@@ -88,6 +102,8 @@ impl Cpu {
             pc: 0,
             status: 0,
             cycles: 0,
+            irq_inhibit: true,
+            jammed: false,
         };
 
         // Initialize status register with required flags
@@ -126,6 +142,8 @@ impl Cpu {
         self.status = 0;
         self.set_flag(flags::UNUSED);
         self.set_flag(flags::INTERRUPT_DISABLE);
+        self.irq_inhibit = true;
+        self.jammed = false;
 
         // Load PC from RESET vector ($FFFC-$FFFD)
         let lo = bus.read(vectors::RESET) as u16;
@@ -136,6 +154,17 @@ impl Cpu {
         self.cycles = 7;
     }
 
+    /// Check whether the CPU has been halted by a KIL opcode
+    ///
+    /// A jammed CPU stops fetching instructions and ignores NMI and IRQ.
+    /// Only a RESET brings it back.
+    ///
+    /// # Returns
+    /// true if the CPU is jammed
+    pub fn is_jammed(&self) -> bool {
+        self.jammed
+    }
+
     // ========================================
     // Status Flag Manipulation Methods
     // ========================================
@@ -295,22 +324,8 @@ impl Cpu {
     /// - The B flag is pushed as 0 (unlike BRK which pushes it as 1)
     /// - The I flag is set after the interrupt to prevent further IRQs
     /// - NMI cannot be disabled by the I flag
-    pub fn nmi(&mut self, bus: &mut crate::bus::Bus) {
-        // Push PC to stack (high byte first, then low byte)
-        self.stack_push_u16(bus, self.pc);
-
-        // Push status flags with B flag clear and UNUSED flag set
-        // NMI pushes status with B=0 (to distinguish from BRK which pushes B=1)
-        let status_to_push = (self.status & !flags::BREAK) | flags::UNUSED;
-        self.stack_push(bus, status_to_push);
-
-        // Set the Interrupt Disable flag
-        self.set_interrupt_disable(true);
-
-        // Load PC from NMI vector ($FFFA-$FFFB)
-        let lo = bus.read(vectors::NMI) as u16;
-        let hi = bus.read(vectors::NMI.wrapping_add(1)) as u16;
-        self.pc = (hi << 8) | lo;
+    pub fn nmi<B: CpuBus>(&mut self, bus: &mut B) {
+        self.enter_interrupt(bus, vectors::NMI);
     }
 
     /// Trigger an IRQ (Interrupt Request)
@@ -335,27 +350,72 @@ impl Cpu {
     /// - The B flag is pushed as 0 (same as NMI)
     /// - Shares the same vector as BRK ($FFFE-$FFFF)
     /// - The I flag is set after the interrupt to prevent nested IRQs
-    pub fn irq(&mut self, bus: &mut crate::bus::Bus) {
+    pub fn irq<B: CpuBus>(&mut self, bus: &mut B) {
         // Check if interrupts are disabled; if so, ignore this IRQ
         if self.get_interrupt_disable() {
             return;
         }
 
+        self.enter_interrupt(bus, vectors::IRQ);
+    }
+
+    /// Poll the IRQ line at an instruction boundary
+    ///
+    /// The 6502 samples the level-triggered IRQ line at the end of every
+    /// instruction and takes the interrupt if it is asserted and not masked.
+    /// The mask used is the I flag as seen by the poll: CLI, SEI and PLP only
+    /// change I after the poll, so an IRQ can still be taken right after SEI and
+    /// is taken one instruction late after CLI.
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus for stack operations and reading IRQ vector
+    /// * `irq_line` - Whether any device is asserting the IRQ line
+    ///
+    /// # Returns
+    /// true if the IRQ was taken
+    pub fn poll_irq<B: CpuBus>(&mut self, bus: &mut B, irq_line: bool) -> bool {
+        if !irq_line || self.irq_inhibit {
+            return false;
+        }
+
+        self.enter_interrupt(bus, vectors::IRQ);
+        true
+    }
+
+    /// Push PC and status, set I, and jump through an interrupt vector
+    ///
+    /// Shared by NMI and IRQ. The status is pushed with B clear and UNUSED set
+    /// (BRK pushes B set instead). Takes 7 cycles, which are added to the
+    /// cycle counter.
+    ///
+    /// # Arguments
+    /// * `bus` - The memory bus for stack operations and reading the vector
+    /// * `vector` - Address of the interrupt vector
+    fn enter_interrupt<B: CpuBus>(&mut self, bus: &mut B, vector: u16) {
+        let mut bus = CycleCounter::new(bus);
+        let bus = &mut bus;
+
+        // The opcode fetch and the following read happen but are discarded
+        bus.read(self.pc);
+        bus.read(self.pc);
+
         // Push PC to stack (high byte first, then low byte)
         self.stack_push_u16(bus, self.pc);
 
         // Push status flags with B flag clear and UNUSED flag set
-        // IRQ pushes status with B=0 (to distinguish from BRK which pushes B=1)
         let status_to_push = (self.status & !flags::BREAK) | flags::UNUSED;
         self.stack_push(bus, status_to_push);
 
         // Set the Interrupt Disable flag
         self.set_interrupt_disable(true);
+        self.irq_inhibit = true;
 
-        // Load PC from IRQ vector ($FFFE-$FFFF)
-        let lo = bus.read(vectors::IRQ) as u16;
-        let hi = bus.read(vectors::IRQ.wrapping_add(1)) as u16;
+        // Load PC from the vector
+        let lo = bus.read(vector) as u16;
+        let hi = bus.read(vector.wrapping_add(1)) as u16;
         self.pc = (hi << 8) | lo;
+
+        self.cycles = self.cycles.wrapping_add(bus.cycles());
     }
 }
 
@@ -1284,4 +1344,184 @@ mod tests {
         assert_eq!(vectors::RESET, 0xFFFC, "RESET vector at $FFFC");
         assert_eq!(vectors::IRQ, 0xFFFE, "IRQ vector at $FFFE");
     }
+
+    // ========================================
+    // Bus Cycle Tests
+    // ========================================
+
+    /// Branch opcodes, whose timing depends on the flags
+    const BRANCHES: [u8; 8] = [0x10, 0x30, 0x50, 0x70, 0x90, 0xB0, 0xD0, 0xF0];
+
+    /// KIL opcodes, which halt the CPU
+    const KILS: [u8; 12] = [
+        0x02, 0x12, 0x22, 0x32, 0x42, 0x52, 0x62, 0x72, 0x92, 0xB2, 0xD2, 0xF2,
+    ];
+
+    /// Execute `opcode` at $0200 with the operand bytes $80 $02 and `index` in X and Y
+    ///
+    /// The zero page pointer at $80 also points at $0280, so indexing by $FF
+    /// crosses a page in every indexed mode.
+    fn step_opcode(opcode: u8, index: u8) -> (u8, u64) {
+        let mut cpu = Cpu::new();
+        let mut bus = crate::bus::Bus::new();
+        bus.write(0x0200, opcode);
+        bus.write(0x0201, 0x80);
+        bus.write(0x0202, 0x02);
+        bus.write(0x0080, 0x80);
+        bus.write(0x0081, 0x02);
+        cpu.pc = 0x0200;
+        cpu.x = index;
+        cpu.y = index;
+
+        let cycles = cpu.step(&mut bus);
+        (cycles, bus.cpu_cycles())
+    }
+
+    #[test]
+    fn test_every_cycle_is_one_bus_access() {
+        use crate::cpu::addressing::AddressingMode;
+        use crate::cpu::opcodes::OPCODE_TABLE;
+
+        for info in OPCODE_TABLE.iter() {
+            if BRANCHES.contains(&info.opcode) || KILS.contains(&info.opcode) {
+                continue;
+            }
+
+            let (cycles, accesses) = step_opcode(info.opcode, 0x00);
+            assert_eq!(cycles, info.cycles, "{:02X} {}", info.opcode, info.mnemonic);
+            assert_eq!(
+                accesses, cycles as u64,
+                "{:02X} {}",
+                info.opcode, info.mnemonic
+            );
+
+            // Crossing a page costs a dummy read for the opcodes that pay for it
+            let indexed = matches!(
+                info.mode,
+                AddressingMode::AbsoluteX
+                    | AddressingMode::AbsoluteY
+                    | AddressingMode::IndirectIndexed
+            );
+            let expected = info.cycles + (indexed && info.page_cycle) as u8;
+            let (cycles, accesses) = step_opcode(info.opcode, 0xFF);
+            assert_eq!(
+                cycles, expected,
+                "{:02X} {} page cross",
+                info.opcode, info.mnemonic
+            );
+            assert_eq!(accesses, cycles as u64);
+        }
+    }
+
+    #[test]
+    fn test_branch_dummy_reads() {
+        let mut cpu = Cpu::new();
+        let mut bus = crate::bus::Bus::new();
+
+        // BNE not taken, taken, and taken across a page
+        for (offset, expected) in [(None, 2), (Some(0x02), 3), (Some(0x7F), 4)] {
+            bus.write(0x02F0, 0xD0);
+            bus.write(0x02F1, offset.unwrap_or(0));
+            cpu.pc = 0x02F0;
+            cpu.set_zero(offset.is_none());
+
+            let start = bus.cpu_cycles();
+            assert_eq!(cpu.step(&mut bus), expected);
+            assert_eq!(bus.cpu_cycles() - start, expected as u64);
+        }
+    }
+
+    #[test]
+    fn test_indexed_read_dummy_read_on_page_cross() {
+        let mut cpu = Cpu::new();
+        let mut bus = crate::bus::Bus::new();
+
+        // LDA $3FF2,X with X = $10 reads $3F02 (a mirror of PPUSTATUS) before
+        // the high byte is fixed, clearing the VBlank flag
+        bus.write(0x0200, 0xBD);
+        bus.write(0x0201, 0xF2);
+        bus.write(0x0202, 0x3F);
+        bus.ppu_mut().ppustatus |= 0x80;
+        cpu.pc = 0x0200;
+        cpu.x = 0x10;
+
+        assert_eq!(cpu.step(&mut bus), 5);
+        assert_eq!(bus.ppu().ppustatus & 0x80, 0);
+    }
+
+    #[test]
+    fn test_indexed_write_always_dummy_reads() {
+        let mut cpu = Cpu::new();
+        let mut bus = crate::bus::Bus::new();
+
+        // STA $3F02,X with X = 0 reads PPUSTATUS before writing
+        bus.write(0x0200, 0x9D);
+        bus.write(0x0201, 0x02);
+        bus.write(0x0202, 0x3F);
+        bus.ppu_mut().ppustatus |= 0x80;
+        cpu.pc = 0x0200;
+        cpu.x = 0x00;
+
+        assert_eq!(cpu.step(&mut bus), 5);
+        assert_eq!(bus.ppu().ppustatus & 0x80, 0);
+    }
+
+    #[test]
+    fn test_read_modify_write_writes_twice() {
+        use crate::cartridge::mappers::Mapper1;
+        use crate::cartridge::{Cartridge, Mapper, Mirroring};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        // MMC1 with $FF at $8000: the first write of INC is the unmodified
+        // value, which resets the shift register; the second write lands on
+        // the next cycle and is ignored
+        let cartridge = Cartridge {
+            prg_rom: vec![0xFF; 32 * 1024],
+            chr_rom: vec![0; 8 * 1024],
+            trainer: None,
+            mapper: 1,
+            mirroring: Mirroring::Horizontal,
+            has_battery: false,
+            ..Cartridge::new()
+        };
+        let mapper: Box<dyn Mapper> = Box::new(Mapper1::new(cartridge));
+        let mut bus = crate::bus::Bus::new();
+        bus.set_mapper(Rc::new(RefCell::new(mapper)));
+
+        let mut cpu = Cpu::new();
+        // LDA #$00; STA $8000; INC $8000; STA $8000 x4
+        let program = [
+            0xA9, 0x00, 0x8D, 0x00, 0x80, 0xEE, 0x00, 0x80, 0x8D, 0x00, 0x80, 0x8D, 0x00, 0x80,
+            0x8D, 0x00, 0x80, 0x8D, 0x00, 0x80,
+        ];
+        for (i, &byte) in program.iter().enumerate() {
+            bus.write(0x0200 + i as u16, byte);
+        }
+        cpu.pc = 0x0200;
+
+        assert_eq!(cpu.step(&mut bus), 2);
+        assert_eq!(cpu.step(&mut bus), 4);
+        assert_eq!(cpu.step(&mut bus), 6);
+
+        // Only the four writes after the reset count, so the control register
+        // is not written yet and still holds its power-on value
+        for _ in 0..4 {
+            cpu.step(&mut bus);
+        }
+        let mirroring = bus.mapper().unwrap().borrow().mirroring();
+        assert_eq!(mirroring, Mirroring::Horizontal);
+    }
+
+    #[test]
+    fn test_interrupt_takes_seven_bus_cycles() {
+        let mut cpu = Cpu::new();
+        let mut bus = crate::bus::Bus::new();
+        cpu.pc = 0x1234;
+        let cycles = cpu.cycles;
+
+        cpu.nmi(&mut bus);
+        assert_eq!(bus.cpu_cycles(), 7);
+        assert_eq!(cpu.cycles, cycles + 7);
+    }
 }
@@ -32,9 +32,11 @@ pub struct DisassembledInstruction {
 impl DisassembledInstruction {
     /// Format the instruction as assembly code
     ///
+    /// Unofficial opcodes are prefixed with `*`, as in the nestest log.
+    ///
     /// # Returns
     ///
-    /// A string like "LDA #$42" or "JMP $8000"
+    /// A string like "LDA #$42", "JMP $8000" or "*LAX $10"
     pub fn format_assembly(&self) -> String {
         let operand_str = match self.addressing_mode.as_str() {
             "Implied" | "Accumulator" => String::new(),
@@ -124,7 +126,17 @@ impl DisassembledInstruction {
             _ => String::new(),
         };
 
-        format!("{}{}", self.mnemonic, operand_str)
+        let marker = if self.is_unofficial() { "*" } else { "" };
+        format!("{}{}{}", marker, self.mnemonic, operand_str)
+    }
+
+    /// Check whether the opcode is unofficial (undocumented)
+    ///
+    /// # Returns
+    ///
+    /// True for the 105 opcodes outside the documented 6502 instruction set
+    pub fn is_unofficial(&self) -> bool {
+        OPCODE_TABLE[self.opcode as usize].unofficial
     }
 
     /// Format the instruction bytes as hex
@@ -378,13 +390,48 @@ mod tests {
     #[test]
     fn test_disassemble_illegal_opcode() {
         let mut bus = Bus::new();
-        bus.write(0x8000, 0x02); // ??? (illegal opcode)
+        bus.write(0x8000, 0x02); // KIL (illegal opcode)
 
         let instr = disassemble_instruction(0x8000, &mut bus);
 
         assert_eq!(instr.opcode, 0x02);
-        assert_eq!(instr.mnemonic, "???");
+        assert_eq!(instr.mnemonic, "KIL");
         assert_eq!(instr.length, 1);
+        assert!(instr.is_unofficial());
+    }
+
+    #[test]
+    fn test_disassemble_unofficial_opcodes() {
+        let mut bus = Bus::new();
+
+        bus.write(0x8000, 0xA7); // LAX $10
+        bus.write(0x8001, 0x10);
+        bus.write(0x8002, 0xDF); // DCP $1234,X
+        bus.write(0x8003, 0x34);
+        bus.write(0x8004, 0x12);
+        bus.write(0x8005, 0x0C); // NOP $0400
+        bus.write(0x8006, 0x00);
+        bus.write(0x8007, 0x04);
+        bus.write(0x8008, 0xEB); // SBC #$01
+        bus.write(0x8009, 0x01);
+        bus.write(0x800A, 0xEA); // NOP (official)
+
+        let instructions = disassemble_count(0x8000, 5, &mut bus);
+
+        assert_eq!(instructions[0].format_assembly(), "*LAX $10");
+        assert_eq!(instructions[1].format_assembly(), "*DCP $1234,X");
+        assert_eq!(instructions[2].format_assembly(), "*NOP $0400");
+        assert_eq!(instructions[3].format_assembly(), "*SBC #$01");
+        assert_eq!(instructions[4].format_assembly(), "NOP");
+        assert!(!instructions[4].is_unofficial());
+    }
+
+    #[test]
+    fn test_no_undefined_opcodes() {
+        for info in OPCODE_TABLE.iter() {
+            assert_ne!(info.mnemonic, "???", "opcode {:02X}", info.opcode);
+        }
+        assert_eq!(OPCODE_TABLE.iter().filter(|i| i.unofficial).count(), 105);
     }
 
     #[test]
@@ -252,8 +252,6 @@ impl MemoryViewer {
         start: usize,
         length: usize,
     ) -> String {
-        let mut output = String::new();
-
         let data = match region {
             MemoryRegion::PpuNametables => {
                 let mut data = Vec::new();
@@ -282,43 +280,7 @@ impl MemoryViewer {
             }
         };
 
-        let rows = data.len().div_ceil(self.bytes_per_row);
-
-        for row in 0..rows {
-            let addr = start + (row * self.bytes_per_row);
-            output.push_str(&format!("${:04X}:  ", addr));
-
-            // Hex bytes
-            for col in 0..self.bytes_per_row {
-                let offset = row * self.bytes_per_row + col;
-                if offset < data.len() {
-                    output.push_str(&format!("{:02X} ", data[offset]));
-                } else {
-                    output.push_str("   ");
-                }
-            }
-
-            output.push_str(" | ");
-
-            // ASCII representation
-            for col in 0..self.bytes_per_row {
-                let offset = row * self.bytes_per_row + col;
-                if offset < data.len() {
-                    let byte = data[offset];
-                    if (0x20..=0x7E).contains(&byte) {
-                        output.push(byte as char);
-                    } else {
-                        output.push('.');
-                    }
-                } else {
-                    output.push(' ');
-                }
-            }
-
-            output.push('\n');
-        }
-
-        output
+        self.format_bytes(&data, start)
     }
 
     /// Search for a byte pattern in CPU memory
@@ -402,6 +364,58 @@ impl MemoryViewer {
         (hi << 8) | lo
     }
 
+    /// Format a buffer as hex dump rows
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to format
+    /// * `start` - Offset shown for the first byte
+    ///
+    /// # Returns
+    ///
+    /// The hex dump rows, with ASCII representation
+    fn format_bytes(&self, data: &[u8], start: usize) -> String {
+        let mut output = String::new();
+
+        let rows = data.len().div_ceil(self.bytes_per_row);
+
+        for row in 0..rows {
+            let addr = start + (row * self.bytes_per_row);
+            output.push_str(&format!("${:04X}:  ", addr));
+
+            // Hex bytes
+            for col in 0..self.bytes_per_row {
+                let offset = row * self.bytes_per_row + col;
+                if offset < data.len() {
+                    output.push_str(&format!("{:02X} ", data[offset]));
+                } else {
+                    output.push_str("   ");
+                }
+            }
+
+            output.push_str(" | ");
+
+            // ASCII representation
+            for col in 0..self.bytes_per_row {
+                let offset = row * self.bytes_per_row + col;
+                if offset < data.len() {
+                    let byte = data[offset];
+                    if (0x20..=0x7E).contains(&byte) {
+                        output.push(byte as char);
+                    } else {
+                        output.push('.');
+                    }
+                } else {
+                    output.push(' ');
+                }
+            }
+
+            output.push('\n');
+        }
+
+        output
+    }
+
     /// Dump zero page memory ($0000-$00FF)
     ///
     /// # Arguments
@@ -461,6 +475,23 @@ impl MemoryViewer {
         output.push_str(&self.dump_ppu_memory(ppu, MemoryRegion::PpuOam, 0, 256));
         output
     }
+
+    /// Dump the cartridge's expansion audio RAM (e.g. Namco 163 wavetables)
+    ///
+    /// # Arguments
+    ///
+    /// * `bus` - Reference to the bus
+    ///
+    /// # Returns
+    ///
+    /// A formatted hex dump, or None if the cartridge has no audio RAM
+    pub fn dump_audio_ram(&self, bus: &Bus) -> Option<String> {
+        let mapper = bus.mapper()?.borrow();
+        let ram = mapper.audio_ram()?;
+        let mut output = format!("Audio RAM ({} bytes):\n", ram.len());
+        output.push_str(&self.format_bytes(ram, 0));
+        Some(output)
+    }
 }
 
 impl Default for MemoryViewer {
@@ -551,6 +582,38 @@ mod tests {
         assert!(dump.contains("01"));
     }
 
+    #[test]
+    fn test_dump_audio_ram() {
+        use crate::cartridge::{mappers::create_mapper, Cartridge, Mirroring};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut bus = Bus::new();
+        let viewer = MemoryViewer::new();
+
+        // No cartridge, no audio RAM
+        assert_eq!(viewer.dump_audio_ram(&bus), None);
+
+        // Namco 163 wavetable RAM, written through its data port
+        let cartridge = Cartridge {
+            prg_rom: vec![0; 32 * 1024],
+            chr_rom: vec![0; 8 * 1024],
+            trainer: None,
+            mapper: 19,
+            mirroring: Mirroring::Vertical,
+            has_battery: false,
+            ..Cartridge::new()
+        };
+        bus.set_mapper(Rc::new(RefCell::new(create_mapper(cartridge).unwrap())));
+        bus.write(0xF800, 0x90);
+        bus.write(0x4800, 0xAB);
+
+        let dump = viewer.dump_audio_ram(&bus).unwrap();
+        assert!(dump.starts_with("Audio RAM (128 bytes):"));
+        assert!(dump.contains("$0010:  AB "));
+        assert!(dump.contains("$0070:"));
+    }
+
     #[test]
     #[should_panic(expected = "bytes_per_row must be greater than 0")]
     fn test_bytes_per_row_zero_panics() {
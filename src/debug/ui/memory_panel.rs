@@ -14,6 +14,7 @@ enum MemoryViewerTab {
     Stack = 2,
     PpuVram = 3,
     Oam = 4,
+    AudioRam = 5,
 }
 
 impl From<usize> for MemoryViewerTab {
@@ -24,6 +25,7 @@ impl From<usize> for MemoryViewerTab {
             2 => MemoryViewerTab::Stack,
             3 => MemoryViewerTab::PpuVram,
             4 => MemoryViewerTab::Oam,
+            5 => MemoryViewerTab::AudioRam,
             _ => MemoryViewerTab::CpuMemory,
         }
     }
@@ -79,6 +81,12 @@ pub(super) fn show(
                 {
                     ui_state.memory_tab = MemoryViewerTab::Oam as usize;
                 }
+                if ui
+                    .selectable_label(current_tab == MemoryViewerTab::AudioRam, "Audio RAM")
+                    .clicked()
+                {
+                    ui_state.memory_tab = MemoryViewerTab::AudioRam as usize;
+                }
             });
 
             ui.separator();
@@ -90,6 +98,7 @@ pub(super) fn show(
                 MemoryViewerTab::Stack => show_stack_tab(ui, ui_state, debugger, bus, cpu),
                 MemoryViewerTab::PpuVram => show_ppu_vram_tab(ui, ui_state, debugger, ppu),
                 MemoryViewerTab::Oam => show_oam_tab(ui, debugger, ppu),
+                MemoryViewerTab::AudioRam => show_audio_ram_tab(ui, debugger, bus),
             }
         });
 
@@ -492,6 +501,27 @@ fn show_oam_tab(ui: &mut egui::Ui, debugger: &Debugger, ppu: &Ppu) {
         });
 }
 
+/// Show expansion audio RAM viewer tab
+fn show_audio_ram_tab(ui: &mut egui::Ui, debugger: &Debugger, bus: &Bus) {
+    ui.heading("Expansion Audio RAM");
+    ui.label("Sound memory inside the cartridge (e.g. Namco 163 wavetables and channel registers)");
+    ui.separator();
+
+    match debugger.memory.dump_audio_ram(bus) {
+        Some(dump) => {
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
+                    ui.label(dump);
+                });
+        }
+        None => {
+            ui.label("The cartridge has no expansion audio RAM");
+        }
+    }
+}
+
 /// Parse hex pattern from string (e.g., "DE AD BE EF" -> [0xDE, 0xAD, 0xBE, 0xEF])
 fn parse_hex_pattern(pattern: &str) -> Option<Vec<u8>> {
     let tokens: Vec<&str> = pattern.split_whitespace().collect();
@@ -5,6 +5,7 @@
 
 use super::framebuffer::{FrameBuffer, SCREEN_HEIGHT, SCREEN_WIDTH};
 use crate::input::{ControllerIO, InputConfig, Player, UnifiedInputHandler};
+use crate::region::Region;
 use pixels::{Pixels, SurfaceTexture};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -19,7 +20,7 @@ use winit::window::{Window, WindowId};
 pub struct WindowConfig {
     /// Scale factor (1x, 2x, 3x, 4x, etc.)
     pub scale: u32,
-    /// Target frame rate in Hz (typically 60 for NTSC NES)
+    /// Target frame rate in Hz (60 for NTSC, 50 for PAL and Dendy)
     pub target_fps: u32,
     /// Whether to enable VSync
     pub vsync: bool,
@@ -49,6 +50,22 @@ impl WindowConfig {
         self
     }
 
+    /// Set the target frame rate to match a console region
+    ///
+    /// # Arguments
+    /// * `region` - The console region being emulated
+    ///
+    /// # Example
+    /// ```
+    /// use nes_rs::{Region, WindowConfig};
+    ///
+    /// let config = WindowConfig::new().with_region(Region::Pal);
+    /// assert_eq!(config.target_fps, 50);
+    /// ```
+    pub fn with_region(self, region: Region) -> Self {
+        self.with_fps(region.frame_rate().round() as u32)
+    }
+
     /// Set VSync enabled or disabled
     pub fn with_vsync(mut self, vsync: bool) -> Self {
         self.vsync = vsync;
@@ -382,6 +399,19 @@ mod tests {
         assert_eq!(duration.as_micros(), 16666); // ~16.67ms for 60 FPS
     }
 
+    #[test]
+    fn test_frame_rate_from_region() {
+        assert_eq!(WindowConfig::new().with_region(Region::Ntsc).target_fps, 60);
+        assert_eq!(WindowConfig::new().with_region(Region::Pal).target_fps, 50);
+        assert_eq!(
+            WindowConfig::new().with_region(Region::Dendy).target_fps,
+            50
+        );
+
+        let config = WindowConfig::new().with_region(Region::Pal);
+        assert_eq!(config.frame_duration().as_micros(), 20000);
+    }
+
     #[test]
     fn test_scale_clamping() {
         let config = WindowConfig::new().with_scale(100);
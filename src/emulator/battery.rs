@@ -0,0 +1,391 @@
+// Battery-backed save RAM
+//
+// Cartridges with a battery keep their PRG-RAM (SRAM) while the console is
+// off, which is where games like Zelda and Final Fantasy store progress. The
+// emulator mirrors this with a raw `.sav` image per ROM: it is read when the
+// ROM is loaded and written back when the ROM is changed, when the emulator is
+// dropped, and every `battery_save_interval` seconds while running. Writes are
+// skipped while the RAM matches the last image read or written.
+
+use super::Emulator;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File extension of battery save files
+const SAV_EXTENSION: &str = "sav";
+
+/// Errors that can occur while loading or saving battery RAM
+#[derive(Debug)]
+pub enum BatterySaveError {
+    /// I/O error
+    Io(io::Error),
+
+    /// The cartridge has no PRG-RAM (or no cartridge is inserted)
+    NoPrgRam,
+}
+
+impl std::fmt::Display for BatterySaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatterySaveError::Io(e) => write!(f, "I/O error: {}", e),
+            BatterySaveError::NoPrgRam => write!(f, "Cartridge has no PRG-RAM"),
+        }
+    }
+}
+
+impl std::error::Error for BatterySaveError {}
+
+impl From<io::Error> for BatterySaveError {
+    fn from(e: io::Error) -> Self {
+        BatterySaveError::Io(e)
+    }
+}
+
+/// Get the battery save file for a ROM
+///
+/// Creates a path like: <directory>/<rom_name>.sav
+///
+/// # Arguments
+///
+/// * `directory` - Directory holding the battery saves
+/// * `rom_path` - Path to the ROM file
+///
+/// # Returns
+///
+/// The path of the ROM's `.sav` file
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::emulator::battery_save_path;
+/// use std::path::Path;
+///
+/// let path = battery_save_path(Path::new("saves"), Path::new("roms/zelda.nes"));
+/// assert_eq!(path, Path::new("saves/zelda.sav"));
+/// ```
+pub fn battery_save_path(directory: &Path, rom_path: &Path) -> PathBuf {
+    let rom_name = rom_path
+        .file_stem()
+        .map(Path::new)
+        .unwrap_or(Path::new("default"));
+    directory.join(rom_name).with_extension(SAV_EXTENSION)
+}
+
+impl Emulator {
+    /// Write battery-backed PRG-RAM to the ROM's `.sav` file
+    ///
+    /// Does nothing unless a battery-backed ROM was loaded with
+    /// [`Emulator::load_rom`] and its RAM changed since the file was last
+    /// read or written. Called automatically on ROM change, periodically while
+    /// running, and when the emulator is dropped.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the file was written, `Ok(false)` if there was nothing to save
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nes_rs::emulator::Emulator;
+    ///
+    /// let mut emulator = Emulator::new();
+    /// emulator.load_rom("zelda.nes").expect("Failed to load ROM");
+    /// emulator.save_battery().expect("Failed to write save RAM");
+    /// ```
+    pub fn save_battery(&mut self) -> Result<bool, BatterySaveError> {
+        let Some(path) = &self.battery_path else {
+            return Ok(false);
+        };
+        let Some(ram) = self.prg_ram() else {
+            return Ok(false);
+        };
+        if ram == self.battery_contents {
+            return Ok(false);
+        }
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(path, &ram)?;
+        self.battery_contents = ram;
+        Ok(true)
+    }
+
+    /// Start battery saves for a ROM loaded from `rom_path`
+    ///
+    /// Reads the existing `.sav` file into PRG-RAM if there is one.
+    pub(super) fn load_battery(&mut self, rom_path: &Path) -> Result<(), BatterySaveError> {
+        if !self.cartridge.as_ref().is_some_and(|c| c.has_battery) {
+            return Ok(());
+        }
+
+        let path = battery_save_path(&self.config.save_state.battery_directory, rom_path);
+        match fs::read(&path) {
+            Ok(data) => self.write_prg_ram(&data)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        self.battery_contents = self.prg_ram().unwrap_or_default();
+        self.battery_path = Some(path);
+        Ok(())
+    }
+
+    /// Export PRG-RAM as a raw SRAM image
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File to write
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or error
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nes_rs::emulator::Emulator;
+    ///
+    /// let mut emulator = Emulator::new();
+    /// emulator.load_rom("zelda.nes").expect("Failed to load ROM");
+    /// emulator.export_sram("backup.sav").expect("Failed to export SRAM");
+    /// ```
+    pub fn export_sram<P: AsRef<Path>>(&self, path: P) -> Result<(), BatterySaveError> {
+        let ram = self.prg_ram().ok_or(BatterySaveError::NoPrgRam)?;
+        fs::write(path, ram)?;
+        Ok(())
+    }
+
+    /// Import a raw SRAM image into PRG-RAM
+    ///
+    /// Images of a different size fill PRG-RAM from the start. For a
+    /// battery-backed ROM the imported RAM is written to its `.sav` file on
+    /// the next battery save.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File to read
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or error
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nes_rs::emulator::Emulator;
+    ///
+    /// let mut emulator = Emulator::new();
+    /// emulator.load_rom("zelda.nes").expect("Failed to load ROM");
+    /// emulator.import_sram("backup.sav").expect("Failed to import SRAM");
+    /// ```
+    pub fn import_sram<P: AsRef<Path>>(&mut self, path: P) -> Result<(), BatterySaveError> {
+        let data = fs::read(path)?;
+        self.write_prg_ram(&data)
+    }
+
+    /// Count a frame towards the periodic battery save
+    pub(super) fn tick_battery_timer(&mut self) {
+        let interval = self.config.save_state.battery_save_interval;
+        if self.battery_path.is_none() || interval == 0 {
+            return;
+        }
+
+        self.battery_frames += 1;
+        let interval_frames = (interval as f64 * self.region().frame_rate()) as u32;
+        if self.battery_frames >= interval_frames {
+            self.battery_frames = 0;
+            // A failed write is retried at the next interval
+            let _ = self.save_battery();
+        }
+    }
+
+    /// Copy of the mapper's PRG-RAM
+    pub(super) fn prg_ram(&self) -> Option<Vec<u8>> {
+        let mapper = self.bus.mapper()?;
+        let mapper = mapper.borrow();
+        mapper.prg_ram().map(<[u8]>::to_vec)
+    }
+
+    /// Overwrite the start of the mapper's PRG-RAM
+    pub(super) fn write_prg_ram(&mut self, data: &[u8]) -> Result<(), BatterySaveError> {
+        let mapper = self.bus.mapper().ok_or(BatterySaveError::NoPrgRam)?;
+        let mut mapper = mapper.borrow_mut();
+        let ram = mapper.prg_ram_mut().ok_or(BatterySaveError::NoPrgRam)?;
+        let len = ram.len().min(data.len());
+        ram[..len].copy_from_slice(&data[..len]);
+        Ok(())
+    }
+}
+
+impl Drop for Emulator {
+    fn drop(&mut self) {
+        // Nothing can report an error from here
+        let _ = self.save_battery();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::Cartridge;
+    use crate::emulator::EmulatorConfig;
+
+    /// A fresh directory under the system temp directory
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nes_rs_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// An emulator with a battery-backed MMC1 cartridge saving to `dir`
+    fn battery_emulator(dir: &Path) -> Emulator {
+        let mut config = EmulatorConfig::default();
+        config.save_state.battery_directory = dir.to_path_buf();
+        let mut emulator = Emulator::with_config(config);
+        emulator
+            .insert_cartridge(Cartridge {
+                prg_rom: vec![0xEA; 32 * 1024],
+                chr_rom: vec![0; 8 * 1024],
+                mapper: 1,
+                has_battery: true,
+                ..Cartridge::new()
+            })
+            .unwrap();
+        emulator.load_battery(Path::new("roms/game.nes")).unwrap();
+        emulator
+    }
+
+    #[test]
+    fn test_battery_save_path() {
+        let dir = Path::new("saves");
+        assert_eq!(
+            battery_save_path(dir, Path::new("/roms/game.nes")),
+            PathBuf::from("saves/game.sav")
+        );
+        assert_eq!(
+            battery_save_path(dir, Path::new("/")),
+            PathBuf::from("saves/default.sav")
+        );
+    }
+
+    #[test]
+    fn test_battery_ram_round_trip() {
+        let dir = temp_dir("battery_round_trip");
+        let sav_path = dir.join("game.sav");
+
+        let mut emulator = battery_emulator(&dir);
+        assert!(!emulator.save_battery().unwrap(), "RAM is unchanged");
+        assert!(!sav_path.exists());
+
+        emulator.bus_mut().write(0x6000, 0x42);
+        emulator.bus_mut().write(0x7FFF, 0x99);
+        assert!(emulator.save_battery().unwrap());
+        assert!(!emulator.save_battery().unwrap(), "Already saved");
+
+        let data = fs::read(&sav_path).unwrap();
+        assert_eq!(data.len(), 8 * 1024);
+        assert_eq!((data[0], data[0x1FFF]), (0x42, 0x99));
+
+        // Loading the ROM again restores the RAM
+        let mut emulator = battery_emulator(&dir);
+        assert_eq!(emulator.bus_mut().read(0x6000), 0x42);
+        assert_eq!(emulator.bus_mut().read(0x7FFF), 0x99);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_battery_saved_on_drop() {
+        let dir = temp_dir("battery_drop");
+
+        let mut emulator = battery_emulator(&dir);
+        emulator.bus_mut().write(0x6123, 0x5A);
+        drop(emulator);
+
+        let data = fs::read(dir.join("game.sav")).unwrap();
+        assert_eq!(data[0x123], 0x5A);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_power_cycle_keeps_battery_ram() {
+        let dir = temp_dir("battery_power_cycle");
+
+        let mut emulator = battery_emulator(&dir);
+        emulator.bus_mut().write(0x6000, 0x77);
+        emulator.bus_mut().write(0x0000, 0x77);
+        emulator.power_cycle().unwrap();
+        assert_eq!(emulator.bus_mut().read(0x6000), 0x77);
+        assert_eq!(emulator.bus_mut().read(0x0000), 0x00);
+
+        // The ROM is still saving to its file
+        assert!(emulator.save_battery().unwrap());
+        assert_eq!(fs::read(dir.join("game.sav")).unwrap()[0], 0x77);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_battery_periodic_save() {
+        let dir = temp_dir("battery_periodic");
+        let sav_path = dir.join("game.sav");
+
+        let mut emulator = battery_emulator(&dir);
+        emulator.config_mut().save_state.battery_save_interval = 1;
+        emulator.bus_mut().write(0x6000, 0x01);
+
+        // One second of NTSC frames
+        for _ in 0..59 {
+            emulator.tick_battery_timer();
+        }
+        assert!(!sav_path.exists());
+        emulator.tick_battery_timer();
+        assert_eq!(fs::read(&sav_path).unwrap()[0], 0x01);
+
+        // Nothing is written while disabled
+        emulator.config_mut().save_state.battery_save_interval = 0;
+        emulator.bus_mut().write(0x6000, 0x02);
+        for _ in 0..120 {
+            emulator.tick_battery_timer();
+        }
+        assert_eq!(fs::read(&sav_path).unwrap()[0], 0x01);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sram_import_export() {
+        let dir = temp_dir("sram_import_export");
+        let image = dir.join("backup.sav");
+
+        let mut emulator = battery_emulator(&dir);
+        emulator.bus_mut().write(0x6010, 0xAB);
+        emulator.export_sram(&image).unwrap();
+
+        // Short images fill PRG-RAM from the start
+        let mut emulator = battery_emulator(&dir);
+        emulator.bus_mut().write(0x7000, 0xCD);
+        fs::write(&image, [0x11, 0x22]).unwrap();
+        emulator.import_sram(&image).unwrap();
+        assert_eq!(emulator.bus_mut().read(0x6000), 0x11);
+        assert_eq!(emulator.bus_mut().read(0x6001), 0x22);
+        assert_eq!(emulator.bus_mut().read(0x7000), 0xCD);
+
+        // Cartridges without PRG-RAM have nothing to import or export
+        let mut emulator = Emulator::with_config(EmulatorConfig::default());
+        assert!(matches!(
+            emulator.export_sram(&image),
+            Err(BatterySaveError::NoPrgRam)
+        ));
+        assert!(matches!(
+            emulator.import_sram(&image),
+            Err(BatterySaveError::NoPrgRam)
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,6 +2,7 @@
 //
 // Handles emulator configuration, settings persistence, and speed control.
 
+use crate::region::Region;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
@@ -29,6 +30,14 @@ pub struct EmulatorConfig {
 
     /// Hotkeys
     pub hotkeys: HotkeyConfig,
+
+    /// Rewind settings
+    #[serde(default)]
+    pub rewind: RewindConfig,
+
+    /// Console region override (None = use the region from the ROM header)
+    #[serde(default)]
+    pub region: Option<Region>,
 }
 
 /// Video configuration
@@ -68,6 +77,23 @@ pub struct SaveStateConfig {
 
     /// Save directory
     pub save_directory: PathBuf,
+
+    /// Directory for battery-backed save RAM (`<rom_name>.sav` files)
+    #[serde(default = "default_battery_directory")]
+    pub battery_directory: PathBuf,
+
+    /// Seconds between automatic battery saves while running (0 = only on
+    /// exit and ROM change)
+    #[serde(default = "default_battery_save_interval")]
+    pub battery_save_interval: u32,
+}
+
+fn default_battery_directory() -> PathBuf {
+    PathBuf::from("saves")
+}
+
+fn default_battery_save_interval() -> u32 {
+    30
 }
 
 /// Screenshot configuration
@@ -102,6 +128,38 @@ pub struct HotkeyConfig {
     pub pause: String,
 }
 
+/// Rewind configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RewindConfig {
+    /// Enable recording rewind snapshots
+    pub enabled: bool,
+
+    /// Frames between snapshots
+    pub snapshot_interval: u32,
+
+    /// Maximum number of snapshots kept
+    pub buffer_length: usize,
+
+    /// Snapshots per keyframe (the rest are stored as deltas against it)
+    pub keyframe_interval: usize,
+
+    /// Memory cap for the snapshot buffer, in megabytes
+    pub memory_cap_mb: usize,
+}
+
+impl Default for RewindConfig {
+    fn default() -> Self {
+        RewindConfig {
+            enabled: true,
+            snapshot_interval: 2,
+            buffer_length: 600,
+            keyframe_interval: 60,
+            memory_cap_mb: 64,
+        }
+    }
+}
+
 /// Speed mode for emulation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SpeedMode {
@@ -155,6 +213,8 @@ impl Default for EmulatorConfig {
                 slots: 10,
                 auto_save_on_exit: false,
                 save_directory: PathBuf::from("saves"),
+                battery_directory: default_battery_directory(),
+                battery_save_interval: default_battery_save_interval(),
             },
             screenshot: ScreenshotConfig {
                 screenshot_directory: PathBuf::from("screenshots"),
@@ -168,6 +228,8 @@ impl Default for EmulatorConfig {
                 fast_forward: "Tab".to_string(),
                 pause: "P".to_string(),
             },
+            rewind: RewindConfig::default(),
+            region: None,
         }
     }
 }
@@ -260,5 +322,48 @@ mod tests {
 
         assert_eq!(config.video.scale, deserialized.video.scale);
         assert_eq!(config.audio.volume, deserialized.audio.volume);
+        assert_eq!(
+            config.rewind.buffer_length,
+            deserialized.rewind.buffer_length
+        );
+    }
+
+    #[test]
+    fn test_config_without_rewind_settings() {
+        // Configuration files written before rewind existed still load
+        let mut value = toml::Value::try_from(EmulatorConfig::default()).unwrap();
+        let table = value.as_table_mut().unwrap();
+        table.remove("rewind");
+
+        let config: EmulatorConfig = toml::from_str(&toml::to_string(&value).unwrap()).unwrap();
+        assert!(config.rewind.enabled);
+        assert_eq!(config.rewind.snapshot_interval, 2);
+    }
+
+    #[test]
+    fn test_config_without_battery_settings() {
+        // Configuration files written before battery saves existed still load
+        let mut value = toml::Value::try_from(EmulatorConfig::default()).unwrap();
+        let save_state = value["save_state"].as_table_mut().unwrap();
+        save_state.remove("battery_directory");
+        save_state.remove("battery_save_interval");
+
+        let config: EmulatorConfig = toml::from_str(&toml::to_string(&value).unwrap()).unwrap();
+        assert_eq!(config.save_state.battery_directory, PathBuf::from("saves"));
+        assert_eq!(config.save_state.battery_save_interval, 30);
+    }
+
+    #[test]
+    fn test_config_region_override() {
+        assert_eq!(EmulatorConfig::default().region, None);
+
+        let config = EmulatorConfig {
+            region: Some(Region::Pal),
+            ..EmulatorConfig::default()
+        };
+        let toml_str = toml::to_string(&config).expect("Failed to serialize");
+        let deserialized: EmulatorConfig =
+            toml::from_str(&toml_str).expect("Failed to deserialize");
+        assert_eq!(deserialized.region, Some(Region::Pal));
     }
 }
@@ -0,0 +1,288 @@
+// CPU jam reporting
+//
+// A KIL opcode halts the 6502 until the next RESET. When that happens the
+// emulator records a `CpuJam` describing where the CPU stopped: the program
+// counter, the most recent CPU trace entries (when a trace logger is attached)
+// and the PRG-ROM banks mapped into $8000-$FFFF. Test harnesses use it to fail
+// fast with a useful report instead of running until their cycle limit.
+
+use super::Emulator;
+use crate::debug::{CpuDebugger, Logger, TraceEntry};
+use std::fmt;
+
+/// Number of trace entries kept in a jam report
+pub const JAM_TRACE_LENGTH: usize = 32;
+
+/// Size of the PRG-ROM windows reported in a jam
+const PRG_WINDOW_SIZE: usize = 0x2000;
+
+/// Start addresses of the reported PRG-ROM windows
+const PRG_WINDOWS: [u16; 4] = [0x8000, 0xA000, 0xC000, 0xE000];
+
+/// Details of a CPU halted by a KIL opcode
+#[derive(Debug, Clone)]
+pub struct CpuJam {
+    /// Address of the KIL opcode
+    pub pc: u16,
+
+    /// The KIL opcode that halted the CPU
+    pub opcode: u8,
+
+    /// CPU cycle count when the CPU halted
+    pub cycle: u64,
+
+    /// The last CPU trace entries before the halt (empty without a trace logger)
+    pub trace: Vec<TraceEntry>,
+
+    /// 8KB PRG-ROM bank mapped at $8000, $A000, $C000 and $E000
+    ///
+    /// None when the window is not backed by PRG-ROM or the mapper does not
+    /// report its mapping.
+    pub prg_banks: [Option<usize>; 4],
+}
+
+impl fmt::Display for CpuJam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "CPU jammed by KIL ${:02X} at ${:04X} (cycle {})",
+            self.opcode, self.pc, self.cycle
+        )?;
+
+        write!(f, "PRG banks:")?;
+        for (address, bank) in PRG_WINDOWS.iter().zip(self.prg_banks) {
+            match bank {
+                Some(bank) => write!(f, " ${:04X}={}", address, bank)?,
+                None => write!(f, " ${:04X}=?", address)?,
+            }
+        }
+        writeln!(f)?;
+
+        if self.trace.is_empty() {
+            write!(f, "No trace available (attach a trace logger)")
+        } else {
+            write!(f, "Last {} trace entries:", self.trace.len())?;
+            for entry in &self.trace {
+                write!(f, "\n  {}", entry)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+impl Emulator {
+    /// Get the jam report if the CPU is halted by a KIL opcode
+    ///
+    /// The report is cleared by [`Emulator::reset`].
+    ///
+    /// # Returns
+    ///
+    /// The jam details, or None if the CPU is running
+    pub fn jam(&self) -> Option<&CpuJam> {
+        self.jam.as_ref()
+    }
+
+    /// Attach or detach a CPU trace logger
+    ///
+    /// While attached and CPU tracing is enabled (see
+    /// [`Logger::is_cpu_trace_enabled`]), the CPU state is logged before every
+    /// instruction, and a jam report includes the most recent entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `logger` - The logger to attach, or None to detach it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::debug::{LogLevel, Logger};
+    /// use nes_rs::emulator::{Emulator, EmulatorConfig};
+    ///
+    /// let mut logger = Logger::new();
+    /// logger.set_log_level(LogLevel::Trace);
+    /// logger.enable_cpu_trace();
+    ///
+    /// let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    /// emulator.set_trace_logger(Some(logger));
+    /// emulator.step_instruction();
+    /// assert_eq!(emulator.trace_logger().unwrap().trace_buffer().len(), 1);
+    /// ```
+    pub fn set_trace_logger(&mut self, logger: Option<Logger>) {
+        self.trace_logger = logger;
+    }
+
+    /// Get the attached trace logger
+    pub fn trace_logger(&self) -> Option<&Logger> {
+        self.trace_logger.as_ref()
+    }
+
+    /// Get the attached trace logger mutably
+    pub fn trace_logger_mut(&mut self) -> Option<&mut Logger> {
+        self.trace_logger.as_mut()
+    }
+
+    /// Log the CPU state before an instruction executes
+    pub(super) fn log_trace(&mut self) {
+        if self.cpu.is_jammed() {
+            return;
+        }
+        let Some(logger) = self.trace_logger.as_mut() else {
+            return;
+        };
+        if !logger.is_cpu_trace_enabled() {
+            return;
+        }
+
+        let mut debugger = CpuDebugger::new();
+        debugger.set_capture_stack(false);
+        let state = debugger.capture_state(&self.cpu, &mut self.bus);
+        logger.log_cpu_state(&state);
+    }
+
+    /// Record the jam report the first time the CPU halts
+    pub(super) fn record_jam(&mut self) {
+        if self.jam.is_some() || !self.cpu.is_jammed() {
+            return;
+        }
+
+        let pc = self.cpu.pc;
+        let opcode = self.bus.read(pc);
+        let trace = self
+            .trace_logger
+            .as_ref()
+            .map(|logger| logger.last_entries(JAM_TRACE_LENGTH).to_vec())
+            .unwrap_or_default();
+
+        let mut prg_banks = [None; 4];
+        if let Some(mapper) = self.bus.mapper() {
+            let mapper = mapper.borrow();
+            for (bank, &address) in prg_banks.iter_mut().zip(PRG_WINDOWS.iter()) {
+                *bank = mapper
+                    .prg_rom_offset(address)
+                    .map(|offset| offset / PRG_WINDOW_SIZE);
+            }
+        }
+
+        self.jam = Some(CpuJam {
+            pc,
+            opcode,
+            cycle: self.cpu.cycles,
+            trace,
+            prg_banks,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::{Cartridge, Mirroring};
+    use crate::debug::LogLevel;
+    use crate::emulator::{EmulatorConfig, SaveState};
+
+    /// Create an emulator with a 16KB NROM cartridge running `program` from $C000
+    fn create_test_emulator(program: &[u8]) -> Emulator {
+        let mut prg_rom = vec![0xEA; 16 * 1024];
+        prg_rom[..program.len()].copy_from_slice(program);
+        prg_rom[0x3FFA..].copy_from_slice(&[0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0]);
+
+        let cartridge = Cartridge {
+            prg_rom,
+            chr_rom: vec![0; 8 * 1024],
+            trainer: None,
+            mapper: 0,
+            mirroring: Mirroring::Vertical,
+            has_battery: false,
+            ..Cartridge::new()
+        };
+
+        let mut emulator = Emulator::with_config(EmulatorConfig::default());
+        emulator.insert_cartridge(cartridge).unwrap();
+        emulator
+    }
+
+    fn trace_logger() -> Logger {
+        let mut logger = Logger::new();
+        logger.set_log_level(LogLevel::Trace);
+        logger.enable_cpu_trace();
+        logger
+    }
+
+    #[test]
+    fn test_kil_reports_jam() {
+        // LDA #$01; LDX #$02; KIL
+        let mut emulator = create_test_emulator(&[0xA9, 0x01, 0xA2, 0x02, 0x02]);
+        emulator.set_trace_logger(Some(trace_logger()));
+
+        assert!(!emulator.step_instruction().jammed);
+        assert!(!emulator.step_instruction().jammed);
+        assert!(emulator.jam().is_none());
+        assert!(emulator.step_instruction().jammed);
+
+        let jam = emulator.jam().unwrap();
+        assert_eq!(jam.pc, 0xC004);
+        assert_eq!(jam.opcode, 0x02);
+        assert_eq!(jam.prg_banks, [Some(0), Some(1), Some(0), Some(1)]);
+        assert_eq!(jam.trace.len(), 3);
+        match jam.trace.last().unwrap() {
+            TraceEntry::Cpu(state) => assert_eq!(state.pc, 0xC004),
+            entry => panic!("unexpected trace entry {:?}", entry),
+        }
+
+        let report = jam.to_string();
+        assert!(report.contains("KIL $02 at $C004"), "{}", report);
+        assert!(
+            report.contains("$8000=0 $A000=1 $C000=0 $E000=1"),
+            "{}",
+            report
+        );
+    }
+
+    #[test]
+    fn test_jammed_cpu_keeps_system_running() {
+        // CLI; KIL
+        let mut emulator = create_test_emulator(&[0x58, 0x02]);
+        emulator.step_instruction();
+        emulator.step_instruction();
+        let jam = emulator.jam().unwrap().clone();
+        assert!(jam.trace.is_empty());
+
+        // Frames still complete, nothing is traced and interrupts are ignored
+        emulator.bus_mut().write(0x2000, 0x80);
+        let result = emulator.run_frame();
+        assert!(result.jammed);
+        assert!(result.frame_complete);
+        assert_eq!(emulator.cpu().pc, 0xC001);
+        assert_eq!(emulator.jam().unwrap().cycle, jam.cycle);
+        assert!(emulator.jam().unwrap().to_string().contains("No trace"));
+    }
+
+    #[test]
+    fn test_save_state_keeps_jam() {
+        // LDA #$01; KIL
+        let mut emulator = create_test_emulator(&[0xA9, 0x01, 0x02]);
+        emulator.step_instruction();
+        emulator.step_instruction();
+        let state = SaveState::from_emulator(&emulator).unwrap();
+
+        emulator.reset();
+        state.restore_to_emulator(&mut emulator).unwrap();
+
+        assert!(emulator.cpu().is_jammed());
+        assert_eq!(emulator.jam().unwrap().pc, 0xC002);
+        assert!(emulator.step_instruction().jammed);
+        assert_eq!(emulator.cpu().pc, 0xC002);
+    }
+
+    #[test]
+    fn test_reset_clears_jam() {
+        let mut emulator = create_test_emulator(&[0x02]);
+        emulator.step_instruction();
+        assert!(emulator.jam().is_some());
+
+        emulator.reset();
+        assert!(emulator.jam().is_none());
+        assert!(!emulator.cpu().is_jammed());
+        assert_eq!(emulator.cpu().pc, 0xC000);
+    }
+}
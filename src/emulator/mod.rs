@@ -4,20 +4,36 @@
 // (CPU, PPU, APU, Bus) and implements quality-of-life features like save states,
 // screenshots, speed control, and configuration management.
 
+mod battery;
 mod config;
+mod jam;
+mod movie;
 mod recent_roms;
+mod rewind;
 mod save_state;
+mod scheduler;
 mod screenshot;
 
-pub use config::{EmulatorConfig, SpeedMode};
+pub use battery::{battery_save_path, BatterySaveError};
+pub use config::{EmulatorConfig, RewindConfig, SpeedMode};
+pub use jam::{CpuJam, JAM_TRACE_LENGTH};
+pub use movie::{MovieMode, MovieSession};
 pub use recent_roms::RecentRomsList;
+pub use rewind::RewindBuffer;
 pub use save_state::{SaveState, SaveStateError};
+pub use scheduler::RunResult;
 pub use screenshot::{save_screenshot, ScreenshotError};
 
 use crate::bus::Bus;
+use crate::cartridge::mappers::{create_mapper, MapperError};
 use crate::cartridge::Cartridge;
 use crate::cpu::Cpu;
+use crate::debug::Logger;
+use crate::input::movie::{COMMAND_POWER, COMMAND_RESET};
+use crate::region::Region;
+use std::cell::RefCell;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::time::Instant;
 
 /// Main emulator structure
@@ -33,9 +49,8 @@ pub struct Emulator {
 
     /// Currently loaded cartridge
     ///
-    /// Note: The cartridge is stored here but not yet fully integrated with the Bus.
-    /// When the mapper system is implemented, this will be properly wired into
-    /// the Bus's memory mapping system.
+    /// Kept for its header metadata (mapper number, battery, etc.). The live
+    /// memory state lives in the mapper attached to the Bus.
     cartridge: Option<Cartridge>,
 
     /// Configuration
@@ -53,6 +68,30 @@ pub struct Emulator {
     /// Frame timing for speed control
     #[allow(dead_code)]
     last_frame_time: Option<Instant>,
+
+    /// Rewind snapshots
+    rewind: RewindBuffer,
+
+    /// Rewinding continuously (see [`Emulator::rewind_hold`])
+    rewinding: bool,
+
+    /// Movie being recorded or played back
+    movie: Option<MovieSession>,
+
+    /// Report of the CPU halting on a KIL opcode
+    jam: Option<CpuJam>,
+
+    /// Logger receiving a CPU trace before every instruction
+    trace_logger: Option<Logger>,
+
+    /// Battery save file of the loaded ROM (None = no battery-backed RAM)
+    battery_path: Option<PathBuf>,
+
+    /// PRG-RAM contents last read from or written to the battery save file
+    battery_contents: Vec<u8>,
+
+    /// Frames since the last periodic battery save
+    battery_frames: u32,
 }
 
 impl Emulator {
@@ -72,22 +111,64 @@ impl Emulator {
     /// let mut emulator = Emulator::new();
     /// ```
     pub fn new() -> Self {
+        Self::with_config(EmulatorConfig::load_or_default())
+    }
+
+    /// Create a new emulator instance with the given configuration
+    ///
+    /// Unlike [`Emulator::new`], this does not read or write the configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration to use
+    ///
+    /// # Returns
+    ///
+    /// A new emulator instance
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::emulator::{Emulator, EmulatorConfig};
+    ///
+    /// let emulator = Emulator::with_config(EmulatorConfig::default());
+    /// ```
+    pub fn with_config(config: EmulatorConfig) -> Self {
+        let region = config.region.unwrap_or_default();
         Emulator {
             cpu: Cpu::new(),
-            bus: Bus::new(),
+            bus: Self::create_bus(region),
             cartridge: None,
-            config: EmulatorConfig::load_or_default(),
+            config,
             rom_path: None,
             paused: false,
             speed_mode: SpeedMode::Normal,
             last_frame_time: None,
+            rewind: RewindBuffer::new(),
+            rewinding: false,
+            movie: None,
+            jam: None,
+            trace_logger: None,
+            battery_path: None,
+            battery_contents: Vec::new(),
+            battery_frames: 0,
         }
     }
 
+    /// Create a bus that records audio for [`Emulator::audio_samples`]
+    fn create_bus(region: Region) -> Bus {
+        let mut bus = Bus::new();
+        bus.set_audio_capture(true);
+        bus.set_region(region);
+        bus
+    }
+
     /// Load a ROM file
     ///
     /// Loads a ROM from the specified path and initializes the emulator state.
-    /// Adds the ROM to the recent ROMs list.
+    /// The battery save RAM of the previous ROM is written out first, and the
+    /// new ROM's `.sav` file is read if it has battery-backed RAM. Adds the
+    /// ROM to the recent ROMs list.
     ///
     /// # Arguments
     ///
@@ -108,26 +189,61 @@ impl Emulator {
     pub fn load_rom<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn std::error::Error>> {
         let path = path.as_ref();
         let cartridge = Cartridge::from_ines_file(path)?;
-
-        // Load PRG-ROM data into bus
-        // Note: This is a temporary solution until the mapper system is implemented.
-        // The Bus currently uses a fixed ROM array instead of a proper cartridge interface.
-        if !cartridge.prg_rom.is_empty() {
-            // Load PRG-ROM starting at offset 0x3FE0 in the Bus ROM array
-            // (which maps to $8000 in CPU address space)
-            self.bus.load_rom(&cartridge.prg_rom, 0x3FE0);
-        }
-
-        // Store the cartridge and path
-        self.cartridge = Some(cartridge);
+        self.save_battery()?;
+        self.insert_cartridge(cartridge)?;
         self.rom_path = Some(path.to_path_buf());
+        self.load_battery(path)?;
 
         // Add to recent ROMs list
         let mut recent_roms = RecentRomsList::load_or_default();
         recent_roms.add(path);
         recent_roms.save()?;
 
-        // Reset the emulator
+        Ok(())
+    }
+
+    /// Insert a cartridge
+    ///
+    /// Creates the cartridge's mapper, attaches it to the bus (and through it to
+    /// the PPU), switches to the cartridge's region (unless the configuration
+    /// overrides it), and resets the emulator. Unlike [`Emulator::load_rom`],
+    /// this does not touch the file system, so battery-backed RAM is not
+    /// loaded or saved.
+    ///
+    /// # Arguments
+    ///
+    /// * `cartridge` - The cartridge to insert
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or an unsupported mapper error
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nes_rs::emulator::{Emulator, EmulatorConfig};
+    /// use nes_rs::Cartridge;
+    ///
+    /// let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    /// let cartridge = Cartridge::from_ines_file("game.nes").unwrap();
+    /// emulator.insert_cartridge(cartridge).expect("Unsupported mapper");
+    /// ```
+    pub fn insert_cartridge(&mut self, cartridge: Cartridge) -> Result<(), MapperError> {
+        // The mapper is shared between the CPU bus and the PPU
+        let mapper = create_mapper(cartridge.clone())?;
+        self.bus.set_mapper(Rc::new(RefCell::new(mapper)));
+        self.bus
+            .set_region(self.config.region.unwrap_or(cartridge.region));
+        self.cartridge = Some(cartridge);
+
+        // Snapshots, movies and battery saves of the previous game do not
+        // apply to this one
+        self.rewind.clear();
+        self.movie = None;
+        self.battery_path = None;
+        self.battery_contents.clear();
+        self.battery_frames = 0;
+
         self.reset();
 
         Ok(())
@@ -146,9 +262,68 @@ impl Emulator {
     /// emulator.reset();
     /// ```
     pub fn reset(&mut self) {
+        // Release any IRQ the cartridge was holding
+        if let Some(mapper) = self.bus.mapper() {
+            mapper.borrow_mut().irq_acknowledge();
+        }
+
         self.cpu.reset(&mut self.bus);
+        self.jam = None;
         // PPU and APU will be reset through the bus
         self.paused = false;
+
+        self.record_movie_command(COMMAND_RESET);
+    }
+
+    /// Power cycle the emulator
+    ///
+    /// Turns the console off and on again: unlike [`Emulator::reset`], RAM,
+    /// the PPU, the APU and the mapper all return to their power-on state.
+    /// Battery-backed PRG-RAM keeps its contents.
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::emulator::{Emulator, EmulatorConfig};
+    ///
+    /// let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    /// emulator.power_cycle().unwrap();
+    /// ```
+    pub fn power_cycle(&mut self) -> Result<(), MapperError> {
+        // Keep the movie out of the way so the reset below is not recorded
+        let movie = self.movie.take();
+        let battery = (
+            self.battery_path.take(),
+            std::mem::take(&mut self.battery_contents),
+        );
+        let battery_ram = self
+            .cartridge
+            .as_ref()
+            .filter(|cartridge| cartridge.has_battery)
+            .and_then(|_| self.prg_ram());
+
+        self.cpu = Cpu::new();
+        self.bus = Self::create_bus(self.region());
+        let result = match self.cartridge.take() {
+            Some(cartridge) => self.insert_cartridge(cartridge),
+            None => {
+                self.reset();
+                Ok(())
+            }
+        };
+
+        if let Some(ram) = battery_ram {
+            // The mapper was just created from the same cartridge
+            let _ = self.write_prg_ram(&ram);
+        }
+        (self.battery_path, self.battery_contents) = battery;
+        self.movie = movie;
+        self.record_movie_command(COMMAND_POWER);
+        result
     }
 
     /// Save state to a file
@@ -344,6 +519,29 @@ impl Emulator {
         &mut self.bus
     }
 
+    /// Get a nestest-format trace line for the next instruction
+    ///
+    /// The line records the state before the instruction at PC executes: its
+    /// address, its bytes, the disassembly (with the effective address and the
+    /// value stored there, as nestest prints them), the A, X, Y, P and SP
+    /// registers and the CPU cycle count. Call it before each
+    /// [`Emulator::step_instruction`] to build a log that can be compared line
+    /// by line with nestest's. Memory is read without clocking the system.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::emulator::{Emulator, EmulatorConfig};
+    ///
+    /// let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    /// let line = emulator.trace();
+    /// assert!(line.contains("CYC:"));
+    /// emulator.step_instruction();
+    /// ```
+    pub fn trace(&mut self) -> String {
+        self.cpu.trace(&mut self.bus)
+    }
+
     /// Get reference to configuration
     pub fn config(&self) -> &EmulatorConfig {
         &self.config
@@ -354,6 +552,33 @@ impl Emulator {
         &mut self.config
     }
 
+    /// Get the console region being emulated
+    ///
+    /// Comes from the configuration override if set, otherwise from the
+    /// inserted cartridge's header (NTSC without a cartridge).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::emulator::{Emulator, EmulatorConfig};
+    /// use nes_rs::Region;
+    ///
+    /// let emulator = Emulator::with_config(EmulatorConfig::default());
+    /// assert_eq!(emulator.region(), Region::Ntsc);
+    /// ```
+    pub fn region(&self) -> Region {
+        self.bus.region()
+    }
+
+    /// Get the inserted cartridge
+    ///
+    /// Its header fields are after database corrections, and its
+    /// [`Cartridge::crc32`] and [`Cartridge::sha1`] identify the game
+    /// independently of the file name.
+    pub fn cartridge(&self) -> Option<&Cartridge> {
+        self.cartridge.as_ref()
+    }
+
     /// Get the currently loaded ROM path
     pub fn rom_path(&self) -> Option<&Path> {
         self.rom_path.as_deref()
@@ -593,4 +818,50 @@ mod tests {
             assert_eq!(emulator.speed_mode(), *mode);
         }
     }
+
+    /// A 16KB NROM cartridge made for `region`
+    fn cartridge_for(region: Region) -> Cartridge {
+        Cartridge {
+            prg_rom: vec![0xEA; 16 * 1024],
+            chr_rom: vec![0; 8 * 1024],
+            region,
+            ..Cartridge::new()
+        }
+    }
+
+    #[test]
+    fn test_region_follows_cartridge() {
+        let mut emulator = Emulator::with_config(EmulatorConfig::default());
+        assert_eq!(emulator.region(), Region::Ntsc);
+
+        emulator
+            .insert_cartridge(cartridge_for(Region::Pal))
+            .unwrap();
+        assert_eq!(emulator.region(), Region::Pal);
+        assert_eq!(emulator.bus().ppu().region(), Region::Pal);
+        assert_eq!(emulator.bus().apu().region(), Region::Pal);
+
+        emulator.power_cycle().unwrap();
+        assert_eq!(emulator.region(), Region::Pal);
+
+        emulator
+            .insert_cartridge(cartridge_for(Region::Ntsc))
+            .unwrap();
+        assert_eq!(emulator.region(), Region::Ntsc);
+    }
+
+    #[test]
+    fn test_region_config_override() {
+        let config = EmulatorConfig {
+            region: Some(Region::Dendy),
+            ..EmulatorConfig::default()
+        };
+        let mut emulator = Emulator::with_config(config);
+        assert_eq!(emulator.region(), Region::Dendy);
+
+        emulator
+            .insert_cartridge(cartridge_for(Region::Pal))
+            .unwrap();
+        assert_eq!(emulator.region(), Region::Dendy);
+    }
 }
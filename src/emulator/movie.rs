@@ -0,0 +1,488 @@
+// Movie recording and playback
+//
+// A movie session records the controller input of every frame, or plays it
+// back by overriding the controllers at the start of each frame. Both start
+// from a power cycle so that playback replays exactly what was recorded.
+//
+// The current movie frame is stored in save states. Loading a state (or
+// rewinding) during a read-write session truncates the movie at that frame,
+// continues recording from there and counts a rerecord. In read-only mode the
+// movie is left intact and playback resumes from the state's frame.
+
+use super::Emulator;
+use crate::input::movie::{Movie, MovieError, MovieFrame, COMMAND_POWER, COMMAND_RESET};
+
+/// What a movie session is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovieMode {
+    /// Appending the player's input to the movie
+    Recording,
+
+    /// Feeding the movie's input to the controllers
+    Playing,
+
+    /// Playback reached the end of the movie; input is back to the player
+    Finished,
+}
+
+/// An active movie and the playback position within it
+#[derive(Debug, Clone)]
+pub struct MovieSession {
+    /// The movie being recorded or played
+    movie: Movie,
+
+    /// Recording or playback state
+    mode: MovieMode,
+
+    /// Whether loading a state keeps the movie intact (read-only) or resumes
+    /// recording from that point (read-write)
+    read_only: bool,
+
+    /// Index of the next frame to record or play
+    frame: usize,
+
+    /// Commands (reset, power) issued since the last recorded frame
+    pending_commands: u8,
+}
+
+impl MovieSession {
+    /// The movie being recorded or played
+    pub fn movie(&self) -> &Movie {
+        &self.movie
+    }
+
+    /// Recording or playback state
+    pub fn mode(&self) -> MovieMode {
+        self.mode
+    }
+
+    /// Whether the session is read-only
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Index of the next frame to record or play
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+}
+
+impl Emulator {
+    /// Start recording a new movie
+    ///
+    /// Power cycles the console so the movie starts from a known state.
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success, or `NoRomLoaded`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nes_rs::emulator::Emulator;
+    ///
+    /// let mut emulator = Emulator::new();
+    /// emulator.load_rom("game.nes").expect("Failed to load ROM");
+    /// emulator.start_movie_recording().expect("Failed to start recording");
+    /// for _ in 0..600 {
+    ///     emulator.run_frame();
+    /// }
+    /// let movie = emulator.stop_movie().unwrap();
+    /// movie.save("game.fm2").expect("Failed to save movie");
+    /// ```
+    pub fn start_movie_recording(&mut self) -> Result<(), MovieError> {
+        let rom_filename = self
+            .rom_path()
+            .and_then(|p| p.file_stem())
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let cartridge = self.cartridge.as_ref().ok_or(MovieError::NoRomLoaded)?;
+        let movie = Movie::new(cartridge, &rom_filename);
+
+        self.movie = None;
+        self.power_cycle().map_err(|_| MovieError::NoRomLoaded)?;
+        self.movie = Some(MovieSession {
+            movie,
+            mode: MovieMode::Recording,
+            read_only: false,
+            frame: 0,
+            pending_commands: 0,
+        });
+
+        Ok(())
+    }
+
+    /// Start playing back a movie
+    ///
+    /// Checks that the movie was recorded with the loaded ROM, then power
+    /// cycles the console and replays the movie's input from the first frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `movie` - The movie to play
+    /// * `read_only` - Keep the movie intact when a state is loaded during playback
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success, `RomMismatch`, or `NoRomLoaded`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nes_rs::emulator::Emulator;
+    /// use nes_rs::input::Movie;
+    ///
+    /// let mut emulator = Emulator::new();
+    /// emulator.load_rom("game.nes").expect("Failed to load ROM");
+    /// let movie = Movie::load("game.fm2").expect("Failed to load movie");
+    /// emulator.play_movie(movie, true).expect("Movie does not match the ROM");
+    /// ```
+    pub fn play_movie(&mut self, movie: Movie, read_only: bool) -> Result<(), MovieError> {
+        let cartridge = self.cartridge.as_ref().ok_or(MovieError::NoRomLoaded)?;
+        movie.verify_rom(cartridge)?;
+
+        self.movie = None;
+        self.power_cycle().map_err(|_| MovieError::NoRomLoaded)?;
+        self.movie = Some(MovieSession {
+            movie,
+            mode: MovieMode::Playing,
+            read_only,
+            frame: 0,
+            pending_commands: 0,
+        });
+
+        Ok(())
+    }
+
+    /// Stop the current movie session
+    ///
+    /// # Returns
+    ///
+    /// The recorded or played movie, if a session was active
+    pub fn stop_movie(&mut self) -> Option<Movie> {
+        self.movie.take().map(|session| session.movie)
+    }
+
+    /// Get the active movie session
+    pub fn movie(&self) -> Option<&MovieSession> {
+        self.movie.as_ref()
+    }
+
+    /// Switch the active movie session between read-only and read-write
+    ///
+    /// # Arguments
+    ///
+    /// * `read_only` - Keep the movie intact when a state is loaded
+    pub fn set_movie_read_only(&mut self, read_only: bool) {
+        if let Some(session) = self.movie.as_mut() {
+            session.read_only = read_only;
+        }
+    }
+
+    /// Remember a reset or power cycle so it is recorded with the next frame
+    pub(super) fn record_movie_command(&mut self, command: u8) {
+        if let Some(session) = self.movie.as_mut() {
+            if session.mode == MovieMode::Recording {
+                session.pending_commands |= command;
+            }
+        }
+    }
+
+    /// Record or play back the input of the frame about to run
+    pub(super) fn apply_movie_frame(&mut self) {
+        let Some(session) = self.movie.as_mut() else {
+            return;
+        };
+
+        match session.mode {
+            MovieMode::Recording => {
+                let frame = MovieFrame {
+                    commands: std::mem::take(&mut session.pending_commands),
+                    controller1: self.bus.controller_io.controller1(),
+                    controller2: self.bus.controller_io.controller2(),
+                };
+                session.movie.truncate(session.frame);
+                session.movie.push_frame(frame);
+                session.frame += 1;
+            }
+            MovieMode::Playing => {
+                let Some(&frame) = session.movie.frames().get(session.frame) else {
+                    session.mode = MovieMode::Finished;
+                    return;
+                };
+                session.frame += 1;
+
+                if frame.commands & COMMAND_POWER != 0 {
+                    // The cartridge was power cycled before, so this cannot fail
+                    let _ = self.power_cycle();
+                } else if frame.commands & COMMAND_RESET != 0 {
+                    self.reset();
+                }
+                self.bus.set_controller1(frame.controller1);
+                self.bus.set_controller2(frame.controller2);
+            }
+            MovieMode::Finished => {}
+        }
+    }
+
+    /// Check that a save state taken at movie frame `frame` can be loaded
+    pub(super) fn check_movie_frame(&self, frame: u64) -> Result<(), String> {
+        match self.movie {
+            Some(ref session) if !session.read_only && frame > session.movie.len() as u64 => {
+                Err(format!(
+                    "save state is from movie frame {}, after the end of the movie ({} frames)",
+                    frame,
+                    session.movie.len()
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Move the movie to `frame` after a save state was loaded
+    pub(super) fn seek_movie(&mut self, frame: u64) {
+        let Some(session) = self.movie.as_mut() else {
+            return;
+        };
+
+        let frame = frame as usize;
+        if session.read_only {
+            session.mode = if frame < session.movie.len() {
+                MovieMode::Playing
+            } else {
+                MovieMode::Finished
+            };
+        } else {
+            session.movie.truncate(frame);
+            session.movie.add_rerecord();
+            session.mode = MovieMode::Recording;
+        }
+        session.frame = frame;
+        session.pending_commands = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::{Cartridge, Mirroring};
+    use crate::emulator::{EmulatorConfig, SaveState};
+    use crate::input::Controller;
+
+    /// NROM program that reads controller 1 in every NMI
+    ///
+    /// $00 holds the last read, $01 a running sum and $02 the NMI count.
+    const PROGRAM: &[u8] = &[
+        0x78, // E000: SEI
+        0xD8, // E001: CLD
+        0xA2, 0xFF, // E002: LDX #$FF
+        0x9A, // E004: TXS
+        0xA9, 0x80, 0x8D, 0x00, 0x20, // E005: NMI on
+        0x4C, 0x0A, 0xE0, // E00A: JMP $E00A
+        // NMI handler
+        0xA9, 0x01, 0x8D, 0x16, 0x40, // E00D: strobe on
+        0xA9, 0x00, 0x8D, 0x16, 0x40, // E012: strobe off
+        0xA2, 0x08, // E017: LDX #8
+        0xAD, 0x16, 0x40, // E019: LDA $4016
+        0x4A, // E01C: LSR A
+        0x26, 0x00, // E01D: ROL $00
+        0xCA, // E01F: DEX
+        0xD0, 0xF7, // E020: BNE $E019
+        0xA5, 0x00, // E022: LDA $00
+        0x18, // E024: CLC
+        0x65, 0x01, // E025: ADC $01
+        0x85, 0x01, // E027: STA $01
+        0xE6, 0x02, // E029: INC $02
+        0x40, // E02B: RTI
+    ];
+
+    fn create_test_emulator() -> Emulator {
+        let mut prg_rom = vec![0; 32 * 1024];
+        prg_rom[0x6000..0x6000 + PROGRAM.len()].copy_from_slice(PROGRAM);
+        prg_rom[0x7FFA..].copy_from_slice(&[0x0D, 0xE0, 0x00, 0xE0, 0x00, 0xE0]);
+
+        let cartridge = Cartridge {
+            prg_rom,
+            chr_rom: vec![0x55; 8 * 1024],
+            trainer: None,
+            mapper: 0,
+            mirroring: Mirroring::Vertical,
+            has_battery: false,
+            ..Cartridge::new()
+        };
+
+        let mut emulator = Emulator::with_config(EmulatorConfig::default());
+        emulator.insert_cartridge(cartridge).unwrap();
+        emulator
+    }
+
+    /// Input pattern for frame `i`
+    fn input(i: usize) -> Controller {
+        Controller::from_bits((i as u8).wrapping_mul(29) ^ 0x5A)
+    }
+
+    /// Record `frames` frames of `input`, resetting at frame 10
+    fn record(emulator: &mut Emulator, frames: usize) -> Movie {
+        emulator.start_movie_recording().unwrap();
+        for i in 0..frames {
+            if i == 10 {
+                emulator.reset();
+            }
+            emulator.bus_mut().set_controller1(input(i));
+            emulator.run_frame();
+        }
+        emulator.stop_movie().unwrap()
+    }
+
+    #[test]
+    fn test_record_captures_input_and_reset() {
+        let mut emulator = create_test_emulator();
+        let movie = record(&mut emulator, 20);
+
+        assert_eq!(movie.len(), 20);
+        assert_eq!(movie.frames()[3].controller1, input(3));
+        assert_eq!(movie.frames()[10].commands, COMMAND_RESET);
+        assert_eq!(movie.frames()[11].commands, 0);
+        assert_eq!(emulator.bus().ram_contents()[0x02], 20);
+    }
+
+    #[test]
+    fn test_playback_reproduces_recording() {
+        let mut recorder = create_test_emulator();
+        let movie = record(&mut recorder, 30);
+        let expected = recorder.bus().ram_contents().to_vec();
+
+        // Play back through FM2 text, with the player mashing other buttons
+        let movie = Movie::parse(&movie.to_fm2()).unwrap();
+        let mut player = create_test_emulator();
+        for _ in 0..10 {
+            player.run_frame();
+        }
+        player.play_movie(movie, true).unwrap();
+        for _ in 0..30 {
+            player
+                .bus_mut()
+                .set_controller1(Controller::from_bits(0xFF));
+            player.run_frame();
+        }
+
+        assert_eq!(player.movie().unwrap().mode(), MovieMode::Playing);
+        assert_eq!(player.bus().ram_contents(), &expected[..]);
+
+        // The movie has ended; input goes back to the player
+        player.run_frame();
+        assert_eq!(player.movie().unwrap().mode(), MovieMode::Finished);
+    }
+
+    #[test]
+    fn test_play_movie_rejects_other_rom() {
+        let mut emulator = create_test_emulator();
+        let movie = record(&mut emulator, 2);
+
+        let mut other = create_test_emulator();
+        let mut cartridge = other.cartridge.clone().unwrap();
+        cartridge.prg_rom[0] ^= 0xFF;
+        other.insert_cartridge(cartridge).unwrap();
+
+        assert!(matches!(
+            other.play_movie(movie, true),
+            Err(MovieError::RomMismatch { .. })
+        ));
+        assert!(other.movie().is_none());
+    }
+
+    #[test]
+    fn test_load_state_read_write_rerecords() {
+        let mut emulator = create_test_emulator();
+        emulator.start_movie_recording().unwrap();
+        for i in 0..10 {
+            emulator.bus_mut().set_controller1(input(i));
+            emulator.run_frame();
+        }
+        let state = SaveState::from_emulator(&emulator).unwrap();
+        for i in 10..20 {
+            emulator.bus_mut().set_controller1(input(i));
+            emulator.run_frame();
+        }
+
+        state.restore_to_emulator(&mut emulator).unwrap();
+        let session = emulator.movie().unwrap();
+        assert_eq!(session.mode(), MovieMode::Recording);
+        assert_eq!(session.frame(), 10);
+        assert_eq!(session.movie().len(), 10);
+        assert_eq!(session.movie().rerecord_count(), 1);
+
+        // Recording continues from the loaded frame with new input
+        emulator
+            .bus_mut()
+            .set_controller1(Controller::from_bits(0x01));
+        emulator.run_frame();
+        let movie = emulator.stop_movie().unwrap();
+        assert_eq!(movie.len(), 11);
+        assert_eq!(movie.frames()[10].controller1, Controller::from_bits(0x01));
+    }
+
+    #[test]
+    fn test_load_state_read_only_keeps_movie() {
+        let mut recorder = create_test_emulator();
+        let movie = record(&mut recorder, 20);
+
+        let mut player = create_test_emulator();
+        player.play_movie(movie.clone(), true).unwrap();
+        for _ in 0..5 {
+            player.run_frame();
+        }
+        let state = SaveState::from_emulator(&player).unwrap();
+        for _ in 0..10 {
+            player.run_frame();
+        }
+
+        state.restore_to_emulator(&mut player).unwrap();
+        let session = player.movie().unwrap();
+        assert_eq!(session.mode(), MovieMode::Playing);
+        assert_eq!(session.frame(), 5);
+        assert_eq!(session.movie(), &movie);
+
+        // Playback resumes and still ends where the recording did
+        for _ in 5..20 {
+            player.run_frame();
+        }
+        assert_eq!(player.bus().ram_contents(), recorder.bus().ram_contents());
+    }
+
+    #[test]
+    fn test_switch_to_read_write_during_playback() {
+        let mut recorder = create_test_emulator();
+        let movie = record(&mut recorder, 20);
+
+        let mut player = create_test_emulator();
+        player.play_movie(movie, true).unwrap();
+        for _ in 0..8 {
+            player.run_frame();
+        }
+        let state = SaveState::from_emulator(&player).unwrap();
+        player.run_frame();
+
+        player.set_movie_read_only(false);
+        state.restore_to_emulator(&mut player).unwrap();
+        let session = player.movie().unwrap();
+        assert_eq!(session.mode(), MovieMode::Recording);
+        assert_eq!(session.movie().len(), 8);
+        assert_eq!(session.movie().rerecord_count(), 1);
+    }
+
+    #[test]
+    fn test_state_past_movie_end_rejected_in_read_write() {
+        let mut emulator = create_test_emulator();
+        emulator.start_movie_recording().unwrap();
+        for _ in 0..10 {
+            emulator.run_frame();
+        }
+        let state = SaveState::from_emulator(&emulator).unwrap();
+
+        emulator.start_movie_recording().unwrap();
+        assert!(state.restore_to_emulator(&mut emulator).is_err());
+        assert_eq!(emulator.movie().unwrap().frame(), 0);
+    }
+}
@@ -0,0 +1,505 @@
+// Rewind support
+//
+// Keeps a ring of in-memory snapshots taken every few frames so the player can
+// step back in time. Snapshots are the binary save-state payload (see
+// `save_state`) without the timestamp and frame buffer, never written to disk.
+// The screen is redrawn after rewinding by emulating the following frame.
+//
+// Snapshots are stored in groups: each group starts with an RLE-compressed
+// keyframe, and the following snapshots are stored as the XOR against that
+// keyframe, RLE compressed. Consecutive frames differ in only a few bytes, so
+// the deltas are mostly zeros and compress to a fraction of a full snapshot.
+// When the buffer is over its limits the oldest whole group is dropped, since
+// its deltas cannot be decoded without the keyframe.
+
+use super::config::RewindConfig;
+use super::save_state::{SaveState, SaveStateError};
+use super::{Emulator, RunResult};
+use crate::state::{rle_compress, rle_decompress};
+use std::collections::VecDeque;
+
+/// A keyframe and the deltas stored against it
+#[derive(Debug)]
+struct SnapshotGroup {
+    /// RLE-compressed keyframe payload
+    keyframe: Vec<u8>,
+
+    /// Uncompressed payload size shared by every snapshot in the group
+    raw_len: usize,
+
+    /// RLE-compressed XOR of each later snapshot against the keyframe
+    deltas: Vec<Vec<u8>>,
+}
+
+impl SnapshotGroup {
+    /// Number of snapshots in the group (the keyframe plus its deltas)
+    fn len(&self) -> usize {
+        1 + self.deltas.len()
+    }
+
+    /// Bytes held by the group
+    fn memory_used(&self) -> usize {
+        self.keyframe.len() + self.deltas.iter().map(Vec::len).sum::<usize>()
+    }
+}
+
+/// Ring buffer of rewind snapshots
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::emulator::{Emulator, EmulatorConfig};
+///
+/// let mut emulator = Emulator::with_config(EmulatorConfig::default());
+/// for _ in 0..10 {
+///     emulator.run_frame();
+/// }
+/// assert!(!emulator.rewind_buffer().is_empty());
+/// ```
+#[derive(Debug, Default)]
+pub struct RewindBuffer {
+    /// Snapshot groups, oldest first
+    groups: VecDeque<SnapshotGroup>,
+
+    /// Decompressed keyframe of the newest group, for encoding deltas
+    keyframe_cache: Option<Vec<u8>>,
+
+    /// Frames run since the newest snapshot was taken or restored
+    frames_since_snapshot: u32,
+
+    /// Total number of snapshots
+    len: usize,
+
+    /// Total bytes held by all groups
+    memory_used: usize,
+}
+
+impl RewindBuffer {
+    /// Create an empty rewind buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of snapshots in the buffer
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the buffer holds no snapshots
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes used by the stored snapshots
+    pub fn memory_used(&self) -> usize {
+        self.memory_used
+    }
+
+    /// Drop every snapshot
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Count a finished frame
+    ///
+    /// # Returns
+    ///
+    /// true if a snapshot is due
+    fn advance_frame(&mut self, config: &RewindConfig) -> bool {
+        self.frames_since_snapshot += 1;
+        self.frames_since_snapshot >= config.snapshot_interval.max(1)
+    }
+
+    /// Store a snapshot payload as the newest entry
+    fn push(&mut self, payload: Vec<u8>, config: &RewindConfig) {
+        self.frames_since_snapshot = 0;
+
+        let delta = match (self.groups.back_mut(), &self.keyframe_cache) {
+            (Some(group), Some(keyframe))
+                if group.raw_len == payload.len()
+                    && group.len() < config.keyframe_interval.max(1) =>
+            {
+                let delta = rle_compress(&xor(&payload, keyframe));
+                self.memory_used += delta.len();
+                group.deltas.push(delta);
+                true
+            }
+            _ => false,
+        };
+
+        if !delta {
+            let keyframe = rle_compress(&payload);
+            self.memory_used += keyframe.len();
+            self.groups.push_back(SnapshotGroup {
+                keyframe,
+                raw_len: payload.len(),
+                deltas: Vec::new(),
+            });
+            self.keyframe_cache = Some(payload);
+        }
+        self.len += 1;
+
+        self.enforce_limits(config);
+    }
+
+    /// Drop the oldest groups until the buffer fits its limits
+    ///
+    /// The newest group is always kept so rewinding stays possible.
+    fn enforce_limits(&mut self, config: &RewindConfig) {
+        let memory_cap = config.memory_cap_mb.saturating_mul(1024 * 1024);
+        while self.groups.len() > 1
+            && (self.len > config.buffer_length || self.memory_used > memory_cap)
+        {
+            if let Some(group) = self.groups.pop_front() {
+                self.len -= group.len();
+                self.memory_used -= group.memory_used();
+            }
+        }
+    }
+
+    /// Decode the newest snapshot
+    fn latest(&mut self) -> Result<Option<Vec<u8>>, SaveStateError> {
+        let Some(group) = self.groups.back() else {
+            return Ok(None);
+        };
+
+        let keyframe = match self.keyframe_cache.take() {
+            Some(keyframe) => keyframe,
+            None => rle_decompress(&group.keyframe, group.raw_len)?,
+        };
+        let keyframe = &*self.keyframe_cache.insert(keyframe);
+
+        match group.deltas.last() {
+            Some(delta) => Ok(Some(xor(&rle_decompress(delta, group.raw_len)?, keyframe))),
+            None => Ok(Some(keyframe.clone())),
+        }
+    }
+
+    /// Remove the newest snapshot
+    fn pop(&mut self) {
+        let Some(group) = self.groups.back_mut() else {
+            return;
+        };
+
+        match group.deltas.pop() {
+            Some(delta) => self.memory_used -= delta.len(),
+            None => {
+                self.memory_used -= group.keyframe.len();
+                self.groups.pop_back();
+                self.keyframe_cache = None;
+            }
+        }
+        self.len -= 1;
+    }
+}
+
+/// XOR two equally sized payloads
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+impl Emulator {
+    /// Take a rewind snapshot if one is due after the frame that just finished
+    pub(super) fn record_rewind_frame(&mut self) {
+        if !self.config.rewind.enabled || !self.rewind.advance_frame(&self.config.rewind) {
+            return;
+        }
+
+        if let Ok(state) = SaveState::from_emulator_for_rewind(self) {
+            self.rewind.push(state.to_payload(), &self.config.rewind);
+        }
+    }
+
+    /// Step back to the previous rewind snapshot
+    ///
+    /// Each step goes back `snapshot_interval` frames. The restored snapshot
+    /// stays in the buffer until the next step moves past it.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the emulator was rewound, `Ok(false)` if there is no
+    /// older snapshot
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::emulator::{Emulator, EmulatorConfig};
+    ///
+    /// let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    /// for _ in 0..10 {
+    ///     emulator.run_frame();
+    /// }
+    /// assert!(emulator.rewind_step().unwrap());
+    /// ```
+    pub fn rewind_step(&mut self) -> Result<bool, SaveStateError> {
+        // The newest snapshot is the current frame; skip past it
+        if self.rewind.frames_since_snapshot == 0 {
+            if self.rewind.len() <= 1 {
+                return Ok(false);
+            }
+            self.rewind.pop();
+        }
+
+        let Some(payload) = self.rewind.latest()? else {
+            return Ok(false);
+        };
+        SaveState::from_payload(&payload)?.restore_to_emulator(self)?;
+        self.rewind.frames_since_snapshot = 0;
+
+        Ok(true)
+    }
+
+    /// Start or stop rewinding continuously
+    ///
+    /// While held, [`Emulator::run_frame`] steps back one snapshot per call
+    /// instead of running the game. Intended for a frontend's rewind button.
+    ///
+    /// # Arguments
+    ///
+    /// * `held` - Whether the rewind button is held down
+    pub fn rewind_hold(&mut self, held: bool) {
+        self.rewinding = held;
+    }
+
+    /// Check if the emulator is rewinding continuously
+    ///
+    /// # Returns
+    ///
+    /// true while the rewind button is held
+    pub fn is_rewinding(&self) -> bool {
+        self.rewinding
+    }
+
+    /// Get the rewind snapshot buffer
+    pub fn rewind_buffer(&self) -> &RewindBuffer {
+        &self.rewind
+    }
+
+    /// Rewind by one step in place of running a frame
+    pub(super) fn run_rewind_frame(&mut self) -> RunResult {
+        self.bus.clear_audio_samples();
+
+        // A snapshot taken from this emulator that cannot be restored means the
+        // buffer is unusable; drop it rather than keep failing every frame
+        match self.rewind_step() {
+            Ok(true) => self.render_rewound_frame(),
+            Ok(false) => {}
+            Err(_) => self.rewind.clear(),
+        }
+
+        RunResult {
+            cpu_cycles: 0,
+            frame_complete: true,
+            audio_samples: 0,
+            jammed: self.cpu.is_jammed(),
+        }
+    }
+
+    /// Draw the frame that follows the snapshot just restored
+    ///
+    /// Snapshots do not hold the frame buffer, so the frame is emulated and
+    /// the snapshot restored again, which keeps the new frame on screen.
+    fn render_rewound_frame(&mut self) {
+        let Ok(Some(payload)) = self.rewind.latest() else {
+            return;
+        };
+        while !self.execute_instruction().1 {}
+        self.bus.clear_audio_samples();
+
+        if let Ok(state) = SaveState::from_payload(&payload) {
+            let _ = state.restore_to_emulator(self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::save_state::determinism_tests::{create_test_emulator, run_and_record};
+    use super::*;
+
+    fn config(buffer_length: usize, keyframe_interval: usize) -> RewindConfig {
+        RewindConfig {
+            enabled: true,
+            snapshot_interval: 1,
+            buffer_length,
+            keyframe_interval,
+            memory_cap_mb: 64,
+        }
+    }
+
+    #[test]
+    fn test_buffer_push_and_pop_roundtrip() {
+        let config = config(100, 4);
+        let mut buffer = RewindBuffer::new();
+        let payloads: Vec<Vec<u8>> = (0..10u8)
+            .map(|i| {
+                let mut payload = vec![0xAA; 1000];
+                payload[i as usize * 10] = i;
+                payload
+            })
+            .collect();
+
+        for payload in &payloads {
+            buffer.push(payload.clone(), &config);
+        }
+        assert_eq!(buffer.len(), 10);
+        assert_eq!(buffer.groups.len(), 3);
+
+        for payload in payloads.iter().rev() {
+            assert_eq!(buffer.latest().unwrap().as_ref(), Some(payload));
+            buffer.pop();
+        }
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.memory_used(), 0);
+        assert_eq!(buffer.latest().unwrap(), None);
+    }
+
+    #[test]
+    fn test_deltas_are_smaller_than_keyframes() {
+        let config = config(100, 10);
+        let mut buffer = RewindBuffer::new();
+        let mut payload: Vec<u8> = (0..4096).map(|i| (i * 7) as u8).collect();
+
+        buffer.push(payload.clone(), &config);
+        let keyframe_size = buffer.memory_used();
+        payload[100] ^= 0xFF;
+        buffer.push(payload, &config);
+
+        assert!(buffer.memory_used() - keyframe_size < keyframe_size / 10);
+    }
+
+    #[test]
+    fn test_buffer_length_limit_drops_oldest_group() {
+        let config = config(6, 3);
+        let mut buffer = RewindBuffer::new();
+        for i in 0..10u8 {
+            buffer.push(vec![i; 64], &config);
+        }
+
+        // Groups of 3: [0-2] [3-5] [6-8] [9] -> the oldest two are dropped
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.latest().unwrap(), Some(vec![9; 64]));
+    }
+
+    #[test]
+    fn test_memory_cap_keeps_newest_group() {
+        let config = RewindConfig {
+            memory_cap_mb: 0,
+            ..config(100, 2)
+        };
+        let mut buffer = RewindBuffer::new();
+        for i in 0..5u8 {
+            buffer.push(vec![i; 64], &config);
+        }
+
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.latest().unwrap(), Some(vec![4; 64]));
+    }
+
+    #[test]
+    fn test_size_change_starts_new_keyframe() {
+        let config = config(100, 10);
+        let mut buffer = RewindBuffer::new();
+        buffer.push(vec![1; 64], &config);
+        buffer.push(vec![2; 80], &config);
+
+        assert_eq!(buffer.groups.len(), 2);
+        assert_eq!(buffer.latest().unwrap(), Some(vec![2; 80]));
+    }
+
+    #[test]
+    fn test_rewind_step_restores_earlier_frames() {
+        let mut emulator = create_test_emulator();
+        emulator.config_mut().rewind = config(100, 8);
+
+        // Record the machine state after each of 20 frames
+        let states: Vec<String> = (0..20)
+            .map(|_| run_and_record(&mut emulator, 1).2)
+            .collect();
+        assert_eq!(emulator.rewind_buffer().len(), 20);
+
+        // Each step goes back exactly one frame
+        for expected in states.iter().rev().skip(1).take(15) {
+            assert!(emulator.rewind_step().unwrap());
+            assert!(&run_and_record(&mut emulator, 0).2 == expected);
+        }
+    }
+
+    #[test]
+    fn test_rewind_then_replay_is_deterministic() {
+        let mut emulator = create_test_emulator();
+        run_and_record(&mut emulator, 10);
+        let first = run_and_record(&mut emulator, 4);
+
+        // Snapshots are every 2 frames: two steps back lands 4 frames back
+        assert!(emulator.rewind_step().unwrap());
+        assert!(emulator.rewind_step().unwrap());
+        let second = run_and_record(&mut emulator, 4);
+
+        assert!(first == second);
+    }
+
+    #[test]
+    fn test_rewind_hold_runs_backwards() {
+        let mut emulator = create_test_emulator();
+        run_and_record(&mut emulator, 20);
+        let frame = emulator.bus().ppu().frame;
+
+        emulator.rewind_hold(true);
+        assert!(emulator.is_rewinding());
+        let result = emulator.run_frame();
+        assert_eq!(result.cpu_cycles, 0);
+        assert!(emulator.bus().ppu().frame < frame);
+
+        emulator.rewind_hold(false);
+        assert!(!emulator.is_rewinding());
+        assert!(emulator.run_frame().cpu_cycles > 0);
+    }
+
+    #[test]
+    fn test_rewind_hold_redraws_the_screen() {
+        let mut emulator = create_test_emulator();
+        emulator.config_mut().rewind = config(100, 8);
+        let frames: Vec<Vec<u8>> = (0..20)
+            .map(|_| run_and_record(&mut emulator, 1).0)
+            .collect();
+        emulator.bus_mut().ppu_mut().frame_buffer.fill(0xFF);
+
+        // The snapshot after frame 18 is restored and frame 19 drawn again
+        emulator.rewind_hold(true);
+        emulator.run_frame();
+        emulator.run_frame();
+        assert!(emulator.bus().ppu().frame() == frames[18].as_slice());
+        assert!(emulator.audio_samples().is_empty());
+    }
+
+    #[test]
+    fn test_snapshots_omit_timestamp_and_frame() {
+        let mut emulator = create_test_emulator();
+        run_and_record(&mut emulator, 5);
+
+        let state = SaveState::from_emulator_for_rewind(&emulator).unwrap();
+        let saved = SaveState::from_emulator(&emulator).unwrap();
+        assert!(state.to_payload().len() < saved.to_payload().len());
+
+        // Snapshots of the same machine state are identical
+        let payload = state.to_payload();
+        assert_eq!(
+            SaveState::from_emulator_for_rewind(&emulator)
+                .unwrap()
+                .to_payload(),
+            payload
+        );
+    }
+
+    #[test]
+    fn test_rewind_step_on_empty_buffer() {
+        let mut emulator = create_test_emulator();
+        assert!(!emulator.rewind_step().unwrap());
+
+        emulator.config_mut().rewind.enabled = false;
+        run_and_record(&mut emulator, 5);
+        assert!(emulator.rewind_buffer().is_empty());
+        assert!(!emulator.rewind_step().unwrap());
+    }
+}
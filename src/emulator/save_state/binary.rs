@@ -0,0 +1,905 @@
+// Binary save state container
+//
+// Save states are stored as a small header followed by a checksummed,
+// optionally RLE-compressed payload of tagged chunks:
+//
+// | Offset | Size | Field                                         |
+// |--------|------|-----------------------------------------------|
+// | 0      | 4    | Magic "NSST"                                  |
+// | 4      | 2    | Container version                             |
+// | 6      | 1    | Flags (bit 0: payload is RLE compressed)      |
+// | 7      | 4    | Uncompressed payload length                   |
+// | 11     | 4    | CRC32 of the uncompressed payload             |
+// | 15     | 4    | Stored payload length                         |
+// | 19     | ...  | Payload                                       |
+//
+// Each chunk in the payload is a 4-byte tag, a 16-bit chunk version and a
+// length-prefixed body. Every chunk is versioned on its own: a decoder accepts
+// its current version and any older one it knows how to upgrade, rejects
+// newer ones, and unknown chunks are skipped so that adding a chunk does not
+// break older builds. All integers are little-endian.
+
+use super::{
+    BusState, CpuState, MapperStateVersion, PpuState, SaveState, SaveStateError, SAVE_STATE_VERSION,
+};
+use crate::apu::Apu;
+use crate::input::ControllerIO;
+use crate::state::{crc32, rle_compress, rle_decompress, StateError, StateReader, StateWriter};
+
+/// Magic bytes at the start of every binary save state
+pub(super) const MAGIC: &[u8; 4] = b"NSST";
+
+/// Version of the container layout (header and chunk framing)
+const CONTAINER_VERSION: u16 = 1;
+
+/// Header flag: the payload is RLE compressed
+const FLAG_RLE: u8 = 0x01;
+
+/// Size of the fixed container header
+const HEADER_SIZE: usize = 19;
+
+// ========================================
+// Chunks
+// ========================================
+
+/// A chunk tag together with the newest version this build understands
+#[derive(Debug, Clone, Copy)]
+struct Chunk {
+    tag: [u8; 4],
+    version: u16,
+}
+
+impl Chunk {
+    /// Human-readable tag for error messages
+    fn name(&self) -> String {
+        String::from_utf8_lossy(&self.tag).trim_end().to_string()
+    }
+}
+
+/// Timestamp and ROM name
+const CHUNK_HEADER: Chunk = Chunk {
+    tag: *b"HEAD",
+    version: 1,
+};
+
+/// CPU registers
+///
+/// Version 2 adds the jammed flag.
+const CHUNK_CPU: Chunk = Chunk {
+    tag: *b"CPU ",
+    version: 2,
+};
+
+/// PPU registers, timing, render pipeline and frame buffer
+///
+/// Version 2 adds the I/O latch.
+const CHUNK_PPU: Chunk = Chunk {
+    tag: *b"PPU ",
+    version: 2,
+};
+
+/// APU channels and frame counter
+///
+/// The body is `Apu::save_state`, so the version must be bumped whenever that
+/// layout changes.
+const CHUNK_APU: Chunk = Chunk {
+    tag: *b"APU ",
+    version: 1,
+};
+
+/// DMA progress, controller ports and open bus
+///
+/// Version 2 adds the CPU open-bus value; version 3 adds the PPU dot phase;
+/// version 4 adds the pending DMC DMA; version 5 adds the CPU cycle state.
+const CHUNK_BUS: Chunk = Chunk {
+    tag: *b"BUS ",
+    version: 5,
+};
+
+/// Mapper state blob (optional)
+///
+/// Version 2 prefixes the blob with the mapper number and the mapper's own
+/// state version, which are checked against the cartridge when loading.
+/// Version 1 is the bare blob.
+const CHUNK_MAPPER: Chunk = Chunk {
+    tag: *b"MAPR",
+    version: 2,
+};
+
+/// CPU work RAM
+const CHUNK_RAM: Chunk = Chunk {
+    tag: *b"RAM ",
+    version: 1,
+};
+
+/// Nametable VRAM
+const CHUNK_VRAM: Chunk = Chunk {
+    tag: *b"VRAM",
+    version: 1,
+};
+
+/// Palette RAM
+const CHUNK_PALETTE: Chunk = Chunk {
+    tag: *b"PALT",
+    version: 1,
+};
+
+/// Sprite OAM
+const CHUNK_OAM: Chunk = Chunk {
+    tag: *b"OAM ",
+    version: 1,
+};
+
+/// Movie frame (optional)
+const CHUNK_MOVIE: Chunk = Chunk {
+    tag: *b"MOVI",
+    version: 1,
+};
+
+/// Append a chunk with the body produced by `write_body`
+fn write_chunk(payload: &mut StateWriter, chunk: Chunk, write_body: impl FnOnce(&mut StateWriter)) {
+    let mut body = StateWriter::new();
+    write_body(&mut body);
+    payload.write_raw(&chunk.tag);
+    payload.write_u16(chunk.version);
+    payload.write_bytes(&body.into_bytes());
+}
+
+/// Decode a chunk body, requiring the decoder to consume all of it
+fn read_chunk<T>(
+    body: &[u8],
+    read_body: impl FnOnce(&mut StateReader) -> Result<T, StateError>,
+) -> Result<T, StateError> {
+    let mut reader = StateReader::new(body);
+    let value = read_body(&mut reader)?;
+    reader.finish()?;
+    Ok(value)
+}
+
+// ========================================
+// Container
+// ========================================
+
+/// Wrap a chunk payload in the container header, compressing it if that helps
+fn pack(payload: &[u8]) -> Vec<u8> {
+    let compressed = rle_compress(payload);
+    let (flags, stored) = if compressed.len() < payload.len() {
+        (FLAG_RLE, compressed.as_slice())
+    } else {
+        (0, payload)
+    };
+
+    let mut writer = StateWriter::new();
+    writer.write_raw(MAGIC);
+    writer.write_u16(CONTAINER_VERSION);
+    writer.write_u8(flags);
+    writer.write_u32(payload.len() as u32);
+    writer.write_u32(crc32(payload));
+    writer.write_bytes(stored);
+    writer.into_bytes()
+}
+
+/// Validate the container header and checksum and return the chunk payload
+fn unpack(data: &[u8]) -> Result<Vec<u8>, SaveStateError> {
+    if data.len() < HEADER_SIZE || &data[..4] != MAGIC {
+        return Err(SaveStateError::InvalidData(
+            "not a binary save state".to_string(),
+        ));
+    }
+
+    let mut reader = StateReader::new(&data[4..]);
+    let version = reader.read_u16()?;
+    if version != CONTAINER_VERSION {
+        return Err(SaveStateError::VersionMismatch {
+            expected: CONTAINER_VERSION as u32,
+            found: version as u32,
+        });
+    }
+    let flags = reader.read_u8()?;
+    let payload_len = reader.read_u32()? as usize;
+    let checksum = reader.read_u32()?;
+    let stored = reader.read_bytes()?;
+    reader.finish()?;
+
+    let payload = if flags & FLAG_RLE != 0 {
+        rle_decompress(stored, payload_len)?
+    } else if stored.len() == payload_len {
+        stored.to_vec()
+    } else {
+        return Err(SaveStateError::InvalidData(format!(
+            "payload size mismatch: expected {} bytes, found {}",
+            payload_len,
+            stored.len()
+        )));
+    };
+
+    let found = crc32(&payload);
+    if found != checksum {
+        return Err(SaveStateError::ChecksumMismatch {
+            expected: checksum,
+            found,
+        });
+    }
+
+    Ok(payload)
+}
+
+// ========================================
+// Encoding
+// ========================================
+
+impl SaveState {
+    /// Encode this save state in the binary container format
+    ///
+    /// # Returns
+    ///
+    /// The encoded save state
+    pub fn to_bytes(&self) -> Vec<u8> {
+        pack(&self.to_payload())
+    }
+
+    /// Encode the uncompressed chunk payload, without the container header
+    ///
+    /// Used for in-memory snapshots, where the checksum is unnecessary.
+    pub(crate) fn to_payload(&self) -> Vec<u8> {
+        let mut payload = StateWriter::new();
+
+        write_chunk(&mut payload, CHUNK_HEADER, |w| {
+            w.write_bytes(self.timestamp.as_bytes());
+            w.write_bool(self.rom_name.is_some());
+            if let Some(ref rom_name) = self.rom_name {
+                w.write_bytes(rom_name.as_bytes());
+            }
+        });
+        write_chunk(&mut payload, CHUNK_CPU, |w| self.cpu_state.write(w));
+        write_chunk(&mut payload, CHUNK_PPU, |w| self.ppu_state.write(w));
+        write_chunk(&mut payload, CHUNK_APU, |w| self.apu_state.save_state(w));
+        write_chunk(&mut payload, CHUNK_BUS, |w| self.bus_state.write(w));
+        if let Some(ref mapper_state) = self.mapper_state {
+            match self.mapper_version {
+                Some(saved) => write_chunk(&mut payload, CHUNK_MAPPER, |w| {
+                    w.write_u16(saved.mapper);
+                    w.write_u16(saved.version);
+                    w.write_raw(mapper_state);
+                }),
+                // A blob of unknown layout keeps the version 1 framing
+                None => write_chunk(
+                    &mut payload,
+                    Chunk {
+                        version: 1,
+                        ..CHUNK_MAPPER
+                    },
+                    |w| w.write_raw(mapper_state),
+                ),
+            }
+        }
+        write_chunk(&mut payload, CHUNK_RAM, |w| w.write_raw(&self.ram));
+        write_chunk(&mut payload, CHUNK_VRAM, |w| w.write_raw(&self.vram));
+        write_chunk(&mut payload, CHUNK_PALETTE, |w| {
+            w.write_raw(&self.palette_ram)
+        });
+        write_chunk(&mut payload, CHUNK_OAM, |w| w.write_raw(&self.oam));
+        if let Some(movie_frame) = self.movie_frame {
+            write_chunk(&mut payload, CHUNK_MOVIE, |w| w.write_u64(movie_frame));
+        }
+
+        payload.into_bytes()
+    }
+
+    /// Decode a save state from the binary container format
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Bytes produced by `to_bytes`
+    ///
+    /// # Returns
+    ///
+    /// The decoded save state, or an error if the data is corrupt, a required
+    /// chunk is missing, or a chunk was written by a newer version
+    pub fn from_bytes(data: &[u8]) -> Result<Self, SaveStateError> {
+        Self::from_payload(&unpack(data)?)
+    }
+
+    /// Decode a chunk payload produced by `to_payload`
+    pub(crate) fn from_payload(payload: &[u8]) -> Result<Self, SaveStateError> {
+        let mut reader = StateReader::new(payload);
+
+        let mut header = None;
+        let mut cpu_state = None;
+        let mut ppu_state = None;
+        let mut apu_state = None;
+        let mut bus_state = None;
+        let mut mapper_state = None;
+        let mut mapper_version = None;
+        let mut ram = None;
+        let mut vram = None;
+        let mut palette_ram = None;
+        let mut oam = None;
+        let mut movie_frame = None;
+
+        while reader.remaining() > 0 {
+            let mut tag = [0; 4];
+            tag.copy_from_slice(reader.read_raw(4)?);
+            let version = reader.read_u16()?;
+            let body = reader.read_bytes()?;
+
+            let chunk = [
+                CHUNK_HEADER,
+                CHUNK_CPU,
+                CHUNK_PPU,
+                CHUNK_APU,
+                CHUNK_BUS,
+                CHUNK_MAPPER,
+                CHUNK_RAM,
+                CHUNK_VRAM,
+                CHUNK_PALETTE,
+                CHUNK_OAM,
+                CHUNK_MOVIE,
+            ]
+            .into_iter()
+            .find(|chunk| chunk.tag == tag);
+
+            // Chunks from newer builds that this one does not know about
+            let Some(chunk) = chunk else {
+                continue;
+            };
+            if version == 0 || version > chunk.version {
+                return Err(SaveStateError::UnsupportedChunkVersion {
+                    chunk: chunk.name(),
+                    supported: chunk.version,
+                    found: version,
+                });
+            }
+
+            match &chunk.tag {
+                b"HEAD" => {
+                    header = Some(read_chunk(body, |r| {
+                        let timestamp = read_string(r)?;
+                        let rom_name = if r.read_bool()? {
+                            Some(read_string(r)?)
+                        } else {
+                            None
+                        };
+                        Ok((timestamp, rom_name))
+                    })?)
+                }
+                b"CPU " => cpu_state = Some(read_chunk(body, |r| CpuState::read(r, version))?),
+                b"PPU " => ppu_state = Some(read_chunk(body, |r| PpuState::read(r, version))?),
+                b"APU " => {
+                    apu_state = Some(read_chunk(body, |r| {
+                        let mut apu = Apu::new();
+                        apu.load_state(r)?;
+                        Ok(apu)
+                    })?)
+                }
+                b"BUS " => bus_state = Some(read_chunk(body, |r| BusState::read(r, version))?),
+                b"MAPR" => {
+                    let (saved, blob) = read_chunk(body, |r| {
+                        let saved = if version >= 2 {
+                            Some(MapperStateVersion {
+                                mapper: r.read_u16()?,
+                                version: r.read_u16()?,
+                            })
+                        } else {
+                            None
+                        };
+                        Ok((saved, r.read_raw(r.remaining())?.to_vec()))
+                    })?;
+                    mapper_version = saved;
+                    mapper_state = Some(blob);
+                }
+                b"RAM " => ram = Some(body.to_vec()),
+                b"VRAM" => vram = Some(body.to_vec()),
+                b"PALT" => palette_ram = Some(body.to_vec()),
+                b"OAM " => oam = Some(body.to_vec()),
+                b"MOVI" => movie_frame = Some(read_chunk(body, |r| r.read_u64())?),
+                _ => unreachable!("chunk tags are matched above"),
+            }
+        }
+
+        let (timestamp, rom_name) = require(header, CHUNK_HEADER)?;
+        Ok(SaveState {
+            version: SAVE_STATE_VERSION,
+            timestamp,
+            rom_name,
+            cpu_state: require(cpu_state, CHUNK_CPU)?,
+            ppu_state: require(ppu_state, CHUNK_PPU)?,
+            apu_state: require(apu_state, CHUNK_APU)?,
+            bus_state: require(bus_state, CHUNK_BUS)?,
+            ram: require(ram, CHUNK_RAM)?,
+            vram: require(vram, CHUNK_VRAM)?,
+            palette_ram: require(palette_ram, CHUNK_PALETTE)?,
+            oam: require(oam, CHUNK_OAM)?,
+            mapper_state,
+            mapper_version,
+            movie_frame,
+        })
+    }
+}
+
+/// Unwrap a required chunk
+fn require<T>(value: Option<T>, chunk: Chunk) -> Result<T, SaveStateError> {
+    value.ok_or_else(|| SaveStateError::InvalidData(format!("missing {} chunk", chunk.name())))
+}
+
+/// Read a length-prefixed UTF-8 string
+fn read_string(reader: &mut StateReader) -> Result<String, StateError> {
+    String::from_utf8(reader.read_bytes()?.to_vec())
+        .map_err(|_| StateError::Invalid("invalid UTF-8 string".to_string()))
+}
+
+impl CpuState {
+    fn write(&self, writer: &mut StateWriter) {
+        writer.write_u8(self.a);
+        writer.write_u8(self.x);
+        writer.write_u8(self.y);
+        writer.write_u8(self.sp);
+        writer.write_u16(self.pc);
+        writer.write_u8(self.status);
+        writer.write_u64(self.cycles);
+        writer.write_bool(self.irq_inhibit);
+        writer.write_bool(self.jammed);
+    }
+
+    fn read(reader: &mut StateReader, version: u16) -> Result<Self, StateError> {
+        let mut state = CpuState {
+            a: reader.read_u8()?,
+            x: reader.read_u8()?,
+            y: reader.read_u8()?,
+            sp: reader.read_u8()?,
+            pc: reader.read_u16()?,
+            status: reader.read_u8()?,
+            cycles: reader.read_u64()?,
+            irq_inhibit: reader.read_bool()?,
+            jammed: false,
+        };
+        if version >= 2 {
+            state.jammed = reader.read_bool()?;
+        }
+        Ok(state)
+    }
+}
+
+impl PpuState {
+    fn write(&self, writer: &mut StateWriter) {
+        writer.write_u8(self.ppuctrl);
+        writer.write_u8(self.ppumask);
+        writer.write_u8(self.ppustatus);
+        writer.write_u8(self.oam_addr);
+        writer.write_u16(self.v);
+        writer.write_u16(self.t);
+        writer.write_u8(self.fine_x);
+        writer.write_bool(self.write_latch);
+        writer.write_u8(self.read_buffer);
+
+        writer.write_u16(self.scanline);
+        writer.write_u16(self.cycle);
+        writer.write_u64(self.frame);
+        writer.write_bool(self.nmi_pending);
+        writer.write_bool(self.vblank_just_set);
+
+        writer.write_u16(self.bg_pattern_shift_low);
+        writer.write_u16(self.bg_pattern_shift_high);
+        writer.write_u16(self.bg_attribute_shift_low);
+        writer.write_u16(self.bg_attribute_shift_high);
+        writer.write_u8(self.bg_nametable_byte);
+        writer.write_u8(self.bg_attribute_byte);
+        writer.write_u8(self.bg_pattern_low);
+        writer.write_u8(self.bg_pattern_high);
+
+        for &(y, tile, attributes, x) in &self.secondary_oam {
+            writer.write_raw(&[y, tile, attributes, x]);
+        }
+        writer.write_u8(self.sprite_count as u8);
+        writer.write_raw(&self.sprite_pattern_shift_low);
+        writer.write_raw(&self.sprite_pattern_shift_high);
+        writer.write_raw(&self.sprite_attributes);
+        writer.write_raw(&self.sprite_x_positions);
+        writer.write_bool(self.sprite_0_present);
+
+        writer.write_bytes(&self.frame_buffer);
+
+        writer.write_u8(self.io_latch);
+        for &frame in &self.io_latch_refresh {
+            writer.write_u64(frame);
+        }
+    }
+
+    fn read(reader: &mut StateReader, version: u16) -> Result<Self, StateError> {
+        let mut state = PpuState {
+            ppuctrl: reader.read_u8()?,
+            ppumask: reader.read_u8()?,
+            ppustatus: reader.read_u8()?,
+            oam_addr: reader.read_u8()?,
+            v: reader.read_u16()?,
+            t: reader.read_u16()?,
+            fine_x: reader.read_u8()?,
+            write_latch: reader.read_bool()?,
+            read_buffer: reader.read_u8()?,
+
+            scanline: reader.read_u16()?,
+            cycle: reader.read_u16()?,
+            frame: reader.read_u64()?,
+            nmi_pending: reader.read_bool()?,
+            vblank_just_set: reader.read_bool()?,
+
+            bg_pattern_shift_low: reader.read_u16()?,
+            bg_pattern_shift_high: reader.read_u16()?,
+            bg_attribute_shift_low: reader.read_u16()?,
+            bg_attribute_shift_high: reader.read_u16()?,
+            bg_nametable_byte: reader.read_u8()?,
+            bg_attribute_byte: reader.read_u8()?,
+            bg_pattern_low: reader.read_u8()?,
+            bg_pattern_high: reader.read_u8()?,
+
+            ..Default::default()
+        };
+
+        for sprite in state.secondary_oam.iter_mut() {
+            let bytes = reader.read_raw(4)?;
+            *sprite = (bytes[0], bytes[1], bytes[2], bytes[3]);
+        }
+        state.sprite_count = reader.read_u8()? as usize;
+        state
+            .sprite_pattern_shift_low
+            .copy_from_slice(reader.read_raw(8)?);
+        state
+            .sprite_pattern_shift_high
+            .copy_from_slice(reader.read_raw(8)?);
+        state.sprite_attributes.copy_from_slice(reader.read_raw(8)?);
+        state
+            .sprite_x_positions
+            .copy_from_slice(reader.read_raw(8)?);
+        state.sprite_0_present = reader.read_bool()?;
+
+        state.frame_buffer = reader.read_bytes()?.to_vec();
+
+        if version >= 2 {
+            state.io_latch = reader.read_u8()?;
+            for frame in state.io_latch_refresh.iter_mut() {
+                *frame = reader.read_u64()?;
+            }
+        }
+        Ok(state)
+    }
+}
+
+impl BusState {
+    fn write(&self, writer: &mut StateWriter) {
+        writer.write_bool(self.dma_pending);
+        writer.write_u8(self.dma_page);
+        writer.write_u16(self.dma_cycles);
+        writer.write_u16(self.oam_dma_clocks);
+        writer.write_u16(self.dmc_stall_cycles);
+        self.controller_io.save_state(writer);
+        writer.write_u8(self.open_bus);
+        writer.write_u8(self.ppu_dot_phase);
+        writer.write_u16(self.dmc_dma_cycles);
+        writer.write_u64(self.cpu_cycles);
+        writer.write_bool(self.irq_sample);
+        writer.write_bool(self.write_cycle);
+    }
+
+    fn read(reader: &mut StateReader, version: u16) -> Result<Self, StateError> {
+        let mut state = BusState {
+            dma_pending: reader.read_bool()?,
+            dma_page: reader.read_u8()?,
+            dma_cycles: reader.read_u16()?,
+            oam_dma_clocks: reader.read_u16()?,
+            dmc_stall_cycles: reader.read_u16()?,
+            controller_io: ControllerIO::new(),
+            open_bus: 0,
+            ppu_dot_phase: 0,
+            dmc_dma_cycles: 0,
+            cpu_cycles: 0,
+            irq_sample: false,
+            write_cycle: false,
+        };
+        state.controller_io.load_state(reader)?;
+        if version >= 2 {
+            state.open_bus = reader.read_u8()?;
+        }
+        if version >= 3 {
+            state.ppu_dot_phase = reader.read_u8()?;
+        }
+        if version >= 4 {
+            state.dmc_dma_cycles = reader.read_u16()?;
+        }
+        if version >= 5 {
+            state.cpu_cycles = reader.read_u64()?;
+            state.irq_sample = reader.read_bool()?;
+            state.write_cycle = reader.read_bool()?;
+        }
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::determinism_tests::{create_test_emulator, run_and_record};
+    use super::*;
+
+    /// Save state of the MMC3 test program after a few frames
+    fn create_test_state() -> SaveState {
+        let mut emulator = create_test_emulator();
+        run_and_record(&mut emulator, 5);
+        SaveState::from_emulator(&emulator).unwrap()
+    }
+
+    /// Split a chunk payload into (tag, version, body) triples
+    fn chunks(payload: &[u8]) -> Vec<([u8; 4], u16, Vec<u8>)> {
+        let mut reader = StateReader::new(payload);
+        let mut chunks = Vec::new();
+        while reader.remaining() > 0 {
+            let mut tag = [0; 4];
+            tag.copy_from_slice(reader.read_raw(4).unwrap());
+            let version = reader.read_u16().unwrap();
+            chunks.push((tag, version, reader.read_bytes().unwrap().to_vec()));
+        }
+        chunks
+    }
+
+    /// Rebuild a container from (tag, version, body) triples
+    fn rebuild(chunks: &[([u8; 4], u16, Vec<u8>)]) -> Vec<u8> {
+        let mut payload = StateWriter::new();
+        for (tag, version, body) in chunks {
+            payload.write_raw(tag);
+            payload.write_u16(*version);
+            payload.write_bytes(body);
+        }
+        pack(&payload.into_bytes())
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let state = create_test_state();
+        let restored = SaveState::from_bytes(&state.to_bytes()).unwrap();
+
+        assert_eq!(state.to_json().unwrap(), restored.to_json().unwrap());
+    }
+
+    #[test]
+    fn test_binary_roundtrip_restores_emulator() {
+        let mut original = create_test_emulator();
+        run_and_record(&mut original, 5);
+        let bytes = SaveState::from_emulator(&original).unwrap().to_bytes();
+
+        let mut copy = create_test_emulator();
+        SaveState::from_bytes(&bytes)
+            .unwrap()
+            .restore_to_emulator(&mut copy)
+            .unwrap();
+
+        assert!(run_and_record(&mut original, 3) == run_and_record(&mut copy, 3));
+    }
+
+    #[test]
+    fn test_binary_is_compressed() {
+        let state = create_test_state();
+        let bytes = state.to_bytes();
+
+        assert_eq!(bytes[6] & FLAG_RLE, FLAG_RLE);
+        assert!(bytes.len() < state.to_json().unwrap().len() / 10);
+    }
+
+    #[test]
+    fn test_corruption_detected_by_checksum() {
+        let mut bytes = create_test_state().to_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+
+        assert!(SaveState::from_bytes(&bytes).is_err());
+
+        // Corrupt the stored checksum itself
+        let mut bytes = create_test_state().to_bytes();
+        bytes[11] ^= 0xFF;
+        assert!(matches!(
+            SaveState::from_bytes(&bytes),
+            Err(SaveStateError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_non_state_data() {
+        assert!(SaveState::from_bytes(b"{\"version\":2}").is_err());
+        assert!(SaveState::from_bytes(&[]).is_err());
+        assert!(SaveState::from_bytes(MAGIC).is_err());
+    }
+
+    #[test]
+    fn test_unknown_chunk_is_skipped() {
+        let state = create_test_state();
+        let mut chunks = chunks(&unpack(&state.to_bytes()).unwrap());
+        chunks.insert(2, (*b"XTRA", 7, vec![1, 2, 3, 4]));
+
+        let restored = SaveState::from_bytes(&rebuild(&chunks)).unwrap();
+        assert_eq!(state.to_json().unwrap(), restored.to_json().unwrap());
+    }
+
+    #[test]
+    fn test_newer_chunk_version_rejected() {
+        let state = create_test_state();
+        let original = chunks(&unpack(&state.to_bytes()).unwrap());
+
+        // Every chunk checks its version, including the APU and mapper chunks
+        for index in 0..original.len() {
+            let mut chunks = original.clone();
+            let (tag, version, _) = &mut chunks[index];
+            let name = String::from_utf8_lossy(tag).trim_end().to_string();
+            *version += 1;
+
+            match SaveState::from_bytes(&rebuild(&chunks)) {
+                Err(SaveStateError::UnsupportedChunkVersion {
+                    chunk,
+                    supported,
+                    found,
+                }) => {
+                    assert_eq!(chunk, name);
+                    assert_eq!(supported, original[index].1);
+                    assert_eq!(found, original[index].1 + 1);
+                }
+                other => panic!("expected UnsupportedChunkVersion, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_version_1_chunks_still_load() {
+        let mut state = create_test_state();
+        state.bus_state.open_bus = 0x40;
+        state.ppu_state.io_latch = 0x1F;
+        let mut chunks = chunks(&unpack(&state.to_bytes()).unwrap());
+
+        // Version 1 CPU, PPU and BUS chunks end before the jammed flag and the
+        // open-bus fields
+        for chunk in chunks.iter_mut() {
+            let trailing = match &chunk.0 {
+                b"PPU " => 1 + 8 * 8,
+                b"CPU " => 1,
+                b"BUS " => 2 + 2 + 10,
+                _ => continue,
+            };
+            chunk.1 = 1;
+            chunk.2.truncate(chunk.2.len() - trailing);
+        }
+
+        let restored = SaveState::from_bytes(&rebuild(&chunks)).unwrap();
+        assert_eq!(restored.bus_state.open_bus, 0);
+        assert_eq!(restored.ppu_state.io_latch, 0);
+        assert_eq!(restored.ppu_state.frame, state.ppu_state.frame);
+    }
+
+    #[test]
+    fn test_version_2_bus_chunk_still_loads() {
+        let mut state = create_test_state();
+        state.bus_state.open_bus = 0x40;
+        state.bus_state.ppu_dot_phase = 3;
+        let mut chunks = chunks(&unpack(&state.to_bytes()).unwrap());
+
+        // A version 2 BUS chunk ends before the PPU dot phase
+        let bus = chunks.iter_mut().find(|c| &c.0 == b"BUS ").unwrap();
+        bus.1 = 2;
+        bus.2.truncate(bus.2.len() - 1 - 2 - 10);
+
+        let restored = SaveState::from_bytes(&rebuild(&chunks)).unwrap();
+        assert_eq!(restored.bus_state.open_bus, 0x40);
+        assert_eq!(restored.bus_state.ppu_dot_phase, 0);
+    }
+
+    #[test]
+    fn test_version_3_bus_chunk_still_loads() {
+        let mut state = create_test_state();
+        state.bus_state.ppu_dot_phase = 3;
+        state.bus_state.dmc_dma_cycles = 4;
+        let mut chunks = chunks(&unpack(&state.to_bytes()).unwrap());
+
+        // A version 3 BUS chunk ends before the pending DMC DMA
+        let bus = chunks.iter_mut().find(|c| &c.0 == b"BUS ").unwrap();
+        bus.1 = 3;
+        bus.2.truncate(bus.2.len() - 2 - 10);
+
+        let restored = SaveState::from_bytes(&rebuild(&chunks)).unwrap();
+        assert_eq!(restored.bus_state.ppu_dot_phase, 3);
+        assert_eq!(restored.bus_state.dmc_dma_cycles, 0);
+    }
+
+    #[test]
+    fn test_version_4_bus_chunk_still_loads() {
+        let mut state = create_test_state();
+        state.bus_state.dmc_dma_cycles = 4;
+        state.bus_state.irq_sample = true;
+        let mut chunks = chunks(&unpack(&state.to_bytes()).unwrap());
+
+        // A version 4 BUS chunk ends before the CPU cycle state
+        let bus = chunks.iter_mut().find(|c| &c.0 == b"BUS ").unwrap();
+        bus.1 = 4;
+        bus.2.truncate(bus.2.len() - 10);
+
+        let restored = SaveState::from_bytes(&rebuild(&chunks)).unwrap();
+        assert_eq!(restored.bus_state.dmc_dma_cycles, 4);
+        assert!(!restored.bus_state.irq_sample);
+    }
+
+    #[test]
+    fn test_missing_chunk_rejected() {
+        let state = create_test_state();
+        let mut chunks = chunks(&unpack(&state.to_bytes()).unwrap());
+        chunks.retain(|c| &c.0 != b"APU ");
+
+        assert!(matches!(
+            SaveState::from_bytes(&rebuild(&chunks)),
+            Err(SaveStateError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_mapper_chunk_is_optional() {
+        let mut state = create_test_state();
+        state.mapper_state = None;
+
+        let restored = SaveState::from_bytes(&state.to_bytes()).unwrap();
+        assert!(restored.mapper_state.is_none());
+    }
+
+    #[test]
+    fn test_movie_frame_round_trip() {
+        let mut state = create_test_state();
+        assert!(state.movie_frame.is_none());
+        let restored = SaveState::from_bytes(&state.to_bytes()).unwrap();
+        assert!(restored.movie_frame.is_none());
+
+        state.movie_frame = Some(1234);
+        let restored = SaveState::from_bytes(&state.to_bytes()).unwrap();
+        assert_eq!(restored.movie_frame, Some(1234));
+    }
+
+    #[test]
+    fn test_mapper_chunk_records_mapper_and_version() {
+        let state = create_test_state();
+        assert_eq!(
+            state.mapper_version,
+            Some(MapperStateVersion {
+                mapper: 4,
+                version: 1
+            })
+        );
+
+        let restored = SaveState::from_bytes(&state.to_bytes()).unwrap();
+        assert_eq!(restored.mapper_version, state.mapper_version);
+        assert_eq!(restored.mapper_state, state.mapper_state);
+    }
+
+    #[test]
+    fn test_version_1_mapper_chunk_still_loads() {
+        let state = create_test_state();
+        let mut chunks = chunks(&unpack(&state.to_bytes()).unwrap());
+
+        // A version 1 MAPR chunk is the bare blob
+        let mapper = chunks.iter_mut().find(|c| &c.0 == b"MAPR").unwrap();
+        mapper.1 = 1;
+        mapper.2.drain(..4);
+
+        let restored = SaveState::from_bytes(&rebuild(&chunks)).unwrap();
+        assert_eq!(restored.mapper_version, None);
+        assert_eq!(restored.mapper_state, state.mapper_state);
+    }
+
+    #[test]
+    fn test_decode_accepts_json() {
+        let state = create_test_state();
+        let json = state.to_json().unwrap();
+
+        let from_json = SaveState::decode(json.as_bytes()).unwrap();
+        let from_binary = SaveState::decode(&state.to_bytes()).unwrap();
+        assert_eq!(from_json.to_json().unwrap(), json);
+        assert_eq!(from_binary.to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn test_decode_accepts_version_1_json() {
+        let state = SaveState::decode(super::super::tests::v1_json().as_bytes()).unwrap();
+        assert_eq!(state.version, SAVE_STATE_VERSION);
+        assert_eq!(state.cpu_state.pc, 0x8123);
+        assert_eq!(state.ram[0x10], 0x42);
+    }
+}
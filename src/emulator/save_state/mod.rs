@@ -0,0 +1,1320 @@
+// Save state functionality
+//
+// Implements serialization and deserialization of the complete emulator state
+// to enable save states and quick save/load functionality.
+//
+// Save state files use the compact binary container in `binary`, where every
+// chunk is versioned on its own so older files keep loading. JSON is kept as a
+// human-readable debug export; version 1 JSON files, the original save state
+// format, are upgraded by `v1` when loaded.
+
+mod binary;
+mod v1;
+
+use crate::apu::Apu;
+use crate::cartridge::mappers::MapperError;
+use crate::input::ControllerIO;
+use crate::state::StateError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Errors that can occur during save state operations
+#[derive(Debug)]
+pub enum SaveStateError {
+    /// I/O error
+    Io(io::Error),
+
+    /// Serialization/deserialization error
+    Serialization(serde_json::Error),
+
+    /// Save state version mismatch
+    VersionMismatch { expected: u32, found: u32 },
+
+    /// No ROM loaded
+    NoRomLoaded,
+
+    /// Saved data does not fit the running emulator (e.g. memory size mismatch)
+    InvalidData(String),
+
+    /// Mapper state could not be restored
+    Mapper(MapperError),
+
+    /// The save state payload does not match its checksum
+    ChecksumMismatch { expected: u32, found: u32 },
+
+    /// A chunk, or the mapper state inside it, has a version this build
+    /// cannot read
+    UnsupportedChunkVersion {
+        chunk: String,
+        supported: u16,
+        found: u16,
+    },
+}
+
+impl std::fmt::Display for SaveStateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveStateError::Io(e) => write!(f, "I/O error: {}", e),
+            SaveStateError::Serialization(e) => write!(f, "Serialization error: {}", e),
+            SaveStateError::VersionMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Version mismatch: expected {}, found {}",
+                    expected, found
+                )
+            }
+            SaveStateError::NoRomLoaded => write!(f, "No ROM loaded"),
+            SaveStateError::InvalidData(msg) => write!(f, "Invalid save state: {}", msg),
+            SaveStateError::Mapper(e) => write!(f, "Mapper state error: {}", e),
+            SaveStateError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "Checksum mismatch: expected {:08X}, found {:08X}",
+                expected, found
+            ),
+            SaveStateError::UnsupportedChunkVersion {
+                chunk,
+                supported,
+                found,
+            } => write!(
+                f,
+                "Unsupported {} chunk version {} (newest supported is {})",
+                chunk, found, supported
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveStateError {}
+
+impl From<io::Error> for SaveStateError {
+    fn from(e: io::Error) -> Self {
+        SaveStateError::Io(e)
+    }
+}
+
+impl From<MapperError> for SaveStateError {
+    fn from(e: MapperError) -> Self {
+        SaveStateError::Mapper(e)
+    }
+}
+
+impl From<StateError> for SaveStateError {
+    fn from(e: StateError) -> Self {
+        SaveStateError::InvalidData(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for SaveStateError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveStateError::Serialization(e)
+    }
+}
+
+/// Version of the JSON save state layout
+///
+/// Binary save states are versioned per chunk instead; see `binary`.
+const SAVE_STATE_VERSION: u32 = 2;
+
+/// Complete emulator save state
+///
+/// Contains all the state needed to restore the emulator to an exact point in time.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveState {
+    /// JSON layout version for compatibility checking
+    version: u32,
+
+    /// Timestamp when the save state was created
+    timestamp: String,
+
+    /// ROM identifier (file name for validation)
+    rom_name: Option<String>,
+
+    /// CPU state
+    cpu_state: CpuState,
+
+    /// PPU state
+    ppu_state: PpuState,
+
+    /// APU state (every channel, the frame counter and the DMC)
+    apu_state: Apu,
+
+    /// Bus state (DMA progress and controller ports)
+    bus_state: BusState,
+
+    /// RAM contents
+    ram: Vec<u8>,
+
+    /// VRAM contents (nametables)
+    vram: Vec<u8>,
+
+    /// Palette RAM
+    palette_ram: Vec<u8>,
+
+    /// OAM (sprite memory)
+    oam: Vec<u8>,
+
+    /// Mapper state blob (bank registers, IRQ counters, PRG-RAM, CHR-RAM)
+    mapper_state: Option<Vec<u8>>,
+
+    /// Mapper and blob layout that `mapper_state` was written by
+    ///
+    /// Missing from states saved before mapper blobs were versioned.
+    #[serde(default)]
+    mapper_version: Option<MapperStateVersion>,
+
+    /// Movie frame at the time of the save, if a movie was active
+    #[serde(default)]
+    movie_frame: Option<u64>,
+}
+
+/// CPU state for serialization
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CpuState {
+    a: u8,
+    x: u8,
+    y: u8,
+    sp: u8,
+    pc: u16,
+    status: u8,
+    cycles: u64,
+    irq_inhibit: bool,
+    #[serde(default)]
+    jammed: bool,
+}
+
+/// PPU state for serialization
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PpuState {
+    // PPU registers
+    ppuctrl: u8,
+    ppumask: u8,
+    ppustatus: u8,
+    oam_addr: u8,
+
+    // Internal scroll registers
+    v: u16,
+    t: u16,
+    fine_x: u8,
+    write_latch: bool,
+    read_buffer: u8,
+
+    // I/O latch (PPU open bus)
+    #[serde(default)]
+    io_latch: u8,
+    #[serde(default)]
+    io_latch_refresh: [u64; 8],
+
+    // Timing
+    scanline: u16,
+    cycle: u16,
+    frame: u64,
+    nmi_pending: bool,
+    vblank_just_set: bool,
+
+    // Background pipeline
+    bg_pattern_shift_low: u16,
+    bg_pattern_shift_high: u16,
+    bg_attribute_shift_low: u16,
+    bg_attribute_shift_high: u16,
+    bg_nametable_byte: u8,
+    bg_attribute_byte: u8,
+    bg_pattern_low: u8,
+    bg_pattern_high: u8,
+
+    // Sprite pipeline
+    secondary_oam: [(u8, u8, u8, u8); 8],
+    sprite_count: usize,
+    sprite_pattern_shift_low: [u8; 8],
+    sprite_pattern_shift_high: [u8; 8],
+    sprite_attributes: [u8; 8],
+    sprite_x_positions: [u8; 8],
+    sprite_0_present: bool,
+
+    /// Last rendered frame, so the screen is correct right after loading
+    ///
+    /// Empty for rewind snapshots and for states upgraded from version 1,
+    /// which did not save it.
+    frame_buffer: Vec<u8>,
+}
+
+/// Identifies the layout of a mapper state blob
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct MapperStateVersion {
+    /// iNES mapper number of the cartridge
+    mapper: u16,
+    /// `Mapper::state_version` of the mapper that wrote the blob
+    version: u16,
+}
+
+/// Bus state for serialization
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BusState {
+    dma_pending: bool,
+    dma_page: u8,
+    dma_cycles: u16,
+    oam_dma_clocks: u16,
+    dmc_stall_cycles: u16,
+    controller_io: ControllerIO,
+    #[serde(default)]
+    open_bus: u8,
+    #[serde(default)]
+    ppu_dot_phase: u8,
+    #[serde(default)]
+    dmc_dma_cycles: u16,
+    #[serde(default)]
+    cpu_cycles: u64,
+    #[serde(default)]
+    irq_sample: bool,
+    #[serde(default)]
+    write_cycle: bool,
+}
+
+impl SaveState {
+    /// Create a save state from the current emulator state
+    ///
+    /// # Arguments
+    ///
+    /// * `emulator` - Reference to the emulator
+    ///
+    /// # Returns
+    ///
+    /// Result containing the save state or an error
+    pub fn from_emulator(emulator: &super::Emulator) -> Result<Self, SaveStateError> {
+        let mut state = Self::from_emulator_for_rewind(emulator)?;
+        state.timestamp = chrono::Local::now().to_rfc3339();
+        state.ppu_state.frame_buffer = emulator.bus().ppu().frame_buffer.to_vec();
+        Ok(state)
+    }
+
+    /// Create a rewind snapshot from the current emulator state
+    ///
+    /// Like [`SaveState::from_emulator`], but without the timestamp and the
+    /// frame buffer: they are not needed to resume emulation, and leaving them
+    /// out keeps consecutive snapshots nearly identical for delta compression.
+    pub(super) fn from_emulator_for_rewind(
+        emulator: &super::Emulator,
+    ) -> Result<Self, SaveStateError> {
+        let cpu = emulator.cpu();
+        let bus = emulator.bus();
+
+        // Get ROM name for validation
+        let rom_name = emulator
+            .rom_path()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .map(|s| s.to_string());
+
+        // Capture CPU state
+        let cpu_state = CpuState {
+            a: cpu.a,
+            x: cpu.x,
+            y: cpu.y,
+            sp: cpu.sp,
+            pc: cpu.pc,
+            status: cpu.status,
+            cycles: cpu.cycles,
+            irq_inhibit: cpu.irq_inhibit,
+            jammed: cpu.jammed,
+        };
+
+        // Capture PPU state
+        let ppu = bus.ppu();
+        let ppu_state = PpuState {
+            ppuctrl: ppu.ppuctrl,
+            ppumask: ppu.ppumask,
+            ppustatus: ppu.ppustatus,
+            oam_addr: ppu.oam_addr,
+            v: ppu.v,
+            t: ppu.t,
+            fine_x: ppu.fine_x,
+            write_latch: ppu.write_latch,
+            read_buffer: ppu.read_buffer,
+            io_latch: ppu.io_latch,
+            io_latch_refresh: ppu.io_latch_refresh,
+            scanline: ppu.scanline,
+            cycle: ppu.cycle,
+            frame: ppu.frame,
+            nmi_pending: ppu.nmi_pending,
+            vblank_just_set: ppu.vblank_just_set,
+            bg_pattern_shift_low: ppu.bg_pattern_shift_low,
+            bg_pattern_shift_high: ppu.bg_pattern_shift_high,
+            bg_attribute_shift_low: ppu.bg_attribute_shift_low,
+            bg_attribute_shift_high: ppu.bg_attribute_shift_high,
+            bg_nametable_byte: ppu.bg_nametable_byte,
+            bg_attribute_byte: ppu.bg_attribute_byte,
+            bg_pattern_low: ppu.bg_pattern_low,
+            bg_pattern_high: ppu.bg_pattern_high,
+            secondary_oam: ppu.secondary_oam,
+            sprite_count: ppu.sprite_count,
+            sprite_pattern_shift_low: ppu.sprite_pattern_shift_low,
+            sprite_pattern_shift_high: ppu.sprite_pattern_shift_high,
+            sprite_attributes: ppu.sprite_attributes,
+            sprite_x_positions: ppu.sprite_x_positions,
+            sprite_0_present: ppu.sprite_0_present,
+            frame_buffer: Vec::new(),
+        };
+
+        // Capture bus state
+        let bus_state = BusState {
+            dma_pending: bus.dma_pending,
+            dma_page: bus.dma_page,
+            dma_cycles: bus.dma_cycles,
+            oam_dma_clocks: bus.oam_dma_clocks,
+            dmc_stall_cycles: bus.dmc_stall_cycles,
+            controller_io: bus.controller_io.clone(),
+            open_bus: bus.open_bus,
+            ppu_dot_phase: bus.ppu_dot_phase,
+            dmc_dma_cycles: bus.dmc_dma_cycles,
+            cpu_cycles: bus.cpu_cycles,
+            irq_sample: bus.irq_sample,
+            write_cycle: bus.write_cycle,
+        };
+
+        // Capture memory
+        let ram = bus.ram_contents().to_vec();
+        let vram = ppu.nametables.to_vec();
+        let palette_ram = ppu.palette_ram.to_vec();
+        let oam = ppu.oam.to_vec();
+        let mapper_state = bus.mapper().map(|mapper| mapper.borrow().save_state());
+        let mapper_version = emulator
+            .cartridge()
+            .zip(bus.mapper())
+            .map(|(cartridge, mapper)| MapperStateVersion {
+                mapper: cartridge.mapper,
+                version: mapper.borrow().state_version(),
+            });
+        let movie_frame = emulator.movie().map(|session| session.frame() as u64);
+
+        Ok(SaveState {
+            version: SAVE_STATE_VERSION,
+            timestamp: String::new(),
+            rom_name,
+            cpu_state,
+            ppu_state,
+            apu_state: bus.apu().clone(),
+            bus_state,
+            ram,
+            vram,
+            palette_ram,
+            oam,
+            mapper_state,
+            mapper_version,
+            movie_frame,
+        })
+    }
+
+    /// Restore emulator state from this save state
+    ///
+    /// Everything is validated before the emulator is touched, so a rejected
+    /// save state leaves the running game untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `emulator` - Mutable reference to the emulator
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or error
+    pub fn restore_to_emulator(
+        &self,
+        emulator: &mut super::Emulator,
+    ) -> Result<(), SaveStateError> {
+        // Validate array sizes before copying to prevent panics
+        let ppu = emulator.bus().ppu();
+        if self.ram.len() != emulator.bus().ram_contents().len()
+            || self.vram.len() != ppu.nametables.len()
+            || self.palette_ram.len() != ppu.palette_ram.len()
+            || self.oam.len() != ppu.oam.len()
+            || !(self.ppu_state.frame_buffer.is_empty()
+                || self.ppu_state.frame_buffer.len() == ppu.frame_buffer.len())
+        {
+            return Err(SaveStateError::InvalidData(format!(
+                "memory size mismatch: ram={}, vram={} (expected {}), palette={} (expected {}), oam={} (expected {}), frame={}",
+                self.ram.len(),
+                self.vram.len(),
+                ppu.nametables.len(),
+                self.palette_ram.len(),
+                ppu.palette_ram.len(),
+                self.oam.len(),
+                ppu.oam.len(),
+                self.ppu_state.frame_buffer.len()
+            )));
+        }
+
+        if let Some(frame) = self.movie_frame {
+            emulator
+                .check_movie_frame(frame)
+                .map_err(SaveStateError::InvalidData)?;
+        }
+
+        // Restore the mapper first: it is the only part that can still fail
+        if let Some(ref mapper_state) = self.mapper_state {
+            let mapper = emulator.bus().mapper().ok_or(SaveStateError::NoRomLoaded)?;
+            if let Some(saved) = self.mapper_version {
+                if let Some(cartridge) = emulator.cartridge() {
+                    if cartridge.mapper != saved.mapper {
+                        return Err(SaveStateError::InvalidData(format!(
+                            "state is for mapper {}, but the cartridge uses mapper {}",
+                            saved.mapper, cartridge.mapper
+                        )));
+                    }
+                }
+                let supported = mapper.borrow().state_version();
+                if saved.version != supported {
+                    return Err(SaveStateError::UnsupportedChunkVersion {
+                        chunk: "MAPR".to_string(),
+                        supported,
+                        found: saved.version,
+                    });
+                }
+            }
+            mapper.borrow_mut().load_state(mapper_state)?;
+        }
+
+        // Restore CPU state
+        let cpu = emulator.cpu_mut();
+        cpu.a = self.cpu_state.a;
+        cpu.x = self.cpu_state.x;
+        cpu.y = self.cpu_state.y;
+        cpu.sp = self.cpu_state.sp;
+        cpu.pc = self.cpu_state.pc;
+        cpu.status = self.cpu_state.status;
+        cpu.cycles = self.cpu_state.cycles;
+        cpu.irq_inhibit = self.cpu_state.irq_inhibit;
+        cpu.jammed = self.cpu_state.jammed;
+
+        // Restore bus/memory state
+        let bus = emulator.bus_mut();
+        bus.restore_ram_contents(&self.ram);
+        bus.dma_pending = self.bus_state.dma_pending;
+        bus.dma_page = self.bus_state.dma_page;
+        bus.dma_cycles = self.bus_state.dma_cycles;
+        bus.oam_dma_clocks = self.bus_state.oam_dma_clocks;
+        bus.dmc_stall_cycles = self.bus_state.dmc_stall_cycles;
+        bus.controller_io = self.bus_state.controller_io.clone();
+        bus.open_bus = self.bus_state.open_bus;
+        bus.ppu_dot_phase = self.bus_state.ppu_dot_phase;
+        bus.dmc_dma_cycles = self.bus_state.dmc_dma_cycles;
+        bus.cpu_cycles = self.bus_state.cpu_cycles;
+        bus.irq_sample = self.bus_state.irq_sample;
+        bus.write_cycle = self.bus_state.write_cycle;
+
+        // Restore APU state (the region belongs to the console, not the state)
+        let region = bus.region();
+        *bus.apu_mut() = self.apu_state.clone();
+        bus.apu_mut().set_region(region);
+
+        // The PPU's mirroring follows the (restored) mapper
+        let mirroring = bus.mapper().map(|mapper| mapper.borrow().mirroring());
+
+        // Restore PPU state
+        let ppu = bus.ppu_mut();
+        ppu.ppuctrl = self.ppu_state.ppuctrl;
+        ppu.ppumask = self.ppu_state.ppumask;
+        ppu.ppustatus = self.ppu_state.ppustatus;
+        ppu.oam_addr = self.ppu_state.oam_addr;
+        ppu.v = self.ppu_state.v;
+        ppu.t = self.ppu_state.t;
+        ppu.fine_x = self.ppu_state.fine_x;
+        ppu.write_latch = self.ppu_state.write_latch;
+        ppu.read_buffer = self.ppu_state.read_buffer;
+        ppu.io_latch = self.ppu_state.io_latch;
+        ppu.io_latch_refresh = self.ppu_state.io_latch_refresh;
+        ppu.scanline = self.ppu_state.scanline;
+        ppu.cycle = self.ppu_state.cycle;
+        ppu.frame = self.ppu_state.frame;
+        ppu.nmi_pending = self.ppu_state.nmi_pending;
+        ppu.vblank_just_set = self.ppu_state.vblank_just_set;
+        ppu.bg_pattern_shift_low = self.ppu_state.bg_pattern_shift_low;
+        ppu.bg_pattern_shift_high = self.ppu_state.bg_pattern_shift_high;
+        ppu.bg_attribute_shift_low = self.ppu_state.bg_attribute_shift_low;
+        ppu.bg_attribute_shift_high = self.ppu_state.bg_attribute_shift_high;
+        ppu.bg_nametable_byte = self.ppu_state.bg_nametable_byte;
+        ppu.bg_attribute_byte = self.ppu_state.bg_attribute_byte;
+        ppu.bg_pattern_low = self.ppu_state.bg_pattern_low;
+        ppu.bg_pattern_high = self.ppu_state.bg_pattern_high;
+        ppu.secondary_oam = self.ppu_state.secondary_oam;
+        ppu.sprite_count = self.ppu_state.sprite_count;
+        ppu.sprite_pattern_shift_low = self.ppu_state.sprite_pattern_shift_low;
+        ppu.sprite_pattern_shift_high = self.ppu_state.sprite_pattern_shift_high;
+        ppu.sprite_attributes = self.ppu_state.sprite_attributes;
+        ppu.sprite_x_positions = self.ppu_state.sprite_x_positions;
+        ppu.sprite_0_present = self.ppu_state.sprite_0_present;
+        if !self.ppu_state.frame_buffer.is_empty() {
+            ppu.frame_buffer
+                .copy_from_slice(&self.ppu_state.frame_buffer);
+        }
+        ppu.nametables.copy_from_slice(&self.vram);
+        ppu.palette_ram.copy_from_slice(&self.palette_ram);
+        ppu.oam.copy_from_slice(&self.oam);
+        if let Some(mirroring) = mirroring {
+            ppu.set_mirroring(mirroring);
+        }
+
+        // Rebuild the jam report of a state saved while jammed
+        emulator.jam = None;
+        emulator.record_jam();
+
+        // Resume the movie from the frame the state was saved at
+        if let Some(frame) = self.movie_frame {
+            emulator.seek_movie(frame);
+        }
+
+        Ok(())
+    }
+
+    /// Save this save state to a file
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - Save slot number (0-9)
+    /// * `rom_path` - Optional path to the currently loaded ROM (for naming)
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or error
+    pub fn save_to_file(&self, slot: u8, rom_path: Option<&Path>) -> Result<(), SaveStateError> {
+        let save_dir = Self::get_save_directory(rom_path)?;
+        fs::create_dir_all(&save_dir)?;
+
+        let file_path = save_dir.join(format!("slot_{}.state", slot));
+        fs::write(file_path, self.to_bytes())?;
+
+        Ok(())
+    }
+
+    /// Load a save state from a file
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - Save slot number (0-9)
+    /// * `rom_path` - Optional path to the currently loaded ROM (for naming)
+    ///
+    /// # Returns
+    ///
+    /// Result containing the save state or an error
+    pub fn load_from_file(slot: u8, rom_path: Option<&Path>) -> Result<Self, SaveStateError> {
+        let save_dir = Self::get_save_directory(rom_path)?;
+        let file_path = save_dir.join(format!("slot_{}.state", slot));
+
+        let data = fs::read(file_path)?;
+        Self::decode(&data)
+    }
+
+    /// Decode a save state file in either the binary or the JSON format
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Contents of a save state file
+    ///
+    /// # Returns
+    ///
+    /// Result containing the save state or an error
+    pub fn decode(data: &[u8]) -> Result<Self, SaveStateError> {
+        if data.starts_with(binary::MAGIC) {
+            Self::from_bytes(data)
+        } else {
+            let json = std::str::from_utf8(data)
+                .map_err(|_| SaveStateError::InvalidData("not a save state file".to_string()))?;
+            Self::from_json(json)
+        }
+    }
+
+    /// Export this save state as pretty-printed JSON for debugging
+    ///
+    /// # Returns
+    ///
+    /// Result containing the JSON text or an error
+    pub fn to_json(&self) -> Result<String, SaveStateError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a save state from JSON
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - JSON produced by `to_json`, or a version 1 save state file
+    ///
+    /// # Returns
+    ///
+    /// Result containing the save state, or `VersionMismatch` if the JSON
+    /// was written by a newer version
+    pub fn from_json(json: &str) -> Result<Self, SaveStateError> {
+        /// Just enough of any layout to tell which one it is
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+
+        match serde_json::from_str::<Version>(json)?.version {
+            1 => Ok(serde_json::from_str::<v1::SaveStateV1>(json)?.upgrade()),
+            SAVE_STATE_VERSION => Ok(serde_json::from_str(json)?),
+            found => Err(SaveStateError::VersionMismatch {
+                expected: SAVE_STATE_VERSION,
+                found,
+            }),
+        }
+    }
+
+    /// Export this save state as JSON to `path`
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Destination file
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or error
+    pub fn export_json(&self, path: &Path) -> Result<(), SaveStateError> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Get the save directory for the current ROM
+    ///
+    /// Creates a directory structure like: saves/<rom_name>/
+    fn get_save_directory(rom_path: Option<&Path>) -> Result<PathBuf, SaveStateError> {
+        let base_dir = PathBuf::from("saves");
+
+        if let Some(rom_path) = rom_path {
+            if let Some(rom_name) = rom_path.file_stem() {
+                Ok(base_dir.join(rom_name))
+            } else {
+                Ok(base_dir.join("default"))
+            }
+        } else {
+            // No ROM loaded, use default directory
+            Ok(base_dir.join("default"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::{Emulator, EmulatorConfig};
+    use std::path::PathBuf;
+
+    /// A save state in the version 1 layout, as the original JSON format wrote it
+    pub(super) fn v1_json() -> String {
+        let mut ram = vec![0; 2048];
+        ram[0x10] = 0x42;
+
+        let state = serde_json::json!({
+            "version": 1,
+            "timestamp": "2024-03-01T20:15:00.123456789+01:00",
+            "rom_name": "game.nes",
+            "cpu_state": {
+                "a": 0x12, "x": 0x34, "y": 0x56, "sp": 0xFB,
+                "pc": 0x8123, "status": 0x24, "cycles": 123_456
+            },
+            "ppu_state": {
+                "ppuctrl": 0x90, "ppumask": 0x1E, "ppustatus": 0x00, "oam_addr": 0x00,
+                "v": 0x2042, "t": 0x2040, "fine_x": 3, "write_latch": false,
+                "read_buffer": 0x00, "scanline": 100, "cycle": 200, "frame": 42
+            },
+            "apu_state": { "placeholder": 0 },
+            "ram": ram,
+            "vram": vec![0x24; 2048],
+            "palette_ram": vec![0x0F; 32],
+            "oam": vec![0xFF; 256],
+            "cartridge_ram": null
+        });
+        serde_json::to_string_pretty(&state).unwrap()
+    }
+
+    #[test]
+    fn test_from_json_upgrades_version_1() {
+        let state = SaveState::from_json(&v1_json()).unwrap();
+        assert_eq!(state.version, SAVE_STATE_VERSION);
+        assert_eq!(state.rom_name.as_deref(), Some("game.nes"));
+
+        let mut emulator = Emulator::with_config(EmulatorConfig::default());
+        state.restore_to_emulator(&mut emulator).unwrap();
+
+        let cpu = emulator.cpu();
+        assert_eq!((cpu.a, cpu.x, cpu.y, cpu.sp), (0x12, 0x34, 0x56, 0xFB));
+        assert_eq!(cpu.pc, 0x8123);
+        assert_eq!(cpu.cycles, 123_456);
+
+        let ppu = emulator.bus().ppu();
+        assert_eq!(ppu.ppuctrl, 0x90);
+        assert_eq!((ppu.v, ppu.t, ppu.fine_x), (0x2042, 0x2040, 3));
+        assert_eq!((ppu.scanline, ppu.cycle, ppu.frame), (100, 200, 42));
+        assert_eq!(ppu.nametables[0], 0x24);
+        assert_eq!(ppu.palette_ram[0], 0x0F);
+        assert_eq!(ppu.oam[0], 0xFF);
+        assert_eq!(emulator.bus().ram_contents()[0x10], 0x42);
+
+        // The emulator keeps running from the loaded state
+        emulator.step_instruction();
+    }
+
+    #[test]
+    fn test_save_state_error_display() {
+        let err = SaveStateError::NoRomLoaded;
+        assert_eq!(err.to_string(), "No ROM loaded");
+
+        let err = SaveStateError::VersionMismatch {
+            expected: 1,
+            found: 2,
+        };
+        assert_eq!(err.to_string(), "Version mismatch: expected 1, found 2");
+    }
+
+    #[test]
+    fn test_from_json_rejects_newer_versions() {
+        let json = SaveState {
+            version: 3,
+            timestamp: String::new(),
+            rom_name: None,
+            cpu_state: CpuState::default(),
+            ppu_state: PpuState::default(),
+            apu_state: Apu::new(),
+            bus_state: BusState::default(),
+            ram: vec![0; 2048],
+            vram: vec![0; 2048],
+            palette_ram: vec![0; 32],
+            oam: vec![0; 256],
+            mapper_state: None,
+            mapper_version: None,
+            movie_frame: None,
+        }
+        .to_json()
+        .unwrap();
+
+        assert!(matches!(
+            SaveState::from_json(&json),
+            Err(SaveStateError::VersionMismatch {
+                expected: 2,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_save_state_error_from_io() {
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "test");
+        let err: SaveStateError = io_err.into();
+        assert!(matches!(err, SaveStateError::Io(_)));
+    }
+
+    #[test]
+    fn test_save_state_version_constant() {
+        assert_eq!(SAVE_STATE_VERSION, 2);
+    }
+
+    #[test]
+    fn test_cpu_state_serialization() {
+        let cpu_state = CpuState {
+            a: 0x12,
+            x: 0x34,
+            y: 0x56,
+            sp: 0xFD,
+            pc: 0x8000,
+            status: 0x24,
+            cycles: 1000,
+            irq_inhibit: true,
+            jammed: true,
+        };
+
+        // Test serialization roundtrip
+        let json = serde_json::to_string(&cpu_state).unwrap();
+        let restored: CpuState = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.a, 0x12);
+        assert_eq!(restored.x, 0x34);
+        assert_eq!(restored.y, 0x56);
+        assert_eq!(restored.sp, 0xFD);
+        assert_eq!(restored.pc, 0x8000);
+        assert_eq!(restored.status, 0x24);
+        assert_eq!(restored.cycles, 1000);
+        assert!(restored.jammed);
+    }
+
+    #[test]
+    fn test_ppu_state_serialization() {
+        let ppu_state = PpuState {
+            ppuctrl: 0x80,
+            ppumask: 0x1E,
+            ppustatus: 0x00,
+            oam_addr: 0x00,
+            v: 0x2000,
+            t: 0x2400,
+            fine_x: 3,
+            write_latch: false,
+            read_buffer: 0x00,
+            scanline: 100,
+            cycle: 200,
+            frame: 1000,
+            ..Default::default()
+        };
+
+        // Test serialization roundtrip
+        let json = serde_json::to_string(&ppu_state).unwrap();
+        let restored: PpuState = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.ppuctrl, 0x80);
+        assert_eq!(restored.ppumask, 0x1E);
+        assert_eq!(restored.v, 0x2000);
+        assert_eq!(restored.t, 0x2400);
+        assert_eq!(restored.fine_x, 3);
+        assert_eq!(restored.scanline, 100);
+        assert_eq!(restored.cycle, 200);
+        assert_eq!(restored.frame, 1000);
+    }
+
+    #[test]
+    fn test_apu_state_serialization() {
+        use crate::bus::MemoryMappedDevice;
+
+        let mut apu = Apu::new();
+        apu.write(0x4015, 0x1F); // Enable all channels
+        apu.write(0x4000, 0xBF); // Pulse 1: duty, constant volume
+        apu.write(0x4001, 0x9A); // Pulse 1: sweep
+        apu.write(0x4003, 0x08); // Pulse 1: length counter
+        apu.write(0x4008, 0x81); // Triangle: linear counter
+        apu.write(0x400E, 0x85); // Noise: mode, period
+        apu.write(0x4010, 0x4F); // DMC: loop, rate
+        apu.write(0x4017, 0x80); // Frame counter: 5-step
+        for _ in 0..1000 {
+            apu.clock();
+        }
+
+        let json = serde_json::to_string(&apu).unwrap();
+        let restored: Apu = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(format!("{:?}", restored), format!("{:?}", apu));
+    }
+
+    #[test]
+    fn test_get_save_directory_with_rom() {
+        let rom_path = PathBuf::from("/path/to/game.nes");
+        let save_dir = SaveState::get_save_directory(Some(&rom_path)).unwrap();
+
+        assert_eq!(save_dir, PathBuf::from("saves/game"));
+    }
+
+    #[test]
+    fn test_get_save_directory_without_rom() {
+        let save_dir = SaveState::get_save_directory(None).unwrap();
+
+        assert_eq!(save_dir, PathBuf::from("saves/default"));
+    }
+
+    #[test]
+    fn test_get_save_directory_with_invalid_path() {
+        let rom_path = PathBuf::from("/");
+        let save_dir = SaveState::get_save_directory(Some(&rom_path)).unwrap();
+
+        // Should fall back to default when file_stem() returns None
+        assert_eq!(save_dir, PathBuf::from("saves/default"));
+    }
+
+    #[test]
+    fn test_save_state_structure() {
+        // Create a minimal save state
+        let save_state = SaveState {
+            version: SAVE_STATE_VERSION,
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            rom_name: Some("test.nes".to_string()),
+            cpu_state: CpuState {
+                a: 0,
+                x: 0,
+                y: 0,
+                sp: 0xFD,
+                pc: 0x8000,
+                status: 0x24,
+                cycles: 0,
+                irq_inhibit: true,
+                jammed: false,
+            },
+            ppu_state: PpuState {
+                ppuctrl: 0,
+                ppumask: 0,
+                ppustatus: 0,
+                oam_addr: 0,
+                v: 0,
+                t: 0,
+                fine_x: 0,
+                write_latch: false,
+                read_buffer: 0,
+                scanline: 0,
+                cycle: 0,
+                frame: 0,
+                ..Default::default()
+            },
+            apu_state: Apu::new(),
+            bus_state: BusState::default(),
+            ram: vec![0; 2048],
+            vram: vec![0; 2048],
+            palette_ram: vec![0; 32],
+            oam: vec![0; 256],
+            mapper_state: None,
+            mapper_version: None,
+            movie_frame: None,
+        };
+
+        // Test serialization
+        let json = serde_json::to_string(&save_state).unwrap();
+        assert!(json.contains("\"version\":2"));
+        assert!(json.contains("\"rom_name\":\"test.nes\""));
+
+        // Test deserialization
+        let restored: SaveState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.version, SAVE_STATE_VERSION);
+        assert_eq!(restored.rom_name.as_deref(), Some("test.nes"));
+        assert_eq!(restored.ram.len(), 2048);
+        assert_eq!(restored.vram.len(), 2048);
+        assert_eq!(restored.palette_ram.len(), 32);
+        assert_eq!(restored.oam.len(), 256);
+    }
+
+    #[test]
+    fn test_save_state_with_mapper_state() {
+        let save_state = SaveState {
+            version: SAVE_STATE_VERSION,
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            rom_name: None,
+            cpu_state: CpuState {
+                a: 0,
+                x: 0,
+                y: 0,
+                sp: 0xFD,
+                pc: 0x8000,
+                status: 0x24,
+                cycles: 0,
+                irq_inhibit: true,
+                jammed: false,
+            },
+            ppu_state: PpuState {
+                ppuctrl: 0,
+                ppumask: 0,
+                ppustatus: 0,
+                oam_addr: 0,
+                v: 0,
+                t: 0,
+                fine_x: 0,
+                write_latch: false,
+                read_buffer: 0,
+                scanline: 0,
+                cycle: 0,
+                frame: 0,
+                ..Default::default()
+            },
+            apu_state: Apu::new(),
+            bus_state: BusState::default(),
+            ram: vec![0; 2048],
+            vram: vec![0; 2048],
+            palette_ram: vec![0; 32],
+            oam: vec![0; 256],
+            mapper_state: Some(vec![0xAB; 8192]),
+            mapper_version: None,
+            movie_frame: None,
+        };
+
+        // Serialize and deserialize
+        let json = serde_json::to_string(&save_state).unwrap();
+        let restored: SaveState = serde_json::from_str(&json).unwrap();
+
+        assert!(restored.mapper_state.is_some());
+        assert_eq!(restored.mapper_state.as_ref().unwrap().len(), 8192);
+        assert_eq!(restored.mapper_state.as_ref().unwrap()[0], 0xAB);
+    }
+
+    #[test]
+    fn test_save_state_preserves_cpu_state() {
+        let cpu_state = CpuState {
+            a: 0xFF,
+            x: 0xAA,
+            y: 0x55,
+            sp: 0xF0,
+            pc: 0xC123,
+            status: 0b11010101,
+            cycles: 987654321,
+            irq_inhibit: false,
+            jammed: false,
+        };
+
+        let save_state = SaveState {
+            version: SAVE_STATE_VERSION,
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            rom_name: None,
+            cpu_state,
+            ppu_state: PpuState {
+                ppuctrl: 0,
+                ppumask: 0,
+                ppustatus: 0,
+                oam_addr: 0,
+                v: 0,
+                t: 0,
+                fine_x: 0,
+                write_latch: false,
+                read_buffer: 0,
+                scanline: 0,
+                cycle: 0,
+                frame: 0,
+                ..Default::default()
+            },
+            apu_state: Apu::new(),
+            bus_state: BusState::default(),
+            ram: vec![0; 2048],
+            vram: vec![0; 2048],
+            palette_ram: vec![0; 32],
+            oam: vec![0; 256],
+            mapper_state: None,
+            mapper_version: None,
+            movie_frame: None,
+        };
+
+        let json = serde_json::to_string(&save_state).unwrap();
+        let restored: SaveState = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.cpu_state.a, 0xFF);
+        assert_eq!(restored.cpu_state.x, 0xAA);
+        assert_eq!(restored.cpu_state.y, 0x55);
+        assert_eq!(restored.cpu_state.sp, 0xF0);
+        assert_eq!(restored.cpu_state.pc, 0xC123);
+        assert_eq!(restored.cpu_state.status, 0b11010101);
+        assert_eq!(restored.cpu_state.cycles, 987654321);
+    }
+
+    #[test]
+    fn test_save_state_preserves_ppu_state() {
+        let ppu_state = PpuState {
+            ppuctrl: 0x88,
+            ppumask: 0x1E,
+            ppustatus: 0xA0,
+            oam_addr: 0x40,
+            v: 0x2345,
+            t: 0x2678,
+            fine_x: 5,
+            write_latch: true,
+            read_buffer: 0xCD,
+            scanline: 240,
+            cycle: 340,
+            frame: 12345,
+            ..Default::default()
+        };
+
+        let save_state = SaveState {
+            version: SAVE_STATE_VERSION,
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            rom_name: None,
+            cpu_state: CpuState {
+                a: 0,
+                x: 0,
+                y: 0,
+                sp: 0xFD,
+                pc: 0x8000,
+                status: 0x24,
+                cycles: 0,
+                irq_inhibit: true,
+                jammed: false,
+            },
+            ppu_state,
+            apu_state: Apu::new(),
+            bus_state: BusState::default(),
+            ram: vec![0; 2048],
+            vram: vec![0; 2048],
+            palette_ram: vec![0; 32],
+            oam: vec![0; 256],
+            mapper_state: None,
+            mapper_version: None,
+            movie_frame: None,
+        };
+
+        let json = serde_json::to_string(&save_state).unwrap();
+        let restored: SaveState = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.ppu_state.ppuctrl, 0x88);
+        assert_eq!(restored.ppu_state.ppumask, 0x1E);
+        assert_eq!(restored.ppu_state.ppustatus, 0xA0);
+        assert_eq!(restored.ppu_state.oam_addr, 0x40);
+        assert_eq!(restored.ppu_state.v, 0x2345);
+        assert_eq!(restored.ppu_state.t, 0x2678);
+        assert_eq!(restored.ppu_state.fine_x, 5);
+        assert!(restored.ppu_state.write_latch);
+        assert_eq!(restored.ppu_state.read_buffer, 0xCD);
+        assert_eq!(restored.ppu_state.scanline, 240);
+        assert_eq!(restored.ppu_state.cycle, 340);
+        assert_eq!(restored.ppu_state.frame, 12345);
+    }
+}
+
+// ========================================
+// Determinism Tests
+// ========================================
+
+#[cfg(test)]
+pub(crate) mod determinism_tests {
+    use super::*;
+    use crate::cartridge::{Cartridge, Mirroring};
+    use crate::emulator::{Emulator, EmulatorConfig};
+
+    /// MMC3 test program exercising the PPU, APU, OAM DMA, CHR-RAM and scanline IRQs
+    ///
+    /// Located at $E000 in the fixed last PRG bank.
+    const PROGRAM: &[u8] = &[
+        0x78, // E000: SEI
+        0xD8, // E001: CLD
+        0xA2, 0xFF, // E002: LDX #$FF
+        0x9A, // E004: TXS
+        0xA9, 0x06, 0x8D, 0x00, 0x80, // E005: R6 = bank 1
+        0xA9, 0x01, 0x8D, 0x01, 0x80, //
+        0xA9, 0x05, // E00F: LDA #$05
+        0x8D, 0x00, 0xC0, // E011: STA $C000 (IRQ latch)
+        0x8D, 0x01, 0xC0, // E014: STA $C001 (IRQ reload)
+        0x8D, 0x01, 0xE0, // E017: STA $E001 (IRQ enable)
+        0xA9, 0x0F, 0x8D, 0x15, 0x40, // E01A: enable APU channels
+        0xA9, 0xBF, 0x8D, 0x00, 0x40, // E01F: pulse 1 volume
+        0xA9, 0x08, 0x8D, 0x03, 0x40, // E024: pulse 1 length
+        0xA9, 0x88, 0x8D, 0x00, 0x20, // E029: NMI on, sprites at $1000
+        0xA9, 0x1E, 0x8D, 0x01, 0x20, // E02E: rendering on
+        0x58, // E033: CLI
+        0xE6, 0x10, // E034: INC $10
+        0xA5, 0x10, // E036: LDA $10
+        0x8D, 0x02, 0x40, // E038: STA $4002
+        0x6D, 0x00, 0x80, // E03B: ADC $8000 (banked)
+        0x9D, 0x00, 0x02, // E03E: STA $0200,X (sprite data)
+        0xE8, // E041: INX
+        0x4C, 0x34, 0xE0, // E042: JMP $E034
+        // NMI handler
+        0x48, // E045: PHA
+        0xE6, 0x11, // E046: INC $11
+        0xA9, 0x02, 0x8D, 0x14, 0x40, // E048: OAM DMA from $0200
+        0xA9, 0x10, 0x8D, 0x06, 0x20, // E04D: PPUADDR = $10xx
+        0xA5, 0x11, 0x8D, 0x06, 0x20, // E052
+        0x8D, 0x07, 0x20, // E057: write CHR-RAM
+        0xA9, 0x00, 0x8D, 0x05, 0x20, // E05A: reset scroll
+        0x8D, 0x05, 0x20, // E05F
+        0x68, // E062: PLA
+        0x40, // E063: RTI
+        // IRQ handler
+        0x48, // E064: PHA
+        0x8D, 0x00, 0xE0, // E065: STA $E000 (acknowledge)
+        0x8D, 0x01, 0xE0, // E068: STA $E001 (re-enable)
+        0xE6, 0x12, // E06B: INC $12
+        0xA9, 0x06, 0x8D, 0x00, 0x80, // E06D: select R6
+        0xA5, 0x12, 0x29, 0x03, // E072: LDA $12, AND #$03
+        0x8D, 0x01, 0x80, // E076: switch bank
+        0x68, // E079: PLA
+        0x40, // E07A: RTI
+    ];
+
+    /// Build an emulator running `PROGRAM` on an MMC3 cartridge with CHR-RAM
+    pub(crate) fn create_test_emulator() -> Emulator {
+        let mut prg_rom = vec![0; 32 * 1024];
+        for (i, byte) in prg_rom[..0x6000].iter_mut().enumerate() {
+            *byte = (i / 0x2000) as u8 * 0x11;
+        }
+        prg_rom[0x6000..0x6000 + PROGRAM.len()].copy_from_slice(PROGRAM);
+        prg_rom[0x7FFA..].copy_from_slice(&[0x45, 0xE0, 0x00, 0xE0, 0x64, 0xE0]);
+
+        let cartridge = Cartridge {
+            prg_rom,
+            chr_rom: vec![0; 8 * 1024],
+            trainer: None,
+            mapper: 4,
+            mirroring: Mirroring::Vertical,
+            has_battery: false,
+            ..Cartridge::new()
+        };
+
+        let mut emulator = Emulator::with_config(EmulatorConfig::default());
+        emulator.insert_cartridge(cartridge).unwrap();
+        emulator
+    }
+
+    /// Run `frames` frames and collect everything they produce
+    pub(crate) fn run_and_record(
+        emulator: &mut Emulator,
+        frames: usize,
+    ) -> (Vec<u8>, Vec<f32>, String) {
+        let mut video = Vec::new();
+        let mut audio = Vec::new();
+        for _ in 0..frames {
+            emulator.run_frame();
+            video.extend_from_slice(emulator.bus().ppu().frame());
+            audio.extend_from_slice(emulator.audio_samples());
+        }
+
+        // The complete machine state afterwards, minus the wall-clock timestamp
+        // and the frame buffer, which is already in the video
+        let state = SaveState::from_emulator_for_rewind(emulator).unwrap();
+        (video, audio, serde_json::to_string(&state).unwrap())
+    }
+
+    #[test]
+    fn test_save_load_is_deterministic() {
+        let mut emulator = create_test_emulator();
+        run_and_record(&mut emulator, 10);
+
+        // Save just after an MMC3 IRQ is taken, while its line is still asserted
+        while !emulator.bus().irq_sampled() {
+            emulator.step_instruction();
+        }
+
+        let state = SaveState::from_emulator(&emulator).unwrap();
+        assert!(state.bus_state.irq_sample);
+        let first = run_and_record(&mut emulator, 5);
+
+        state.restore_to_emulator(&mut emulator).unwrap();
+        let second = run_and_record(&mut emulator, 5);
+
+        // Sanity check: NMIs and MMC3 IRQs actually ran
+        let ram = emulator.bus().ram_contents();
+        assert!(ram[0x11] > 0, "NMI handler never ran");
+        assert!(ram[0x12] > 0, "MMC3 IRQ handler never ran");
+
+        assert!(first.0 == second.0, "Video diverged after loading state");
+        assert!(first.1 == second.1, "Audio diverged after loading state");
+        assert!(
+            first.2 == second.2,
+            "Machine state diverged after loading state"
+        );
+    }
+
+    #[test]
+    fn test_mapper_state_checked_against_cartridge() {
+        let mut emulator = create_test_emulator();
+        let mut state = SaveState::from_emulator(&emulator).unwrap();
+
+        state.mapper_version = Some(MapperStateVersion {
+            mapper: 1,
+            version: 1,
+        });
+        assert!(matches!(
+            state.restore_to_emulator(&mut emulator),
+            Err(SaveStateError::InvalidData(_))
+        ));
+
+        state.mapper_version = Some(MapperStateVersion {
+            mapper: 4,
+            version: 2,
+        });
+        assert!(matches!(
+            state.restore_to_emulator(&mut emulator),
+            Err(SaveStateError::UnsupportedChunkVersion {
+                supported: 1,
+                found: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_load_state_into_fresh_emulator() {
+        let mut original = create_test_emulator();
+        run_and_record(&mut original, 7);
+        let state = SaveState::from_emulator(&original).unwrap();
+
+        // Loading into a freshly inserted cartridge resumes the same game
+        let mut copy = create_test_emulator();
+        state.restore_to_emulator(&mut copy).unwrap();
+
+        assert!(run_and_record(&mut original, 3) == run_and_record(&mut copy, 3));
+    }
+}
@@ -0,0 +1,104 @@
+// Version 1 JSON save states
+//
+// The first save state format was plain JSON holding the CPU registers, the
+// PPU registers and the four memories. The APU was a placeholder and the
+// cartridge RAM slot was never filled, so both are ignored. Everything added
+// since starts from its power-on value: the APU is silent, no DMA is in
+// flight and the mapper keeps its current state.
+
+use super::{BusState, CpuState, PpuState, SaveState, SAVE_STATE_VERSION};
+use crate::apu::Apu;
+use serde::Deserialize;
+
+/// Layout of a version 1 save state
+#[derive(Debug, Deserialize)]
+pub(super) struct SaveStateV1 {
+    timestamp: String,
+    rom_name: Option<String>,
+    cpu_state: CpuStateV1,
+    ppu_state: PpuStateV1,
+    ram: Vec<u8>,
+    vram: Vec<u8>,
+    palette_ram: Vec<u8>,
+    oam: Vec<u8>,
+}
+
+/// CPU state in a version 1 save state
+#[derive(Debug, Deserialize)]
+struct CpuStateV1 {
+    a: u8,
+    x: u8,
+    y: u8,
+    sp: u8,
+    pc: u16,
+    status: u8,
+    cycles: u64,
+}
+
+/// PPU state in a version 1 save state
+#[derive(Debug, Deserialize)]
+struct PpuStateV1 {
+    ppuctrl: u8,
+    ppumask: u8,
+    ppustatus: u8,
+    oam_addr: u8,
+    v: u16,
+    t: u16,
+    fine_x: u8,
+    write_latch: bool,
+    read_buffer: u8,
+    scanline: u16,
+    cycle: u16,
+    frame: u64,
+}
+
+impl SaveStateV1 {
+    /// Convert to the current layout
+    ///
+    /// The frame buffer is left empty, so loading keeps the current screen
+    /// until the next frame is drawn.
+    pub(super) fn upgrade(self) -> SaveState {
+        let cpu = self.cpu_state;
+        let ppu = self.ppu_state;
+
+        SaveState {
+            version: SAVE_STATE_VERSION,
+            timestamp: self.timestamp,
+            rom_name: self.rom_name,
+            cpu_state: CpuState {
+                a: cpu.a,
+                x: cpu.x,
+                y: cpu.y,
+                sp: cpu.sp,
+                pc: cpu.pc,
+                status: cpu.status,
+                cycles: cpu.cycles,
+                ..CpuState::default()
+            },
+            ppu_state: PpuState {
+                ppuctrl: ppu.ppuctrl,
+                ppumask: ppu.ppumask,
+                ppustatus: ppu.ppustatus,
+                oam_addr: ppu.oam_addr,
+                v: ppu.v,
+                t: ppu.t,
+                fine_x: ppu.fine_x,
+                write_latch: ppu.write_latch,
+                read_buffer: ppu.read_buffer,
+                scanline: ppu.scanline,
+                cycle: ppu.cycle,
+                frame: ppu.frame,
+                ..PpuState::default()
+            },
+            apu_state: Apu::new(),
+            bus_state: BusState::default(),
+            ram: self.ram,
+            vram: self.vram,
+            palette_ram: self.palette_ram,
+            oam: self.oam,
+            mapper_state: None,
+            mapper_version: None,
+            movie_frame: None,
+        }
+    }
+}
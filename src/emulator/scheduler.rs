@@ -0,0 +1,552 @@
+// Emulation scheduler
+//
+// Drives the CPU, PPU, and APU together. The CPU executes one instruction at a
+// time, and every bus access it makes clocks the rest of the system for that
+// cycle (3 PPU dots and 1 APU clock), so registers see reads and writes at the
+// cycle they happen on. DMC DMA halts the CPU at its next read, interleaved
+// with the instruction; OAM DMA and interrupts are serviced at instruction
+// boundaries.
+
+use super::Emulator;
+
+/// Result of advancing the emulator
+///
+/// Returned by [`Emulator::run_frame`], [`Emulator::step_instruction`], and
+/// [`Emulator::run_cycles`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunResult {
+    /// Number of CPU cycles executed (including DMA and interrupt entry)
+    pub cpu_cycles: u64,
+
+    /// True if the PPU completed a frame during this run
+    pub frame_complete: bool,
+
+    /// Number of audio samples produced (see [`Emulator::audio_samples`])
+    pub audio_samples: usize,
+
+    /// True if the CPU is halted by a KIL opcode (see [`Emulator::jam`])
+    pub jammed: bool,
+}
+
+impl Emulator {
+    /// Run the emulator until the PPU completes a frame
+    ///
+    /// The finished frame is available through the PPU frame buffer and the
+    /// audio generated while running it through [`Emulator::audio_samples`].
+    ///
+    /// # Returns
+    ///
+    /// The cycles executed and the number of audio samples produced
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nes_rs::emulator::Emulator;
+    ///
+    /// let mut emulator = Emulator::new();
+    /// emulator.load_rom("game.nes").expect("Failed to load ROM");
+    ///
+    /// let result = emulator.run_frame();
+    /// let frame = emulator.bus().ppu().frame();
+    /// let audio = emulator.audio_samples();
+    /// assert_eq!(audio.len(), result.audio_samples);
+    /// ```
+    pub fn run_frame(&mut self) -> RunResult {
+        if self.rewinding {
+            return self.run_rewind_frame();
+        }
+
+        self.apply_movie_frame();
+        self.bus.clear_audio_samples();
+
+        let mut cpu_cycles = 0;
+        loop {
+            let (cycles, frame_complete) = self.execute_instruction();
+            cpu_cycles += cycles;
+            if frame_complete {
+                break;
+            }
+        }
+        self.record_rewind_frame();
+        self.tick_battery_timer();
+
+        RunResult {
+            cpu_cycles,
+            frame_complete: true,
+            audio_samples: self.bus.audio_samples().len(),
+            jammed: self.cpu.is_jammed(),
+        }
+    }
+
+    /// Execute a single CPU instruction
+    ///
+    /// The PPU and APU are advanced by the same number of cycles, and any
+    /// pending OAM DMA or interrupt is serviced afterwards.
+    ///
+    /// # Returns
+    ///
+    /// The cycles executed and whether a frame was completed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::emulator::{Emulator, EmulatorConfig};
+    ///
+    /// let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    /// let result = emulator.step_instruction();
+    /// assert!(result.cpu_cycles >= 2);
+    /// ```
+    pub fn step_instruction(&mut self) -> RunResult {
+        self.bus.clear_audio_samples();
+
+        let (cpu_cycles, frame_complete) = self.execute_instruction();
+
+        RunResult {
+            cpu_cycles,
+            frame_complete,
+            audio_samples: self.bus.audio_samples().len(),
+            jammed: self.cpu.is_jammed(),
+        }
+    }
+
+    /// Run the emulator for at least the given number of CPU cycles
+    ///
+    /// Instructions are never split, so the run may overshoot by a few cycles
+    /// (or by a full OAM DMA transfer).
+    ///
+    /// # Arguments
+    ///
+    /// * `cycles` - Minimum number of CPU cycles to run
+    ///
+    /// # Returns
+    ///
+    /// The cycles actually executed and whether a frame was completed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::emulator::{Emulator, EmulatorConfig};
+    ///
+    /// let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    /// let result = emulator.run_cycles(1000);
+    /// assert!(result.cpu_cycles >= 1000);
+    /// ```
+    pub fn run_cycles(&mut self, cycles: u64) -> RunResult {
+        self.bus.clear_audio_samples();
+
+        let mut result = RunResult::default();
+        while result.cpu_cycles < cycles {
+            let (executed, frame_complete) = self.execute_instruction();
+            result.cpu_cycles += executed;
+            result.frame_complete |= frame_complete;
+        }
+        result.audio_samples = self.bus.audio_samples().len();
+        result.jammed = self.cpu.is_jammed();
+
+        result
+    }
+
+    /// Get the audio samples produced by the most recent run
+    ///
+    /// One mixed sample is produced per CPU cycle (~1.79 MHz), ready to be fed
+    /// into an [`audio::Resampler`](crate::audio::Resampler).
+    ///
+    /// # Returns
+    ///
+    /// Slice of mixed APU output samples
+    pub fn audio_samples(&self) -> &[f32] {
+        self.bus.audio_samples()
+    }
+
+    /// Execute one instruction and service DMA and interrupts
+    ///
+    /// # Returns
+    ///
+    /// Tuple of (CPU cycles executed, frame completed)
+    pub(super) fn execute_instruction(&mut self) -> (u64, bool) {
+        self.log_trace();
+
+        // The CPU clocks the rest of the system on each of its bus accesses
+        let mut cycles = self.cpu.step(&mut self.bus) as u64;
+        cycles += self.charge_stall_cycles();
+        self.record_jam();
+
+        // The CPU samples the IRQ line before the last cycle of the instruction
+        let irq_line = self.bus.irq_sampled();
+
+        // OAM DMA: the CPU is suspended while 256 bytes are copied to OAM
+        if self.bus.is_dma_active() {
+            let dma_cycles = self.bus.execute_dma(self.cpu.cycles) as u64;
+            self.cpu.cycles = self.cpu.cycles.wrapping_add(dma_cycles);
+            self.tick(dma_cycles);
+            cycles += dma_cycles + self.charge_stall_cycles();
+        }
+
+        // Interrupts are polled at the instruction boundary; NMI has priority.
+        // A jammed CPU never reaches a boundary, so it ignores both. Entering a
+        // handler takes 7 cycles, clocked by the CPU's own bus accesses.
+        if !self.cpu.is_jammed() {
+            let start_cycle = self.cpu.cycles;
+            if self.bus.ppu_nmi_pending() {
+                self.bus.clear_ppu_nmi();
+                self.cpu.nmi(&mut self.bus);
+            } else {
+                self.cpu.poll_irq(&mut self.bus, irq_line);
+            }
+            cycles += self.cpu.cycles.wrapping_sub(start_cycle);
+            cycles += self.charge_stall_cycles();
+        }
+
+        (cycles, self.bus.take_frame_complete())
+    }
+
+    /// Charge the cycles the CPU spent halted by DMC DMA to its cycle count
+    ///
+    /// The bus has already clocked the rest of the system for them.
+    ///
+    /// # Returns
+    ///
+    /// The number of cycles charged
+    fn charge_stall_cycles(&mut self) -> u64 {
+        let stall_cycles = self.bus.take_stall_cycles() as u64;
+        self.cpu.cycles = self.cpu.cycles.wrapping_add(stall_cycles);
+        stall_cycles
+    }
+
+    /// Clock the system for CPU cycles in which the CPU is halted
+    ///
+    /// # Arguments
+    ///
+    /// * `cpu_cycles` - Number of CPU cycles to clock
+    fn tick(&mut self, cpu_cycles: u64) {
+        for _ in 0..cpu_cycles {
+            self.bus.cpu_tick();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::apu::DmcChannel;
+    use crate::emulator::{Emulator, EmulatorConfig};
+    use crate::region::Region;
+
+    /// Cycles per NTSC frame (341 dots * 262 scanlines / 3)
+    const CYCLES_PER_FRAME: u64 = 29781;
+
+    /// Create an emulator running `program` from $8000 with no cartridge inserted
+    fn emulator_with_program(program: &[u8]) -> Emulator {
+        let mut emulator = Emulator::with_config(EmulatorConfig::default());
+        for (i, &byte) in program.iter().enumerate() {
+            emulator.bus_mut().write(0x8000 + i as u16, byte);
+        }
+        // Point all vectors at $8000
+        for vector in [0xFFFA, 0xFFFC, 0xFFFE] {
+            emulator.bus_mut().write_u16(vector, 0x8000);
+        }
+        emulator.reset();
+        emulator
+    }
+
+    #[test]
+    fn test_step_instruction() {
+        // LDA #$42; JMP $8002
+        let mut emulator = emulator_with_program(&[0xA9, 0x42, 0x4C, 0x02, 0x80]);
+
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 2);
+        assert!(!result.frame_complete);
+        assert_eq!(result.audio_samples, 2);
+        assert_eq!(emulator.audio_samples().len(), 2);
+        assert_eq!(emulator.cpu().a, 0x42);
+        assert_eq!(emulator.cpu().pc, 0x8002);
+    }
+
+    #[test]
+    fn test_step_instruction_ticks_ppu() {
+        // JMP $8000
+        let mut emulator = emulator_with_program(&[0x4C, 0x00, 0x80]);
+        let start_cycle = emulator.bus().ppu().cycle();
+
+        emulator.step_instruction();
+
+        // JMP takes 3 CPU cycles = 9 PPU dots
+        assert_eq!(emulator.bus().ppu().cycle(), start_cycle + 9);
+    }
+
+    #[test]
+    fn test_run_cycles() {
+        // JMP $8000
+        let mut emulator = emulator_with_program(&[0x4C, 0x00, 0x80]);
+
+        let result = emulator.run_cycles(100);
+        assert_eq!(result.cpu_cycles, 102); // 34 JMPs
+        assert_eq!(result.audio_samples, 102);
+    }
+
+    #[test]
+    fn test_run_frame() {
+        // JMP $8000
+        let mut emulator = emulator_with_program(&[0x4C, 0x00, 0x80]);
+
+        let first_frame = emulator.bus().ppu().frame_count();
+        let result = emulator.run_frame();
+
+        assert!(result.frame_complete);
+        assert!(result.cpu_cycles <= CYCLES_PER_FRAME + 3);
+        assert_eq!(result.audio_samples as u64, result.cpu_cycles);
+        assert_eq!(emulator.bus().ppu().frame_count(), first_frame + 1);
+
+        let result = emulator.run_frame();
+        assert!(result.cpu_cycles.abs_diff(CYCLES_PER_FRAME) <= 3);
+    }
+
+    #[test]
+    fn test_run_frame_pal_and_dendy() {
+        // 341 dots * 312 scanlines / 3.2 (PAL) or / 3 (Dendy)
+        for (region, cycles_per_frame) in [(Region::Pal, 33248), (Region::Dendy, 35464)] {
+            // JMP $8000
+            let mut emulator = emulator_with_program(&[0x4C, 0x00, 0x80]);
+            emulator.bus_mut().set_region(region);
+
+            emulator.run_frame();
+            let result = emulator.run_frame();
+            assert!(result.frame_complete);
+            assert!(
+                result.cpu_cycles.abs_diff(cycles_per_frame) <= 3,
+                "{}: {} cycles",
+                region,
+                result.cpu_cycles
+            );
+        }
+    }
+
+    #[test]
+    fn test_nmi_serviced() {
+        let mut emulator = emulator_with_program(&[
+            0xA9, 0x80, // LDA #$80
+            0x8D, 0x00, 0x20, // STA $2000 (enable NMI)
+            0x4C, 0x05, 0x80, // JMP $8005
+            0xE8, // NMI handler: INX
+            0x40, // RTI
+        ]);
+        emulator.bus_mut().write_u16(0xFFFA, 0x8008);
+
+        emulator.run_frame();
+        emulator.run_frame();
+
+        assert!(emulator.cpu().x >= 1, "NMI handler should have run");
+    }
+
+    #[test]
+    fn test_oam_dma_serviced() {
+        // LDA #$02; STA $4014; JMP $8005
+        let mut emulator = emulator_with_program(&[0xA9, 0x02, 0x8D, 0x14, 0x40, 0x4C, 0x05, 0x80]);
+        emulator.bus_mut().write(0x0200, 0x55);
+
+        emulator.step_instruction();
+        let result = emulator.step_instruction();
+
+        // STA abs (4 cycles) + 513/514 DMA cycles
+        assert!(result.cpu_cycles == 517 || result.cpu_cycles == 518);
+        assert!(!emulator.bus().is_dma_active());
+        assert_eq!(emulator.bus().ppu().oam[0], 0x55);
+    }
+
+    /// Arrange for the DMC to fetch a byte from $C000 on the `cycle`th CPU
+    /// cycle from now (counting from 1; at least 2)
+    ///
+    /// The timer fires on the first cycle, shifting out the last bit, and again
+    /// `cycle - 1` cycles later, when it empties the sample buffer.
+    fn schedule_dmc_fetch(emulator: &mut Emulator, cycle: u16) {
+        let dmc = &mut emulator.bus_mut().apu_mut().dmc;
+        *dmc = DmcChannel::new();
+        dmc.timer.set_period_direct(cycle - 2);
+        dmc.sample_buffer_empty = false;
+        dmc.bits_remaining = 1;
+        dmc.current_address = 0xC000;
+        dmc.bytes_remaining = 1;
+    }
+
+    /// Create an emulator about to run `STA $4014`, returning it with the
+    /// length of the OAM DMA that will follow
+    fn emulator_before_oam_dma() -> (Emulator, u64) {
+        // LDA #$02; STA $4014; JMP $8005
+        let mut emulator = emulator_with_program(&[0xA9, 0x02, 0x8D, 0x14, 0x40, 0x4C, 0x05, 0x80]);
+        emulator.step_instruction();
+
+        // One extra alignment cycle when the DMA starts on an even cycle
+        let dma_cycles = if (emulator.cpu().cycles + 4).is_multiple_of(2) {
+            514
+        } else {
+            513
+        };
+        (emulator, dma_cycles)
+    }
+
+    #[test]
+    fn test_dmc_fetch_stalls_cpu() {
+        let mut emulator = emulator_with_program(&[
+            0xA9, 0x10, // LDA #$10
+            0x8D, 0x15, 0x40, // STA $4015 (enable DMC, fetch starts immediately)
+            0x4C, 0x05, 0x80, // JMP $8005
+        ]);
+        emulator.bus_mut().write(0x4010, 0x00); // Slowest rate
+        emulator.bus_mut().write(0x4013, 0x01); // 17-byte sample at $C000
+
+        emulator.step_instruction();
+
+        // STA abs (4 cycles); the CPU cannot be halted on the write
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 4);
+        assert_eq!(emulator.bus().apu().dmc.bytes_remaining, 17);
+
+        // JMP abs (3 cycles) + 3 stall cycles before its opcode fetch
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 6);
+        assert_eq!(result.audio_samples, 6);
+        assert_eq!(emulator.bus().apu().dmc.bytes_remaining, 16);
+    }
+
+    #[test]
+    fn test_dmc_stall_on_read_cycle() {
+        // LDA $0000; JMP $8003
+        let mut emulator = emulator_with_program(&[0xAD, 0x00, 0x00, 0x4C, 0x03, 0x80]);
+        schedule_dmc_fetch(&mut emulator, 2);
+
+        // LDA abs (4 cycles) + 4 stall cycles, halting the read that follows
+        // the one on which the sample buffer empties
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 8);
+        assert!(!emulator.bus().apu().dmc.is_active());
+    }
+
+    #[test]
+    fn test_dmc_stall_during_oam_dma() {
+        // On the $4014 write that starts the DMA
+        let (mut emulator, dma_cycles) = emulator_before_oam_dma();
+        schedule_dmc_fetch(&mut emulator, 4);
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 4 + dma_cycles + 2);
+
+        // In the middle of the DMA
+        let (mut emulator, dma_cycles) = emulator_before_oam_dma();
+        schedule_dmc_fetch(&mut emulator, 100);
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 4 + dma_cycles + 2);
+        assert!(!emulator.bus().apu().dmc.is_active());
+    }
+
+    #[test]
+    fn test_dmc_stall_on_second_last_oam_dma_cycle() {
+        let (mut emulator, dma_cycles) = emulator_before_oam_dma();
+        schedule_dmc_fetch(&mut emulator, 3 + dma_cycles as u16);
+
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 4 + dma_cycles + 1);
+        assert!(!emulator.bus().apu().dmc.is_active());
+    }
+
+    #[test]
+    fn test_dmc_stall_on_last_oam_dma_cycle() {
+        let (mut emulator, dma_cycles) = emulator_before_oam_dma();
+        schedule_dmc_fetch(&mut emulator, 4 + dma_cycles as u16);
+
+        // The DMA has released the CPU, so the fetch halts the next read
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 4 + dma_cycles);
+
+        // JMP abs (3 cycles) + 3 stall cycles
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 6);
+        assert!(!emulator.bus().apu().dmc.is_active());
+    }
+
+    #[test]
+    fn test_irq_respects_interrupt_disable() {
+        let mut emulator = emulator_with_program(&[
+            0x78, // SEI
+            0x4C, 0x01, 0x80, // JMP $8001
+            0xE8, // IRQ handler: INX
+            0x40, // RTI
+        ]);
+        emulator.bus_mut().write_u16(0xFFFE, 0x8004);
+
+        // The APU frame counter raises its IRQ in 4-step mode, but I masks it
+        emulator.run_cycles(2 * CYCLES_PER_FRAME);
+        assert!(emulator.bus().apu().frame_irq_pending());
+        assert_eq!(emulator.cpu().pc, 0x8001);
+
+        // Clearing I lets the pending IRQ through after the next instruction
+        emulator.cpu_mut().set_interrupt_disable(false);
+        let result = emulator.step_instruction();
+        assert_eq!(result.cpu_cycles, 3 + 7);
+        assert_eq!(emulator.cpu().pc, 0x8004);
+        assert!(emulator.cpu().get_interrupt_disable());
+    }
+
+    /// Create an emulator with the APU frame IRQ asserted and I set
+    fn emulator_with_frame_irq(program: &[u8]) -> Emulator {
+        // SEI; JMP $8001, followed by the program under test at $8004
+        let mut code = vec![0x78, 0x4C, 0x01, 0x80];
+        code.extend_from_slice(program);
+
+        let mut emulator = emulator_with_program(&code);
+        emulator.bus_mut().write_u16(0xFFFE, 0x9000);
+        emulator.run_cycles(CYCLES_PER_FRAME + 100);
+        assert!(emulator.bus().irq_pending());
+
+        emulator.cpu_mut().pc = 0x8004;
+        emulator
+    }
+
+    #[test]
+    fn test_irq_delayed_after_cli() {
+        // CLI; NOP
+        let mut emulator = emulator_with_frame_irq(&[0x58, 0xEA]);
+
+        // The poll during CLI still sees I set
+        emulator.step_instruction();
+        assert_eq!(emulator.cpu().pc, 0x8005);
+
+        // The IRQ is taken after the following instruction
+        emulator.step_instruction();
+        assert_eq!(emulator.cpu().pc, 0x9000);
+    }
+
+    #[test]
+    fn test_irq_taken_after_sei() {
+        // CLI; SEI
+        let mut emulator = emulator_with_frame_irq(&[0x58, 0x78]);
+        emulator.step_instruction();
+
+        // The poll during SEI still sees I clear, so the IRQ is taken once
+        emulator.step_instruction();
+        assert_eq!(emulator.cpu().pc, 0x9000);
+
+        // The stacked status has I set (from SEI)
+        let status_addr = 0x0100 + emulator.cpu().sp as u16 + 1;
+        let stacked_status = emulator.bus_mut().read(status_addr);
+        assert_ne!(stacked_status & 0x04, 0);
+    }
+
+    #[test]
+    fn test_irq_level_triggered() {
+        // CLI; NOP, IRQ handler: RTI without acknowledging
+        let mut emulator = emulator_with_frame_irq(&[0x58, 0xEA]);
+        emulator.bus_mut().write(0x9000, 0x40);
+
+        emulator.step_instruction(); // CLI
+        emulator.step_instruction(); // NOP, IRQ taken
+        assert_eq!(emulator.cpu().pc, 0x9000);
+
+        // RTI restores I = 0 and the still-asserted line fires again
+        emulator.step_instruction();
+        assert_eq!(emulator.cpu().pc, 0x9000);
+
+        // Acknowledging the frame IRQ releases the line
+        emulator.bus_mut().read(0x4015);
+        emulator.step_instruction();
+        assert_eq!(emulator.cpu().pc, 0x8006);
+    }
+}
@@ -32,19 +32,23 @@
 pub mod config;
 pub mod gamepad;
 pub mod keyboard;
+pub mod movie;
 pub mod unified;
 
 use crate::bus::MemoryMappedDevice;
+use crate::state::{StateError, StateReader, StateWriter};
+use serde::{Deserialize, Serialize};
 
 pub use config::{GamepadMappingConfig, InputConfig, KeyboardMappingConfig};
 pub use gamepad::{GamepadHandler, GamepadMapping};
 pub use keyboard::{Button, KeyboardHandler, KeyboardMapping, Player};
+pub use movie::{Movie, MovieError, MovieFrame};
 pub use unified::UnifiedInputHandler;
 
 /// Controller button state structure
 ///
 /// Represents the state of all 8 buttons on a standard NES controller.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Controller {
     /// A button state
     pub button_a: bool,
@@ -114,6 +118,50 @@ impl Controller {
             _ => false,
         }
     }
+
+    /// Pack the buttons into a byte, in shift-register order
+    ///
+    /// # Returns
+    ///
+    /// Bit 0 = A, 1 = B, 2 = Select, 3 = Start, 4 = Up, 5 = Down, 6 = Left, 7 = Right
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::input::Controller;
+    ///
+    /// let mut controller = Controller::new();
+    /// controller.start = true;
+    /// assert_eq!(controller.to_bits(), 0x08);
+    /// assert_eq!(Controller::from_bits(0x08), controller);
+    /// ```
+    pub fn to_bits(&self) -> u8 {
+        (0..8).fold(0, |bits, index| {
+            bits | ((self.get_button(index) as u8) << index)
+        })
+    }
+
+    /// Unpack buttons from a byte produced by `to_bits`
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - Button bits in shift-register order
+    ///
+    /// # Returns
+    ///
+    /// A controller with the corresponding buttons pressed
+    pub fn from_bits(bits: u8) -> Self {
+        Controller {
+            button_a: bits & 0x01 != 0,
+            button_b: bits & 0x02 != 0,
+            select: bits & 0x04 != 0,
+            start: bits & 0x08 != 0,
+            up: bits & 0x10 != 0,
+            down: bits & 0x20 != 0,
+            left: bits & 0x40 != 0,
+            right: bits & 0x80 != 0,
+        }
+    }
 }
 
 impl Default for Controller {
@@ -129,6 +177,7 @@ impl Default for Controller {
 ///
 /// Supports the standard NES controller strobe and serial read mechanism.
 /// Button states can be updated via set_controller1 and set_controller2 methods.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ControllerIO {
     /// Controller 1 state
     controller1: Controller,
@@ -306,6 +355,43 @@ impl ControllerIO {
     pub fn set_controller2(&mut self, controller: Controller) {
         self.controller2 = controller;
     }
+
+    /// Get the current controller 1 state
+    ///
+    /// # Returns
+    ///
+    /// The buttons last set with `set_controller1`
+    pub fn controller1(&self) -> Controller {
+        self.controller1
+    }
+
+    /// Get the current controller 2 state
+    ///
+    /// # Returns
+    ///
+    /// The buttons last set with `set_controller2`
+    pub fn controller2(&self) -> Controller {
+        self.controller2
+    }
+
+    /// Append the controller port state to a save state
+    pub(crate) fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_u8(self.controller1.to_bits());
+        writer.write_u8(self.controller2.to_bits());
+        writer.write_bool(self.strobe);
+        writer.write_u8(self.button_index1);
+        writer.write_u8(self.button_index2);
+    }
+
+    /// Restore state written by `save_state`
+    pub(crate) fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.controller1 = Controller::from_bits(reader.read_u8()?);
+        self.controller2 = Controller::from_bits(reader.read_u8()?);
+        self.strobe = reader.read_bool()?;
+        self.button_index1 = reader.read_u8()?;
+        self.button_index2 = reader.read_u8()?;
+        Ok(())
+    }
 }
 
 impl MemoryMappedDevice for ControllerIO {
@@ -0,0 +1,651 @@
+// Input movies in FCEUX's FM2 format
+//
+// An FM2 file is a text header of `key value` lines followed by one input
+// line per frame:
+//
+// ```text
+// version 3
+// romFilename game
+// romChecksum base64:jTfT2JEPpgQ7OPyI2ZgT1w==
+// rerecordCount 12
+// port0 1
+// port1 1
+// port2 0
+// |0|R...T..A|........||
+// ```
+//
+// Each input line holds the command bits (1 = soft reset, 2 = power cycle)
+// and one field per port. A gamepad field lists the buttons in `RLDUTSBA`
+// order, with `.` (or a space) for a released button.
+
+use super::Controller;
+use crate::cartridge::Cartridge;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Movie command: soft reset at the start of the frame
+pub const COMMAND_RESET: u8 = 0x01;
+
+/// Movie command: power cycle at the start of the frame
+pub const COMMAND_POWER: u8 = 0x02;
+
+/// Button letters of a gamepad field, from bit 7 (Right) down to bit 0 (A)
+const GAMEPAD_BUTTONS: &[u8; 8] = b"RLDUTSBA";
+
+/// FM2 port type for an empty port
+const PORT_NONE: u8 = 0;
+
+/// FM2 port type for a standard gamepad
+const PORT_GAMEPAD: u8 = 1;
+
+/// Errors that can occur while reading or checking a movie
+#[derive(Debug)]
+pub enum MovieError {
+    /// I/O error
+    Io(io::Error),
+
+    /// Malformed movie file
+    Parse { line: usize, message: String },
+
+    /// Movie uses a feature this emulator does not support
+    Unsupported(String),
+
+    /// Movie was recorded with a different ROM
+    RomMismatch { expected: String, found: String },
+
+    /// No ROM loaded
+    NoRomLoaded,
+}
+
+impl std::fmt::Display for MovieError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MovieError::Io(e) => write!(f, "I/O error: {}", e),
+            MovieError::Parse { line, message } => {
+                write!(f, "Movie parse error on line {}: {}", line, message)
+            }
+            MovieError::Unsupported(feature) => write!(f, "Unsupported movie: {}", feature),
+            MovieError::RomMismatch { expected, found } => write!(
+                f,
+                "ROM checksum mismatch: movie expects {}, loaded ROM is {}",
+                expected, found
+            ),
+            MovieError::NoRomLoaded => write!(f, "No ROM loaded"),
+        }
+    }
+}
+
+impl std::error::Error for MovieError {}
+
+impl From<io::Error> for MovieError {
+    fn from(e: io::Error) -> Self {
+        MovieError::Io(e)
+    }
+}
+
+/// Input for a single frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MovieFrame {
+    /// Command bits (`COMMAND_RESET`, `COMMAND_POWER`)
+    pub commands: u8,
+
+    /// Controller 1 buttons
+    pub controller1: Controller,
+
+    /// Controller 2 buttons
+    pub controller2: Controller,
+}
+
+/// An input movie
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::input::movie::{Movie, MovieFrame};
+/// use nes_rs::input::Controller;
+/// use nes_rs::Cartridge;
+///
+/// let mut movie = Movie::new(&Cartridge::new(), "game");
+/// let mut controller = Controller::new();
+/// controller.start = true;
+/// movie.push_frame(MovieFrame {
+///     controller1: controller,
+///     ..Default::default()
+/// });
+///
+/// let text = movie.to_fm2();
+/// assert!(text.contains("|0|....T...|........||"));
+/// assert_eq!(Movie::parse(&text).unwrap().frames(), movie.frames());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Movie {
+    /// ROM file name (without extension) the movie was recorded with
+    rom_filename: String,
+
+    /// ROM checksum, `base64:` followed by the base64 MD5 digest
+    rom_checksum: String,
+
+    /// Unique identifier of the movie
+    guid: String,
+
+    /// Number of times a save state was loaded while recording
+    rerecord_count: u32,
+
+    /// Whether a gamepad is connected to each controller port
+    ports: [bool; 2],
+
+    /// Free-form comments (`comment` header lines)
+    comments: Vec<String>,
+
+    /// Per-frame input
+    frames: Vec<MovieFrame>,
+}
+
+impl Movie {
+    /// Create an empty movie for `cartridge`
+    ///
+    /// # Arguments
+    ///
+    /// * `cartridge` - The cartridge being recorded
+    /// * `rom_filename` - ROM name stored in the header
+    ///
+    /// # Returns
+    ///
+    /// A movie with gamepads in both ports and no frames
+    pub fn new(cartridge: &Cartridge, rom_filename: &str) -> Self {
+        Movie {
+            rom_filename: rom_filename.to_string(),
+            rom_checksum: rom_checksum(cartridge),
+            guid: new_guid(),
+            rerecord_count: 0,
+            ports: [true, true],
+            comments: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Parse a movie from FM2 text
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Contents of an `.fm2` file
+    ///
+    /// # Returns
+    ///
+    /// The parsed movie or an error
+    pub fn parse(text: &str) -> Result<Self, MovieError> {
+        let mut movie = Movie {
+            rom_filename: String::new(),
+            rom_checksum: String::new(),
+            guid: String::new(),
+            rerecord_count: 0,
+            ports: [true, true],
+            comments: Vec::new(),
+            frames: Vec::new(),
+        };
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let parse_error = |message: String| MovieError::Parse {
+                line: line_number,
+                message,
+            };
+
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('|') {
+                let frame = movie.parse_input_line(line).map_err(parse_error)?;
+                movie.frames.push(frame);
+                continue;
+            }
+
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let number = || {
+                value
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|_| parse_error(format!("invalid value for {}: {}", key, value)))
+            };
+            match key {
+                "version" if number()? != 3 => {
+                    return Err(MovieError::Unsupported(format!("FM2 version {}", value)))
+                }
+                "binary" if number()? != 0 => {
+                    return Err(MovieError::Unsupported("binary input log".to_string()))
+                }
+                "palFlag" if number()? != 0 => {
+                    return Err(MovieError::Unsupported("PAL movie".to_string()))
+                }
+                "fourscore" if number()? != 0 => {
+                    return Err(MovieError::Unsupported("Four Score input".to_string()))
+                }
+                "FDS" if number()? != 0 => {
+                    return Err(MovieError::Unsupported("Famicom Disk System".to_string()))
+                }
+                "port0" | "port1" => {
+                    let port = if key == "port0" { 0 } else { 1 };
+                    movie.ports[port] = match number()? as u8 {
+                        PORT_NONE => false,
+                        PORT_GAMEPAD => true,
+                        other => {
+                            return Err(MovieError::Unsupported(format!(
+                                "input device {} in {}",
+                                other, key
+                            )))
+                        }
+                    };
+                }
+                "port2" if number()? != 0 => {
+                    return Err(MovieError::Unsupported("expansion port input".to_string()))
+                }
+                "rerecordCount" => movie.rerecord_count = number()?,
+                "romFilename" => movie.rom_filename = value.to_string(),
+                "romChecksum" => movie.rom_checksum = value.to_string(),
+                "guid" => movie.guid = value.to_string(),
+                "comment" => movie.comments.push(value.to_string()),
+                // Other keys (emuVersion, subtitles, ...) do not affect playback
+                _ => {}
+            }
+        }
+
+        Ok(movie)
+    }
+
+    /// Parse one `|commands|port0|port1|port2|` input line
+    fn parse_input_line(&self, line: &str) -> Result<MovieFrame, String> {
+        let fields: Vec<&str> = line.split('|').collect();
+        if fields.len() < 5 {
+            return Err(format!(
+                "expected 4 input fields, found {}",
+                fields.len() - 1
+            ));
+        }
+
+        let commands = fields[1]
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| format!("invalid command field: {}", fields[1]))?;
+
+        let mut controllers = [Controller::new(); 2];
+        for (port, controller) in controllers.iter_mut().enumerate() {
+            if self.ports[port] {
+                *controller = parse_gamepad(fields[port + 2])?;
+            }
+        }
+
+        Ok(MovieFrame {
+            commands,
+            controller1: controllers[0],
+            controller2: controllers[1],
+        })
+    }
+
+    /// Serialize the movie as FM2 text
+    ///
+    /// # Returns
+    ///
+    /// The contents of an `.fm2` file
+    pub fn to_fm2(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "version 3");
+        let _ = writeln!(text, "emuVersion 0");
+        let _ = writeln!(text, "rerecordCount {}", self.rerecord_count);
+        let _ = writeln!(text, "palFlag 0");
+        let _ = writeln!(text, "romFilename {}", self.rom_filename);
+        let _ = writeln!(text, "romChecksum {}", self.rom_checksum);
+        let _ = writeln!(text, "guid {}", self.guid);
+        let _ = writeln!(text, "fourscore 0");
+        let _ = writeln!(text, "microphone 0");
+        let _ = writeln!(text, "port0 {}", self.ports[0] as u8);
+        let _ = writeln!(text, "port1 {}", self.ports[1] as u8);
+        let _ = writeln!(text, "port2 0");
+        let _ = writeln!(text, "FDS 0");
+        let _ = writeln!(text, "NewPPU 0");
+        for comment in &self.comments {
+            let _ = writeln!(text, "comment {}", comment);
+        }
+
+        for frame in &self.frames {
+            let _ = write!(text, "|{}|", frame.commands);
+            for (port, controller) in [frame.controller1, frame.controller2].iter().enumerate() {
+                if self.ports[port] {
+                    text.push_str(&format_gamepad(controller));
+                }
+                text.push('|');
+            }
+            text.push_str("|\n");
+        }
+
+        text
+    }
+
+    /// Load a movie from an `.fm2` file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the movie file
+    ///
+    /// # Returns
+    ///
+    /// The movie or an error
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, MovieError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Save the movie to an `.fm2` file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Destination path
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or error
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), MovieError> {
+        fs::write(path, self.to_fm2())?;
+        Ok(())
+    }
+
+    /// Check that the movie was recorded with `cartridge`
+    ///
+    /// # Arguments
+    ///
+    /// * `cartridge` - The loaded cartridge
+    ///
+    /// # Returns
+    ///
+    /// `MovieError::RomMismatch` if the header's ROM checksum does not match
+    pub fn verify_rom(&self, cartridge: &Cartridge) -> Result<(), MovieError> {
+        let found = rom_checksum(cartridge);
+        if self.rom_checksum != found {
+            return Err(MovieError::RomMismatch {
+                expected: self.rom_checksum.clone(),
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    /// Input of every frame
+    pub fn frames(&self) -> &[MovieFrame] {
+        &self.frames
+    }
+
+    /// Number of frames in the movie
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether the movie has no frames
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Append a frame of input
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - Input for the next frame
+    pub fn push_frame(&mut self, frame: MovieFrame) {
+        self.frames.push(frame);
+    }
+
+    /// Drop every frame from `len` onwards
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - Number of frames to keep
+    pub fn truncate(&mut self, len: usize) {
+        self.frames.truncate(len);
+    }
+
+    /// Number of rerecords (save state loads while recording)
+    pub fn rerecord_count(&self) -> u32 {
+        self.rerecord_count
+    }
+
+    /// Count one more rerecord
+    pub fn add_rerecord(&mut self) {
+        self.rerecord_count += 1;
+    }
+
+    /// ROM name stored in the header
+    pub fn rom_filename(&self) -> &str {
+        &self.rom_filename
+    }
+
+    /// ROM checksum stored in the header (`base64:` + MD5)
+    pub fn rom_checksum(&self) -> &str {
+        &self.rom_checksum
+    }
+
+    /// Unique identifier of the movie
+    pub fn guid(&self) -> &str {
+        &self.guid
+    }
+
+    /// Header comments
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// Add a header comment (e.g. "author Name")
+    ///
+    /// # Arguments
+    ///
+    /// * `comment` - Comment text
+    pub fn add_comment(&mut self, comment: &str) {
+        self.comments.push(comment.to_string());
+    }
+}
+
+/// Parse a gamepad field in `RLDUTSBA` order
+fn parse_gamepad(field: &str) -> Result<Controller, String> {
+    let bytes = field.as_bytes();
+    if bytes.len() != GAMEPAD_BUTTONS.len() {
+        return Err(format!("invalid gamepad field: {:?}", field));
+    }
+
+    let bits = bytes.iter().enumerate().fold(0u8, |bits, (index, &c)| {
+        if c == b'.' || c == b' ' {
+            bits
+        } else {
+            bits | (0x80 >> index)
+        }
+    });
+    Ok(Controller::from_bits(bits))
+}
+
+/// Format a controller as a gamepad field in `RLDUTSBA` order
+fn format_gamepad(controller: &Controller) -> String {
+    let bits = controller.to_bits();
+    GAMEPAD_BUTTONS
+        .iter()
+        .enumerate()
+        .map(|(index, &letter)| {
+            if bits & (0x80 >> index) != 0 {
+                letter as char
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+/// FCEUX-style ROM checksum: `base64:` followed by the base64 MD5
+fn rom_checksum(cartridge: &Cartridge) -> String {
+    format!("base64:{}", base64_encode(&cartridge.md5()))
+}
+
+/// Standard base64 encoding with padding
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let group = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(group >> (18 - 6 * i)) as usize & 0x3F] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Generate a GUID for a new movie from the current time
+fn new_guid() -> String {
+    let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0) as u64;
+    let bytes = crate::state::hash::md5(&nanos.to_le_bytes());
+    let hex = crate::state::hash::to_hex(&bytes).to_uppercase();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_cartridge(fill: u8) -> Cartridge {
+        Cartridge {
+            prg_rom: vec![fill; 16 * 1024],
+            chr_rom: vec![fill; 8 * 1024],
+            ..Cartridge::new()
+        }
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_gamepad_field_roundtrip() {
+        for bits in 0..=255u8 {
+            let controller = Controller::from_bits(bits);
+            assert_eq!(parse_gamepad(&format_gamepad(&controller)), Ok(controller));
+        }
+
+        let mut controller = Controller::new();
+        controller.right = true;
+        controller.button_a = true;
+        assert_eq!(format_gamepad(&controller), "R......A");
+    }
+
+    #[test]
+    fn test_parse_fceux_movie() {
+        let text = "version 3\n\
+                    emuVersion 22020\n\
+                    rerecordCount 42\n\
+                    palFlag 0\n\
+                    romFilename Super Game\n\
+                    romChecksum base64:AAAAAAAAAAAAAAAAAAAAAA==\n\
+                    guid 12345678-1234-1234-1234-123456789ABC\n\
+                    fourscore 0\n\
+                    microphone 0\n\
+                    port0 1\n\
+                    port1 0\n\
+                    port2 0\n\
+                    FDS 0\n\
+                    NewPPU 0\n\
+                    comment author Tester\n\
+                    |0|........|||\n\
+                    |1|R  U   A|||\n\
+                    |2|.L..T...|||\n";
+        let movie = Movie::parse(text).unwrap();
+
+        assert_eq!(movie.rerecord_count(), 42);
+        assert_eq!(movie.rom_filename(), "Super Game");
+        assert_eq!(movie.comments(), ["author Tester"]);
+        assert_eq!(movie.len(), 3);
+
+        let frame = movie.frames()[1];
+        assert_eq!(frame.commands, COMMAND_RESET);
+        assert!(frame.controller1.right && frame.controller1.up && frame.controller1.button_a);
+        assert!(!frame.controller1.left && !frame.controller1.start);
+        assert_eq!(movie.frames()[2].commands, COMMAND_POWER);
+        assert!(movie.frames()[2].controller1.left && movie.frames()[2].controller1.start);
+
+        // Port 1 is empty, so it is written back as an empty field
+        assert!(movie.to_fm2().contains("\n|1|R..U...A|||\n"));
+    }
+
+    #[test]
+    fn test_fm2_roundtrip() {
+        let mut movie = Movie::new(&test_cartridge(0x11), "game");
+        movie.add_comment("author Tester");
+        for i in 0..20u8 {
+            movie.push_frame(MovieFrame {
+                commands: if i == 5 { COMMAND_RESET } else { 0 },
+                controller1: Controller::from_bits(i.wrapping_mul(37)),
+                controller2: Controller::from_bits(!i),
+            });
+        }
+        movie.add_rerecord();
+
+        assert_eq!(Movie::parse(&movie.to_fm2()).unwrap(), movie);
+    }
+
+    #[test]
+    fn test_rejects_unsupported_movies() {
+        for header in [
+            "version 2",
+            "binary 1",
+            "palFlag 1",
+            "fourscore 1",
+            "port0 2",
+            "FDS 1",
+        ] {
+            assert!(
+                matches!(Movie::parse(header), Err(MovieError::Unsupported(_))),
+                "{} should be rejected",
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed_input() {
+        for line in ["|x|........|........||", "|0|....|........||", "|0|"] {
+            assert!(
+                matches!(
+                    Movie::parse(&format!("version 3\n{}\n", line)),
+                    Err(MovieError::Parse { line: 2, .. })
+                ),
+                "{} should be rejected",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_rom() {
+        let cartridge = test_cartridge(0x11);
+        let movie = Movie::new(&cartridge, "game");
+
+        assert!(movie.verify_rom(&cartridge).is_ok());
+        assert!(matches!(
+            movie.verify_rom(&test_cartridge(0x22)),
+            Err(MovieError::RomMismatch { .. })
+        ));
+        assert!(movie.rom_checksum().starts_with("base64:"));
+    }
+}
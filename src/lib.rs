@@ -14,24 +14,27 @@ pub mod emulator;
 pub mod input;
 pub mod ppu;
 pub mod ram;
+pub mod region;
+pub mod state;
 
 // Re-export main types for convenience
 pub use apu::Apu;
 #[cfg(feature = "audio")]
 pub use audio::{AudioConfig, AudioOutput, AudioSystem, Mixer};
 pub use bus::{Bus, MemoryMappedDevice};
-pub use cartridge::{Cartridge, INesError, INesHeader, Mapper, Mirroring};
-pub use cpu::Cpu;
+pub use cartridge::{Cartridge, INesError, INesHeader, Mapper, Mirroring, NametableSource};
+pub use cpu::{Cpu, CpuBus, FlatBus};
 pub use debug::{
     disassemble_count, disassemble_instruction, disassemble_range, CpuDebugger, CpuState, DebugUI,
     Debugger, DisassembledInstruction, LogLevel, Logger, MemoryRegion, MemoryViewer, PpuDebugger,
     PpuState, SpriteInfo, TraceEntry,
 };
 pub use display::{FrameBuffer, WindowConfig};
-pub use emulator::{Emulator, EmulatorConfig, SaveState, SaveStateError, SpeedMode};
+pub use emulator::{Emulator, EmulatorConfig, RunResult, SaveState, SaveStateError, SpeedMode};
 pub use input::{Controller, ControllerIO};
 pub use ppu::Ppu;
 pub use ram::Ram;
+pub use region::Region;
 
 #[cfg(test)]
 mod tests {
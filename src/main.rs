@@ -4,6 +4,7 @@
 // Eventually, this will integrate with the full emulator (CPU, PPU, etc.)
 
 use nes_rs::display::{run_display, WindowConfig};
+use nes_rs::emulator::EmulatorConfig;
 use nes_rs::input::InputConfig;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -21,11 +22,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("-------------------");
     println!();
 
+    // Frame pacing follows the console region (NTSC unless configured)
+    let region = EmulatorConfig::load_or_default().region.unwrap_or_default();
+
     // Create window configuration
-    // Default: 3x scale, 60 FPS, VSync enabled
+    // Default: 3x scale, VSync enabled
     let window_config = WindowConfig::new()
         .with_scale(3) // 768x720 window (256x240 * 3)
-        .with_fps(60) // 60 FPS (NTSC)
+        .with_region(region) // 60 FPS (NTSC) or 50 FPS (PAL/Dendy)
         .with_vsync(true); // Enable VSync for smooth display
 
     // Run the display window with test pattern
@@ -27,6 +27,11 @@ pub(super) const NAMETABLE_HEIGHT: usize = 30;
 /// Tile size in pixels (8x8)
 pub(super) const TILE_SIZE: usize = 8;
 
+/// Frames after which an unrefreshed bit of the PPU I/O latch decays to 0
+///
+/// The latch is a capacitor; a bit set to 1 fades after roughly 600ms.
+pub(super) const IO_LATCH_DECAY_FRAMES: u64 = 36;
+
 // ========================================
 // PPU Timing Constants (NTSC)
 // ========================================
@@ -39,7 +44,7 @@ pub(super) const SCANLINES_PER_FRAME: u16 = 262;
 
 /// Total PPU cycles per frame (NTSC)
 /// 341 cycles/scanline × 262 scanlines = 89,342 cycles
-#[allow(dead_code)]
+#[cfg(test)]
 pub(super) const CYCLES_PER_FRAME: u32 =
     (CYCLES_PER_SCANLINE as u32) * (SCANLINES_PER_FRAME as u32);
 
@@ -60,4 +65,21 @@ pub(super) const POSTRENDER_SCANLINE: u16 = 240;
 pub(super) const FIRST_VBLANK_SCANLINE: u16 = 241;
 
 /// Last VBlank scanline
+#[cfg(test)]
 pub(super) const LAST_VBLANK_SCANLINE: u16 = 260;
+
+// ========================================
+// PPU Timing Constants (PAL / Dendy)
+// ========================================
+
+/// Number of scanlines per frame (PAL and Dendy)
+pub(super) const SCANLINES_PER_FRAME_PAL: u16 = 312;
+
+/// Pre-render scanline (PAL and Dendy)
+pub(super) const PRERENDER_SCANLINE_PAL: u16 = 311;
+
+/// First VBlank scanline on the Dendy
+///
+/// The Dendy keeps the NTSC 20-scanline VBlank and pads the frame with 50
+/// extra post-render scanlines instead.
+pub(super) const FIRST_VBLANK_SCANLINE_DENDY: u16 = 291;
@@ -1,8 +1,8 @@
 // PPU memory access methods
 
-use super::constants::NAMETABLE_SIZE;
+use super::constants::{CYCLES_PER_SCANLINE, NAMETABLE_SIZE};
 use super::Ppu;
-use crate::cartridge::Mirroring;
+use crate::cartridge::{Mirroring, NametableSource};
 
 impl Ppu {
     /// Mirror nametable address based on mirroring mode
@@ -64,6 +64,71 @@ impl Ppu {
         mirrored_table * NAMETABLE_SIZE + offset
     }
 
+    /// Get the memory behind a nametable address
+    ///
+    /// The cartridge may select the memory for each nametable; otherwise the
+    /// PPU's internal VRAM is arranged by the mirroring mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Nametable address ($2000-$2FFF)
+    ///
+    /// # Returns
+    ///
+    /// The nametable's memory
+    fn nametable_source(&self, addr: u16) -> NametableSource {
+        self.mapper
+            .as_ref()
+            .and_then(|mapper| mapper.borrow().nametable_source(addr))
+            .unwrap_or_else(|| {
+                let page = self.mirror_nametable_addr(addr) / NAMETABLE_SIZE;
+                NametableSource::Ciram(page as u8)
+            })
+    }
+
+    /// Read a nametable byte ($2000-$3EFF)
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Nametable address, or a mirror in $3000-$3EFF
+    ///
+    /// # Returns
+    ///
+    /// The byte from internal VRAM or cartridge memory
+    fn read_nametable(&self, addr: u16) -> u8 {
+        let addr = 0x2000 | (addr & 0x0FFF);
+        match self.nametable_source(addr) {
+            NametableSource::Ciram(page) => {
+                self.nametables[(page as usize & 1) * NAMETABLE_SIZE + (addr as usize & 0x03FF)]
+            }
+            NametableSource::Cartridge => match self.mapper {
+                Some(ref mapper) => mapper.borrow().nametable_read(addr),
+                None => 0,
+            },
+        }
+    }
+
+    /// Write a nametable byte ($2000-$3EFF)
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Nametable address, or a mirror in $3000-$3EFF
+    /// * `data` - Byte value to write
+    fn write_nametable(&mut self, addr: u16, data: u8) {
+        let addr = 0x2000 | (addr & 0x0FFF);
+        match self.nametable_source(addr) {
+            NametableSource::Ciram(page) => {
+                self.nametables[(page as usize & 1) * NAMETABLE_SIZE + (addr as usize & 0x03FF)] =
+                    data;
+            }
+            NametableSource::Cartridge => {
+                if let Some(ref mapper) = self.mapper {
+                    mapper.borrow_mut().nametable_write(addr, data);
+                }
+            }
+        }
+    }
+
     /// Mirror palette address
     ///
     /// Palette RAM has special mirroring:
@@ -92,7 +157,8 @@ impl Ppu {
     /// Read from PPU memory (VRAM)
     ///
     /// Handles reading from pattern tables (via cartridge), nametables, and palette RAM.
-    /// This is the internal memory read function used by PPUDATA and debugging tools.
+    /// This is a side-effect-free peek for debugging tools; the PPU's own
+    /// accesses use `fetch_ppu_memory`.
     ///
     /// # Arguments
     ///
@@ -117,16 +183,8 @@ impl Ppu {
             }
 
             // Nametables: $2000-$2FFF
-            0x2000..=0x2FFF => {
-                let mirrored_addr = self.mirror_nametable_addr(addr);
-                self.nametables[mirrored_addr]
-            }
-
             // Nametable mirrors: $3000-$3EFF -> $2000-$2EFF
-            0x3000..=0x3EFF => {
-                let mirrored_addr = self.mirror_nametable_addr(addr - 0x1000);
-                self.nametables[mirrored_addr]
-            }
+            0x2000..=0x3EFF => self.read_nametable(addr),
 
             // Palette RAM: $3F00-$3FFF
             0x3F00..=0x3FFF => {
@@ -138,6 +196,67 @@ impl Ppu {
         }
     }
 
+    /// Fetch from PPU memory as the PPU itself does
+    ///
+    /// Unlike `read_ppu_memory`, this models a real access on the PPU address
+    /// bus: the mapper is notified of the address and pattern reads go through
+    /// `Mapper::ppu_fetch`, so latch-based mappers (MMC2/MMC4) can react.
+    /// Used by the rendering pipeline and PPUDATA; debugging tools should keep
+    /// using the side-effect-free `read_ppu_memory`.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - PPU memory address ($0000-$3FFF)
+    ///
+    /// # Returns
+    ///
+    /// The byte value at the specified address
+    pub(super) fn fetch_ppu_memory(&mut self, addr: u16) -> u8 {
+        let addr = addr & 0x3FFF;
+        self.notify_mapper_address(addr);
+
+        match addr {
+            // Pattern tables go through the mapper's side-effecting fetch path
+            0x0000..=0x1FFF => match self.mapper {
+                Some(ref mapper) => mapper.borrow_mut().ppu_fetch(addr),
+                None => 0,
+            },
+            // The mapper may substitute nametable bytes while rendering
+            0x2000..=0x3EFF => {
+                let substitute = self.mapper.as_ref().and_then(|mapper| {
+                    mapper
+                        .borrow_mut()
+                        .nametable_fetch(0x2000 | (addr & 0x0FFF))
+                });
+                substitute.unwrap_or_else(|| self.read_nametable(addr))
+            }
+            _ => self.read_ppu_memory(addr),
+        }
+    }
+
+    /// Notify the mapper that an address was placed on the PPU address bus
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - PPU memory address ($0000-$3FFF)
+    pub(super) fn notify_mapper_address(&mut self, addr: u16) {
+        let ppu_cycle = self.ppu_cycle();
+        if let Some(ref mapper) = self.mapper {
+            mapper.borrow_mut().on_ppu_address(addr & 0x3FFF, ppu_cycle);
+        }
+    }
+
+    /// Monotonic PPU dot count since power-on
+    ///
+    /// # Returns
+    ///
+    /// The number of dots elapsed, counting every frame as a full frame of
+    /// 341-dot scanlines
+    pub(super) fn ppu_cycle(&self) -> u64 {
+        let scanlines = self.frame * self.scanlines_per_frame() as u64 + self.scanline as u64;
+        scanlines * CYCLES_PER_SCANLINE as u64 + self.cycle as u64
+    }
+
     /// Write to PPU memory (VRAM)
     ///
     /// Handles writing to pattern tables (via cartridge), nametables, and palette RAM.
@@ -161,16 +280,8 @@ impl Ppu {
             }
 
             // Nametables: $2000-$2FFF
-            0x2000..=0x2FFF => {
-                let mirrored_addr = self.mirror_nametable_addr(addr);
-                self.nametables[mirrored_addr] = data;
-            }
-
             // Nametable mirrors: $3000-$3EFF -> $2000-$2EFF
-            0x3000..=0x3EFF => {
-                let mirrored_addr = self.mirror_nametable_addr(addr - 0x1000);
-                self.nametables[mirrored_addr] = data;
-            }
+            0x2000..=0x3EFF => self.write_nametable(addr, data),
 
             // Palette RAM: $3F00-$3FFF
             0x3F00..=0x3FFF => {
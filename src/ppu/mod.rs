@@ -65,6 +65,7 @@ mod rendering;
 
 use crate::bus::MemoryMappedDevice;
 use crate::cartridge::{Mapper, Mirroring};
+use crate::region::Region;
 use constants::*;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -151,6 +152,18 @@ pub struct Ppu {
     /// Palette reads ($3F00-$3FFF) are not buffered.
     pub(crate) read_buffer: u8,
 
+    /// I/O latch ("PPU open bus")
+    ///
+    /// The PPU's data bus to the CPU holds the last value written to or read
+    /// from any register. Write-only registers and undriven register bits
+    /// return it.
+    pub(crate) io_latch: u8,
+
+    /// Frame in which each bit of `io_latch` was last refreshed
+    ///
+    /// Bits that are not refreshed for [`IO_LATCH_DECAY_FRAMES`] read as 0.
+    pub(crate) io_latch_refresh: [u64; 8],
+
     // ========================================
     // PPU Memory (VRAM)
     // ========================================
@@ -206,12 +219,15 @@ pub struct Ppu {
     // ========================================
     // Timing (Cycle-accurate execution)
     // ========================================
-    /// Current scanline (0-261)
+    /// Console region, which selects the scanline layout of a frame
+    region: Region,
+
+    /// Current scanline (0-261 on NTSC, 0-311 on PAL and Dendy)
     ///
     /// - 0-239: Visible scanlines
-    /// - 240: Post-render scanline
-    /// - 241-260: VBlank scanlines
-    /// - 261: Pre-render scanline
+    /// - 240: Post-render scanline (240-290 on the Dendy)
+    /// - 241-260: VBlank scanlines (241-310 on PAL, 291-310 on the Dendy)
+    /// - 261: Pre-render scanline (311 on PAL and the Dendy)
     pub(crate) scanline: u16,
 
     /// Current cycle within the scanline (0-340)
@@ -246,40 +262,40 @@ pub struct Ppu {
     ///
     /// 16-bit shift register holding pattern data for 2 tiles.
     /// Shifts left each cycle to output pixels.
-    bg_pattern_shift_low: u16,
+    pub(crate) bg_pattern_shift_low: u16,
 
     /// Background pattern shift register (high bitplane)
     ///
     /// 16-bit shift register holding pattern data for 2 tiles.
     /// Shifts left each cycle to output pixels.
-    bg_pattern_shift_high: u16,
+    pub(crate) bg_pattern_shift_high: u16,
 
     /// Background attribute shift register (low bit)
     ///
     /// 8-bit shift register holding attribute (palette) data.
     /// Extended to 16 bits during shifting for easier processing.
-    bg_attribute_shift_low: u16,
+    pub(crate) bg_attribute_shift_low: u16,
 
     /// Background attribute shift register (high bit)
     ///
     /// 8-bit shift register holding attribute (palette) data.
     /// Extended to 16 bits during shifting for easier processing.
-    bg_attribute_shift_high: u16,
+    pub(crate) bg_attribute_shift_high: u16,
 
     // ========================================
     // Background Tile Fetching Pipeline
     // ========================================
     /// Nametable byte being fetched/latched
-    bg_nametable_byte: u8,
+    pub(crate) bg_nametable_byte: u8,
 
     /// Attribute byte being fetched/latched
-    bg_attribute_byte: u8,
+    pub(crate) bg_attribute_byte: u8,
 
     /// Pattern table low bitplane byte being fetched/latched
-    bg_pattern_low: u8,
+    pub(crate) bg_pattern_low: u8,
 
     /// Pattern table high bitplane byte being fetched/latched
-    bg_pattern_high: u8,
+    pub(crate) bg_pattern_high: u8,
 
     // ========================================
     // Sprite Rendering State
@@ -289,31 +305,31 @@ pub struct Ppu {
     /// During sprite evaluation (cycles 257-320 of previous scanline),
     /// the PPU determines which sprites are visible on the next scanline
     /// and stores them here.
-    secondary_oam: [(u8, u8, u8, u8); 8], // (y, tile, attr, x) for up to 8 sprites
+    pub(crate) secondary_oam: [(u8, u8, u8, u8); 8], // (y, tile, attr, x) for up to 8 sprites
 
     /// Number of sprites in secondary OAM
-    sprite_count: usize,
+    pub(crate) sprite_count: usize,
 
     /// Sprite pattern shift registers (low bitplane) - 8 sprites
-    sprite_pattern_shift_low: [u8; 8],
+    pub(crate) sprite_pattern_shift_low: [u8; 8],
 
     /// Sprite pattern shift registers (high bitplane) - 8 sprites
-    sprite_pattern_shift_high: [u8; 8],
+    pub(crate) sprite_pattern_shift_high: [u8; 8],
 
     /// Sprite attribute latches - 8 sprites
-    sprite_attributes: [u8; 8],
+    pub(crate) sprite_attributes: [u8; 8],
 
     /// Sprite X position counters - 8 sprites
     ///
     /// These count down each cycle. When they reach 0, the sprite becomes active
     /// and its pattern shift registers begin shifting.
-    sprite_x_positions: [u8; 8],
+    pub(crate) sprite_x_positions: [u8; 8],
 
     /// Sprite 0 present flag
     ///
     /// Set to true if sprite 0 is in the secondary OAM for the current scanline.
     /// Used for sprite 0 hit detection.
-    sprite_0_present: bool,
+    pub(crate) sprite_0_present: bool,
 }
 
 impl Ppu {
@@ -346,6 +362,8 @@ impl Ppu {
             fine_x: 0,
             write_latch: false,
             read_buffer: 0x00,
+            io_latch: 0x00,
+            io_latch_refresh: [0; 8],
 
             // PPU memory
             nametables: [0; NAMETABLE_SIZE * 2],
@@ -360,6 +378,7 @@ impl Ppu {
             frame_buffer: [0; SCREEN_WIDTH * SCREEN_HEIGHT],
 
             // Timing
+            region: Region::Ntsc,
             scanline: 0,
             cycle: 0,
             frame: 0,
@@ -393,7 +412,8 @@ impl Ppu {
     ///
     /// Resets all registers and internal state to their default values.
     /// This simulates a power cycle or reset signal.
-    /// Note: Mirroring mode is not reset as it comes from the cartridge.
+    /// Note: Mirroring mode and region are not reset as they come from the
+    /// cartridge.
     pub fn reset(&mut self) {
         self.ppuctrl = 0x00;
         self.ppumask = 0x00;
@@ -404,6 +424,8 @@ impl Ppu {
         self.fine_x = 0;
         self.write_latch = false;
         self.read_buffer = 0x00;
+        self.io_latch = 0x00;
+        self.io_latch_refresh = [0; 8];
         self.nametables = [0; NAMETABLE_SIZE * 2];
         self.palette_ram = [0; PALETTE_SIZE];
         self.oam = [0; 256];
@@ -458,6 +480,58 @@ impl Ppu {
         self.mirroring = mirroring;
     }
 
+    /// Set the console region
+    ///
+    /// PAL and Dendy frames have 312 scanlines instead of 262, and neither
+    /// skips a dot on odd frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - The console region to emulate
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::ppu::Ppu;
+    /// use nes_rs::Region;
+    ///
+    /// let mut ppu = Ppu::new();
+    /// ppu.set_region(Region::Pal);
+    /// assert_eq!(ppu.region(), Region::Pal);
+    /// ```
+    pub fn set_region(&mut self, region: Region) {
+        self.region = region;
+    }
+
+    /// Get the console region
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    /// Number of scanlines per frame in the current region
+    pub(super) fn scanlines_per_frame(&self) -> u16 {
+        match self.region {
+            Region::Ntsc => SCANLINES_PER_FRAME,
+            Region::Pal | Region::Dendy => SCANLINES_PER_FRAME_PAL,
+        }
+    }
+
+    /// Pre-render scanline in the current region
+    fn prerender_scanline(&self) -> u16 {
+        match self.region {
+            Region::Ntsc => PRERENDER_SCANLINE,
+            Region::Pal | Region::Dendy => PRERENDER_SCANLINE_PAL,
+        }
+    }
+
+    /// First VBlank scanline in the current region
+    fn first_vblank_scanline(&self) -> u16 {
+        match self.region {
+            Region::Ntsc | Region::Pal => FIRST_VBLANK_SCANLINE,
+            Region::Dendy => FIRST_VBLANK_SCANLINE_DENDY,
+        }
+    }
+
     /// Set the mapper for CHR-ROM/RAM access
     ///
     /// This should be called when loading a cartridge to provide access to
@@ -595,22 +669,30 @@ impl Ppu {
             self.vblank_just_set = false;
         }
 
+        // Let the mapper observe the start of each scanline
+        if self.cycle == 0 {
+            let rendering_enabled = self.is_rendering_enabled();
+            if let Some(ref mapper) = self.mapper {
+                mapper
+                    .borrow_mut()
+                    .on_scanline(self.scanline, rendering_enabled);
+            }
+        }
+
         // Execute current cycle based on scanline
+        let prerender_scanline = self.prerender_scanline();
         match self.scanline {
             FIRST_VISIBLE_SCANLINE..=LAST_VISIBLE_SCANLINE => {
                 self.visible_scanline_cycle();
             }
-            POSTRENDER_SCANLINE => {
-                self.postrender_scanline_cycle();
+            scanline if scanline == prerender_scanline => {
+                self.prerender_scanline_cycle();
             }
-            FIRST_VBLANK_SCANLINE..=LAST_VBLANK_SCANLINE => {
+            scanline if scanline >= self.first_vblank_scanline() => {
                 self.vblank_scanline_cycle();
             }
-            PRERENDER_SCANLINE => {
-                self.prerender_scanline_cycle();
-            }
-            _ => {
-                // Invalid scanline, should not happen
+            POSTRENDER_SCANLINE.. => {
+                self.postrender_scanline_cycle();
             }
         }
 
@@ -623,7 +705,7 @@ impl Ppu {
             self.scanline += 1;
 
             // Check if we've completed a frame
-            if self.scanline >= SCANLINES_PER_FRAME {
+            if self.scanline >= self.scanlines_per_frame() {
                 self.scanline = 0;
                 self.frame += 1;
                 frame_complete = true;
@@ -631,8 +713,9 @@ impl Ppu {
         }
 
         // Special case: Odd frames skip the last cycle of the pre-render scanline
-        // when rendering is enabled
-        if self.scanline == PRERENDER_SCANLINE
+        // when rendering is enabled (NTSC only)
+        if self.region == Region::Ntsc
+            && self.scanline == PRERENDER_SCANLINE
             && self.cycle == CYCLES_PER_SCANLINE - 1
             && (self.frame & 1) == 1
             && self.is_rendering_enabled()
@@ -655,7 +738,7 @@ impl Ppu {
         // - Cycles 1-256: Render pixels and fetch tiles
         // - Cycle 256: Increment Y scroll
         // - Cycle 257: Copy horizontal scroll from t to v
-        // - Cycles 257-320: Sprite evaluation for next scanline
+        // - Cycles 257-320: Sprite pattern fetches for next scanline
         // - Cycles 321-336: Fetch first two tiles of next scanline
         // - Cycles 337-340: Unused nametable fetches
 
@@ -707,11 +790,9 @@ impl Ppu {
                 self.evaluate_sprites_for_next_scanline();
             }
 
-            // Cycles 258-320: Sprite fetching happens here
+            // Cycles 258-320: Fetch the patterns of the sprites evaluated at 257
             258..=320 => {
-                // Sprite fetching cycles
-                // For now, we've already evaluated sprites at cycle 257
-                // In a more accurate implementation, we'd fetch sprite data here
+                self.perform_sprite_fetch(self.cycle);
             }
 
             // Cycles 321-336: Fetch first two tiles of next scanline
@@ -744,14 +825,14 @@ impl Ppu {
         // No special actions needed
     }
 
-    /// Handle VBlank scanline cycles (241-260)
+    /// Handle VBlank scanline cycles (241-260 on NTSC)
     ///
     /// During VBlank, the PPU is idle and games typically update VRAM/OAM.
     fn vblank_scanline_cycle(&mut self) {
-        // Set VBlank flag at scanline 241, cycle 1
+        // Set VBlank flag at scanline 241 (291 on the Dendy), cycle 1
         // We check for cycle == 0, which will become cycle 1 after the increment in step()
         // This represents the transition from cycle 0 to cycle 1
-        if self.scanline == FIRST_VBLANK_SCANLINE && self.cycle == 0 {
+        if self.scanline == self.first_vblank_scanline() && self.cycle == 0 {
             self.ppustatus |= 0x80; // Set VBlank flag (bit 7)
             self.vblank_just_set = true;
 
@@ -763,7 +844,7 @@ impl Ppu {
         }
     }
 
-    /// Handle pre-render scanline cycle (261)
+    /// Handle pre-render scanline cycle (261, or 311 on PAL and Dendy)
     ///
     /// The pre-render scanline prepares for the next frame by clearing flags
     /// and performing background fetches.
@@ -798,7 +879,10 @@ impl Ppu {
             }
             257 => {
                 self.copy_horizontal_scroll();
+                // Sprite fetches still happen on the pre-render scanline
+                self.evaluate_sprites_for_next_scanline();
             }
+            258..=320 => self.perform_sprite_fetch(self.cycle),
             _ => {}
         }
     }
@@ -850,7 +934,7 @@ impl Ppu {
     ///
     /// # Returns
     ///
-    /// The current scanline (0-261)
+    /// The current scanline (0-261 on NTSC, 0-311 on PAL and Dendy)
     pub fn scanline(&self) -> u16 {
         self.scanline
     }
@@ -1,5 +1,6 @@
 // PPU register handling
 
+use super::constants::IO_LATCH_DECAY_FRAMES;
 use super::Ppu;
 
 impl Ppu {
@@ -18,16 +19,15 @@ impl Ppu {
     /// - PPUSTATUS ($2002): Returns status, clears VBlank flag and address latch
     /// - OAMDATA ($2004): Returns OAM data at current OAM address
     /// - PPUDATA ($2007): Returns buffered PPU data (palette reads are immediate)
-    /// - Write-only registers: Return 0
+    /// - Write-only registers: Return the I/O latch (open bus)
+    ///
+    /// Bits driven by a read refresh the I/O latch; the rest come from it.
     pub(super) fn read_register(&mut self, register: u16) -> u8 {
         match register {
-            0 => {
-                // $2000: PPUCTRL - Write only, return 0
-                0
-            }
-            1 => {
-                // $2001: PPUMASK - Write only, return 0
-                0
+            0 | 1 | 3 | 5 | 6 => {
+                // $2000 PPUCTRL, $2001 PPUMASK, $2003 OAMADDR, $2005 PPUSCROLL
+                // and $2006 PPUADDR are write only
+                self.io_latch_value()
             }
             2 => {
                 // $2002: PPUSTATUS - Read only
@@ -50,24 +50,14 @@ impl Ppu {
                     self.nmi_pending = false;
                 }
 
-                status
-            }
-            3 => {
-                // $2003: OAMADDR - Write only, return 0
-                0
+                // Only bits 7-5 are driven, bits 4-0 are open bus
+                self.refresh_io_latch(status, 0xE0)
             }
             4 => {
                 // $2004: OAMDATA - Read/Write
                 // Read from OAM at current OAM address
-                self.oam[self.oam_addr as usize]
-            }
-            5 => {
-                // $2005: PPUSCROLL - Write only, return 0
-                0
-            }
-            6 => {
-                // $2006: PPUADDR - Write only, return 0
-                0
+                let value = self.oam[self.oam_addr as usize];
+                self.refresh_io_latch(value, 0xFF)
             }
             7 => {
                 // $2007: PPUDATA - Read/Write
@@ -78,15 +68,17 @@ impl Ppu {
                 let value;
 
                 if addr >= 0x3F00 {
-                    // Palette reads are immediate (not buffered)
-                    value = self.read_ppu_memory(addr);
+                    // Palette reads are immediate (not buffered); palette
+                    // entries are 6 bits, so bits 7-6 are open bus
+                    let color = self.fetch_ppu_memory(addr);
+                    value = self.refresh_io_latch(color, 0x3F);
                     // But still update the buffer with nametable data "underneath"
                     // This reads from the mirrored nametable address
                     self.read_buffer = self.read_ppu_memory(addr & 0x2FFF);
                 } else {
                     // Normal reads are buffered
-                    value = self.read_buffer;
-                    self.read_buffer = self.read_ppu_memory(addr);
+                    value = self.refresh_io_latch(self.read_buffer, 0xFF);
+                    self.read_buffer = self.fetch_ppu_memory(addr);
                 }
 
                 // Increment address based on PPUCTRL bit 2
@@ -96,12 +88,46 @@ impl Ppu {
                 value
             }
             _ => {
-                // Should not reach here due to masking, but return 0 as fallback
-                0
+                // Should not reach here due to masking, but return open bus as fallback
+                self.io_latch_value()
             }
         }
     }
 
+    /// Get the I/O latch with decayed bits cleared
+    ///
+    /// # Returns
+    ///
+    /// The value seen when reading an undriven register bit
+    pub(crate) fn io_latch_value(&self) -> u8 {
+        (0..8)
+            .filter(|&bit| {
+                self.frame.saturating_sub(self.io_latch_refresh[bit]) < IO_LATCH_DECAY_FRAMES
+            })
+            .fold(0, |value, bit| value | (self.io_latch & (1 << bit)))
+    }
+
+    /// Drive bits onto the I/O latch
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value on the PPU data bus
+    /// * `mask` - The bits that are driven; the others keep their latched value
+    ///
+    /// # Returns
+    ///
+    /// The value read: `value` for the driven bits, the latch for the rest
+    fn refresh_io_latch(&mut self, value: u8, mask: u8) -> u8 {
+        let result = (value & mask) | (self.io_latch_value() & !mask);
+        self.io_latch = result;
+        for bit in 0..8 {
+            if mask & (1 << bit) != 0 {
+                self.io_latch_refresh[bit] = self.frame;
+            }
+        }
+        result
+    }
+
     /// Write to a PPU register
     ///
     /// # Arguments
@@ -118,7 +144,7 @@ impl Ppu {
     /// - PPUSCROLL ($2005): Sets scroll position (requires 2 writes, updates t and x)
     /// - PPUADDR ($2006): Sets PPU address (requires 2 writes, updates t then v)
     /// - PPUDATA ($2007): Writes to PPU memory and increments v
-    /// - Read-only registers: Writes are ignored
+    /// - Read-only registers: Writes are ignored (but still fill the I/O latch)
     ///
     /// # Mid-frame Register Changes
     ///
@@ -131,6 +157,9 @@ impl Ppu {
     /// - PPUADDR: Writes update t, then v, which immediately affects VRAM address
     ///   and can corrupt scroll position if rendering is enabled
     pub(super) fn write_register(&mut self, register: u16, data: u8) {
+        // Every write fills the I/O latch
+        self.refresh_io_latch(data, 0xFF);
+
         match register {
             0 => {
                 // $2000: PPUCTRL - Write only
@@ -216,11 +245,16 @@ impl Ppu {
                     self.t = (self.t & 0xFF00) | (data as u16);
                     self.v = self.t;
                     self.write_latch = false;
+
+                    // The new address is driven onto the PPU bus right away,
+                    // which mappers watching A12 can observe
+                    self.notify_mapper_address(self.v);
                 }
             }
             7 => {
                 // $2007: PPUDATA - Read/Write
                 // Write to PPU memory at current address (v)
+                self.notify_mapper_address(self.v);
                 self.write_ppu_memory(self.v, data);
 
                 // Increment address based on PPUCTRL bit 2
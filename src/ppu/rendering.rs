@@ -575,7 +575,7 @@ impl Ppu {
         // v register layout: yyy NN YYYYY XXXXX
         // Nametable address = 0x2000 | (v & 0x0FFF)
         let addr = 0x2000 | (self.v & 0x0FFF);
-        self.bg_nametable_byte = self.read_ppu_memory(addr);
+        self.bg_nametable_byte = self.render_fetch(addr);
     }
 
     /// Fetch the attribute byte for the current tile
@@ -586,7 +586,7 @@ impl Ppu {
         // Attribute address = 0x23C0 | (v & 0x0C00) | ((v >> 4) & 0x38) | ((v >> 2) & 0x07)
         let v = self.v;
         let addr = 0x23C0 | (v & 0x0C00) | ((v >> 4) & 0x38) | ((v >> 2) & 0x07);
-        let attr_byte = self.read_ppu_memory(addr);
+        let attr_byte = self.render_fetch(addr);
 
         // Extract the 2-bit palette index based on the current tile position
         // The attribute byte covers a 4x4 tile area (2x2 blocks of 2x2 tiles)
@@ -612,7 +612,7 @@ impl Ppu {
 
         // Tile address = pattern_table_base + tile_index * 16 + fine_y
         let addr = pattern_table_base + (self.bg_nametable_byte as u16) * 16 + fine_y;
-        self.bg_pattern_low = self.read_ppu_memory(addr);
+        self.bg_pattern_low = self.render_fetch(addr);
     }
 
     /// Fetch the pattern table high bitplane byte for the current tile
@@ -629,7 +629,27 @@ impl Ppu {
 
         // Tile address = pattern_table_base + tile_index * 16 + fine_y + 8 (high bitplane)
         let addr = pattern_table_base + (self.bg_nametable_byte as u16) * 16 + fine_y + 8;
-        self.bg_pattern_high = self.read_ppu_memory(addr);
+        self.bg_pattern_high = self.render_fetch(addr);
+    }
+
+    /// Fetch a byte from PPU memory for the rendering pipeline
+    ///
+    /// The PPU only drives its address bus while rendering is enabled, so the
+    /// mapper is notified of the access only in that case.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - PPU memory address ($0000-$3FFF)
+    ///
+    /// # Returns
+    ///
+    /// The byte value at the specified address
+    fn render_fetch(&mut self, addr: u16) -> u8 {
+        if self.is_rendering_enabled() {
+            self.fetch_ppu_memory(addr)
+        } else {
+            self.read_ppu_memory(addr)
+        }
     }
 
     /// Perform background tile fetch based on the current cycle
@@ -802,7 +822,8 @@ impl Ppu {
     ///
     /// This scans through all 64 sprites in OAM and finds up to 8 sprites
     /// that will be visible on the next scanline. The results are stored
-    /// in secondary OAM.
+    /// in secondary OAM; their patterns are fetched during dots 257-320 (see
+    /// [`Ppu::perform_sprite_fetch`]).
     ///
     /// This also sets the sprite overflow flag if more than 8 sprites are found.
     ///
@@ -829,7 +850,8 @@ impl Ppu {
         // The next scanline is the current scanline + 1
         let next_scanline = self.scanline + 1;
 
-        // Skip if we're past visible scanlines
+        // No sprites past the visible scanlines, but the (dummy) pattern
+        // fetches still happen
         if next_scanline >= SCREEN_HEIGHT as u16 {
             self.sprite_count = 0;
             self.sprite_0_present = false;
@@ -890,88 +912,126 @@ impl Ppu {
         } else {
             self.ppustatus &= !0x20;
         }
+    }
+
+    /// Perform the sprite pattern fetch for the current cycle
+    ///
+    /// Dots 257-320 fetch the patterns of the 8 sprites in secondary OAM for
+    /// the next scanline, one sprite per 8 dots in the same rhythm as the
+    /// background tile fetches: the low bitplane on the sprite's 5th dot and
+    /// the high bitplane on its 7th. Mappers that watch the PPU address bus
+    /// (the MMC3's A12 counter) see each fetch at its real dot.
+    pub(super) fn perform_sprite_fetch(&mut self, cycle: u16) {
+        if (cycle & 1) == 0 {
+            return;
+        }
 
-        // Load sprite shift registers for the next scanline
-        self.load_sprite_shift_registers(next_scanline);
+        let slot = ((cycle - 257) / 8) as usize;
+        match self.get_tile_fetch_phase(cycle) {
+            TileFetchPhase::PatternLow => self.fetch_sprite_pattern(slot, false),
+            TileFetchPhase::PatternHigh => self.fetch_sprite_pattern(slot, true),
+            _ => {}
+        }
     }
 
-    /// Load sprite pattern data into shift registers
+    /// Fetch one bitplane of a sprite's pattern into its shift register
     ///
-    /// This fetches the pattern data for all sprites in secondary OAM
-    /// and loads them into the sprite shift registers.
-    fn load_sprite_shift_registers(&mut self, scanline: u16) {
-        for i in 0..8 {
-            if i < self.sprite_count {
-                let (sprite_y, tile_index, attributes, x_pos) = self.secondary_oam[i];
+    /// # Arguments
+    ///
+    /// * `slot` - Secondary OAM slot (0-7)
+    /// * `high` - Fetch the high bitplane instead of the low one
+    fn fetch_sprite_pattern(&mut self, slot: usize, high: bool) {
+        let plane = if high { 8 } else { 0 };
+        let addr = self.sprite_pattern_address(slot) + plane;
+        let pattern = self.render_fetch(addr);
+
+        if slot >= self.sprite_count {
+            // Empty slots load a transparent sprite
+            self.sprite_pattern_shift_low[slot] = 0;
+            self.sprite_pattern_shift_high[slot] = 0;
+            self.sprite_attributes[slot] = 0;
+            self.sprite_x_positions[slot] = 0xFF;
+            return;
+        }
 
-                // Calculate which row of the sprite we're rendering
-                let sprite_y = sprite_y as u16 + 1;
-                let row = (scanline - sprite_y) as usize;
+        let (_, _, attributes, x_pos) = self.secondary_oam[slot];
 
-                // Fetch sprite pixel data for this row
-                let sprite_height = self.get_sprite_height();
+        // Apply horizontal flip if needed by reversing the bits
+        let pattern = if (attributes & 0x40) != 0 {
+            pattern.reverse_bits()
+        } else {
+            pattern
+        };
 
-                // Apply vertical flip
-                let row = if (attributes & 0x80) != 0 {
-                    sprite_height - 1 - row
-                } else {
-                    row
-                };
+        if high {
+            self.sprite_pattern_shift_high[slot] = pattern;
+        } else {
+            self.sprite_pattern_shift_low[slot] = pattern;
+        }
+        self.sprite_attributes[slot] = attributes;
+        self.sprite_x_positions[slot] = x_pos;
+    }
 
-                // Fetch pattern data based on sprite size
-                let (pattern_low, pattern_high) = if sprite_height == 8 {
-                    // 8x8 sprite mode
-                    let pattern_table_base = if (self.ppuctrl & 0x08) != 0 {
-                        0x1000
-                    } else {
-                        0x0000
-                    };
+    /// Address of the low bitplane of a sprite's row on the next scanline
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - Secondary OAM slot (0-7)
+    fn sprite_pattern_address(&self, slot: usize) -> u16 {
+        let sprite_height = self.get_sprite_height();
 
-                    let tile_addr = pattern_table_base + (tile_index as u16) * 16;
-                    let low = self.read_ppu_memory(tile_addr + row as u16);
-                    let high = self.read_ppu_memory(tile_addr + row as u16 + 8);
-                    (low, high)
+        if slot >= self.sprite_count {
+            // No sprite in this slot. The PPU still fetches tile $FF so the
+            // pattern table address lines toggle as usual
+            return if sprite_height == 8 {
+                let pattern_table_base = if (self.ppuctrl & 0x08) != 0 {
+                    0x1000
                 } else {
-                    // 8x16 sprite mode
-                    let pattern_table_base = if (tile_index & 0x01) != 0 {
-                        0x1000
-                    } else {
-                        0x0000
-                    };
+                    0x0000
+                };
+                pattern_table_base + 0xFF * 16
+            } else {
+                // Tile $FF selects the $1000 table and the $FE/$FF pair
+                0x1000 + 0xFE * 16
+            };
+        }
 
-                    let tile_pair = tile_index & 0xFE;
-                    let (tile, tile_row) = if row < 8 {
-                        (tile_pair, row)
-                    } else {
-                        (tile_pair + 1, row - 8)
-                    };
+        let (sprite_y, tile_index, attributes, _) = self.secondary_oam[slot];
 
-                    let tile_addr = pattern_table_base + (tile as u16) * 16;
-                    let low = self.read_ppu_memory(tile_addr + tile_row as u16);
-                    let high = self.read_ppu_memory(tile_addr + tile_row as u16 + 8);
-                    (low, high)
-                };
+        // Calculate which row of the sprite is on the next scanline
+        let sprite_y = sprite_y as u16 + 1;
+        let row = (self.scanline + 1 - sprite_y) as usize;
 
-                // Apply horizontal flip if needed
-                let (pattern_low, pattern_high) = if (attributes & 0x40) != 0 {
-                    // Flip horizontally by reversing the bits
-                    (pattern_low.reverse_bits(), pattern_high.reverse_bits())
-                } else {
-                    (pattern_low, pattern_high)
-                };
+        // Apply vertical flip
+        let row = if (attributes & 0x80) != 0 {
+            sprite_height - 1 - row
+        } else {
+            row
+        };
 
-                // Load into shift registers
-                self.sprite_pattern_shift_low[i] = pattern_low;
-                self.sprite_pattern_shift_high[i] = pattern_high;
-                self.sprite_attributes[i] = attributes;
-                self.sprite_x_positions[i] = x_pos;
+        if sprite_height == 8 {
+            // 8x8 sprite mode
+            let pattern_table_base = if (self.ppuctrl & 0x08) != 0 {
+                0x1000
             } else {
-                // No sprite in this slot
-                self.sprite_pattern_shift_low[i] = 0;
-                self.sprite_pattern_shift_high[i] = 0;
-                self.sprite_attributes[i] = 0;
-                self.sprite_x_positions[i] = 0xFF;
-            }
+                0x0000
+            };
+            pattern_table_base + (tile_index as u16) * 16 + row as u16
+        } else {
+            // 8x16 sprite mode
+            let pattern_table_base = if (tile_index & 0x01) != 0 {
+                0x1000
+            } else {
+                0x0000
+            };
+
+            let tile_pair = tile_index & 0xFE;
+            let (tile, tile_row) = if row < 8 {
+                (tile_pair, row)
+            } else {
+                (tile_pair + 1, row - 8)
+            };
+            pattern_table_base + (tile as u16) * 16 + tile_row as u16
         }
     }
 
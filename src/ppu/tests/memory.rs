@@ -379,6 +379,7 @@ fn create_test_cartridge_chr_rom() -> Cartridge {
         mapper: 0,
         mirroring: Mirroring::Horizontal,
         has_battery: false,
+        ..Cartridge::new()
     }
 }
 
@@ -596,3 +597,74 @@ fn test_pattern_table_sequential_access() {
         assert_eq!(value, i, "Sequential CHR-RAM read failed at index {}", i);
     }
 }
+
+#[test]
+fn test_pattern_fetch_side_effects_vs_peek() {
+    use crate::cartridge::mappers::Mapper9;
+
+    // MMC2 cartridge with each 4KB CHR bank tagged by its number
+    let mut chr_rom = vec![0; 32 * 4 * 1024];
+    for bank in 0..32 {
+        chr_rom[bank * 4 * 1024] = bank as u8;
+    }
+    let cartridge = Cartridge {
+        prg_rom: vec![0; 128 * 1024],
+        chr_rom,
+        trainer: None,
+        mapper: 9,
+        mirroring: Mirroring::Vertical,
+        has_battery: false,
+        ..Cartridge::new()
+    };
+    let mut ppu = Ppu::new();
+    let mut mmc2 = Mapper9::new(cartridge);
+    mmc2.cpu_write(0xB000, 0x01); // Latch 0 FD -> bank 1
+    mmc2.cpu_write(0xC000, 0x02); // Latch 0 FE -> bank 2
+    let mapper = Rc::new(RefCell::new(Box::new(mmc2) as Box<dyn Mapper>));
+    ppu.set_mapper(mapper);
+
+    // Debugger peeks at tile $FE leave the latch alone
+    assert_eq!(ppu.read_ppu_memory(0x0FE8), 0);
+    assert_eq!(ppu.read_ppu_memory(0x0000), 1);
+
+    // A PPUDATA read of tile $FE is a real fetch and flips the latch
+    ppu.write(PPUADDR, 0x0F);
+    ppu.write(PPUADDR, 0xE8);
+    let _ = ppu.read(PPUDATA);
+    assert_eq!(ppu.read_ppu_memory(0x0000), 2);
+}
+
+#[test]
+fn test_mapper_supplied_nametables() {
+    use crate::cartridge::mappers::Mapper5;
+
+    let mut cartridge = create_test_cartridge_chr_rom();
+    cartridge.mapper = 5;
+    let mut mmc5 = Mapper5::new(cartridge);
+    mmc5.cpu_write(0x5105, 0b11_10_01_00); // CIRAM 0, CIRAM 1, ExRAM, fill
+    mmc5.cpu_write(0x5106, 0x42); // Fill tile
+    let mapper = Rc::new(RefCell::new(Box::new(mmc5) as Box<dyn Mapper>));
+    let mut ppu = Ppu::new();
+    ppu.set_mapper(mapper.clone());
+
+    for (addr, value) in [(0x2000u16, 0x11), (0x2400, 0x22), (0x2800, 0x33)] {
+        ppu.write(PPUADDR, (addr >> 8) as u8);
+        ppu.write(PPUADDR, addr as u8);
+        ppu.write(PPUDATA, value);
+    }
+
+    // The first two nametables are the PPU's own VRAM pages
+    assert_eq!(ppu.nametables[0], 0x11);
+    assert_eq!(ppu.nametables[NAMETABLE_SIZE], 0x22);
+
+    // The third lives in the cartridge's ExRAM
+    assert_eq!(ppu.read_ppu_memory(0x2800), 0x33);
+    assert_eq!(mapper.borrow().nametable_read(0x2800), 0x33);
+
+    // The fourth is filled with a single tile, through PPUDATA and the $3000 mirror
+    assert_eq!(ppu.read_ppu_memory(0x3C10), 0x42);
+    ppu.write(PPUADDR, 0x2C);
+    ppu.write(PPUADDR, 0x00);
+    let _ = ppu.read(PPUDATA); // Fill the read buffer
+    assert_eq!(ppu.read(PPUDATA), 0x42);
+}
@@ -46,6 +46,7 @@ pub(crate) fn create_test_cartridge_chr_ram() -> Cartridge {
         mapper: 0,
         mirroring: Mirroring::Horizontal,
         has_battery: false,
+        ..Cartridge::new()
     }
 }
 
@@ -134,17 +134,63 @@ fn test_read_ppustatus_resets_latch() {
 }
 
 #[test]
-fn test_read_write_only_registers_return_zero() {
+fn test_read_write_only_registers_return_io_latch() {
     let mut ppu = Ppu::new();
     ppu.write(PPUCTRL, 0x80);
     ppu.write(PPUMASK, 0x1E);
 
-    // Write-only registers should return 0 when read
+    // Write-only registers return the last value on the PPU data bus
+    assert_eq!(ppu.read(PPUCTRL), 0x1E);
+    assert_eq!(ppu.read(PPUMASK), 0x1E);
+    assert_eq!(ppu.read(OAMADDR), 0x1E);
+    assert_eq!(ppu.read(PPUSCROLL), 0x1E);
+    assert_eq!(ppu.read(PPUADDR), 0x1E);
+}
+
+#[test]
+fn test_read_ppustatus_low_bits_from_io_latch() {
+    let mut ppu = Ppu::new();
+    ppu.ppustatus = 0x80;
+    ppu.write(OAMADDR, 0x5F);
+
+    // Bits 7-5 come from the status, bits 4-0 from the latch
+    assert_eq!(ppu.read(PPUSTATUS), 0x9F);
+
+    // The status bits refresh the latch
+    assert_eq!(ppu.read(PPUCTRL), 0x9F);
+}
+
+#[test]
+fn test_read_palette_high_bits_from_io_latch() {
+    let mut ppu = Ppu::new();
+    ppu.palette_ram[0] = 0x2A;
+    ppu.write(PPUADDR, 0x3F);
+    ppu.write(PPUADDR, 0x00);
+
+    // The last write left $00 on the bus
+    assert_eq!(ppu.read(PPUDATA), 0x2A);
+
+    ppu.v = 0x3F00;
+    ppu.write(OAMADDR, 0xC0);
+    assert_eq!(ppu.read(PPUDATA), 0xEA);
+}
+
+#[test]
+fn test_io_latch_decays() {
+    let mut ppu = Ppu::new();
+    ppu.write(OAMADDR, 0xFF);
+    ppu.frame = IO_LATCH_DECAY_FRAMES - 1;
+    assert_eq!(ppu.read(PPUCTRL), 0xFF);
+
+    // Reading PPUSTATUS refreshes only bits 7-5
+    ppu.ppustatus = 0xE0;
+    ppu.read(PPUSTATUS);
+
+    ppu.frame = IO_LATCH_DECAY_FRAMES;
+    assert_eq!(ppu.read(PPUCTRL), 0xE0);
+
+    ppu.frame = 2 * IO_LATCH_DECAY_FRAMES;
     assert_eq!(ppu.read(PPUCTRL), 0x00);
-    assert_eq!(ppu.read(PPUMASK), 0x00);
-    assert_eq!(ppu.read(OAMADDR), 0x00);
-    assert_eq!(ppu.read(PPUSCROLL), 0x00);
-    assert_eq!(ppu.read(PPUADDR), 0x00);
 }
 
 #[test]
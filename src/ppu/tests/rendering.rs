@@ -1017,6 +1017,63 @@ fn test_scanline_rendering_cycles() {
     );
 }
 
+#[test]
+fn test_rendering_mapper_supplied_nametables() {
+    use crate::cartridge::mappers::Mapper5;
+
+    // Tile $42 is solid color 1; tile $43 is solid color 3
+    let mut chr_rom = vec![0x00; 8 * 1024];
+    chr_rom[0x420..0x428].fill(0xFF);
+    chr_rom[0x430..0x440].fill(0xFF);
+    let mut cartridge = create_test_cartridge_chr_ram();
+    cartridge.mapper = 5;
+    cartridge.chr_rom = chr_rom;
+
+    // Every nametable in fill mode: tile $42 with palette 1
+    let mut mmc5 = Mapper5::new(cartridge);
+    mmc5.cpu_write(0x5105, 0xFF);
+    mmc5.cpu_write(0x5106, 0x42);
+    mmc5.cpu_write(0x5107, 0x01);
+    let mapper = Rc::new(RefCell::new(Box::new(mmc5) as Box<dyn Mapper>));
+
+    let mut ppu = Ppu::new();
+    ppu.set_mapper(mapper.clone());
+    ppu.palette_ram[0] = 0x0F;
+    ppu.palette_ram[5] = 0x16; // Palette 1, color 1
+    ppu.palette_ram[15] = 0x2A; // Palette 3, color 3
+    ppu.write(PPUMASK, 0x0A); // Background, including the left column
+
+    // Run two frames so the second is fully rendered
+    let mut frames = 0;
+    while frames < 2 {
+        frames += ppu.step() as u32;
+    }
+    assert_eq!(ppu.frame()[0], 0x16);
+    assert_eq!(ppu.frame()[120 * 256 + 100], 0x16);
+    assert_eq!(ppu.frame()[239 * 256 + 255], 0x16);
+
+    // Fill the left half with tile $43 and palette 3 through a vertical split
+    // drawn from ExRAM
+    {
+        let mut mapper = mapper.borrow_mut();
+        mapper.cpu_write(0x5104, 0x02);
+        for i in 0..0x3C0 {
+            mapper.cpu_write(0x5C00 + i, 0x43);
+        }
+        for i in 0x3C0..0x400 {
+            mapper.cpu_write(0x5C00 + i, 0xFF);
+        }
+        mapper.cpu_write(0x5104, 0x00);
+        mapper.cpu_write(0x5200, 0x90); // Split tiles 0-15
+    }
+    frames = 0;
+    while frames < 2 {
+        frames += ppu.step() as u32;
+    }
+    assert_eq!(ppu.frame()[120 * 256 + 100], 0x2A);
+    assert_eq!(ppu.frame()[120 * 256 + 200], 0x16);
+}
+
 #[test]
 fn test_background_shift_registers_initialization() {
     let ppu = Ppu::new();
@@ -1137,9 +1194,12 @@ fn test_sprite_shift_register_loading() {
     ppu.write_oam(2, 0x00); // Attributes
     ppu.write_oam(3, 100); // X position
 
-    // Manually trigger sprite evaluation
+    // Manually trigger sprite evaluation and the pattern fetches
     ppu.scanline = 49;
     ppu.evaluate_sprites_for_next_scanline();
+    for cycle in 258..=320 {
+        ppu.perform_sprite_fetch(cycle);
+    }
 
     // Sprite should be loaded into secondary OAM and shift registers
     assert_eq!(ppu.sprite_count, 1, "One sprite should be evaluated");
@@ -1149,6 +1209,55 @@ fn test_sprite_shift_register_loading() {
     );
 }
 
+#[test]
+fn test_sprite_patterns_fetched_one_sprite_per_8_dots() {
+    let mut ppu = Ppu::new();
+    let cartridge = create_test_cartridge_chr_ram();
+    let mapper = Rc::new(RefCell::new(
+        Box::new(Mapper0::new(cartridge)) as Box<dyn Mapper>
+    ));
+    ppu.set_mapper(mapper);
+
+    // Tile 1: low plane $0F, high plane $F0
+    for row in 0..8 {
+        let mapper = ppu.mapper.as_ref().unwrap();
+        mapper.borrow_mut().ppu_write(0x0010 + row, 0x0F);
+        mapper.borrow_mut().ppu_write(0x0018 + row, 0xF0);
+    }
+    ppu.write(PPUMASK, 0x18);
+
+    // Two sprites on scanline 50
+    for i in 0..2 {
+        ppu.write_oam(i * 4, 49);
+        ppu.write_oam(i * 4 + 1, 0x01);
+        ppu.write_oam(i * 4 + 3, i * 16);
+    }
+    ppu.scanline = 49;
+    ppu.evaluate_sprites_for_next_scanline();
+    assert_eq!(ppu.sprite_count, 2);
+
+    // Sprite 0: low plane on dot 261, high plane on dot 263
+    for cycle in 258..=260 {
+        ppu.perform_sprite_fetch(cycle);
+    }
+    assert_eq!(ppu.sprite_pattern_shift_low[0], 0);
+    ppu.perform_sprite_fetch(261);
+    assert_eq!(ppu.sprite_pattern_shift_low[0], 0x0F);
+    assert_eq!(ppu.sprite_pattern_shift_high[0], 0);
+    ppu.perform_sprite_fetch(262);
+    ppu.perform_sprite_fetch(263);
+    assert_eq!(ppu.sprite_pattern_shift_high[0], 0xF0);
+
+    // Sprite 1 follows 8 dots later
+    for cycle in 264..=268 {
+        ppu.perform_sprite_fetch(cycle);
+    }
+    assert_eq!(ppu.sprite_pattern_shift_low[1], 0);
+    ppu.perform_sprite_fetch(269);
+    assert_eq!(ppu.sprite_pattern_shift_low[1], 0x0F);
+    assert_eq!(ppu.sprite_x_positions[1], 16);
+}
+
 #[test]
 fn test_complete_scanline_rendering() {
     let mut ppu = Ppu::new();
@@ -730,3 +730,74 @@ fn test_nmi_cleared_on_prerender() {
         "NMI should be cleared on pre-render scanline"
     );
 }
+
+// Region timing tests
+// ========================================
+
+/// Count the PPU dots in the next frame
+fn dots_in_next_frame(ppu: &mut Ppu) -> u32 {
+    let mut dots = 1;
+    while !ppu.step() {
+        dots += 1;
+    }
+    dots
+}
+
+#[test]
+fn test_pal_frame_has_312_scanlines() {
+    for region in [Region::Pal, Region::Dendy] {
+        let mut ppu = Ppu::new();
+        ppu.set_region(region);
+        ppu.ppumask = 0x18;
+
+        // No dot is skipped on odd frames
+        for _ in 0..3 {
+            assert_eq!(dots_in_next_frame(&mut ppu), 341 * 312, "{}", region);
+        }
+    }
+}
+
+#[test]
+fn test_pal_vblank_timing() {
+    let mut ppu = Ppu::new();
+    ppu.set_region(Region::Pal);
+    ppu.ppuctrl = 0x80;
+
+    // VBlank starts at scanline 241 and lasts 70 scanlines
+    advance_to_scanline_cycle(&mut ppu, 241, 2);
+    assert_ne!(ppu.ppustatus & 0x80, 0, "VBlank should be set at 241");
+    assert!(ppu.nmi_pending());
+
+    advance_to_scanline_cycle(&mut ppu, 310, 340);
+    assert_ne!(ppu.ppustatus & 0x80, 0, "VBlank should last until 310");
+
+    advance_to_scanline_cycle(&mut ppu, 311, 2);
+    assert_eq!(ppu.ppustatus & 0x80, 0, "Pre-render scanline is 311");
+}
+
+#[test]
+fn test_dendy_vblank_timing() {
+    let mut ppu = Ppu::new();
+    ppu.set_region(Region::Dendy);
+    ppu.ppuctrl = 0x80;
+
+    // 51 post-render scanlines, then the NTSC-length VBlank
+    advance_to_scanline_cycle(&mut ppu, 290, 340);
+    assert_eq!(ppu.ppustatus & 0x80, 0, "Still in post-render");
+    assert!(!ppu.nmi_pending());
+
+    advance_to_scanline_cycle(&mut ppu, 291, 2);
+    assert_ne!(ppu.ppustatus & 0x80, 0, "VBlank should be set at 291");
+    assert!(ppu.nmi_pending());
+
+    advance_to_scanline_cycle(&mut ppu, 311, 2);
+    assert_eq!(ppu.ppustatus & 0x80, 0, "Pre-render scanline is 311");
+}
+
+#[test]
+fn test_reset_keeps_region() {
+    let mut ppu = Ppu::new();
+    ppu.set_region(Region::Pal);
+    ppu.reset();
+    assert_eq!(ppu.region(), Region::Pal);
+}
@@ -0,0 +1,130 @@
+// Region module - Console timing variants
+//
+// NES consoles were built in three timing variants. The NTSC console (2A03
+// CPU, 2C02 PPU) runs at 60 Hz with 262 scanlines per frame. The PAL console
+// (2A07 CPU, 2C07 PPU) runs at 50 Hz with 312 scanlines and a slower CPU
+// clock, giving 3.2 PPU dots per CPU cycle. The Dendy, a Famicom clone sold
+// in Russia, combines the PAL frame with an NTSC-style CPU:PPU ratio and APU.
+//
+// | Region | CPU clock     | Dots per CPU cycle | Scanlines | Frame rate |
+// |--------|---------------|--------------------|-----------|------------|
+// | NTSC   | 1.789773 MHz  | 3                  | 262       | 60.0988 Hz |
+// | PAL    | 1.662607 MHz  | 3.2                | 312       | 50.0070 Hz |
+// | Dendy  | 1.773448 MHz  | 3                  | 312       | 50.0070 Hz |
+//
+// The PPU and APU pick their own scanline layout and period tables from the
+// region; this module only describes the system clocks.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Console timing region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Region {
+    /// North America and Japan (60 Hz)
+    #[default]
+    Ntsc,
+    /// Europe and Australia (50 Hz)
+    Pal,
+    /// Dendy Famicom clone (50 Hz, NTSC-style CPU and APU)
+    Dendy,
+}
+
+impl Region {
+    /// Get the CPU clock rate
+    ///
+    /// The APU produces one sample per CPU cycle, so this is also the audio
+    /// input rate.
+    ///
+    /// # Returns
+    ///
+    /// The CPU clock in Hz
+    pub fn cpu_clock_hz(self) -> f64 {
+        match self {
+            Region::Ntsc => 1_789_773.0,
+            Region::Pal => 1_662_607.0,
+            Region::Dendy => 1_773_448.0,
+        }
+    }
+
+    /// Get the frame rate
+    ///
+    /// # Returns
+    ///
+    /// The number of frames the console outputs per second
+    pub fn frame_rate(self) -> f64 {
+        match self {
+            Region::Ntsc => 60.0988,
+            Region::Pal | Region::Dendy => 50.0070,
+        }
+    }
+
+    /// Get the PPU to CPU clock ratio
+    ///
+    /// # Returns
+    ///
+    /// `(dots, cycles)`: the PPU runs `dots` dots every `cycles` CPU cycles
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nes_rs::Region;
+    ///
+    /// assert_eq!(Region::Ntsc.ppu_dot_ratio(), (3, 1));
+    /// assert_eq!(Region::Pal.ppu_dot_ratio(), (16, 5)); // 3.2 dots per cycle
+    /// ```
+    pub fn ppu_dot_ratio(self) -> (u8, u8) {
+        match self {
+            Region::Ntsc | Region::Dendy => (3, 1),
+            Region::Pal => (16, 5),
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Region::Ntsc => write!(f, "NTSC"),
+            Region::Pal => write!(f, "PAL"),
+            Region::Dendy => write!(f, "Dendy"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_region_is_ntsc() {
+        assert_eq!(Region::default(), Region::Ntsc);
+    }
+
+    #[test]
+    fn test_frame_rate_matches_clocks() {
+        // CPU cycles per frame: 341 dots x scanlines / dots per cycle
+        // (NTSC frames alternate between 89341 and 89342 dots)
+        for (region, dots_per_frame) in [
+            (Region::Ntsc, 341.0 * 262.0 - 0.5),
+            (Region::Pal, 341.0 * 312.0),
+            (Region::Dendy, 341.0 * 312.0),
+        ] {
+            let (dots, cycles) = region.ppu_dot_ratio();
+            let cycles_per_frame = dots_per_frame * cycles as f64 / dots as f64;
+            let frame_rate = region.cpu_clock_hz() / cycles_per_frame;
+            assert!(
+                (frame_rate - region.frame_rate()).abs() < 0.001,
+                "{}: {} Hz",
+                region,
+                frame_rate
+            );
+        }
+    }
+
+    #[test]
+    fn test_region_display() {
+        assert_eq!(Region::Ntsc.to_string(), "NTSC");
+        assert_eq!(Region::Pal.to_string(), "PAL");
+        assert_eq!(Region::Dendy.to_string(), "Dendy");
+    }
+}
@@ -0,0 +1,534 @@
+// Hash functions
+//
+// CRC-32 checksums the binary save-state container. The same CRC-32, MD5 and
+// SHA-1 identify ROM images: FCEUX movies (.fm2) identify the game by the MD5
+// of its PRG-ROM followed by its CHR-ROM, and ROM databases such as No-Intro
+// and NesCartDB use the CRC-32 and SHA-1 of the same data.
+
+// ============================================================================
+// CRC-32
+// ============================================================================
+
+// Standard CRC-32 (IEEE 802.3, reflected polynomial 0xEDB88320) as used by
+// zip, PNG and the No-Intro ROM databases. The lookup table is built at
+// compile time.
+
+/// Reflected CRC-32 polynomial
+const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+/// Byte-wise lookup table
+const TABLE: [u32; 256] = build_table();
+
+const fn build_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Incremental CRC-32 hasher
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::state::hash::{crc32, Crc32};
+///
+/// let mut hasher = Crc32::new();
+/// hasher.update(b"1234");
+/// hasher.update(b"56789");
+/// assert_eq!(hasher.finish(), crc32(b"123456789"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Crc32 {
+    crc: u32,
+}
+
+impl Crc32 {
+    /// Create a hasher with the standard initial value
+    pub fn new() -> Self {
+        Crc32 { crc: 0xFFFF_FFFF }
+    }
+
+    /// Feed more data into the checksum
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to checksum
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.crc = TABLE[((self.crc ^ byte as u32) & 0xFF) as usize] ^ (self.crc >> 8);
+        }
+    }
+
+    /// Finish and return the checksum
+    pub fn finish(self) -> u32 {
+        !self.crc
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the CRC-32 of `data`
+///
+/// # Arguments
+///
+/// * `data` - Bytes to checksum
+///
+/// # Returns
+///
+/// The CRC-32 checksum
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::state::hash::crc32;
+///
+/// assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+/// ```
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut hasher = Crc32::new();
+    hasher.update(data);
+    hasher.finish()
+}
+
+// ============================================================================
+// MD5
+// ============================================================================
+
+/// Per-round left rotation amounts
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, //
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, //
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, //
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+/// Per-round constants: floor(abs(sin(i + 1)) * 2^32)
+const K: [u32; 64] = [
+    0xD76AA478, 0xE8C7B756, 0x242070DB, 0xC1BDCEEE, 0xF57C0FAF, 0x4787C62A, 0xA8304613, 0xFD469501,
+    0x698098D8, 0x8B44F7AF, 0xFFFF5BB1, 0x895CD7BE, 0x6B901122, 0xFD987193, 0xA679438E, 0x49B40821,
+    0xF61E2562, 0xC040B340, 0x265E5A51, 0xE9B6C7AA, 0xD62F105D, 0x02441453, 0xD8A1E681, 0xE7D3FBC8,
+    0x21E1CDE6, 0xC33707D6, 0xF4D50D87, 0x455A14ED, 0xA9E3E905, 0xFCEFA3F8, 0x676F02D9, 0x8D2A4C8A,
+    0xFFFA3942, 0x8771F681, 0x6D9D6122, 0xFDE5380C, 0xA4BEEA44, 0x4BDECFA9, 0xF6BB4B60, 0xBEBFBC70,
+    0x289B7EC6, 0xEAA127FA, 0xD4EF3085, 0x04881D05, 0xD9D4D039, 0xE6DB99E5, 0x1FA27CF8, 0xC4AC5665,
+    0xF4292244, 0x432AFF97, 0xAB9423A7, 0xFC93A039, 0x655B59C3, 0x8F0CCC92, 0xFFEFF47D, 0x85845DD1,
+    0x6FA87E4F, 0xFE2CE6E0, 0xA3014314, 0x4E0811A1, 0xF7537E82, 0xBD3AF235, 0x2AD7D2BB, 0xEB86D391,
+];
+
+/// Incremental MD5 hasher
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::state::hash::Md5;
+///
+/// let mut hasher = Md5::new();
+/// hasher.update(b"ab");
+/// hasher.update(b"c");
+/// assert_eq!(
+///     hasher.finish(),
+///     [
+///         0x90, 0x01, 0x50, 0x98, 0x3C, 0xD2, 0x4F, 0xB0, 0xD6, 0x96, 0x3F, 0x7D, 0x28, 0xE1,
+///         0x7F, 0x72
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Md5 {
+    state: [u32; 4],
+    buffer: [u8; 64],
+    buffered: usize,
+    length: u64,
+}
+
+impl Md5 {
+    /// Create a hasher with the standard initial state
+    pub fn new() -> Self {
+        Md5 {
+            state: [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476],
+            buffer: [0; 64],
+            buffered: 0,
+            length: 0,
+        }
+    }
+
+    /// Feed more data into the hash
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to hash
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if self.buffered > 0 {
+            let take = (64 - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if self.buffered < 64 {
+                return;
+            }
+            let block = self.buffer;
+            self.process_block(&block);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            self.process_block(block);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    /// Finish hashing and return the 16-byte digest
+    pub fn finish(mut self) -> [u8; 16] {
+        let bit_length = self.length.wrapping_mul(8);
+
+        // Padding: a single 1 bit, zeros up to 56 mod 64, then the bit length
+        let mut padding = [0u8; 72];
+        padding[0] = 0x80;
+        let pad_len = if self.buffered < 56 {
+            56 - self.buffered
+        } else {
+            120 - self.buffered
+        };
+        padding[pad_len..pad_len + 8].copy_from_slice(&bit_length.to_le_bytes());
+        let length = self.length;
+        self.update(&padding[..pad_len + 8]);
+        self.length = length;
+
+        let mut digest = [0; 16];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+
+    /// Run the compression function over one 64-byte block
+    fn process_block(&mut self, block: &[u8]) {
+        let mut words = [0u32; 16];
+        for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(K[i])
+                .wrapping_add(words[g])
+                .rotate_left(SHIFTS[i]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+
+        self.state[0] = self.state[0].wrapping_add(a);
+        self.state[1] = self.state[1].wrapping_add(b);
+        self.state[2] = self.state[2].wrapping_add(c);
+        self.state[3] = self.state[3].wrapping_add(d);
+    }
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the MD5 digest of `data`
+///
+/// # Arguments
+///
+/// * `data` - Bytes to hash
+///
+/// # Returns
+///
+/// The 16-byte digest
+pub fn md5(data: &[u8]) -> [u8; 16] {
+    let mut hasher = Md5::new();
+    hasher.update(data);
+    hasher.finish()
+}
+
+// ============================================================================
+// SHA-1
+// ============================================================================
+
+/// Incremental SHA-1 hasher
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::state::hash::{to_hex, Sha1};
+///
+/// let mut hasher = Sha1::new();
+/// hasher.update(b"ab");
+/// hasher.update(b"c");
+/// assert_eq!(
+///     to_hex(&hasher.finish()),
+///     "a9993e364706816aba3e25717850c26c9cd0d89d"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: [u8; 64],
+    buffered: usize,
+    length: u64,
+}
+
+impl Sha1 {
+    /// Create a hasher with the standard initial state
+    pub fn new() -> Self {
+        Sha1 {
+            state: [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0],
+            buffer: [0; 64],
+            buffered: 0,
+            length: 0,
+        }
+    }
+
+    /// Feed more data into the hash
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Bytes to hash
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if self.buffered > 0 {
+            let take = (64 - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if self.buffered < 64 {
+                return;
+            }
+            let block = self.buffer;
+            self.process_block(&block);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            self.process_block(block);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    /// Finish hashing and return the 20-byte digest
+    pub fn finish(mut self) -> [u8; 20] {
+        let bit_length = self.length.wrapping_mul(8);
+
+        // Same padding as MD5, but with a big-endian bit length
+        let mut padding = [0u8; 72];
+        padding[0] = 0x80;
+        let pad_len = if self.buffered < 56 {
+            56 - self.buffered
+        } else {
+            120 - self.buffered
+        };
+        padding[pad_len..pad_len + 8].copy_from_slice(&bit_length.to_be_bytes());
+        self.update(&padding[..pad_len + 8]);
+
+        let mut digest = [0; 20];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    /// Run the compression function over one 64-byte block
+    fn process_block(&mut self, block: &[u8]) {
+        let mut words = [0u32; 80];
+        for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = self.state;
+        for (i, &word) in words.iter().enumerate() {
+            let (f, k) = match i / 20 {
+                0 => ((b & c) | (!b & d), 0x5A827999),
+                1 => (b ^ c ^ d, 0x6ED9EBA1),
+                2 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the SHA-1 digest of `data`
+///
+/// # Arguments
+///
+/// * `data` - Bytes to hash
+///
+/// # Returns
+///
+/// The 20-byte digest
+pub fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut hasher = Sha1::new();
+    hasher.update(data);
+    hasher.finish()
+}
+
+/// Format a digest as lowercase hexadecimal
+///
+/// # Arguments
+///
+/// * `digest` - Digest bytes
+///
+/// # Returns
+///
+/// The hex string
+pub fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414F_A339
+        );
+    }
+
+    #[test]
+    fn test_crc32_detects_single_bit_change() {
+        let mut data = vec![0x55; 1024];
+        let original = crc32(&data);
+        data[512] ^= 0x01;
+        assert_ne!(crc32(&data), original);
+    }
+
+    #[test]
+    fn test_sha1_known_values() {
+        assert_eq!(
+            to_hex(&sha1(b"")),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+        assert_eq!(
+            to_hex(&sha1(b"The quick brown fox jumps over the lazy dog")),
+            "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"
+        );
+        assert_eq!(
+            to_hex(&sha1(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+        );
+    }
+
+    #[test]
+    fn test_sha1_incremental_matches_one_shot() {
+        for len in [55, 56, 57, 63, 64, 65, 119, 120, 1000] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 31) as u8).collect();
+            let mut hasher = Sha1::new();
+            for chunk in data.chunks(37) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finish(), sha1(&data), "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_md5_known_values() {
+        assert_eq!(to_hex(&md5(b"")), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(to_hex(&md5(b"abc")), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(
+            to_hex(&md5(b"The quick brown fox jumps over the lazy dog")),
+            "9e107d9d372bb6826bd81d3542a419d6"
+        );
+        assert_eq!(
+            to_hex(&md5(
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"
+            )),
+            "57edf4a22be3c955ac49da2e2107b67a"
+        );
+    }
+
+    #[test]
+    fn test_md5_incremental_matches_one_shot() {
+        let data: Vec<u8> = (0..1000).map(|i| (i * 31) as u8).collect();
+        let mut hasher = Md5::new();
+        for chunk in data.chunks(37) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finish(), md5(&data));
+    }
+
+    #[test]
+    fn test_md5_padding_boundaries() {
+        // Lengths around the 56-byte padding boundary and full blocks
+        for len in [55, 56, 57, 63, 64, 65, 119, 120] {
+            let data = vec![b'a'; len];
+            let mut hasher = Md5::new();
+            hasher.update(&data[..len / 2]);
+            hasher.update(&data[len / 2..]);
+            assert_eq!(hasher.finish(), md5(&data), "length {}", len);
+        }
+        assert_eq!(
+            to_hex(&md5(&[b'a'; 64])),
+            "014842d480b571495a4a0363793f7367"
+        );
+    }
+}
@@ -0,0 +1,312 @@
+// State serialization helpers
+//
+// Components save their internal registers and memory as an explicit
+// little-endian byte encoding, so each component controls exactly what goes
+// into a save state. Mappers use this for their opaque state blob and the
+// emulator uses it for the chunks of the binary save-state container.
+//
+// The module also provides the hash functions and RLE compression used by
+// that container; the hashes also identify ROM images.
+
+pub mod hash;
+mod rle;
+
+pub use hash::{crc32, Crc32};
+pub use rle::{rle_compress, rle_decompress};
+
+use crate::cartridge::Mirroring;
+
+/// Error produced when decoding state data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateError {
+    /// The data ended before the value could be read
+    Truncated {
+        /// Byte offset of the failed read
+        position: usize,
+        /// Number of bytes the read needed
+        needed: usize,
+    },
+    /// The data was complete but contained an invalid value
+    Invalid(String),
+}
+
+impl std::fmt::Display for StateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateError::Truncated { position, needed } => write!(
+                f,
+                "state truncated at byte {} (needed {} more)",
+                position, needed
+            ),
+            StateError::Invalid(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+/// Builder for a state blob
+///
+/// # Example
+/// ```
+/// use nes_rs::state::{StateReader, StateWriter};
+///
+/// let mut writer = StateWriter::new();
+/// writer.write_u8(0x42);
+/// writer.write_bool(true);
+/// let data = writer.into_bytes();
+///
+/// let mut reader = StateReader::new(&data);
+/// assert_eq!(reader.read_u8().unwrap(), 0x42);
+/// assert!(reader.read_bool().unwrap());
+/// assert!(reader.finish().is_ok());
+/// ```
+#[derive(Debug, Default)]
+pub struct StateWriter {
+    data: Vec<u8>,
+}
+
+impl StateWriter {
+    /// Create an empty state writer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a byte
+    pub fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    /// Append a boolean as a single byte
+    pub fn write_bool(&mut self, value: bool) {
+        self.data.push(value as u8);
+    }
+
+    /// Append a little-endian 16-bit value
+    pub fn write_u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Append a little-endian 32-bit value
+    pub fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Append a little-endian 64-bit value
+    pub fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Append a mirroring mode
+    pub fn write_mirroring(&mut self, mirroring: Mirroring) {
+        self.write_u8(match mirroring {
+            Mirroring::Horizontal => 0,
+            Mirroring::Vertical => 1,
+            Mirroring::FourScreen => 2,
+            Mirroring::SingleScreen => 3,
+        });
+    }
+
+    /// Append a length-prefixed byte slice (e.g. PRG-RAM or CHR-RAM)
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_u32(bytes.len() as u32);
+        self.data.extend_from_slice(bytes);
+    }
+
+    /// Append raw bytes without a length prefix
+    pub fn write_raw(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    /// Number of bytes written so far
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether nothing has been written yet
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Finish writing and return the state blob
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Reader for a state blob produced by `StateWriter`
+///
+/// All reads fail with `StateError::Truncated` if the blob is truncated.
+#[derive(Debug)]
+pub struct StateReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> StateReader<'a> {
+    /// Create a reader over a state blob
+    pub fn new(data: &'a [u8]) -> Self {
+        StateReader { data, position: 0 }
+    }
+
+    /// Take the next `len` bytes
+    fn take(&mut self, len: usize) -> Result<&'a [u8], StateError> {
+        let end = self.position.saturating_add(len);
+        if end > self.data.len() {
+            return Err(StateError::Truncated {
+                position: self.position,
+                needed: len,
+            });
+        }
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Read a byte
+    pub fn read_u8(&mut self) -> Result<u8, StateError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Read a boolean
+    pub fn read_bool(&mut self) -> Result<bool, StateError> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    /// Read a little-endian 16-bit value
+    pub fn read_u16(&mut self) -> Result<u16, StateError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Read a little-endian 32-bit value
+    pub fn read_u32(&mut self) -> Result<u32, StateError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Read a little-endian 64-bit value
+    pub fn read_u64(&mut self) -> Result<u64, StateError> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Read a mirroring mode
+    pub fn read_mirroring(&mut self) -> Result<Mirroring, StateError> {
+        match self.read_u8()? {
+            0 => Ok(Mirroring::Horizontal),
+            1 => Ok(Mirroring::Vertical),
+            2 => Ok(Mirroring::FourScreen),
+            3 => Ok(Mirroring::SingleScreen),
+            other => Err(StateError::Invalid(format!(
+                "invalid mirroring mode {}",
+                other
+            ))),
+        }
+    }
+
+    /// Read a length-prefixed byte slice into `dest`
+    ///
+    /// The stored length must match `dest.len()` exactly, so a state saved
+    /// with a different memory size is rejected instead of partially applied.
+    pub fn read_bytes_into(&mut self, dest: &mut [u8]) -> Result<(), StateError> {
+        let len = self.read_u32()? as usize;
+        if len != dest.len() {
+            return Err(StateError::Invalid(format!(
+                "memory size mismatch: expected {} bytes, found {}",
+                dest.len(),
+                len
+            )));
+        }
+        dest.copy_from_slice(self.take(len)?);
+        Ok(())
+    }
+
+    /// Read a length-prefixed byte slice of any length
+    pub fn read_bytes(&mut self) -> Result<&'a [u8], StateError> {
+        let len = self.read_u32()? as usize;
+        self.take(len)
+    }
+
+    /// Read `len` raw bytes without a length prefix
+    pub fn read_raw(&mut self, len: usize) -> Result<&'a [u8], StateError> {
+        self.take(len)
+    }
+
+    /// Number of bytes left to read
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    /// Check that the whole blob was consumed
+    pub fn finish(self) -> Result<(), StateError> {
+        if self.position == self.data.len() {
+            Ok(())
+        } else {
+            Err(StateError::Invalid(format!(
+                "{} trailing bytes in state",
+                self.data.len() - self.position
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut writer = StateWriter::new();
+        writer.write_u8(0x12);
+        writer.write_bool(true);
+        writer.write_u16(0xBEEF);
+        writer.write_u32(0xDEAD_BEEF);
+        writer.write_u64(0x0123_4567_89AB_CDEF);
+        writer.write_mirroring(Mirroring::Vertical);
+        writer.write_bytes(&[1, 2, 3]);
+        let data = writer.into_bytes();
+
+        let mut reader = StateReader::new(&data);
+        assert_eq!(reader.read_u8().unwrap(), 0x12);
+        assert!(reader.read_bool().unwrap());
+        assert_eq!(reader.read_u16().unwrap(), 0xBEEF);
+        assert_eq!(reader.read_u32().unwrap(), 0xDEAD_BEEF);
+        assert_eq!(reader.read_u64().unwrap(), 0x0123_4567_89AB_CDEF);
+        assert_eq!(reader.read_mirroring().unwrap(), Mirroring::Vertical);
+        let mut bytes = [0; 3];
+        reader.read_bytes_into(&mut bytes).unwrap();
+        assert_eq!(bytes, [1, 2, 3]);
+        assert!(reader.finish().is_ok());
+    }
+
+    #[test]
+    fn test_truncated_state_rejected() {
+        let mut reader = StateReader::new(&[0x01]);
+        assert_eq!(
+            reader.read_u16(),
+            Err(StateError::Truncated {
+                position: 0,
+                needed: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_size_mismatch_rejected() {
+        let mut writer = StateWriter::new();
+        writer.write_bytes(&[0; 4]);
+        let data = writer.into_bytes();
+
+        let mut dest = [0; 8];
+        let mut reader = StateReader::new(&data);
+        assert!(reader.read_bytes_into(&mut dest).is_err());
+    }
+
+    #[test]
+    fn test_trailing_bytes_rejected() {
+        let reader = StateReader::new(&[0x00]);
+        assert!(reader.finish().is_err());
+    }
+}
@@ -0,0 +1,177 @@
+// Run-length compression
+//
+// A PackBits-style byte RLE. Save states are dominated by long runs (cleared
+// RAM, blank nametables, a mostly flat frame buffer), which this handles well
+// while staying trivial to decode.
+//
+// Each packet starts with a control byte `n`:
+// - 0..=127: copy the next `n + 1` bytes literally
+// - 129..=255: repeat the next byte `257 - n` times (2..=128)
+// - 128: unused
+
+use super::StateError;
+
+/// Longest run or literal stretch a single packet can hold
+const MAX_PACKET: usize = 128;
+
+/// Compress `data` with run-length encoding
+///
+/// # Arguments
+///
+/// * `data` - Bytes to compress
+///
+/// # Returns
+///
+/// The compressed bytes
+///
+/// # Example
+///
+/// ```
+/// use nes_rs::state::{rle_compress, rle_decompress};
+///
+/// let data = vec![0u8; 2048];
+/// let packed = rle_compress(&data);
+/// assert!(packed.len() < 64);
+/// assert_eq!(rle_decompress(&packed, data.len()).unwrap(), data);
+/// ```
+pub fn rle_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() / 2);
+    let mut literal_start = 0;
+    let mut i = 0;
+
+    while i < data.len() {
+        // Measure the run starting here
+        let byte = data[i];
+        let mut run = 1;
+        while i + run < data.len() && data[i + run] == byte && run < MAX_PACKET {
+            run += 1;
+        }
+
+        if run >= 3 {
+            flush_literals(&mut out, &data[literal_start..i]);
+            out.push((257 - run) as u8);
+            out.push(byte);
+            i += run;
+            literal_start = i;
+        } else {
+            i += run;
+        }
+    }
+    flush_literals(&mut out, &data[literal_start..]);
+
+    out
+}
+
+/// Emit `literals` as one or more literal packets
+fn flush_literals(out: &mut Vec<u8>, literals: &[u8]) {
+    for chunk in literals.chunks(MAX_PACKET) {
+        out.push((chunk.len() - 1) as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+/// Decompress data produced by `rle_compress`
+///
+/// # Arguments
+///
+/// * `data` - Compressed bytes
+/// * `expected_len` - Size of the original data
+///
+/// # Returns
+///
+/// The original bytes, or an error if the data is malformed or does not
+/// decompress to exactly `expected_len` bytes
+pub fn rle_decompress(data: &[u8], expected_len: usize) -> Result<Vec<u8>, StateError> {
+    let mut out = Vec::with_capacity(expected_len);
+    let mut i = 0;
+
+    while i < data.len() {
+        let control = data[i] as usize;
+        i += 1;
+
+        if control < 128 {
+            let len = control + 1;
+            let literals = data.get(i..i + len).ok_or(StateError::Truncated {
+                position: i,
+                needed: len,
+            })?;
+            out.extend_from_slice(literals);
+            i += len;
+        } else if control > 128 {
+            let byte = *data.get(i).ok_or(StateError::Truncated {
+                position: i,
+                needed: 1,
+            })?;
+            out.resize(out.len() + 257 - control, byte);
+            i += 1;
+        }
+
+        if out.len() > expected_len {
+            break;
+        }
+    }
+
+    if out.len() != expected_len {
+        return Err(StateError::Invalid(format!(
+            "decompressed size mismatch: expected {} bytes, found {}",
+            expected_len,
+            out.len()
+        )));
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(data: &[u8]) {
+        let packed = rle_compress(data);
+        assert_eq!(rle_decompress(&packed, data.len()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_rle_roundtrip_edge_cases() {
+        roundtrip(&[]);
+        roundtrip(&[0x42]);
+        roundtrip(&[1, 1]);
+        roundtrip(&[1, 1, 1]);
+        roundtrip(&[1, 2, 2, 3, 3, 3, 4, 4, 4, 4]);
+        roundtrip(&[0xAA; 128]);
+        roundtrip(&[0xAA; 129]);
+        roundtrip(&[0xAA; 1000]);
+    }
+
+    #[test]
+    fn test_rle_roundtrip_incompressible() {
+        let data: Vec<u8> = (0..1000).map(|i| (i * 7 + i / 3) as u8).collect();
+        roundtrip(&data);
+
+        // Literal-only data grows by at most one byte per 128
+        let packed = rle_compress(&data);
+        assert!(packed.len() <= data.len() + data.len().div_ceil(MAX_PACKET));
+    }
+
+    #[test]
+    fn test_rle_compresses_runs() {
+        let mut data = vec![0u8; 4096];
+        data[100] = 1;
+        data[2000..2010].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        let packed = rle_compress(&data);
+        assert!(packed.len() < 128);
+        roundtrip(&data);
+    }
+
+    #[test]
+    fn test_rle_rejects_malformed_data() {
+        // Literal packet promising more bytes than present
+        assert!(rle_decompress(&[5, 1, 2], 6).is_err());
+        // Run packet missing its byte
+        assert!(rle_decompress(&[0xFE], 3).is_err());
+        // Valid data with the wrong expected size
+        let packed = rle_compress(&[7; 10]);
+        assert!(rle_decompress(&packed, 11).is_err());
+        assert!(rle_decompress(&packed, 9).is_err());
+    }
+}
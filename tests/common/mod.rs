@@ -6,7 +6,9 @@
 #![allow(dead_code)]
 
 use nes_rs::bus::Bus;
-use nes_rs::cpu::Cpu;
+use nes_rs::debug::{LogLevel, Logger};
+use nes_rs::emulator::{Emulator, EmulatorConfig, JAM_TRACE_LENGTH};
+use nes_rs::Cartridge;
 use std::fs;
 use std::path::Path;
 
@@ -37,7 +39,7 @@ pub struct TestConfig {
     pub start_pc: Option<u16>,
     /// Starting cycle count
     pub start_cycles: u64,
-    /// Enable trace logging
+    /// Enable trace logging (included in the report if the CPU jams)
     pub trace: bool,
 }
 
@@ -178,6 +180,42 @@ pub fn read_string(bus: &mut Bus, addr: u16, max_len: usize) -> String {
     result
 }
 
+/// Create an emulator with the ROM at `path` inserted
+///
+/// # Arguments
+///
+/// * `path` - Path to the .nes ROM file
+///
+/// # Returns
+///
+/// The emulator, reset and ready to run, or an error message
+pub fn load_emulator(path: &Path) -> Result<Emulator, String> {
+    let cartridge = Cartridge::from_ines_file(path)
+        .map_err(|e| format!("Failed to load ROM from {}: {}", path.display(), e))?;
+
+    let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    emulator
+        .insert_cartridge(cartridge)
+        .map_err(|e| format!("Failed to insert cartridge: {}", e))?;
+
+    Ok(emulator)
+}
+
+/// Attach a CPU trace logger to the emulator
+///
+/// The trace ends up in the jam report if the CPU halts on a KIL opcode.
+///
+/// # Arguments
+///
+/// * `emulator` - The emulator to trace
+pub fn enable_trace(emulator: &mut Emulator) {
+    let mut logger = Logger::new();
+    logger.set_log_level(LogLevel::Trace);
+    logger.enable_cpu_trace();
+    logger.set_max_buffer_size(JAM_TRACE_LENGTH);
+    emulator.set_trace_logger(Some(logger));
+}
+
 /// Run a test ROM and return the result
 ///
 /// # Arguments
@@ -187,40 +225,31 @@ pub fn read_string(bus: &mut Bus, addr: u16, max_len: usize) -> String {
 ///
 /// # Returns
 ///
-/// Result containing the test result or error message
+/// Result containing the test result, or an error message (including the
+/// jam report if the CPU halted)
 pub fn run_test_rom(rom_path: &Path, config: &TestConfig) -> Result<TestResult, String> {
-    // Load ROM
-    let prg_rom = load_rom(rom_path)?;
-
-    // Initialize CPU and Bus
-    let mut cpu = Cpu::new();
-    let mut bus = Bus::new();
-
-    // Load PRG-ROM into bus
-    load_prg_rom(&mut bus, &prg_rom);
+    // Load ROM (the emulator starts from the reset vector)
+    let mut emulator = load_emulator(rom_path)?;
 
-    // Set PC
     if let Some(pc) = config.start_pc {
-        cpu.pc = pc;
-    } else {
-        // Use reset vector
-        let pc_low = bus.read(0xFFFC);
-        let pc_high = bus.read(0xFFFD);
-        cpu.pc = u16::from_le_bytes([pc_low, pc_high]);
+        emulator.cpu_mut().pc = pc;
     }
 
-    cpu.cycles = config.start_cycles;
+    emulator.cpu_mut().cycles = config.start_cycles;
 
-    // Run test
-    let mut total_cycles = 0u64;
+    if config.trace {
+        enable_trace(&mut emulator);
+    }
 
-    while total_cycles < config.max_cycles {
-        // Execute one instruction
-        cpu.step(&mut bus);
-        total_cycles = cpu.cycles;
+    // Run test
+    while emulator.cpu().cycles < config.max_cycles {
+        // Execute one instruction; a jammed CPU will never finish the test
+        if emulator.step_instruction().jammed {
+            return Err(jam_report(&emulator));
+        }
 
         // Check for test completion
-        let result = check_test_result(&mut bus);
+        let result = check_test_result(emulator.bus_mut());
         match result {
             TestResult::Passed | TestResult::Failed(_) => {
                 return Ok(result);
@@ -238,6 +267,22 @@ pub fn run_test_rom(rom_path: &Path, config: &TestConfig) -> Result<TestResult,
     Ok(TestResult::Timeout)
 }
 
+/// Describe why the CPU jammed
+///
+/// # Arguments
+///
+/// * `emulator` - An emulator whose CPU has halted
+///
+/// # Returns
+///
+/// The jam report
+pub fn jam_report(emulator: &Emulator) -> String {
+    emulator
+        .jam()
+        .map(|jam| jam.to_string())
+        .unwrap_or_else(|| "CPU jammed".to_string())
+}
+
 /// Format test result for display
 pub fn format_result(result: &TestResult) -> String {
     match result {
@@ -263,37 +308,41 @@ pub fn format_result(result: &TestResult) -> String {
 ///
 /// # Returns
 ///
-/// Result containing a tuple of (passed: bool, message: String) or error message
+/// Result containing a tuple of (passed: bool, message: String), or an error
+/// message (including the jam report if the CPU halted)
 pub fn run_blargg_style_test(rom_path: &str, max_cycles: u64) -> Result<(bool, String), String> {
     let path = Path::new(rom_path);
     if !path.exists() {
         return Err(format!("ROM file not found: {}", rom_path));
     }
 
-    // Load ROM
-    let prg_rom = load_rom(path)?;
-
-    // Initialize CPU and Bus
-    let mut cpu = Cpu::new();
-    let mut bus = Bus::new();
-
-    // Load PRG-ROM
-    load_prg_rom(&mut bus, &prg_rom);
-
-    // Use reset vector
-    cpu.reset(&mut bus);
+    // Load ROM (the emulator starts from the reset vector)
+    let mut emulator = load_emulator(path)?;
+    enable_trace(&mut emulator);
 
     // Run test with timeout
-    while cpu.cycles < max_cycles {
-        cpu.step(&mut bus);
+    let mut total_cycles = 0u64;
+    while total_cycles < max_cycles {
+        let result = emulator.step_instruction();
+        if result.jammed {
+            return Err(jam_report(&emulator));
+        }
+        total_cycles += result.cpu_cycles;
 
         // Check test status ($6000)
         // $80 = running, $81 = need reset, $00-$7F = completed with result code
+        let bus = emulator.bus_mut();
+
+        // $6001-$6003 hold the signature $DE $B0 $61 once the status is valid
+        if [bus.read(0x6001), bus.read(0x6002), bus.read(0x6003)] != [0xDE, 0xB0, 0x61] {
+            continue;
+        }
+
         let status = bus.read(0x6000);
 
         // Handle "need reset" handshake ($81)
         if status == 0x81 {
-            cpu.reset(&mut bus);
+            emulator.reset();
             continue;
         }
 
@@ -1,8 +1,8 @@
 // Nestest ROM integration test
 // This test runs the Nestest ROM and compares the CPU trace log with the golden log
 
-use nes_rs::bus::Bus;
-use nes_rs::cpu::Cpu;
+use nes_rs::emulator::{Emulator, EmulatorConfig};
+use nes_rs::Cartridge;
 use std::fs;
 use std::io::Write;
 
@@ -11,50 +11,35 @@ use std::io::Write;
 fn nestest_cpu_test() {
     // Load the Nestest ROM
     let rom_path = "tests/nes-test-rom/other/nestest.nes";
-    let rom_data = fs::read(rom_path).expect("Failed to load Nestest ROM");
+    let cartridge = Cartridge::from_ines_file(rom_path).expect("Failed to load Nestest ROM");
 
     // Load the golden log
     let log_path = "tests/nes-test-rom/other/nestest.log";
     let golden_log = fs::read_to_string(log_path).expect("Failed to load golden log");
     let golden_lines: Vec<&str> = golden_log.lines().collect();
 
-    // Initialize CPU and Bus
-    let mut cpu = Cpu::new();
-    let mut bus = Bus::new();
-
-    // Load ROM into memory (skip iNES header, load PRG-ROM at $8000 and mirror at $C000)
-    // Nestest ROM is 16KB PRG-ROM, loaded at $8000-$BFFF and mirrored at $C000-$FFFF
-
-    // Parse iNES header to get PRG-ROM size
-    let prg_rom_banks = rom_data[4] as usize; // Number of 16KB PRG-ROM banks
-    let prg_rom_size = prg_rom_banks * 16384; // Size in bytes
-
-    // Skip 16-byte iNES header and read only PRG-ROM
-    let prg_rom = &rom_data[16..16 + prg_rom_size];
-
-    // Load PRG-ROM at $8000 and mirror at $C000
-    for (i, &byte) in prg_rom.iter().enumerate() {
-        let addr_8000 = 0x8000_u16.wrapping_add(i as u16);
-        let addr_c000 = 0xC000_u16.wrapping_add(i as u16);
-
-        bus.write(addr_8000, byte);
-        bus.write(addr_c000, byte);
-    }
+    // Initialize the emulator with the Nestest cartridge (NROM-128)
+    let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    emulator
+        .insert_cartridge(cartridge)
+        .expect("Failed to insert cartridge");
 
     // Set PC to $C000 for automation mode (instead of using reset vector)
-    cpu.pc = 0xC000;
-    cpu.cycles = 7; // Start at cycle 7 to match golden log
+    emulator.cpu_mut().pc = 0xC000;
+    emulator.cpu_mut().cycles = 7; // Start at cycle 7 to match golden log
 
     // Open output file for trace log
     let mut trace_file =
         fs::File::create("nestest_trace.log").expect("Failed to create trace log file");
 
     let mut mismatches = Vec::new();
-    let max_instructions = 5003; // Nestest runs about 5003 instructions in automation mode
+    // The golden log covers the official opcode tests (about 5003 instructions)
+    // followed by the unofficial opcode tests, 8991 instructions in total
+    let max_instructions = golden_lines.len();
 
     for instruction_num in 0..max_instructions {
         // Generate trace before executing the instruction
-        let trace_line = cpu.trace(&mut bus);
+        let trace_line = emulator.trace();
 
         // Write to trace file
         writeln!(trace_file, "{}", trace_line).expect("Failed to write to trace file");
@@ -82,12 +67,12 @@ fn nestest_cpu_test() {
         }
 
         // Execute the instruction
-        cpu.step(&mut bus);
+        emulator.step_instruction();
 
         // Check if test is complete by reading $02 and $03
-        // $02 should be $00 and $03 should be $00 for success
-        let result_02 = bus.read(0x02);
-        let result_03 = bus.read(0x03);
+        // $02 reports official opcode failures, $03 unofficial opcode failures
+        let result_02 = emulator.bus_mut().read(0x02);
+        let result_03 = emulator.bus_mut().read(0x03);
 
         if result_02 != 0 || result_03 != 0 {
             println!("\nNestest failed!");
@@ -105,8 +90,8 @@ fn nestest_cpu_test() {
     println!("Trace log written to: nestest_trace.log");
 
     // Check final test result
-    let result_02 = bus.read(0x02);
-    let result_03 = bus.read(0x03);
+    let result_02 = emulator.bus_mut().read(0x02);
+    let result_03 = emulator.bus_mut().read(0x03);
     println!("\nFinal test result:");
     println!("$02 = {:02X} (expected: 00)", result_02);
     println!("$03 = {:02X} (expected: 00)", result_03);
@@ -120,14 +105,15 @@ fn nestest_cpu_test() {
 
     // For the test to pass, we require:
     // 1. Test result registers show success ($02 and $03 are both $00)
-    // 2. Very few trace mismatches (we allow some due to cycle counting differences)
+    // 2. The trace matches the golden log, unofficial opcodes included
     assert_eq!(result_02, 0, "Test failed: $02 should be $00");
     assert_eq!(result_03, 0, "Test failed: $03 should be $00");
-
-    if !mismatches.is_empty() {
-        println!("\nNote: {} trace mismatches detected", mismatches.len());
-        println!("This may be due to cycle counting or PPU differences");
-    }
+    assert!(
+        mismatches.is_empty(),
+        "{} trace mismatches, first at instruction {}",
+        mismatches.len(),
+        mismatches.first().map_or(0, |m| m.0)
+    );
 }
 
 /// Compare trace lines, ignoring PPU values since we don't have PPU implemented
@@ -169,8 +155,8 @@ fn compare_trace_lines(actual: &str, expected: &str) -> bool {
 #[test]
 fn nestest_quick_smoke_test() {
     // Quick smoke test to verify basic CPU execution
-    let mut cpu = Cpu::new();
-    let mut bus = Bus::new();
+    let mut emulator = Emulator::with_config(EmulatorConfig::default());
+    let bus = emulator.bus_mut();
 
     // Write a simple program: LDA #$42, STA $00, BRK
     bus.write(0x8000, 0xA9); // LDA #$42
@@ -179,15 +165,15 @@ fn nestest_quick_smoke_test() {
     bus.write(0x8003, 0x00);
     bus.write(0x8004, 0x00); // BRK
 
-    cpu.pc = 0x8000;
+    emulator.cpu_mut().pc = 0x8000;
 
     // Execute LDA #$42
-    cpu.step(&mut bus);
-    assert_eq!(cpu.a, 0x42);
-    assert_eq!(cpu.pc, 0x8002);
+    emulator.step_instruction();
+    assert_eq!(emulator.cpu().a, 0x42);
+    assert_eq!(emulator.cpu().pc, 0x8002);
 
     // Execute STA $00
-    cpu.step(&mut bus);
-    assert_eq!(bus.read(0x00), 0x42);
-    assert_eq!(cpu.pc, 0x8004);
+    emulator.step_instruction();
+    assert_eq!(emulator.bus_mut().read(0x00), 0x42);
+    assert_eq!(emulator.cpu().pc, 0x8004);
 }
@@ -0,0 +1,356 @@
+// ProcessorTests single-step CPU conformance runner
+//
+// Runs the community "ProcessorTests" 6502 JSON suites against the CPU core on
+// a flat 64KB bus. Each file holds the cases for one opcode (e.g. `a9.json`);
+// every case gives the initial CPU state and memory, the expected final state
+// and memory, and the expected bus access on each cycle of the instruction.
+//
+// The suites are not bundled with the repository. Download them from
+// https://github.com/SingleStepTests/ProcessorTests and either place the
+// `nes6502/v1` directory (decimal mode disabled, as on the 2A03) at
+// tests/ProcessorTests/nes6502/v1 or point PROCESSOR_TESTS_DIR at it.
+// PROCESSOR_TESTS_OPCODES limits the run to a comma-separated list of opcodes
+// (e.g. `a9,8d`).
+
+use nes_rs::cpu::{Cpu, CpuBus, FlatBus};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Default location of the ProcessorTests JSON files
+const DEFAULT_TESTS_DIR: &str = "tests/ProcessorTests/nes6502/v1";
+
+/// Environment variable overriding the test directory
+const TESTS_DIR_VAR: &str = "PROCESSOR_TESTS_DIR";
+
+/// Environment variable selecting the opcodes to run
+const OPCODES_VAR: &str = "PROCESSOR_TESTS_OPCODES";
+
+/// KIL opcodes halt the CPU instead of completing, so their cases do not apply
+const KIL_OPCODES: [u8; 12] = [
+    0x02, 0x12, 0x22, 0x32, 0x42, 0x52, 0x62, 0x72, 0x92, 0xB2, 0xD2, 0xF2,
+];
+
+/// B (bit 4) and bit 5 are not stored in the 6502 status register
+///
+/// They only exist in pushed copies of P, which the memory and bus-cycle
+/// checks already cover.
+const STATUS_MASK: u8 = 0b1100_1111;
+
+/// Number of failing cases printed per opcode
+const MAX_REPORTED_FAILURES: usize = 3;
+
+// ============================================================================
+// Test Case Format
+// ============================================================================
+
+/// One single-instruction test case
+#[derive(Debug, Deserialize)]
+struct TestCase {
+    name: String,
+    initial: CpuState,
+    #[serde(rename = "final")]
+    expected: CpuState,
+    cycles: Vec<BusCycle>,
+}
+
+/// CPU registers and the memory locations touched by a test case
+#[derive(Debug, Deserialize)]
+struct CpuState {
+    pc: u16,
+    s: u8,
+    a: u8,
+    x: u8,
+    y: u8,
+    p: u8,
+    ram: Vec<(u16, u8)>,
+}
+
+/// One bus cycle: address, data and "read" or "write"
+type BusCycle = (u16, u8, String);
+
+// ============================================================================
+// Tracing Bus
+// ============================================================================
+
+/// Flat memory bus that records every access
+struct TracingBus {
+    memory: FlatBus,
+    cycles: Vec<BusCycle>,
+}
+
+impl TracingBus {
+    fn new() -> Self {
+        TracingBus {
+            memory: FlatBus::new(),
+            cycles: Vec::new(),
+        }
+    }
+}
+
+impl CpuBus for TracingBus {
+    fn read(&mut self, addr: u16) -> u8 {
+        let data = self.memory.read(addr);
+        self.cycles.push((addr, data, "read".to_string()));
+        data
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.memory.write(addr, data);
+        self.cycles.push((addr, data, "write".to_string()));
+    }
+
+    fn tick(&mut self) {
+        // A cycle without an access never matches an expected cycle
+        self.memory.tick();
+    }
+}
+
+// ============================================================================
+// Runner
+// ============================================================================
+
+/// Run a single test case
+///
+/// # Returns
+///
+/// A description of every mismatch (empty if the case passed)
+fn run_case(case: &TestCase) -> Vec<String> {
+    let mut bus = TracingBus::new();
+    for &(addr, value) in &case.initial.ram {
+        bus.memory.load(addr, &[value]);
+    }
+
+    let mut cpu = Cpu::new();
+    cpu.pc = case.initial.pc;
+    cpu.sp = case.initial.s;
+    cpu.a = case.initial.a;
+    cpu.x = case.initial.x;
+    cpu.y = case.initial.y;
+    cpu.status = case.initial.p;
+
+    cpu.step(&mut bus);
+
+    let expected = &case.expected;
+    let mut errors = Vec::new();
+    let registers = [
+        ("PC", cpu.pc, expected.pc),
+        ("S", cpu.sp as u16, expected.s as u16),
+        ("A", cpu.a as u16, expected.a as u16),
+        ("X", cpu.x as u16, expected.x as u16),
+        ("Y", cpu.y as u16, expected.y as u16),
+        (
+            "P",
+            (cpu.status & STATUS_MASK) as u16,
+            (expected.p & STATUS_MASK) as u16,
+        ),
+    ];
+    for (name, actual, wanted) in registers {
+        if actual != wanted {
+            errors.push(format!(
+                "{}: expected ${:02X}, got ${:02X}",
+                name, wanted, actual
+            ));
+        }
+    }
+
+    for &(addr, wanted) in &expected.ram {
+        let actual = bus.memory.peek(addr);
+        if actual != wanted {
+            errors.push(format!(
+                "${:04X}: expected ${:02X}, got ${:02X}",
+                addr, wanted, actual
+            ));
+        }
+    }
+
+    if bus.cycles != case.cycles {
+        errors.push(format!(
+            "bus cycles:\n      expected {}\n      got      {}",
+            format_cycles(&case.cycles),
+            format_cycles(&bus.cycles)
+        ));
+    }
+
+    errors
+}
+
+/// Format a bus trace as `r $0200=$A9 w $0010=$42 ...`
+fn format_cycles(cycles: &[BusCycle]) -> String {
+    cycles
+        .iter()
+        .map(|(addr, data, kind)| {
+            let kind = if kind == "write" { 'w' } else { 'r' };
+            format!("{} ${:04X}=${:02X}", kind, addr, data)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Results of one opcode file
+struct OpcodeReport {
+    total: usize,
+    failures: Vec<(String, Vec<String>)>,
+}
+
+/// Run every case in a ProcessorTests JSON file
+fn run_file(path: &Path) -> Result<OpcodeReport, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let cases: Vec<TestCase> = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+    let failures = cases
+        .iter()
+        .filter_map(|case| {
+            let errors = run_case(case);
+            (!errors.is_empty()).then(|| (case.name.clone(), errors))
+        })
+        .collect();
+
+    Ok(OpcodeReport {
+        total: cases.len(),
+        failures,
+    })
+}
+
+/// Directory holding the JSON files
+fn tests_dir() -> PathBuf {
+    std::env::var_os(TESTS_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_TESTS_DIR))
+}
+
+/// Opcodes selected with PROCESSOR_TESTS_OPCODES (None = all)
+fn selected_opcodes() -> Option<Vec<u8>> {
+    let list = std::env::var(OPCODES_VAR).ok()?;
+    Some(
+        list.split(',')
+            .map(|opcode| {
+                u8::from_str_radix(opcode.trim(), 16)
+                    .unwrap_or_else(|_| panic!("Invalid opcode in {}: {}", OPCODES_VAR, opcode))
+            })
+            .collect(),
+    )
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[test]
+#[ignore] // Run with: cargo test --test processor_tests -- --ignored --nocapture
+fn processor_tests_all_opcodes() {
+    let dir = tests_dir();
+    assert!(
+        dir.is_dir(),
+        "ProcessorTests not found at {} (set {} to the nes6502/v1 directory)",
+        dir.display(),
+        TESTS_DIR_VAR
+    );
+
+    let selected = selected_opcodes();
+    let mut files: Vec<(u8, PathBuf)> = fs::read_dir(&dir)
+        .expect("Failed to read ProcessorTests directory")
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let opcode = u8::from_str_radix(path.file_stem()?.to_str()?, 16).ok()?;
+            Some((opcode, path))
+        })
+        .filter(|(opcode, _)| selected.as_ref().is_none_or(|list| list.contains(opcode)))
+        .collect();
+    files.sort();
+
+    let mut failed_opcodes = Vec::new();
+    let mut skipped = 0;
+
+    for (opcode, path) in &files {
+        if KIL_OPCODES.contains(opcode) {
+            skipped += 1;
+            continue;
+        }
+
+        let report = run_file(path).unwrap_or_else(|e| panic!("{}", e));
+        let passed = report.total - report.failures.len();
+        println!("${:02X}: {}/{} passed", opcode, passed, report.total);
+        if report.failures.is_empty() {
+            continue;
+        }
+
+        for (name, errors) in report.failures.iter().take(MAX_REPORTED_FAILURES) {
+            println!("  case \"{}\"", name);
+            for error in errors {
+                println!("    {}", error);
+            }
+        }
+        failed_opcodes.push(*opcode);
+    }
+
+    println!(
+        "\n{} opcodes run, {} failed, {} KIL opcodes skipped",
+        files.len() - skipped,
+        failed_opcodes.len(),
+        skipped
+    );
+    assert!(
+        !files.is_empty(),
+        "No opcode files found in {}",
+        dir.display()
+    );
+    assert!(
+        failed_opcodes.is_empty(),
+        "Failing opcodes: {}",
+        failed_opcodes
+            .iter()
+            .map(|opcode| format!("${:02X}", opcode))
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+}
+
+/// LDA #$42 followed by STA $10, in the ProcessorTests format
+const SAMPLE_CASES: &str = r#"[
+    {
+        "name": "a9 42 85",
+        "initial": { "pc": 512, "s": 253, "a": 0, "x": 0, "y": 0, "p": 38,
+                     "ram": [[512, 169], [513, 66], [514, 133]] },
+        "final": { "pc": 514, "s": 253, "a": 66, "x": 0, "y": 0, "p": 36,
+                   "ram": [[512, 169], [513, 66], [514, 133]] },
+        "cycles": [[512, 169, "read"], [513, 66, "read"]]
+    },
+    {
+        "name": "85 10 00",
+        "initial": { "pc": 512, "s": 253, "a": 66, "x": 0, "y": 0, "p": 36,
+                     "ram": [[512, 133], [513, 16], [16, 0]] },
+        "final": { "pc": 514, "s": 253, "a": 66, "x": 0, "y": 0, "p": 36,
+                   "ram": [[512, 133], [513, 16], [16, 66]] },
+        "cycles": [[512, 133, "read"], [513, 16, "read"], [16, 66, "write"]]
+    }
+]"#;
+
+#[test]
+fn processor_tests_runner_passes_sample_cases() {
+    let cases: Vec<TestCase> = serde_json::from_str(SAMPLE_CASES).unwrap();
+    for case in &cases {
+        assert_eq!(run_case(case), Vec::<String>::new(), "case {}", case.name);
+    }
+}
+
+#[test]
+fn processor_tests_runner_reports_mismatches() {
+    let mut cases: Vec<TestCase> = serde_json::from_str(SAMPLE_CASES).unwrap();
+    let case = &mut cases[1];
+    case.expected.a = 0x43;
+    case.expected.ram[2] = (0x0010, 0x43);
+    case.cycles[2].0 = 0x0011;
+
+    let errors = run_case(case);
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert_eq!(errors[0], "A: expected $43, got $42");
+    assert_eq!(errors[1], "$0010: expected $43, got $42");
+    assert!(errors[2].contains("w $0011=$42"), "{}", errors[2]);
+    assert!(errors[2].contains("w $0010=$42"), "{}", errors[2]);
+}
@@ -45,7 +45,7 @@ enum Commands {
         doc: bool,
         #[arg(long)]
         ignored: bool,
-        /// Run only CPU module tests
+        /// Run only CPU module tests (and the ProcessorTests suites when present)
         #[arg(long)]
         cpu: bool,
         /// Run only PPU module tests
@@ -266,7 +266,12 @@ fn run_test(doc: bool, ignored: bool, cpu: bool, ppu: bool, memory: bool) -> Res
             cmd.arg("--").arg("--ignored");
         }
 
-        match execute_command(&mut cmd) {
+        let mut result = execute_command(&mut cmd);
+        if module_path == "cpu" && result.is_ok() {
+            result = run_processor_tests(use_no_default_features);
+        }
+
+        match result {
             Ok(_) => {
                 println!("{} {} tests passed\n", "✓".green(), module_name);
             }
@@ -288,6 +293,49 @@ fn run_test(doc: bool, ignored: bool, cpu: bool, ppu: bool, memory: bool) -> Res
     }
 }
 
+fn run_processor_tests(use_no_default_features: bool) -> Result<()> {
+    use std::path::PathBuf;
+
+    // Single-step conformance suites are downloaded separately (see tests/processor_tests.rs)
+    let tests_dir = std::env::var_os("PROCESSOR_TESTS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("tests/ProcessorTests/nes6502/v1"));
+
+    if !tests_dir.is_dir() {
+        println!(
+            "{} ProcessorTests not found at {}, skipping (set PROCESSOR_TESTS_DIR)",
+            "⚠".yellow(),
+            tests_dir.display().to_string().yellow()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} Running ProcessorTests from {}...",
+        "→".blue(),
+        tests_dir.display().to_string().cyan()
+    );
+
+    let mut cmd = Command::new("cargo");
+    cmd.arg("test");
+
+    if use_no_default_features {
+        cmd.arg("--no-default-features");
+    } else {
+        cmd.arg("--all-features");
+    }
+
+    cmd.arg("--release")
+        .arg("--test")
+        .arg("processor_tests")
+        .arg("--")
+        .arg("--ignored")
+        .arg("--nocapture")
+        .env("PROCESSOR_TESTS_DIR", &tests_dir);
+
+    execute_command(&mut cmd)
+}
+
 fn run_bench() -> Result<()> {
     let mut cmd = Command::new("cargo");
     cmd.arg("bench");